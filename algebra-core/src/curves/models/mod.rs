@@ -1,4 +1,7 @@
-use crate::fields::{Field, PrimeField, SquareRootField};
+use crate::{
+    biginteger::BigInteger,
+    fields::{Field, PrimeField, SquareRootField},
+};
 
 pub mod bls12;
 pub mod bn;
@@ -36,6 +39,57 @@ pub trait SWModelParameters: ModelParameters {
     }
 }
 
+/// Parameters of a short Weierstrass curve with `a = 0` that admits the GLV
+/// endomorphism `(x, y) -> (ENDO_COEFF * x, y)`, which acts on the prime-order
+/// subgroup as multiplication by `LAMBDA`.
+pub trait GLVParameters: SWModelParameters {
+    /// A primitive cube root of unity in the base field.
+    const ENDO_COEFF: Self::BaseField;
+    /// The primitive cube root of unity in the scalar field matching
+    /// `ENDO_COEFF`.
+    const LAMBDA: Self::ScalarField;
+
+    /// Decomposes `k` into `(k1, k2)` such that `k = k1 + LAMBDA * k2`, by
+    /// dividing `k` by `LAMBDA` viewed as an integer. Both halves are about
+    /// half the size of the scalar field modulus when `LAMBDA` is close to
+    /// its square root, as it is for BLS12 curves (`LAMBDA = x^2 - 1`).
+    fn glv_decompose(
+        k: Self::ScalarField,
+    ) -> (
+        <Self::ScalarField as PrimeField>::BigInt,
+        <Self::ScalarField as PrimeField>::BigInt,
+    ) {
+        Self::glv_decompose_repr(k.into_repr())
+    }
+
+    /// Like `glv_decompose`, but for an integer `k` that need not be reduced
+    /// modulo the scalar field order, so that `k = k1 + LAMBDA * k2` holds
+    /// over the integers.
+    fn glv_decompose_repr(
+        k: <Self::ScalarField as PrimeField>::BigInt,
+    ) -> (
+        <Self::ScalarField as PrimeField>::BigInt,
+        <Self::ScalarField as PrimeField>::BigInt,
+    ) {
+        let lambda = Self::LAMBDA.into_repr();
+        let one = <Self::ScalarField as PrimeField>::BigInt::from(1);
+        let mut quotient = <Self::ScalarField as PrimeField>::BigInt::from(0);
+        let mut remainder = <Self::ScalarField as PrimeField>::BigInt::from(0);
+        for i in (0..k.num_bits() as usize).rev() {
+            remainder.mul2();
+            quotient.mul2();
+            if k.get_bit(i) {
+                remainder.add_nocarry(&one);
+            }
+            if remainder >= lambda {
+                remainder.sub_noborrow(&lambda);
+                quotient.add_nocarry(&one);
+            }
+        }
+        (remainder, quotient)
+    }
+}
+
 pub trait TEModelParameters: ModelParameters {
     const COEFF_A: Self::BaseField;
     const COEFF_D: Self::BaseField;
@@ -1,6 +1,6 @@
 use algebra_core::{
     biginteger::{BigInteger256, BigInteger384},
-    curves::models::{ModelParameters, SWModelParameters},
+    curves::models::{GLVParameters, ModelParameters, SWModelParameters},
     field_new, Zero,
 };
 
@@ -52,6 +52,28 @@ impl SWModelParameters for Parameters {
     }
 }
 
+impl GLVParameters for Parameters {
+    /// ENDO_COEFF = 80949648264912719408558363140637477264845294720710499478137287262712535938301461879813459410945
+    #[rustfmt::skip]
+    const ENDO_COEFF: Fq = field_new!(Fq, BigInteger384([
+        0xdacd106da5847973,
+        0xd8fe2454bac2a79a,
+        0x1ada4fd6fd832edc,
+        0xfb9868449d150908,
+        0xd63eb8aeea32285e,
+        0x167d6a36f873fd0,
+    ]));
+
+    /// LAMBDA = x^2 - 1 = 91893752504881257701523279626832445440
+    #[rustfmt::skip]
+    const LAMBDA: Fr = field_new!(Fr, BigInteger256([
+        0xae8012cd506fe7e2,
+        0x8adb5f3b1ec9d536,
+        0x15b65fe3a66fe319,
+        0x91f331343200452,
+    ]));
+}
+
 /// G1_GENERATOR_X =
 /// 81937999373150964239938255573465948239988671502647976594219695644855304257327692006745978603320413799295628339695
 #[rustfmt::skip]
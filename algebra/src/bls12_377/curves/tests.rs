@@ -39,6 +39,26 @@ fn test_g1_generator() {
     assert!(generator.is_in_correct_subgroup_assuming_on_curve());
 }
 
+#[test]
+fn test_g1_glv_parameters() {
+    use algebra_core::{biginteger::BigInteger, curves::models::GLVParameters};
+
+    let mut rng = test_rng();
+    for _ in 0..10 {
+        let p: G1Affine = rng.gen::<G1Projective>().into_affine();
+        let endo_p = G1Affine::new(p.x * &g1::Parameters::ENDO_COEFF, p.y, false);
+        assert_eq!(endo_p, p.mul(g1::Parameters::LAMBDA).into_affine());
+
+        let k: Fr = rng.gen();
+        let (k1, k2) = g1::Parameters::glv_decompose(k);
+        assert!(k1.num_bits() <= 128);
+        assert!(k2.num_bits() <= 128);
+        let k1 = Fr::from_repr(k1).unwrap();
+        let k2 = Fr::from_repr(k2).unwrap();
+        assert_eq!(k, k1 + &(g1::Parameters::LAMBDA * &k2));
+    }
+}
+
 #[test]
 fn test_g2_projective_curve() {
     curve_tests::<G2Projective>();
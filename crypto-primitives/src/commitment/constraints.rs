@@ -1,4 +1,4 @@
-use crate::CommitmentScheme;
+use crate::{CommitmentScheme, Vec};
 use algebra_core::Field;
 use core::fmt::Debug;
 use r1cs_core::{ConstraintSystem, SynthesisError};
@@ -20,4 +20,32 @@ pub trait CommitmentGadget<C: CommitmentScheme, ConstraintF: Field> {
         input: &[UInt8],
         r: &Self::RandomnessGadget,
     ) -> Result<Self::OutputGadget, SynthesisError>;
+
+    /// Returns whether `commitment` opens to `input` with randomness `r`, by
+    /// recomputing the commitment and comparing byte encodings. This relies on
+    /// `OutputGadget::to_bytes` being injective.
+    fn verify_opening<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        parameters: &Self::ParametersGadget,
+        commitment: &Self::OutputGadget,
+        input: &[UInt8],
+        r: &Self::RandomnessGadget,
+    ) -> Result<Boolean, SynthesisError> {
+        let expected = Self::check_commitment_gadget(cs.ns(|| "recompute"), parameters, input, r)?;
+        let expected_bytes = expected.to_bytes(cs.ns(|| "expected to bytes"))?;
+        let commitment_bytes = commitment.to_bytes(cs.ns(|| "commitment to bytes"))?;
+        assert_eq!(expected_bytes.len(), commitment_bytes.len());
+
+        let mut bits_equal = Vec::new();
+        for (i, (a, b)) in expected_bytes.iter().zip(&commitment_bytes).enumerate() {
+            for (j, (a, b)) in a.into_bits_le().iter().zip(b.into_bits_le()).enumerate() {
+                bits_equal.push(Boolean::xnor(
+                    cs.ns(|| format!("byte {} bit {} equal", i, j)),
+                    a,
+                    &b,
+                )?);
+            }
+        }
+        Boolean::kary_and(cs.ns(|| "all equal"), &bits_equal)
+    }
 }
@@ -52,6 +52,32 @@ where
         input: &[UInt8],
         r: &Self::RandomnessGadget,
     ) -> Result<Self::OutputGadget, SynthesisError> {
+        let mut result = Self::commit_to_input(&mut cs, parameters, input)?;
+
+        // Compute h^r
+        let rand_bits: Vec<_> = r.0.iter().flat_map(|byte| byte.into_bits_le()).collect();
+        result.precomputed_base_scalar_mul(
+            cs.ns(|| "Randomizer"),
+            rand_bits
+                .iter()
+                .zip(&parameters.params.randomness_generator),
+        )?;
+
+        Ok(result)
+    }
+}
+
+impl<ConstraintF, G, GG> PedersenCommitmentGadget<G, ConstraintF, GG>
+where
+    ConstraintF: PrimeField,
+    G: Group,
+    GG: GroupGadget<G, ConstraintF>,
+{
+    fn commit_to_input<CS: ConstraintSystem<ConstraintF>, W: PedersenWindow>(
+        mut cs: CS,
+        parameters: &PedersenCommitmentGadgetParameters<G, W, ConstraintF>,
+        input: &[UInt8],
+    ) -> Result<GG, SynthesisError> {
         assert!((input.len() * 8) <= (W::WINDOW_SIZE * W::NUM_WINDOWS));
 
         let mut padded_input = input.to_vec();
@@ -72,17 +98,53 @@ where
             .flat_map(|byte| byte.into_bits_le())
             .collect();
         let input_in_bits = input_in_bits.chunks(W::WINDOW_SIZE);
-        let mut result = GG::precomputed_base_multiscalar_mul(
+        GG::precomputed_base_multiscalar_mul(
             cs.ns(|| "multiexp"),
             &parameters.params.generators,
             input_in_bits,
-        )?;
+        )
+    }
+
+    /// Adds two commitments. Pedersen commitments are additively homomorphic:
+    /// the randomness of the sum is the sum of the randomnesses, and the input
+    /// of the sum is obtained by adding the inputs window by window, reading
+    /// each window as a little-endian integer. The sum is thus a commitment to
+    /// the sum of the inputs only if no window overflows, since carries do
+    /// not propagate to the next window.
+    pub fn add_commitments<CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        a: &GG,
+        b: &GG,
+    ) -> Result<GG, SynthesisError> {
+        a.add(cs, b)
+    }
+
+    /// Like `check_commitment_gadget`, but takes the randomness as a
+    /// little-endian bit slice of any length, such as the bits of an existing
+    /// field element. Committing to the bits of `r` gives the same output as
+    /// committing with `PedersenRandomness(r)`.
+    ///
+    /// Returns `SynthesisError::Unsatisfiable` if `randomness_bits` is longer
+    /// than the number of powers of the randomness generator, that is, longer
+    /// than the scalar field modulus.
+    pub fn check_commitment_gadget_with_bits<
+        CS: ConstraintSystem<ConstraintF>,
+        W: PedersenWindow,
+    >(
+        mut cs: CS,
+        parameters: &PedersenCommitmentGadgetParameters<G, W, ConstraintF>,
+        input: &[UInt8],
+        randomness_bits: &[Boolean],
+    ) -> Result<GG, SynthesisError> {
+        if randomness_bits.len() > parameters.params.randomness_generator.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let mut result = Self::commit_to_input(&mut cs, parameters, input)?;
 
         // Compute h^r
-        let rand_bits: Vec<_> = r.0.iter().flat_map(|byte| byte.into_bits_le()).collect();
         result.precomputed_base_scalar_mul(
             cs.ns(|| "Randomizer"),
-            rand_bits
+            randomness_bits
                 .iter()
                 .zip(&parameters.params.randomness_generator),
         )?;
@@ -201,7 +263,7 @@ where
 mod test {
     use algebra::{
         ed_on_bls12_381::{EdwardsProjective as JubJub, Fq, Fr},
-        test_rng, ProjectiveCurve, UniformRand,
+        test_rng, BitIterator, FpParameters, PrimeField, ProjectiveCurve, UniformRand,
     };
 
     use crate::{
@@ -275,4 +337,212 @@ mod test {
         assert_eq!(primitive_result.y, gadget_result.y.value.unwrap());
         assert!(cs.is_satisfied());
     }
+
+    #[test]
+    fn verify_opening_test() {
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let rng = &mut test_rng();
+        let input = [1u8; 4];
+        let parameters = BitsCOMM::setup(rng).unwrap();
+        let randomness = PedersenRandomness(Fr::rand(rng));
+        let commitment = BitsCOMM::commit(&parameters, &input, &randomness).unwrap();
+
+        let input_bytes = UInt8::alloc_vec(cs.ns(|| "input"), &input).unwrap();
+        let gadget_parameters =
+            <BitsCOMMGadget as CommitmentGadget<BitsCOMM, Fq>>::ParametersGadget::alloc(
+                &mut cs.ns(|| "gadget_parameters"),
+                || Ok(&parameters),
+            )
+            .unwrap();
+        let commitment_gadget =
+            <BitsCOMMGadget as CommitmentGadget<BitsCOMM, Fq>>::OutputGadget::alloc(
+                &mut cs.ns(|| "commitment"),
+                || Ok(&commitment),
+            )
+            .unwrap();
+
+        let wrong_randomness = PedersenRandomness(Fr::rand(rng));
+        for (i, (r, expected)) in [(randomness, true), (wrong_randomness, false)]
+            .iter()
+            .enumerate()
+        {
+            let mut cs = cs.ns(|| format!("opening {}", i));
+            let randomness_gadget =
+                <BitsCOMMGadget as CommitmentGadget<BitsCOMM, Fq>>::RandomnessGadget::alloc(
+                    &mut cs.ns(|| "gadget_randomness"),
+                    || Ok(r),
+                )
+                .unwrap();
+            let result = <BitsCOMMGadget as CommitmentGadget<BitsCOMM, Fq>>::verify_opening(
+                &mut cs.ns(|| "verify opening"),
+                &gadget_parameters,
+                &commitment_gadget,
+                &input_bytes,
+                &randomness_gadget,
+            )
+            .unwrap();
+            assert_eq!(result.get_value().unwrap(), *expected);
+        }
+        assert!(cs.is_satisfied());
+    }
+
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    struct BitsWindow;
+
+    impl PedersenWindow for BitsWindow {
+        const WINDOW_SIZE: usize = 4;
+        const NUM_WINDOWS: usize = 8;
+    }
+
+    type BitsCOMM = PedersenCommitment<JubJub, BitsWindow>;
+    type BitsCOMMGadget = PedersenCommitmentGadget<JubJub, Fq, EdwardsGadget>;
+
+    /// Commits to the same input with `randomness` allocated through the
+    /// randomness gadget, and with its low `num_bits` bits.
+    fn commit_with_bits(randomness: Fr, num_bits: usize) {
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let rng = &mut test_rng();
+        let input = [1u8; 4];
+        let parameters = BitsCOMM::setup(rng).unwrap();
+
+        let input_bytes = UInt8::alloc_vec(cs.ns(|| "input"), &input).unwrap();
+        let gadget_parameters =
+            <BitsCOMMGadget as CommitmentGadget<BitsCOMM, Fq>>::ParametersGadget::alloc(
+                &mut cs.ns(|| "gadget_parameters"),
+                || Ok(&parameters),
+            )
+            .unwrap();
+        let randomness_gadget =
+            <BitsCOMMGadget as CommitmentGadget<BitsCOMM, Fq>>::RandomnessGadget::alloc(
+                &mut cs.ns(|| "gadget_randomness"),
+                || Ok(&PedersenRandomness(randomness)),
+            )
+            .unwrap();
+        let expected = <BitsCOMMGadget as CommitmentGadget<BitsCOMM, Fq>>::check_commitment_gadget(
+            &mut cs.ns(|| "gadget_evaluation"),
+            &gadget_parameters,
+            &input_bytes,
+            &randomness_gadget,
+        )
+        .unwrap();
+
+        let mut bits = BitIterator::new(randomness.into_repr()).collect::<Vec<_>>();
+        bits.reverse();
+        assert!(bits[num_bits..].iter().all(|b| !b));
+        let randomness_bits = bits[..num_bits]
+            .iter()
+            .enumerate()
+            .map(|(i, b)| Boolean::alloc(cs.ns(|| format!("randomness bit {}", i)), || Ok(*b)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let result = BitsCOMMGadget::check_commitment_gadget_with_bits(
+            &mut cs.ns(|| "gadget_evaluation_with_bits"),
+            &gadget_parameters,
+            &input_bytes,
+            &randomness_bits,
+        )
+        .unwrap();
+
+        let primitive_result =
+            BitsCOMM::commit(&parameters, &input, &PedersenRandomness(randomness))
+                .unwrap()
+                .into_affine();
+        assert_eq!(primitive_result.x, result.x.value.unwrap());
+        assert_eq!(primitive_result.y, result.y.value.unwrap());
+        assert_eq!(expected.x.value, result.x.value);
+        assert_eq!(expected.y.value, result.y.value);
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn commitment_gadget_with_bits_test() {
+        let num_bits = <Fr as PrimeField>::Params::MODULUS_BITS as usize;
+        commit_with_bits(Fr::rand(&mut test_rng()), num_bits);
+    }
+
+    #[test]
+    fn commitment_gadget_with_short_bits_test() {
+        commit_with_bits(Fr::from(0xdead_beef_u64), 32);
+    }
+
+    #[test]
+    fn commitment_gadget_with_too_many_bits_test() {
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let rng = &mut test_rng();
+        let parameters = BitsCOMM::setup(rng).unwrap();
+        let gadget_parameters =
+            <BitsCOMMGadget as CommitmentGadget<BitsCOMM, Fq>>::ParametersGadget::alloc(
+                &mut cs.ns(|| "gadget_parameters"),
+                || Ok(&parameters),
+            )
+            .unwrap();
+        let num_bits = <Fr as PrimeField>::Params::MODULUS_BITS as usize + 1;
+        let randomness_bits = vec![Boolean::constant(false); num_bits];
+        let result = BitsCOMMGadget::check_commitment_gadget_with_bits(
+            &mut cs.ns(|| "gadget_evaluation_with_bits"),
+            &gadget_parameters,
+            &[],
+            &randomness_bits,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn commitment_gadget_homomorphism_test() {
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let rng = &mut test_rng();
+        let parameters = BitsCOMM::setup(rng).unwrap();
+        let gadget_parameters =
+            <BitsCOMMGadget as CommitmentGadget<BitsCOMM, Fq>>::ParametersGadget::alloc(
+                &mut cs.ns(|| "gadget_parameters"),
+                || Ok(&parameters),
+            )
+            .unwrap();
+
+        // No 4-bit window of the sum overflows.
+        let a = [0x21u8, 0x43, 0x05, 0x70];
+        let b = [0x12u8, 0x34, 0x50, 0x07];
+        let sum = [0x33u8, 0x77, 0x55, 0x77];
+        let a_randomness = PedersenRandomness(Fr::rand(rng));
+        let b_randomness = PedersenRandomness(Fr::rand(rng));
+        let sum_randomness = PedersenRandomness(a_randomness.0 + &b_randomness.0);
+
+        let mut commit = |name: &str, input: &[u8], randomness: &PedersenRandomness<JubJub>| {
+            let mut cs = cs.ns(|| name);
+            let input = UInt8::alloc_vec(cs.ns(|| "input"), input).unwrap();
+            let randomness =
+                <BitsCOMMGadget as CommitmentGadget<BitsCOMM, Fq>>::RandomnessGadget::alloc(
+                    cs.ns(|| "randomness"),
+                    || Ok(randomness),
+                )
+                .unwrap();
+            <BitsCOMMGadget as CommitmentGadget<BitsCOMM, Fq>>::check_commitment_gadget(
+                cs.ns(|| "commit"),
+                &gadget_parameters,
+                &input,
+                &randomness,
+            )
+            .unwrap()
+        };
+        let a_commitment = commit("a", &a, &a_randomness);
+        let b_commitment = commit("b", &b, &b_randomness);
+        let sum_commitment = commit("sum", &sum, &sum_randomness);
+
+        let result = BitsCOMMGadget::add_commitments(
+            cs.ns(|| "add commitments"),
+            &a_commitment,
+            &b_commitment,
+        )
+        .unwrap();
+        result
+            .enforce_equal(cs.ns(|| "enforce equal"), &sum_commitment)
+            .unwrap();
+
+        let primitive_result = BitsCOMM::commit(&parameters, &sum, &sum_randomness)
+            .unwrap()
+            .into_affine();
+        assert_eq!(primitive_result.x, result.x.value.unwrap());
+        assert_eq!(primitive_result.y, result.y.value.unwrap());
+        assert!(cs.is_satisfied());
+    }
 }
@@ -0,0 +1,123 @@
+use crate::{
+    commitment::blake2s::constraints::Blake2sParametersGadget,
+    crh::{blake2s::Blake2sCRH, FixedLengthCRH, FixedLengthCRHGadget},
+    prf::blake2s::constraints::{blake2s_gadget, Blake2sOutputGadget},
+    Vec,
+};
+use algebra_core::PrimeField;
+use r1cs_core::{ConstraintSystem, SynthesisError};
+use r1cs_std::prelude::*;
+
+pub struct Blake2sCRHGadget;
+
+impl<ConstraintF: PrimeField> FixedLengthCRHGadget<Blake2sCRH, ConstraintF> for Blake2sCRHGadget {
+    type OutputGadget = Blake2sOutputGadget;
+    type ParametersGadget = Blake2sParametersGadget;
+
+    fn check_evaluation_gadget<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        _: &Self::ParametersGadget,
+        input: &[UInt8],
+    ) -> Result<Self::OutputGadget, SynthesisError> {
+        assert!(input.len() * 8 <= Blake2sCRH::INPUT_SIZE_BITS);
+        // Pad the input to the fixed length, as the native hash does.
+        let mut padded_input = input.to_vec();
+        padded_input.resize(Blake2sCRH::INPUT_SIZE_BITS / 8, UInt8::constant(0u8));
+
+        let input_bits = padded_input
+            .iter()
+            .flat_map(UInt8::into_bits_le)
+            .collect::<Vec<_>>();
+        let mut result = Vec::with_capacity(32);
+        for (i, int) in blake2s_gadget(cs.ns(|| "Blake2s Eval"), &input_bits)?
+            .into_iter()
+            .enumerate()
+        {
+            let chunk = int.to_bytes(&mut cs.ns(|| format!("Result ToBytes {}", i)))?;
+            result.extend_from_slice(&chunk);
+        }
+        Ok(Blake2sOutputGadget(result))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        crh::{
+            blake2s::{constraints::Blake2sCRHGadget, Blake2sCRH},
+            FixedLengthCRH, FixedLengthCRHGadget,
+        },
+        prf::blake2s::constraints::blake2s_gadget,
+    };
+    use algebra::{ed_on_bls12_381::Fq as Fr, test_rng};
+    use r1cs_core::ConstraintSystem;
+    use r1cs_std::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use rand::Rng;
+
+    #[test]
+    fn blake2s_test_vectors() {
+        // The first is from RFC 7693, Appendix B; the second is the digest of
+        // the empty string from the reference implementation.
+        let vectors: [(&[u8], &str); 2] = [
+            (
+                b"abc",
+                "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982",
+            ),
+            (
+                b"",
+                "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9",
+            ),
+        ];
+        for (i, (input, expected)) in vectors.iter().enumerate() {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let input_bytes =
+                UInt8::alloc_vec(cs.ns(|| format!("input {}", i)), &input[..]).unwrap();
+            let input_bits = input_bytes
+                .iter()
+                .flat_map(UInt8::into_bits_le)
+                .collect::<Vec<_>>();
+            let digest = blake2s_gadget(cs.ns(|| format!("digest {}", i)), &input_bits).unwrap();
+            let hex = digest
+                .iter()
+                .flat_map(|word| word.get_value().unwrap().to_le_bytes().to_vec())
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>();
+            assert_eq!(&hex, expected);
+            assert!(cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn crh_primitive_gadget_test() {
+        let rng = &mut test_rng();
+        let parameters = Blake2sCRH::setup(rng).unwrap();
+
+        // Two digests, as in a Merkle tree, and an input that needs padding.
+        for &len in &[64, 20] {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let input = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let input_bytes = UInt8::alloc_vec(cs.ns(|| "input"), &input).unwrap();
+
+            let primitive_result = Blake2sCRH::evaluate(&parameters, &input).unwrap();
+
+            let gadget_parameters =
+                <Blake2sCRHGadget as FixedLengthCRHGadget<Blake2sCRH, Fr>>::ParametersGadget::alloc(
+                    &mut cs.ns(|| "gadget_parameters"),
+                    || Ok(&parameters),
+                )
+                .unwrap();
+            let gadget_result =
+                <Blake2sCRHGadget as FixedLengthCRHGadget<Blake2sCRH, Fr>>::check_evaluation_gadget(
+                    &mut cs.ns(|| "gadget_evaluation"),
+                    &gadget_parameters,
+                    &input_bytes,
+                )
+                .unwrap();
+
+            for i in 0..32 {
+                assert_eq!(primitive_result[i], gadget_result.0[i].get_value().unwrap());
+            }
+            assert!(cs.is_satisfied());
+        }
+    }
+}
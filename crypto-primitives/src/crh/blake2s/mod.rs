@@ -0,0 +1,43 @@
+use crate::{crh::FixedLengthCRH, Error};
+use blake2::Blake2s as b2s;
+use digest::Digest;
+use rand::Rng;
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// Blake2s-256 on inputs of exactly two digests, for use as the two-to-one
+/// hash of a Merkle tree. Shorter inputs are padded with zeros.
+pub struct Blake2sCRH;
+
+impl FixedLengthCRH for Blake2sCRH {
+    const INPUT_SIZE_BITS: usize = 512;
+    type Output = [u8; 32];
+    type Parameters = ();
+
+    fn setup<R: Rng>(_: &mut R) -> Result<Self::Parameters, Error> {
+        Ok(())
+    }
+
+    fn evaluate(_: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error> {
+        let eval_time = start_timer!(|| "Blake2sCRH::Eval");
+
+        if input.len() * 8 > Self::INPUT_SIZE_BITS {
+            panic!(
+                "incorrect input length {:?} for input size {:?}",
+                input.len(),
+                Self::INPUT_SIZE_BITS
+            );
+        }
+
+        let mut padded_input = [0u8; 64];
+        padded_input[..input.len()].copy_from_slice(input);
+
+        let mut h = b2s::new();
+        h.input(padded_input.as_ref());
+        let mut result = [0u8; 32];
+        result.copy_from_slice(&h.result());
+        end_timer!(eval_time);
+        Ok(result)
+    }
+}
@@ -0,0 +1,175 @@
+use crate::crh::{
+    compose::{ComposeCRH, ComposeCRHParameters},
+    FixedLengthCRH, FixedLengthCRHGadget,
+};
+use algebra_core::Field;
+use core::{borrow::Borrow, marker::PhantomData};
+use r1cs_core::{ConstraintSystem, SynthesisError};
+use r1cs_std::prelude::*;
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = "P1: Clone, P2: Clone"))]
+pub struct ComposeCRHGadgetParameters<P1, P2> {
+    pub first: P1,
+    pub second: P2,
+}
+
+/// The in-circuit counterpart of `ComposeCRH<H1, H2>`. The output of
+/// `HG1::OutputGadget::to_bytes` must agree with the native `ToBytes` encoding
+/// of `H1::Output`.
+pub struct ComposeCRHGadget<HG1, HG2> {
+    #[doc(hidden)]
+    _first: PhantomData<HG1>,
+    #[doc(hidden)]
+    _second: PhantomData<HG2>,
+}
+
+impl<H1, H2, HG1, HG2, ConstraintF> FixedLengthCRHGadget<ComposeCRH<H1, H2>, ConstraintF>
+    for ComposeCRHGadget<HG1, HG2>
+where
+    H1: FixedLengthCRH,
+    H2: FixedLengthCRH,
+    HG1: FixedLengthCRHGadget<H1, ConstraintF>,
+    HG2: FixedLengthCRHGadget<H2, ConstraintF>,
+    ConstraintF: Field,
+{
+    type OutputGadget = HG2::OutputGadget;
+    type ParametersGadget =
+        ComposeCRHGadgetParameters<HG1::ParametersGadget, HG2::ParametersGadget>;
+
+    fn check_evaluation_gadget<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        parameters: &Self::ParametersGadget,
+        input: &[UInt8],
+    ) -> Result<Self::OutputGadget, SynthesisError> {
+        let intermediate =
+            HG1::check_evaluation_gadget(cs.ns(|| "first"), &parameters.first, input)?;
+        let intermediate_bytes = intermediate.to_bytes(cs.ns(|| "first to bytes"))?;
+        HG2::check_evaluation_gadget(cs.ns(|| "second"), &parameters.second, &intermediate_bytes)
+    }
+}
+
+impl<H1, H2, P1, P2, ConstraintF> AllocGadget<ComposeCRHParameters<H1, H2>, ConstraintF>
+    for ComposeCRHGadgetParameters<P1, P2>
+where
+    H1: FixedLengthCRH,
+    H2: FixedLengthCRH,
+    P1: AllocGadget<H1::Parameters, ConstraintF>,
+    P2: AllocGadget<H2::Parameters, ConstraintF>,
+    ConstraintF: Field,
+{
+    fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        val: T,
+    ) -> Result<Self, SynthesisError>
+    where
+        T: Borrow<ComposeCRHParameters<H1, H2>>,
+    {
+        let val = val.borrow();
+        Ok(ComposeCRHGadgetParameters {
+            first: P1::alloc_constant(cs.ns(|| "first"), &val.first)?,
+            second: P2::alloc_constant(cs.ns(|| "second"), &val.second)?,
+        })
+    }
+
+    fn alloc<F, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        value_gen: F,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<ComposeCRHParameters<H1, H2>>,
+    {
+        let val = value_gen()?;
+        let val = val.borrow();
+        Ok(ComposeCRHGadgetParameters {
+            first: P1::alloc(cs.ns(|| "first"), || Ok(&val.first))?,
+            second: P2::alloc(cs.ns(|| "second"), || Ok(&val.second))?,
+        })
+    }
+
+    fn alloc_input<F, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        value_gen: F,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<ComposeCRHParameters<H1, H2>>,
+    {
+        let val = value_gen()?;
+        let val = val.borrow();
+        Ok(ComposeCRHGadgetParameters {
+            first: P1::alloc_input(cs.ns(|| "first"), || Ok(&val.first))?,
+            second: P2::alloc_input(cs.ns(|| "second"), || Ok(&val.second))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crh::{
+        compose::{constraints::ComposeCRHGadget, ComposeCRH},
+        pedersen::{constraints::PedersenCRHGadget, PedersenCRH, PedersenWindow},
+        FixedLengthCRH, FixedLengthCRHGadget,
+    };
+    use algebra::{
+        ed_on_bls12_381::{EdwardsAffine as JubJub, Fq as Fr},
+        test_rng, to_bytes,
+    };
+    use r1cs_core::ConstraintSystem;
+    use r1cs_std::{
+        ed_on_bls12_381::EdwardsGadget, prelude::*, test_constraint_system::TestConstraintSystem,
+    };
+    use rand::Rng;
+
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    pub(super) struct Window;
+
+    // 64 bytes, enough for the two coordinates of a point.
+    impl PedersenWindow for Window {
+        const WINDOW_SIZE: usize = 128;
+        const NUM_WINDOWS: usize = 4;
+    }
+
+    type InnerCRH = PedersenCRH<JubJub, Window>;
+    type InnerCRHGadget = PedersenCRHGadget<JubJub, Fr, EdwardsGadget>;
+    type TestCRH = ComposeCRH<InnerCRH, InnerCRH>;
+    type TestCRHGadget = ComposeCRHGadget<InnerCRHGadget, InnerCRHGadget>;
+
+    #[test]
+    fn compose_primitive_gadget_test() {
+        let rng = &mut test_rng();
+        let parameters = TestCRH::setup(rng).unwrap();
+
+        for &len in &[20, 64] {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let input = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let input_bytes = UInt8::alloc_vec(cs.ns(|| "input"), &input).unwrap();
+
+            let primitive_result = TestCRH::evaluate(&parameters, &input).unwrap();
+            let first = InnerCRH::evaluate(&parameters.first, &input).unwrap();
+            assert_eq!(
+                primitive_result,
+                InnerCRH::evaluate(&parameters.second, &to_bytes![first].unwrap()).unwrap()
+            );
+
+            let gadget_parameters =
+                <TestCRHGadget as FixedLengthCRHGadget<TestCRH, Fr>>::ParametersGadget::alloc(
+                    &mut cs.ns(|| "gadget_parameters"),
+                    || Ok(&parameters),
+                )
+                .unwrap();
+            let gadget_result =
+                <TestCRHGadget as FixedLengthCRHGadget<TestCRH, Fr>>::check_evaluation_gadget(
+                    &mut cs.ns(|| "gadget_evaluation"),
+                    &gadget_parameters,
+                    &input_bytes,
+                )
+                .unwrap();
+
+            assert_eq!(primitive_result.x, gadget_result.x.value.unwrap());
+            assert_eq!(primitive_result.y, gadget_result.y.value.unwrap());
+            assert!(cs.is_satisfied());
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{crh::FixedLengthCRH, Error};
+use algebra_core::to_bytes;
+use core::marker::PhantomData;
+use rand::Rng;
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "H1: FixedLengthCRH, H2: FixedLengthCRH"),
+    Default(bound = "H1: FixedLengthCRH, H2: FixedLengthCRH")
+)]
+pub struct ComposeCRHParameters<H1: FixedLengthCRH, H2: FixedLengthCRH> {
+    pub first: H1::Parameters,
+    pub second: H2::Parameters,
+}
+
+/// The composition `H2(H1(x))`: the byte encoding of the output of `H1` is
+/// the input of `H2`, so it must fit in `H2::INPUT_SIZE_BITS`.
+pub struct ComposeCRH<H1: FixedLengthCRH, H2: FixedLengthCRH> {
+    first: PhantomData<H1>,
+    second: PhantomData<H2>,
+}
+
+impl<H1: FixedLengthCRH, H2: FixedLengthCRH> FixedLengthCRH for ComposeCRH<H1, H2> {
+    const INPUT_SIZE_BITS: usize = H1::INPUT_SIZE_BITS;
+    type Output = H2::Output;
+    type Parameters = ComposeCRHParameters<H1, H2>;
+
+    fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+        Ok(ComposeCRHParameters {
+            first: H1::setup(rng)?,
+            second: H2::setup(rng)?,
+        })
+    }
+
+    fn evaluate(parameters: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error> {
+        let eval_time = start_timer!(|| "ComposeCRH::Eval");
+        let intermediate = H1::evaluate(&parameters.first, input)?;
+        let result = H2::evaluate(&parameters.second, &to_bytes![intermediate]?)?;
+        end_timer!(eval_time);
+        Ok(result)
+    }
+}
@@ -1,7 +1,7 @@
 use algebra_core::Field;
 use core::fmt::Debug;
 
-use crate::crh::FixedLengthCRH;
+use crate::crh::{FixedLengthCRH, VariableLengthCRH};
 use r1cs_core::{ConstraintSystem, SynthesisError};
 
 use r1cs_std::prelude::*;
@@ -23,3 +23,23 @@ pub trait FixedLengthCRHGadget<H: FixedLengthCRH, ConstraintF: Field>: Sized {
         input: &[UInt8],
     ) -> Result<Self::OutputGadget, SynthesisError>;
 }
+
+pub trait VariableLengthCRHGadget<H: VariableLengthCRH, ConstraintF: Field>: Sized {
+    type OutputGadget: ConditionalEqGadget<ConstraintF>
+        + EqGadget<ConstraintF>
+        + ToBytesGadget<ConstraintF>
+        + CondSelectGadget<ConstraintF>
+        + AllocGadget<H::Output, ConstraintF>
+        + Debug
+        + Clone
+        + Sized;
+    type ParametersGadget: AllocGadget<H::Parameters, ConstraintF> + Clone;
+
+    /// The length of `input` must be known when generating constraints, but
+    /// is otherwise unrestricted.
+    fn check_evaluation_gadget<CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        parameters: &Self::ParametersGadget,
+        input: &[UInt8],
+    ) -> Result<Self::OutputGadget, SynthesisError>;
+}
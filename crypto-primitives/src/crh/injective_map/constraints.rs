@@ -45,6 +45,10 @@ pub trait InjectiveMapGadget<
     ) -> Result<Self::OutputGadget, SynthesisError>;
 }
 
+/// Maps a twisted Edwards point to its `x`-coordinate. The point gadget
+/// always holds affine coordinates, including when it stands for a
+/// `TEProjective` value (which is normalized when allocated), so the output
+/// is canonical without any further constraints.
 pub struct TECompressorGadget;
 
 impl<ConstraintF, P>
@@ -128,3 +132,60 @@ where
         IG::evaluate_map(cs.ns(|| "InjectiveMap"), &result)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::crh::injective_map::{
+        constraints::{InjectiveMapGadget, TECompressorGadget},
+        InjectiveMap, TECompressor,
+    };
+    use algebra::{
+        ed_on_bls12_381::{EdwardsProjective as JubJub, Fq},
+        test_rng, UniformRand,
+    };
+    use r1cs_core::ConstraintSystem;
+    use r1cs_std::{
+        ed_on_bls12_381::EdwardsGadget, prelude::*, test_constraint_system::TestConstraintSystem,
+    };
+
+    #[test]
+    fn te_compressor_projective_test() {
+        let rng = &mut test_rng();
+        let mut cs = TestConstraintSystem::<Fq>::new();
+
+        let point = JubJub::rand(rng);
+        // The same point with every projective coordinate scaled by `lambda`.
+        let lambda = Fq::rand(rng);
+        let rescaled = JubJub::new(
+            point.x * &lambda,
+            point.y * &lambda,
+            point.t * &lambda,
+            point.z * &lambda,
+        );
+        assert_eq!(point, rescaled);
+
+        let mut outputs = Vec::new();
+        for (i, ge) in [point, rescaled].iter().enumerate() {
+            let ge_gadget = <EdwardsGadget as AllocGadget<JubJub, Fq>>::alloc(
+                cs.ns(|| format!("alloc {}", i)),
+                || Ok(ge),
+            )
+            .unwrap();
+            let output =
+                <TECompressorGadget as InjectiveMapGadget<
+                    JubJub,
+                    TECompressor,
+                    Fq,
+                    EdwardsGadget,
+                >>::evaluate_map(cs.ns(|| format!("compress {}", i)), &ge_gadget)
+                .unwrap();
+            outputs.push(output.get_value().unwrap());
+        }
+        assert_eq!(outputs[0], outputs[1]);
+        assert_eq!(
+            outputs[0],
+            <TECompressor as InjectiveMap<JubJub>>::injective_map(&point).unwrap()
+        );
+        assert!(cs.is_satisfied());
+    }
+}
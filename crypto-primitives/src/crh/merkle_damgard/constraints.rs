@@ -0,0 +1,122 @@
+use crate::crh::{
+    merkle_damgard::MerkleDamgard, FixedLengthCRH, FixedLengthCRHGadget, VariableLengthCRHGadget,
+};
+use algebra_core::{to_bytes, Field};
+use core::marker::PhantomData;
+use r1cs_core::{ConstraintSystem, SynthesisError};
+use r1cs_std::prelude::*;
+
+/// The in-circuit counterpart of `MerkleDamgard<H>`. The output of
+/// `HG::OutputGadget::to_bytes` must agree with the native `ToBytes` encoding
+/// of `H::Output`.
+pub struct MerkleDamgardGadget<H, HG, ConstraintF>
+where
+    H: FixedLengthCRH,
+    HG: FixedLengthCRHGadget<H, ConstraintF>,
+    ConstraintF: Field,
+{
+    _crh: PhantomData<H>,
+    _crh_gadget: PhantomData<HG>,
+    _engine: PhantomData<ConstraintF>,
+}
+
+impl<H, HG, ConstraintF> VariableLengthCRHGadget<MerkleDamgard<H>, ConstraintF>
+    for MerkleDamgardGadget<H, HG, ConstraintF>
+where
+    H: FixedLengthCRH,
+    HG: FixedLengthCRHGadget<H, ConstraintF>,
+    ConstraintF: Field,
+{
+    type OutputGadget = HG::OutputGadget;
+    type ParametersGadget = HG::ParametersGadget;
+
+    fn check_evaluation_gadget<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        parameters: &Self::ParametersGadget,
+        input: &[UInt8],
+    ) -> Result<Self::OutputGadget, SynthesisError> {
+        let block_size = MerkleDamgard::<H>::block_size();
+        let mut padded_input = input.to_vec();
+        padded_input.resize(
+            (input.len() + block_size - 1) / block_size * block_size,
+            UInt8::constant(0u8),
+        );
+        padded_input.extend(UInt8::constant_vec(&MerkleDamgard::<H>::length_block(
+            input.len(),
+        )));
+
+        let mut state: Option<HG::OutputGadget> = None;
+        for (i, block) in padded_input.chunks(block_size).enumerate() {
+            let mut cs = cs.ns(|| format!("block {}", i));
+            let mut h_input = match &state {
+                Some(state) => state.to_bytes(cs.ns(|| "chaining value"))?,
+                None => UInt8::constant_vec(&to_bytes![H::Output::default()].unwrap()),
+            };
+            h_input.extend_from_slice(block);
+            state = Some(HG::check_evaluation_gadget(
+                cs.ns(|| "compress"),
+                parameters,
+                &h_input,
+            )?);
+        }
+
+        // There is always at least the length block.
+        Ok(state.unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crh::{
+        blake2s::{constraints::Blake2sCRHGadget, Blake2sCRH},
+        merkle_damgard::{constraints::MerkleDamgardGadget, MerkleDamgard},
+        VariableLengthCRH, VariableLengthCRHGadget,
+    };
+    use algebra::{ed_on_bls12_381::Fq as Fr, test_rng};
+    use r1cs_core::ConstraintSystem;
+    use r1cs_std::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use rand::Rng;
+
+    type TestCRH = MerkleDamgard<Blake2sCRH>;
+    type TestCRHGadget = MerkleDamgardGadget<Blake2sCRH, Blake2sCRHGadget, Fr>;
+
+    #[test]
+    fn merkle_damgard_primitive_gadget_test() {
+        let rng = &mut test_rng();
+        let parameters = TestCRH::setup(rng).unwrap();
+        assert_eq!(TestCRH::block_size(), 32);
+
+        // Lengths on either side of one and two blocks, and the empty input.
+        for &len in &[0, 1, 31, 32, 33, 63, 64, 65] {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let input = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let input_bytes = UInt8::alloc_vec(cs.ns(|| "input"), &input).unwrap();
+
+            let primitive_result = TestCRH::evaluate(&parameters, &input).unwrap();
+
+            let gadget_parameters =
+                <TestCRHGadget as VariableLengthCRHGadget<TestCRH, Fr>>::ParametersGadget::alloc(
+                    &mut cs.ns(|| "gadget_parameters"),
+                    || Ok(&parameters),
+                )
+                .unwrap();
+            let gadget_result =
+                <TestCRHGadget as VariableLengthCRHGadget<TestCRH, Fr>>::check_evaluation_gadget(
+                    &mut cs.ns(|| "gadget_evaluation"),
+                    &gadget_parameters,
+                    &input_bytes,
+                )
+                .unwrap();
+
+            for i in 0..32 {
+                assert_eq!(
+                    primitive_result[i],
+                    gadget_result.0[i].get_value().unwrap(),
+                    "length {}",
+                    len
+                );
+            }
+            assert!(cs.is_satisfied());
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{
+    crh::{FixedLengthCRH, VariableLengthCRH},
+    Error, Vec,
+};
+use algebra_core::to_bytes;
+use core::marker::PhantomData;
+use rand::Rng;
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// The Merkle-Damgård construction over the compression function `H`. Each
+/// input to `H` is the byte encoding of the previous output (or of the
+/// default output, for the first block) followed by a block of the padded
+/// message. The message is padded with zeros to a whole number of blocks, and
+/// then followed by a block encoding its length in bits.
+pub struct MerkleDamgard<H: FixedLengthCRH> {
+    crh: PhantomData<H>,
+}
+
+impl<H: FixedLengthCRH> MerkleDamgard<H> {
+    /// The length of the byte encoding of `H::Output`, which is assumed to
+    /// be the same for every output.
+    pub fn chaining_value_size() -> usize {
+        to_bytes![H::Output::default()].unwrap().len()
+    }
+
+    /// The number of message bytes compressed by each call to `H`.
+    pub fn block_size() -> usize {
+        let input_size = H::INPUT_SIZE_BITS / 8;
+        let chaining_value_size = Self::chaining_value_size();
+        assert!(
+            input_size > chaining_value_size,
+            "the input of the compression function must be larger than its output"
+        );
+        input_size - chaining_value_size
+    }
+
+    /// The encoding of the message length, as a 64-bit little-endian integer
+    /// counting bits, padded with zeros to a block.
+    pub fn length_block(input_len: usize) -> Vec<u8> {
+        let mut block = (input_len as u64 * 8).to_le_bytes().to_vec();
+        block.resize(Self::block_size(), 0u8);
+        block
+    }
+}
+
+impl<H: FixedLengthCRH> VariableLengthCRH for MerkleDamgard<H> {
+    type Output = H::Output;
+    type Parameters = H::Parameters;
+
+    fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+        H::setup(rng)
+    }
+
+    fn evaluate(parameters: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error> {
+        let eval_time = start_timer!(|| "MerkleDamgard::Eval");
+
+        let block_size = Self::block_size();
+        let mut padded_input = input.to_vec();
+        padded_input.resize(
+            (input.len() + block_size - 1) / block_size * block_size,
+            0u8,
+        );
+        padded_input.extend(Self::length_block(input.len()));
+
+        let mut state = H::Output::default();
+        for block in padded_input.chunks(block_size) {
+            let mut h_input = to_bytes![state]?;
+            h_input.extend_from_slice(block);
+            state = H::evaluate(parameters, &h_input)?;
+        }
+
+        end_timer!(eval_time);
+
+        Ok(state)
+    }
+}
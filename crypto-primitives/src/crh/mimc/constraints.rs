@@ -0,0 +1,200 @@
+use crate::{
+    crh::{
+        mimc::{MiMCCRH, MiMCParameters, MiMCRoundParams},
+        FixedLengthCRHGadget,
+    },
+    Vec,
+};
+use algebra_core::PrimeField;
+use r1cs_core::{ConstraintSystem, SynthesisError};
+use r1cs_std::{fields::fp::FpGadget, prelude::*};
+
+use core::{borrow::Borrow, marker::PhantomData};
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = "F: PrimeField"))]
+pub struct MiMCCRHGadgetParameters<F: PrimeField> {
+    params: MiMCParameters<F>,
+}
+
+pub struct MiMCCRHGadget<F: PrimeField> {
+    #[doc(hidden)]
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField> MiMCCRHGadget<F> {
+    /// The in-circuit counterpart of `MiMCCRH::permute`. Each round costs as
+    /// many constraints as `pow_by_constant` with `P::EXPONENT`, which is 2
+    /// for an exponent of 3.
+    pub fn permute<CS: ConstraintSystem<F>, P: MiMCRoundParams>(
+        mut cs: CS,
+        parameters: &MiMCParameters<F>,
+        state: &mut (FpGadget<F>, FpGadget<F>),
+    ) -> Result<(), SynthesisError> {
+        for (i, c) in parameters.round_constants.iter().enumerate() {
+            let mut cs = cs.ns(|| format!("round {}", i));
+            let t = state
+                .0
+                .add_constant(cs.ns(|| "add round constant"), c)?
+                .pow_by_constant(cs.ns(|| "pow"), &[P::EXPONENT])?;
+            let l = state.1.add(cs.ns(|| "add to right half"), &t)?;
+            state.1 = core::mem::replace(&mut state.0, l);
+        }
+        Ok(())
+    }
+}
+
+impl<F, P> FixedLengthCRHGadget<MiMCCRH<F, P>, F> for MiMCCRHGadget<F>
+where
+    F: PrimeField,
+    P: MiMCRoundParams,
+{
+    type OutputGadget = FpGadget<F>;
+    type ParametersGadget = MiMCCRHGadgetParameters<F>;
+
+    fn check_evaluation_gadget<CS: ConstraintSystem<F>>(
+        mut cs: CS,
+        parameters: &Self::ParametersGadget,
+        input: &[UInt8],
+    ) -> Result<Self::OutputGadget, SynthesisError> {
+        assert!(input.len() * 8 <= P::INPUT_SIZE_BITS);
+        // Pad the input if it is not the current length.
+        let mut padded_input = input.to_vec();
+        padded_input.resize(P::INPUT_SIZE_BITS / 8, UInt8::constant(0u8));
+
+        let mut state = (
+            FpGadget::zero(cs.ns(|| "initial left"))?,
+            FpGadget::zero(cs.ns(|| "initial right"))?,
+        );
+        let chunks = padded_input.chunks(MiMCCRH::<F, P>::bytes_per_element());
+        for (i, chunk) in chunks.enumerate() {
+            let mut cs = cs.ns(|| format!("absorb {}", i));
+            let bits = chunk
+                .iter()
+                .flat_map(UInt8::into_bits_le)
+                .collect::<Vec<_>>();
+            let element = FpGadget::from_bits_le(cs.ns(|| "pack input"), &bits)?;
+            state.0.add_in_place(cs.ns(|| "add input"), &element)?;
+            Self::permute::<_, P>(cs.ns(|| "permute"), &parameters.params, &mut state)?;
+        }
+
+        Ok(state.0)
+    }
+}
+
+impl<F: PrimeField> AllocGadget<MiMCParameters<F>, F> for MiMCCRHGadgetParameters<F> {
+    fn alloc_constant<T, CS: ConstraintSystem<F>>(_cs: CS, val: T) -> Result<Self, SynthesisError>
+    where
+        T: Borrow<MiMCParameters<F>>,
+    {
+        let params = val.borrow().clone();
+        Ok(MiMCCRHGadgetParameters { params })
+    }
+
+    fn alloc<FN, T, CS: ConstraintSystem<F>>(cs: CS, value_gen: FN) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<MiMCParameters<F>>,
+    {
+        let params = value_gen()?.borrow().clone();
+        Self::alloc_constant(cs, params)
+    }
+
+    fn alloc_input<FN, T, CS: ConstraintSystem<F>>(
+        cs: CS,
+        value_gen: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<MiMCParameters<F>>,
+    {
+        let params = value_gen()?.borrow().clone();
+        Self::alloc_constant(cs, params)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crh::{
+        mimc::{constraints::MiMCCRHGadget, MiMCCRH, MiMCRoundParams},
+        FixedLengthCRH, FixedLengthCRHGadget,
+    };
+    use algebra::{bls12_377::Fr, test_rng, UniformRand};
+    use r1cs_core::ConstraintSystem;
+    use r1cs_std::{
+        fields::fp::FpGadget, prelude::*, test_constraint_system::TestConstraintSystem,
+    };
+    use rand::Rng;
+
+    type TestCRH = MiMCCRH<Fr, RoundParams>;
+    type TestCRHGadget = MiMCCRHGadget<Fr>;
+
+    #[derive(Clone)]
+    struct RoundParams;
+
+    impl MiMCRoundParams for RoundParams {
+        const ROUNDS: usize = 322;
+        const EXPONENT: u64 = 3;
+        // Enough for two field elements, as in a Merkle tree.
+        const INPUT_SIZE_BITS: usize = 512;
+    }
+
+    #[test]
+    fn crh_primitive_gadget_test() {
+        let rng = &mut test_rng();
+        let parameters = TestCRH::setup(rng).unwrap();
+
+        // A full-length input, and one that needs padding.
+        for (i, &len) in [64, 20].iter().enumerate() {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let input = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let input_bytes = UInt8::alloc_vec(cs.ns(|| "input"), &input).unwrap();
+
+            let primitive_result = TestCRH::evaluate(&parameters, &input).unwrap();
+
+            let gadget_parameters =
+                <TestCRHGadget as FixedLengthCRHGadget<TestCRH, Fr>>::ParametersGadget::alloc(
+                    &mut cs.ns(|| "gadget_parameters"),
+                    || Ok(&parameters),
+                )
+                .unwrap();
+            let gadget_result =
+                <TestCRHGadget as FixedLengthCRHGadget<TestCRH, Fr>>::check_evaluation_gadget(
+                    &mut cs.ns(|| "gadget_evaluation"),
+                    &gadget_parameters,
+                    &input_bytes,
+                )
+                .unwrap();
+
+            println!("input {}: {} constraints", i, cs.num_constraints());
+            assert_eq!(gadget_result.get_value().unwrap(), primitive_result);
+            assert!(cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn permute_constraints_per_round_test() {
+        let rng = &mut test_rng();
+        let parameters = TestCRH::setup(rng).unwrap();
+
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let mut native_state = (Fr::rand(rng), Fr::rand(rng));
+        let mut state = (
+            FpGadget::alloc(cs.ns(|| "left"), || Ok(native_state.0)).unwrap(),
+            FpGadget::alloc(cs.ns(|| "right"), || Ok(native_state.1)).unwrap(),
+        );
+        let num_constraints = cs.num_constraints();
+        TestCRHGadget::permute::<_, RoundParams>(cs.ns(|| "permute"), &parameters, &mut state)
+            .unwrap();
+        TestCRH::permute(&parameters, &mut native_state);
+
+        // One squaring and one multiplication for `x^3`.
+        assert_eq!(
+            cs.num_constraints() - num_constraints,
+            2 * RoundParams::ROUNDS
+        );
+        assert_eq!(state.0.get_value().unwrap(), native_state.0);
+        assert_eq!(state.1.get_value().unwrap(), native_state.1);
+        assert!(cs.is_satisfied());
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{crh::FixedLengthCRH, Error, Vec};
+use algebra_core::{FpParameters, PrimeField, UniformRand};
+use core::marker::PhantomData;
+use rand::Rng;
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+pub trait MiMCRoundParams: Clone {
+    const ROUNDS: usize;
+    /// The exponent of the round function `x -> (x + c_i)^EXPONENT`. Since
+    /// the rounds form a Feistel network, this need not be a permutation of
+    /// the field, so the cheapest choice is 3.
+    const EXPONENT: u64;
+    const INPUT_SIZE_BITS: usize;
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct MiMCParameters<F: PrimeField> {
+    /// `round_constants[i]` is added in round `i`.
+    pub round_constants: Vec<F>,
+}
+
+/// MiMC-2n/n in sponge mode: each input element is added to the left half of
+/// the state, which is then permuted, and the output is the final left half.
+pub struct MiMCCRH<F: PrimeField, P: MiMCRoundParams> {
+    field: PhantomData<F>,
+    round_params: PhantomData<P>,
+}
+
+impl<F: PrimeField, P: MiMCRoundParams> MiMCCRH<F, P> {
+    /// The number of input bytes packed into each field element. This stays
+    /// below the capacity of `F`, so that packing is injective.
+    pub fn bytes_per_element() -> usize {
+        F::Params::CAPACITY as usize / 8
+    }
+
+    /// Each round maps `(l, r)` to `(r + (l + c_i)^EXPONENT, l)`.
+    pub fn permute(parameters: &MiMCParameters<F>, state: &mut (F, F)) {
+        for c in &parameters.round_constants {
+            let t = (state.0 + c).pow(&[P::EXPONENT]);
+            *state = (state.1 + &t, state.0);
+        }
+    }
+}
+
+impl<F: PrimeField, P: MiMCRoundParams> FixedLengthCRH for MiMCCRH<F, P> {
+    const INPUT_SIZE_BITS: usize = P::INPUT_SIZE_BITS;
+    type Output = F;
+    type Parameters = MiMCParameters<F>;
+
+    fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+        let time = start_timer!(|| format!("MiMCCRH::Setup: {} rounds", P::ROUNDS));
+        let round_constants = (0..P::ROUNDS).map(|_| F::rand(rng)).collect();
+        end_timer!(time);
+        Ok(MiMCParameters { round_constants })
+    }
+
+    fn evaluate(parameters: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error> {
+        let eval_time = start_timer!(|| "MiMCCRH::Eval");
+
+        if input.len() * 8 > P::INPUT_SIZE_BITS {
+            panic!(
+                "incorrect input length {:?} for input size {:?}",
+                input.len(),
+                P::INPUT_SIZE_BITS
+            );
+        }
+
+        // Pad the input if it is not the current length.
+        let mut padded_input = input.to_vec();
+        padded_input.resize(P::INPUT_SIZE_BITS / 8, 0u8);
+
+        let mut state = (F::zero(), F::zero());
+        for chunk in padded_input.chunks(Self::bytes_per_element()) {
+            // Pack the chunk into a field element, in little-endian order.
+            let element = chunk.iter().rev().fold(F::zero(), |acc, byte| {
+                acc * &F::from(256u64) + &F::from(*byte)
+            });
+            state.0 += &element;
+            Self::permute(parameters, &mut state);
+        }
+
+        end_timer!(eval_time);
+
+        Ok(state.0)
+    }
+}
@@ -2,9 +2,15 @@ use algebra_core::bytes::ToBytes;
 use core::hash::Hash;
 use rand::Rng;
 
+pub mod blake2s;
 pub mod bowe_hopwood;
+pub mod compose;
 pub mod injective_map;
+pub mod merkle_damgard;
+pub mod mimc;
 pub mod pedersen;
+pub mod poseidon;
+pub mod sha256;
 
 use crate::Error;
 
@@ -21,3 +27,12 @@ pub trait FixedLengthCRH {
     fn setup<R: Rng>(r: &mut R) -> Result<Self::Parameters, Error>;
     fn evaluate(parameters: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error>;
 }
+
+/// A CRH accepting inputs of any length.
+pub trait VariableLengthCRH {
+    type Output: ToBytes + Clone + Eq + Hash + Default;
+    type Parameters: Clone + Default;
+
+    fn setup<R: Rng>(r: &mut R) -> Result<Self::Parameters, Error>;
+    fn evaluate(parameters: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error>;
+}
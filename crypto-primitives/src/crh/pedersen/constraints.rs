@@ -52,22 +52,15 @@ where
         parameters: &Self::ParametersGadget,
         input: &[UInt8],
     ) -> Result<Self::OutputGadget, SynthesisError> {
-        let mut padded_input = input.to_vec();
-        // Pad the input if it is not the current length.
-        if input.len() * 8 < W::WINDOW_SIZE * W::NUM_WINDOWS {
-            let current_length = input.len();
-            for _ in current_length..(W::WINDOW_SIZE * W::NUM_WINDOWS / 8) {
-                padded_input.push(UInt8::constant(0u8));
-            }
+        if input.len() * 8 > W::WINDOW_SIZE * W::NUM_WINDOWS {
+            return Err(SynthesisError::Unsatisfiable);
         }
-        assert_eq!(padded_input.len() * 8, W::WINDOW_SIZE * W::NUM_WINDOWS);
         assert_eq!(parameters.params.generators.len(), W::NUM_WINDOWS);
 
-        // Allocate new variable for the result.
-        let input_in_bits: Vec<_> = padded_input
-            .iter()
-            .flat_map(|byte| byte.into_bits_le())
-            .collect();
+        // Pad the input bits to the capacity, which need not be a whole
+        // number of bytes.
+        let mut input_in_bits: Vec<_> = input.iter().flat_map(|byte| byte.into_bits_le()).collect();
+        input_in_bits.resize(W::WINDOW_SIZE * W::NUM_WINDOWS, Boolean::constant(false));
         let input_in_bits = input_in_bits.chunks(W::WINDOW_SIZE);
         let result =
             GG::precomputed_base_multiscalar_mul(cs, &parameters.params.generators, input_in_bits)?;
@@ -156,9 +149,9 @@ mod test {
     fn generate_input<CS: ConstraintSystem<Fr>, R: Rng>(
         mut cs: CS,
         rng: &mut R,
-    ) -> ([u8; 128], Vec<UInt8>) {
-        let mut input = [1u8; 128];
-        rng.fill_bytes(&mut input);
+        len: usize,
+    ) -> (Vec<u8>, Vec<UInt8>) {
+        let input = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
 
         let mut input_bytes = vec![];
         for (byte_i, input_byte) in input.iter().enumerate() {
@@ -171,38 +164,124 @@ mod test {
     #[test]
     fn crh_primitive_gadget_test() {
         let rng = &mut test_rng();
-        let mut cs = TestConstraintSystem::<Fr>::new();
+        let parameters = TestCRH::setup(rng).unwrap();
 
-        let (input, input_bytes) = generate_input(&mut cs, rng);
-        println!("number of constraints for input: {}", cs.num_constraints());
+        // An input shorter than the capacity, and one that fills it.
+        for &len in &[100, 128] {
+            let mut cs = TestConstraintSystem::<Fr>::new();
 
-        let parameters = TestCRH::setup(rng).unwrap();
-        let primitive_result = TestCRH::evaluate(&parameters, &input).unwrap();
+            let (input, input_bytes) = generate_input(&mut cs, rng, len);
+            println!("number of constraints for input: {}", cs.num_constraints());
+
+            let primitive_result = TestCRH::evaluate(&parameters, &input).unwrap();
+
+            let gadget_parameters =
+                <TestCRHGadget as FixedLengthCRHGadget<TestCRH, Fr>>::ParametersGadget::alloc(
+                    &mut cs.ns(|| "gadget_parameters"),
+                    || Ok(&parameters),
+                )
+                .unwrap();
+            println!(
+                "number of constraints for input + params: {}",
+                cs.num_constraints()
+            );
+
+            let gadget_result =
+                <TestCRHGadget as FixedLengthCRHGadget<TestCRH, Fr>>::check_evaluation_gadget(
+                    &mut cs.ns(|| "gadget_evaluation"),
+                    &gadget_parameters,
+                    &input_bytes,
+                )
+                .unwrap();
+
+            println!("number of constraints total: {}", cs.num_constraints());
+
+            let primitive_result = primitive_result.into_affine();
+            assert_eq!(primitive_result.x, gadget_result.x.value.unwrap());
+            assert_eq!(primitive_result.y, gadget_result.y.value.unwrap());
+            assert!(cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn crh_unaligned_window_test() {
+        // 4 windows of 63 bits hold 31.5 bytes.
+        #[derive(Clone, PartialEq, Eq, Hash)]
+        struct UnalignedWindow;
+
+        impl PedersenWindow for UnalignedWindow {
+            const WINDOW_SIZE: usize = 63;
+            const NUM_WINDOWS: usize = 4;
+        }
+
+        type UnalignedCRH = PedersenCRH<JubJub, UnalignedWindow>;
+
+        let rng = &mut test_rng();
+        let parameters = UnalignedCRH::setup(rng).unwrap();
+
+        for &len in &[20, 31] {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+
+            let (input, input_bytes) = generate_input(&mut cs, rng, len);
+            let primitive_result = UnalignedCRH::evaluate(&parameters, &input).unwrap();
+
+            let gadget_parameters =
+                <TestCRHGadget as FixedLengthCRHGadget<UnalignedCRH, Fr>>::ParametersGadget::alloc(
+                    &mut cs.ns(|| "gadget_parameters"),
+                    || Ok(&parameters),
+                )
+                .unwrap();
+            let gadget_result =
+                <TestCRHGadget as FixedLengthCRHGadget<UnalignedCRH, Fr>>::check_evaluation_gadget(
+                    &mut cs.ns(|| "gadget_evaluation"),
+                    &gadget_parameters,
+                    &input_bytes,
+                )
+                .unwrap();
 
+            let primitive_result = primitive_result.into_affine();
+            assert_eq!(primitive_result.x, gadget_result.x.value.unwrap());
+            assert_eq!(primitive_result.y, gadget_result.y.value.unwrap());
+            assert!(cs.is_satisfied());
+        }
+
+        // 32 bytes do not fit.
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let (_, input_bytes) = generate_input(&mut cs, rng, 32);
         let gadget_parameters =
-            <TestCRHGadget as FixedLengthCRHGadget<TestCRH, Fr>>::ParametersGadget::alloc(
+            <TestCRHGadget as FixedLengthCRHGadget<UnalignedCRH, Fr>>::ParametersGadget::alloc(
                 &mut cs.ns(|| "gadget_parameters"),
                 || Ok(&parameters),
             )
             .unwrap();
-        println!(
-            "number of constraints for input + params: {}",
-            cs.num_constraints()
-        );
-
-        let gadget_result =
-            <TestCRHGadget as FixedLengthCRHGadget<TestCRH, Fr>>::check_evaluation_gadget(
+        let result =
+            <TestCRHGadget as FixedLengthCRHGadget<UnalignedCRH, Fr>>::check_evaluation_gadget(
                 &mut cs.ns(|| "gadget_evaluation"),
                 &gadget_parameters,
                 &input_bytes,
+            );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn crh_gadget_over_capacity_test() {
+        let rng = &mut test_rng();
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let (_, input_bytes) = generate_input(&mut cs, rng, 129);
+        let parameters = TestCRH::setup(rng).unwrap();
+        let gadget_parameters =
+            <TestCRHGadget as FixedLengthCRHGadget<TestCRH, Fr>>::ParametersGadget::alloc(
+                &mut cs.ns(|| "gadget_parameters"),
+                || Ok(&parameters),
             )
             .unwrap();
 
-        println!("number of constraints total: {}", cs.num_constraints());
-
-        let primitive_result = primitive_result.into_affine();
-        assert_eq!(primitive_result.x, gadget_result.x.value.unwrap());
-        assert_eq!(primitive_result.y, gadget_result.y.value.unwrap());
-        assert!(cs.is_satisfied());
+        let result = <TestCRHGadget as FixedLengthCRHGadget<TestCRH, Fr>>::check_evaluation_gadget(
+            &mut cs.ns(|| "gadget_evaluation"),
+            &gadget_parameters,
+            &input_bytes,
+        );
+        assert!(result.is_err());
     }
 }
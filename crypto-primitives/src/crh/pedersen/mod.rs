@@ -78,18 +78,6 @@ impl<G: Group, W: PedersenWindow> FixedLengthCRH for PedersenCRH<G, W> {
             );
         }
 
-        let mut padded_input = Vec::with_capacity(input.len());
-        let mut input = input;
-        // Pad the input if it is not the current length.
-        if (input.len() * 8) < W::WINDOW_SIZE * W::NUM_WINDOWS {
-            let current_length = input.len();
-            padded_input.extend_from_slice(input);
-            for _ in current_length..((W::WINDOW_SIZE * W::NUM_WINDOWS) / 8) {
-                padded_input.push(0u8);
-            }
-            input = padded_input.as_slice();
-        }
-
         assert_eq!(
             parameters.generators.len(),
             W::NUM_WINDOWS,
@@ -100,8 +88,12 @@ impl<G: Group, W: PedersenWindow> FixedLengthCRH for PedersenCRH<G, W> {
             W::NUM_WINDOWS
         );
 
+        // Pad the input bits to the capacity, which need not be a whole
+        // number of bytes.
+        let mut bits = bytes_to_bits(input);
+        bits.resize(W::WINDOW_SIZE * W::NUM_WINDOWS, false);
+
         // Compute sum of h_i^{m_i} for all i.
-        let bits = bytes_to_bits(input);
         let result = cfg_chunks!(bits, W::WINDOW_SIZE)
             .zip(&parameters.generators)
             .map(|(bits, generator_powers)| {
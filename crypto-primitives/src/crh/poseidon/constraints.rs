@@ -0,0 +1,197 @@
+use crate::{
+    crh::{
+        poseidon::{PoseidonCRH, PoseidonParameters, PoseidonRoundParams},
+        FixedLengthCRHGadget,
+    },
+    Vec,
+};
+use algebra_core::PrimeField;
+use r1cs_core::{ConstraintSystem, SynthesisError};
+use r1cs_std::{fields::fp::FpGadget, prelude::*};
+
+use core::{borrow::Borrow, marker::PhantomData};
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = "F: PrimeField"))]
+pub struct PoseidonCRHGadgetParameters<F: PrimeField> {
+    params: PoseidonParameters<F>,
+}
+
+pub struct PoseidonCRHGadget<F: PrimeField> {
+    #[doc(hidden)]
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField> PoseidonCRHGadget<F> {
+    /// The in-circuit counterpart of `PoseidonCRH::permute`.
+    pub fn permute<CS: ConstraintSystem<F>, P: PoseidonRoundParams>(
+        mut cs: CS,
+        parameters: &PoseidonParameters<F>,
+        state: &mut [FpGadget<F>],
+    ) -> Result<(), SynthesisError> {
+        for (i, round_constants) in parameters.round_constants.iter().enumerate() {
+            let mut cs = cs.ns(|| format!("round {}", i));
+            for (j, (s, c)) in state.iter_mut().zip(round_constants).enumerate() {
+                s.add_constant_in_place(cs.ns(|| format!("add round constant {}", j)), c)?;
+            }
+            let num_sboxes = if PoseidonCRH::<F, P>::is_full_round(i) {
+                state.len()
+            } else {
+                1
+            };
+            for (j, s) in state[..num_sboxes].iter_mut().enumerate() {
+                *s = s.pow_by_constant(cs.ns(|| format!("sbox {}", j)), &[P::SBOX])?;
+            }
+            let mut mixed = Vec::with_capacity(state.len());
+            for (j, row) in parameters.mds.iter().enumerate() {
+                let mut cs = cs.ns(|| format!("mix {}", j));
+                let mut acc = FpGadget::zero(cs.ns(|| "zero"))?;
+                for (k, (m, s)) in row.iter().zip(state.iter()).enumerate() {
+                    let term = s.mul_by_constant(cs.ns(|| format!("term {}", k)), m)?;
+                    acc.add_in_place(cs.ns(|| format!("add term {}", k)), &term)?;
+                }
+                mixed.push(acc);
+            }
+            state.clone_from_slice(&mixed);
+        }
+        Ok(())
+    }
+}
+
+impl<F, P> FixedLengthCRHGadget<PoseidonCRH<F, P>, F> for PoseidonCRHGadget<F>
+where
+    F: PrimeField,
+    P: PoseidonRoundParams,
+{
+    type OutputGadget = FpGadget<F>;
+    type ParametersGadget = PoseidonCRHGadgetParameters<F>;
+
+    fn check_evaluation_gadget<CS: ConstraintSystem<F>>(
+        mut cs: CS,
+        parameters: &Self::ParametersGadget,
+        input: &[UInt8],
+    ) -> Result<Self::OutputGadget, SynthesisError> {
+        assert!(input.len() * 8 <= P::INPUT_SIZE_BITS);
+        // Pad the input if it is not the current length.
+        let mut padded_input = input.to_vec();
+        padded_input.resize(P::INPUT_SIZE_BITS / 8, UInt8::constant(0u8));
+
+        let mut elements = Vec::new();
+        let chunks = padded_input.chunks(PoseidonCRH::<F, P>::bytes_per_element());
+        for (i, chunk) in chunks.enumerate() {
+            let bits = chunk
+                .iter()
+                .flat_map(UInt8::into_bits_le)
+                .collect::<Vec<_>>();
+            elements.push(FpGadget::from_bits_le(
+                cs.ns(|| format!("pack input {}", i)),
+                &bits,
+            )?);
+        }
+
+        let mut state = Vec::with_capacity(P::WIDTH);
+        for i in 0..P::WIDTH {
+            state.push(FpGadget::zero(cs.ns(|| format!("initial state {}", i)))?);
+        }
+        for (i, chunk) in elements.chunks(P::WIDTH - 1).enumerate() {
+            let mut cs = cs.ns(|| format!("absorb {}", i));
+            for (j, (s, e)) in state[1..].iter_mut().zip(chunk).enumerate() {
+                s.add_in_place(cs.ns(|| format!("add input {}", j)), e)?;
+            }
+            Self::permute::<_, P>(cs.ns(|| "permute"), &parameters.params, &mut state)?;
+        }
+
+        Ok(state.swap_remove(1))
+    }
+}
+
+impl<F: PrimeField> AllocGadget<PoseidonParameters<F>, F> for PoseidonCRHGadgetParameters<F> {
+    fn alloc_constant<T, CS: ConstraintSystem<F>>(_cs: CS, val: T) -> Result<Self, SynthesisError>
+    where
+        T: Borrow<PoseidonParameters<F>>,
+    {
+        let params = val.borrow().clone();
+        Ok(PoseidonCRHGadgetParameters { params })
+    }
+
+    fn alloc<FN, T, CS: ConstraintSystem<F>>(cs: CS, value_gen: FN) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<PoseidonParameters<F>>,
+    {
+        let params = value_gen()?.borrow().clone();
+        Self::alloc_constant(cs, params)
+    }
+
+    fn alloc_input<FN, T, CS: ConstraintSystem<F>>(
+        cs: CS,
+        value_gen: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<PoseidonParameters<F>>,
+    {
+        let params = value_gen()?.borrow().clone();
+        Self::alloc_constant(cs, params)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crh::{
+        poseidon::{constraints::PoseidonCRHGadget, PoseidonCRH, PoseidonRoundParams},
+        FixedLengthCRH, FixedLengthCRHGadget,
+    };
+    use algebra::{bls12_377::Fr, test_rng};
+    use r1cs_core::ConstraintSystem;
+    use r1cs_std::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use rand::Rng;
+
+    type TestCRH = PoseidonCRH<Fr, RoundParams>;
+    type TestCRHGadget = PoseidonCRHGadget<Fr>;
+
+    #[derive(Clone)]
+    struct RoundParams;
+
+    impl PoseidonRoundParams for RoundParams {
+        const WIDTH: usize = 3;
+        const FULL_ROUNDS: usize = 8;
+        const PARTIAL_ROUNDS: usize = 31;
+        const SBOX: u64 = 17;
+        // Enough for two field elements, as in a Merkle tree.
+        const INPUT_SIZE_BITS: usize = 512;
+    }
+
+    #[test]
+    fn crh_primitive_gadget_test() {
+        let rng = &mut test_rng();
+        let parameters = TestCRH::setup(rng).unwrap();
+
+        // A full-length input, and one that needs padding.
+        for (i, &len) in [64, 20].iter().enumerate() {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let input = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let input_bytes = UInt8::alloc_vec(cs.ns(|| "input"), &input).unwrap();
+
+            let primitive_result = TestCRH::evaluate(&parameters, &input).unwrap();
+
+            let gadget_parameters =
+                <TestCRHGadget as FixedLengthCRHGadget<TestCRH, Fr>>::ParametersGadget::alloc(
+                    &mut cs.ns(|| "gadget_parameters"),
+                    || Ok(&parameters),
+                )
+                .unwrap();
+            let gadget_result =
+                <TestCRHGadget as FixedLengthCRHGadget<TestCRH, Fr>>::check_evaluation_gadget(
+                    &mut cs.ns(|| "gadget_evaluation"),
+                    &gadget_parameters,
+                    &input_bytes,
+                )
+                .unwrap();
+
+            println!("input {}: {} constraints", i, cs.num_constraints());
+            assert_eq!(gadget_result.get_value().unwrap(), primitive_result);
+            assert!(cs.is_satisfied());
+        }
+    }
+}
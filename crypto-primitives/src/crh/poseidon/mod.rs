@@ -0,0 +1,144 @@
+use crate::{crh::FixedLengthCRH, Error, Vec};
+use algebra_core::{FpParameters, PrimeField, UniformRand};
+use core::marker::PhantomData;
+use rand::Rng;
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+pub trait PoseidonRoundParams: Clone {
+    /// The number of field elements in the state. One of them is the
+    /// capacity, and the rest absorb the input.
+    const WIDTH: usize;
+    const FULL_ROUNDS: usize;
+    const PARTIAL_ROUNDS: usize;
+    /// The exponent of the S-box `x -> x^SBOX`, which must be coprime to
+    /// `p - 1` for the S-box to be a permutation.
+    const SBOX: u64;
+    const INPUT_SIZE_BITS: usize;
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct PoseidonParameters<F: PrimeField> {
+    /// `round_constants[i]` is added to the state at the start of round `i`.
+    pub round_constants: Vec<Vec<F>>,
+    /// The matrix multiplied into the state at the end of every round.
+    pub mds: Vec<Vec<F>>,
+}
+
+pub struct PoseidonCRH<F: PrimeField, P: PoseidonRoundParams> {
+    field: PhantomData<F>,
+    round_params: PhantomData<P>,
+}
+
+impl<F: PrimeField, P: PoseidonRoundParams> PoseidonCRH<F, P> {
+    /// The number of input bytes packed into each field element. This stays
+    /// below the capacity of `F`, so that packing is injective.
+    pub fn bytes_per_element() -> usize {
+        F::Params::CAPACITY as usize / 8
+    }
+
+    /// Full rounds are split evenly between the beginning and the end of the
+    /// permutation; the partial rounds in between only apply the S-box to the
+    /// first element of the state.
+    pub fn is_full_round(round: usize) -> bool {
+        let half_full_rounds = P::FULL_ROUNDS / 2;
+        round < half_full_rounds || round >= half_full_rounds + P::PARTIAL_ROUNDS
+    }
+
+    pub fn permute(parameters: &PoseidonParameters<F>, state: &mut [F]) {
+        for (i, round_constants) in parameters.round_constants.iter().enumerate() {
+            for (s, c) in state.iter_mut().zip(round_constants) {
+                *s += c;
+            }
+            let num_sboxes = if Self::is_full_round(i) {
+                state.len()
+            } else {
+                1
+            };
+            for s in state[..num_sboxes].iter_mut() {
+                *s = s.pow(&[P::SBOX]);
+            }
+            let mixed = parameters
+                .mds
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .zip(state.iter())
+                        .fold(F::zero(), |acc, (m, s)| acc + &(*m * s))
+                })
+                .collect::<Vec<_>>();
+            state.copy_from_slice(&mixed);
+        }
+    }
+}
+
+impl<F: PrimeField, P: PoseidonRoundParams> FixedLengthCRH for PoseidonCRH<F, P> {
+    const INPUT_SIZE_BITS: usize = P::INPUT_SIZE_BITS;
+    type Output = F;
+    type Parameters = PoseidonParameters<F>;
+
+    fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+        let time = start_timer!(|| format!(
+            "PoseidonCRH::Setup: width {}, {} full and {} partial rounds",
+            P::WIDTH,
+            P::FULL_ROUNDS,
+            P::PARTIAL_ROUNDS
+        ));
+        let round_constants = (0..P::FULL_ROUNDS + P::PARTIAL_ROUNDS)
+            .map(|_| (0..P::WIDTH).map(|_| F::rand(rng)).collect())
+            .collect();
+        // The Cauchy matrix `1 / (x_i + y_j)` with `x_i = i` and
+        // `y_j = WIDTH + j` is MDS, since all the `x_i` and `y_j` are distinct.
+        let mds = (0..P::WIDTH)
+            .map(|i| {
+                (0..P::WIDTH)
+                    .map(|j| F::from((i + P::WIDTH + j) as u64).inverse().unwrap())
+                    .collect()
+            })
+            .collect();
+        end_timer!(time);
+        Ok(PoseidonParameters {
+            round_constants,
+            mds,
+        })
+    }
+
+    fn evaluate(parameters: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error> {
+        let eval_time = start_timer!(|| "PoseidonCRH::Eval");
+
+        if input.len() * 8 > P::INPUT_SIZE_BITS {
+            panic!(
+                "incorrect input length {:?} for input size {:?}",
+                input.len(),
+                P::INPUT_SIZE_BITS
+            );
+        }
+
+        // Pad the input if it is not the current length.
+        let mut padded_input = input.to_vec();
+        padded_input.resize(P::INPUT_SIZE_BITS / 8, 0u8);
+
+        // Pack the input into field elements, in little-endian order.
+        let elements = padded_input
+            .chunks(Self::bytes_per_element())
+            .map(|chunk| {
+                chunk.iter().rev().fold(F::zero(), |acc, byte| {
+                    acc * &F::from(256u64) + &F::from(*byte)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut state = vec![F::zero(); P::WIDTH];
+        for chunk in elements.chunks(P::WIDTH - 1) {
+            for (s, e) in state[1..].iter_mut().zip(chunk) {
+                *s += e;
+            }
+            Self::permute(parameters, &mut state);
+        }
+
+        end_timer!(eval_time);
+
+        Ok(state[1])
+    }
+}
@@ -0,0 +1,314 @@
+use algebra_core::PrimeField;
+use r1cs_core::{ConstraintSystem, SynthesisError};
+
+use crate::Vec;
+use r1cs_std::prelude::*;
+
+// The constants and functions below follow RFC 6234, Section 5.1 and 6.2.
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const H: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const BLOCK_SIZE: usize = 64;
+
+// CH( x, y, z) = (x AND y) XOR ( (NOT x) AND z), computed as
+// z XOR (x AND (y XOR z)).
+fn ch<ConstraintF: PrimeField, CS: ConstraintSystem<ConstraintF>>(
+    mut cs: CS,
+    x: &UInt32,
+    y: &UInt32,
+    z: &UInt32,
+) -> Result<UInt32, SynthesisError> {
+    let mut bits = Vec::with_capacity(32);
+    for (i, ((x, y), z)) in x
+        .to_bits_le()
+        .iter()
+        .zip(y.to_bits_le().iter())
+        .zip(z.to_bits_le().iter())
+        .enumerate()
+    {
+        let mut cs = cs.ns(|| format!("bit {}", i));
+        let y_xor_z = Boolean::xor(cs.ns(|| "y ^ z"), y, z)?;
+        let x_and = Boolean::and(cs.ns(|| "x & (y ^ z)"), x, &y_xor_z)?;
+        bits.push(Boolean::xor(cs.ns(|| "z ^ (x & (y ^ z))"), z, &x_and)?);
+    }
+    Ok(UInt32::from_bits_le(&bits))
+}
+
+// MAJ( x, y, z) = (x AND y) XOR (x AND z) XOR (y AND z), computed as
+// (x AND y) XOR (z AND (x XOR y)).
+fn maj<ConstraintF: PrimeField, CS: ConstraintSystem<ConstraintF>>(
+    mut cs: CS,
+    x: &UInt32,
+    y: &UInt32,
+    z: &UInt32,
+) -> Result<UInt32, SynthesisError> {
+    let mut bits = Vec::with_capacity(32);
+    for (i, ((x, y), z)) in x
+        .to_bits_le()
+        .iter()
+        .zip(y.to_bits_le().iter())
+        .zip(z.to_bits_le().iter())
+        .enumerate()
+    {
+        let mut cs = cs.ns(|| format!("bit {}", i));
+        let x_and_y = Boolean::and(cs.ns(|| "x & y"), x, y)?;
+        let x_xor_y = Boolean::xor(cs.ns(|| "x ^ y"), x, y)?;
+        let z_and = Boolean::and(cs.ns(|| "z & (x ^ y)"), z, &x_xor_y)?;
+        bits.push(Boolean::xor(
+            cs.ns(|| "(x & y) ^ (z & (x ^ y))"),
+            &x_and_y,
+            &z_and,
+        )?);
+    }
+    Ok(UInt32::from_bits_le(&bits))
+}
+
+// Computes ROTR^a(x) XOR ROTR^b(x) XOR op(x), where op is ROTR^c for the
+// BSIG functions and SHR^c for the SSIG functions.
+fn sigma<ConstraintF: PrimeField, CS: ConstraintSystem<ConstraintF>>(
+    mut cs: CS,
+    x: &UInt32,
+    a: usize,
+    b: usize,
+    c: usize,
+    shift: bool,
+) -> Result<UInt32, SynthesisError> {
+    let third = if shift { x.shr(c) } else { x.rotr(c) };
+    x.rotr(a)
+        .xor(cs.ns(|| "first xor"), &x.rotr(b))?
+        .xor(cs.ns(|| "second xor"), &third)
+}
+
+// Interprets 4 bytes as a big-endian word.
+fn word_from_bytes(bytes: &[UInt8]) -> UInt32 {
+    let bits = bytes
+        .iter()
+        .rev()
+        .flat_map(UInt8::into_bits_le)
+        .collect::<Vec<_>>();
+    UInt32::from_bits_le(&bits)
+}
+
+// 6.2.  SHA-224 and SHA-256 Processing
+// Processes the 64-byte `block`, updating the intermediate hash `h`.
+fn sha256_compression<ConstraintF: PrimeField, CS: ConstraintSystem<ConstraintF>>(
+    mut cs: CS,
+    h: &mut [UInt32],
+    block: &[UInt8],
+) -> Result<(), SynthesisError> {
+    assert_eq!(h.len(), 8);
+    assert_eq!(block.len(), BLOCK_SIZE);
+
+    // 1. Prepare the message schedule W.
+    let mut w = block.chunks(4).map(word_from_bytes).collect::<Vec<_>>();
+    for t in 16..64 {
+        let mut cs = cs.ns(|| format!("schedule {}", t));
+        let s0 = sigma(cs.ns(|| "SSIG0"), &w[t - 15], 7, 18, 3, true)?;
+        let s1 = sigma(cs.ns(|| "SSIG1"), &w[t - 2], 17, 19, 10, true)?;
+        let w_t = UInt32::addmany(
+            cs.ns(|| "W[t]"),
+            &[s1, w[t - 7].clone(), s0, w[t - 16].clone()],
+        )?;
+        w.push(w_t);
+    }
+
+    // 2. Initialize the working variables.
+    let mut v = h.to_vec();
+
+    // 3. Perform the main hash computation.
+    for t in 0..64 {
+        let mut cs = cs.ns(|| format!("round {}", t));
+        let s1 = sigma(cs.ns(|| "BSIG1"), &v[4], 6, 11, 25, false)?;
+        let ch = ch(cs.ns(|| "CH"), &v[4], &v[5], &v[6])?;
+        let s0 = sigma(cs.ns(|| "BSIG0"), &v[0], 2, 13, 22, false)?;
+        let maj = maj(cs.ns(|| "MAJ"), &v[0], &v[1], &v[2])?;
+
+        // T1 = h + BSIG1(e) + CH(e,f,g) + Kt + Wt and T2 = BSIG0(a) + MAJ(a,b,c)
+        // are folded into the two additions that use them.
+        let t1 = [v[7].clone(), s1, ch, UInt32::constant(K[t]), w[t].clone()];
+        let e = UInt32::addmany(cs.ns(|| "d + T1"), &[&[v[3].clone()], &t1[..]].concat())?;
+        let a = UInt32::addmany(cs.ns(|| "T1 + T2"), &[&t1[..], &[s0, maj]].concat())?;
+
+        v.pop();
+        v.insert(0, a);
+        v[4] = e;
+    }
+
+    // 4. Compute the intermediate hash value.
+    for (i, (h, v)) in h.iter_mut().zip(v).enumerate() {
+        *h = UInt32::addmany(cs.ns(|| format!("H[{}]", i)), &[h.clone(), v])?;
+    }
+
+    Ok(())
+}
+
+/// An in-circuit SHA-256 hasher. As with a native hasher, the message can be
+/// passed to `update` in any number of pieces, and the digest is obtained
+/// from `finalize`. The length of the message must be known when generating
+/// constraints.
+#[derive(Clone, Debug)]
+pub struct Sha256Gadget {
+    state: Vec<UInt32>,
+    /// The bytes not yet compressed, of which there are fewer than a block.
+    pending: Vec<UInt8>,
+    /// The number of bytes passed to `update` so far.
+    length: u64,
+    num_blocks: usize,
+}
+
+impl Default for Sha256Gadget {
+    fn default() -> Self {
+        Self {
+            state: H.iter().map(|&h| UInt32::constant(h)).collect(),
+            pending: Vec::with_capacity(BLOCK_SIZE),
+            length: 0,
+            num_blocks: 0,
+        }
+    }
+}
+
+impl Sha256Gadget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update<ConstraintF: PrimeField, CS: ConstraintSystem<ConstraintF>>(
+        &mut self,
+        mut cs: CS,
+        data: &[UInt8],
+    ) -> Result<(), SynthesisError> {
+        self.length += data.len() as u64;
+        self.pending.extend_from_slice(data);
+        while self.pending.len() >= BLOCK_SIZE {
+            let block = self.pending.drain(..BLOCK_SIZE).collect::<Vec<_>>();
+            sha256_compression(
+                cs.ns(|| format!("block {}", self.num_blocks)),
+                &mut self.state,
+                &block,
+            )?;
+            self.num_blocks += 1;
+        }
+        Ok(())
+    }
+
+    /// Pads the message and outputs its 32-byte digest.
+    pub fn finalize<ConstraintF: PrimeField, CS: ConstraintSystem<ConstraintF>>(
+        mut self,
+        mut cs: CS,
+    ) -> Result<Vec<UInt8>, SynthesisError> {
+        // 4.1.  SHA-224 and SHA-256: append a one bit, then zeros until the
+        // length is 56 mod 64 bytes, then the message length in bits as a
+        // 64-bit big-endian integer.
+        let bit_length = self.length * 8;
+        let num_zeros = (BLOCK_SIZE - 1 - 8 + BLOCK_SIZE - self.pending.len()) % BLOCK_SIZE;
+        let mut padding = Vec::with_capacity(1 + num_zeros + 8);
+        padding.push(UInt8::constant(0x80));
+        padding.extend((0..num_zeros).map(|_| UInt8::constant(0)));
+        padding.extend(bit_length.to_be_bytes().iter().map(|&b| UInt8::constant(b)));
+        self.update(cs.ns(|| "padding"), &padding)?;
+        assert!(self.pending.is_empty());
+
+        let mut digest = Vec::with_capacity(32);
+        for h in &self.state {
+            let bits = h.to_bits_le();
+            digest.extend(bits.chunks(8).rev().map(UInt8::from_bits_le));
+        }
+        Ok(digest)
+    }
+
+    /// Hashes `data` in one go.
+    pub fn digest<ConstraintF: PrimeField, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        data: &[UInt8],
+    ) -> Result<Vec<UInt8>, SynthesisError> {
+        let mut sha256 = Self::new();
+        sha256.update(cs.ns(|| "update"), data)?;
+        sha256.finalize(cs.ns(|| "finalize"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Sha256Gadget;
+    use algebra::{bls12_377::Fr, test_rng};
+    use digest::Digest;
+    use r1cs_core::ConstraintSystem;
+    use r1cs_std::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use rand::Rng;
+    use sha2::Sha256;
+
+    fn digest_value(digest: &[UInt8]) -> Vec<u8> {
+        digest.iter().map(|b| b.get_value().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_sha256_rfc6234_vectors() {
+        let vectors: [(&[u8], &str); 3] = [
+            (
+                b"",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                b"abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ];
+        for (i, (input, expected)) in vectors.iter().enumerate() {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let input_bytes =
+                UInt8::alloc_vec(cs.ns(|| format!("input {}", i)), &input[..]).unwrap();
+            let digest =
+                Sha256Gadget::digest(cs.ns(|| format!("digest {}", i)), &input_bytes).unwrap();
+            let hex = digest_value(&digest)
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>();
+            assert_eq!(&hex, expected);
+            assert!(cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn test_sha256_multi_block() {
+        let rng = &mut test_rng();
+        for &len in &[55, 56, 64, 119, 200] {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let input = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let input_bytes = UInt8::alloc_vec(cs.ns(|| "input"), &input).unwrap();
+
+            // Feed the input in uneven pieces.
+            let mut sha256 = Sha256Gadget::new();
+            for (i, piece) in input_bytes.chunks(37).enumerate() {
+                sha256
+                    .update(cs.ns(|| format!("update {}", i)), piece)
+                    .unwrap();
+            }
+            let digest = sha256.finalize(cs.ns(|| "finalize")).unwrap();
+
+            assert_eq!(
+                digest_value(&digest),
+                Sha256::digest(&input).as_slice(),
+                "length {}",
+                len
+            );
+            assert!(cs.is_satisfied());
+        }
+    }
+}
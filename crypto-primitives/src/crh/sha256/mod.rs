@@ -0,0 +1,4 @@
+// Only the in-circuit implementation lives here; natively, use a crate such as
+// `sha2`.
+#[cfg(feature = "r1cs")]
+pub mod constraints;
@@ -0,0 +1,185 @@
+use crate::{
+    crh::{FixedLengthCRH, FixedLengthCRHGadget},
+    hash_to_curve::TryAndIncrement,
+};
+use algebra_core::{curves::SWModelParameters, Field, FpParameters, PrimeField};
+use r1cs_core::{ConstraintSystem, SynthesisError};
+use r1cs_std::{
+    fields::fp::FpGadget, groups::curves::short_weierstrass::AffineGadget, prelude::*, Assignment,
+};
+
+use core::marker::PhantomData;
+
+/// The in-circuit counterpart of `TryAndIncrement`, for curves whose base
+/// field is the constraint field.
+///
+/// All `MAX_TRIES` candidates are hashed. For each candidate `x`, a witness
+/// `s` with either `s^2 = rhs` or `s^2 = n * rhs`, where `rhs = x^3 + a * x +
+/// b` and `n` is a fixed non-residue, shows whether `x` is on the curve. The
+/// first candidate on the curve is selected, and if there is none the
+/// constraints are unsatisfiable.
+pub struct TryAndIncrementGadget<H, HG, P, ConstraintF>
+where
+    H: FixedLengthCRH,
+    HG: FixedLengthCRHGadget<H, ConstraintF>,
+    P: SWModelParameters<BaseField = ConstraintF>,
+    ConstraintF: PrimeField,
+{
+    #[doc(hidden)]
+    _hash: PhantomData<H>,
+    #[doc(hidden)]
+    _hash_gadget: PhantomData<HG>,
+    #[doc(hidden)]
+    _params: PhantomData<P>,
+}
+
+impl<H, HG, P, ConstraintF> TryAndIncrementGadget<H, HG, P, ConstraintF>
+where
+    H: FixedLengthCRH,
+    HG: FixedLengthCRHGadget<H, ConstraintF>,
+    P: SWModelParameters<BaseField = ConstraintF>,
+    ConstraintF: PrimeField,
+{
+    pub fn check_hash_to_curve<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        parameters: &HG::ParametersGadget,
+        input: &[UInt8],
+    ) -> Result<AffineGadget<P, ConstraintF, FpGadget<ConstraintF>>, SynthesisError> {
+        // A generator of the multiplicative group is a non-residue.
+        let non_residue = ConstraintF::multiplicative_generator();
+        let bytes_per_element = (ConstraintF::Params::CAPACITY / 8) as usize;
+
+        let mut hash_input = input.to_vec();
+        hash_input.push(UInt8::constant(0));
+
+        let mut found = Boolean::constant(false);
+        let mut x = FpGadget::zero(cs.ns(|| "initial x"))?;
+        let mut root = x.clone();
+        for counter in 0..TryAndIncrement::<H, P>::MAX_TRIES {
+            let mut cs = cs.ns(|| format!("Try {}", counter));
+            *hash_input.last_mut().unwrap() = UInt8::constant(counter);
+            let output = HG::check_evaluation_gadget(cs.ns(|| "hash"), parameters, &hash_input)?;
+            let mut output_bytes = output.to_bytes(cs.ns(|| "output to bytes"))?;
+            output_bytes.truncate(bytes_per_element);
+            let candidate_x = UInt8::pack_to_field(cs.ns(|| "pack x"), &output_bytes)?;
+
+            // x^3 + a * x + b
+            let a_x = candidate_x.mul_by_constant(cs.ns(|| "a * x"), &P::COEFF_A)?;
+            let rhs = candidate_x
+                .square(cs.ns(|| "x^2"))?
+                .mul(cs.ns(|| "x^3"), &candidate_x)?
+                .add(cs.ns(|| "x^3 + a * x"), &a_x)?
+                .add_constant(cs.ns(|| "x^3 + a * x + b"), &P::COEFF_B)?;
+
+            let is_on_curve = Boolean::alloc(cs.ns(|| "is on curve"), || {
+                Ok(rhs.get_value().get()?.sqrt().is_some())
+            })?;
+            let candidate_root = FpGadget::alloc(cs.ns(|| "root"), || {
+                let rhs = rhs.get_value().get()?;
+                rhs.sqrt().or_else(|| (rhs * &non_residue).sqrt()).get()
+            })?;
+            let non_residue_rhs = rhs.mul_by_constant(cs.ns(|| "n * rhs"), &non_residue)?;
+            let square = FpGadget::conditionally_select(
+                cs.ns(|| "rhs or n * rhs"),
+                &is_on_curve,
+                &rhs,
+                &non_residue_rhs,
+            )?;
+            candidate_root.square_equals(cs.ns(|| "check root"), &square)?;
+
+            let is_first = Boolean::and(cs.ns(|| "is first"), &is_on_curve, &found.not())?;
+            x = FpGadget::conditionally_select(cs.ns(|| "select x"), &is_first, &candidate_x, &x)?;
+            root = FpGadget::conditionally_select(
+                cs.ns(|| "select root"),
+                &is_first,
+                &candidate_root,
+                &root,
+            )?;
+            found = Boolean::or(cs.ns(|| "found"), &found, &is_on_curve)?;
+        }
+        found.enforce_equal(cs.ns(|| "found a point"), &Boolean::constant(true))?;
+
+        // Of the two square roots, take the one whose canonical
+        // representation is even.
+        let root_bits = root.to_bits(cs.ns(|| "root to bits"))?;
+        let neg_root = root.negate(cs.ns(|| "-root"))?;
+        let y = FpGadget::conditionally_select(
+            cs.ns(|| "select y"),
+            root_bits.last().unwrap(),
+            &neg_root,
+            &root,
+        )?;
+        Ok(AffineGadget::new(x, y, Boolean::constant(false)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        crh::{
+            mimc::{constraints::MiMCCRHGadget, MiMCCRH, MiMCRoundParams},
+            FixedLengthCRH, FixedLengthCRHGadget,
+        },
+        hash_to_curve::{TryAndIncrement, TryAndIncrementGadget},
+    };
+    use algebra::{
+        bls12_377::{g1::Parameters as G1Parameters, Fq},
+        test_rng, ProjectiveCurve,
+    };
+    use r1cs_core::ConstraintSystem;
+    use r1cs_std::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use rand::Rng;
+
+    type TestCRH = MiMCCRH<Fq, RoundParams>;
+    type TestCRHGadget = MiMCCRHGadget<Fq>;
+    type TestHashToCurve = TryAndIncrement<TestCRH, G1Parameters>;
+    type TestHashToCurveGadget = TryAndIncrementGadget<TestCRH, TestCRHGadget, G1Parameters, Fq>;
+
+    #[derive(Clone)]
+    struct RoundParams;
+
+    impl MiMCRoundParams for RoundParams {
+        const ROUNDS: usize = 20;
+        const EXPONENT: u64 = 3;
+        // Room for a 31-byte message and the counter.
+        const INPUT_SIZE_BITS: usize = 256;
+    }
+
+    #[test]
+    fn try_and_increment_test() {
+        let rng = &mut test_rng();
+        let parameters = TestCRH::setup(rng).unwrap();
+
+        for (i, &len) in [0, 1, 20, 31].iter().enumerate() {
+            let input = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let native_result = TestHashToCurve::hash(&parameters, &input).unwrap();
+            assert!(native_result.is_on_curve());
+
+            let mut cs = TestConstraintSystem::<Fq>::new();
+            let input_bytes = UInt8::alloc_vec(cs.ns(|| "input"), &input).unwrap();
+            let gadget_parameters =
+                <TestCRHGadget as FixedLengthCRHGadget<TestCRH, Fq>>::ParametersGadget::alloc(
+                    &mut cs.ns(|| "gadget_parameters"),
+                    || Ok(&parameters),
+                )
+                .unwrap();
+            let gadget_result = TestHashToCurveGadget::check_hash_to_curve(
+                cs.ns(|| "hash to curve"),
+                &gadget_parameters,
+                &input_bytes,
+            )
+            .unwrap();
+
+            assert_eq!(
+                gadget_result.get_value().unwrap().into_affine(),
+                native_result,
+                "message {} hashes to different points",
+                i
+            );
+            if !cs.is_satisfied() {
+                println!("{:?}", cs.which_is_unsatisfied().unwrap());
+            }
+            assert!(cs.is_satisfied());
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{crh::FixedLengthCRH, CryptoError, Error, Vec};
+use algebra_core::{
+    curves::{short_weierstrass_jacobian::GroupAffine, SWModelParameters},
+    to_bytes, BigInteger, Field, FpParameters, PrimeField, ToBytes, ToConstraintField, Zero,
+};
+use core::marker::PhantomData;
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+#[cfg(feature = "r1cs")]
+pub use constraints::*;
+
+/// Hashes byte strings to points of a short Weierstrass curve by
+/// try-and-increment.
+///
+/// For `counter = 0, 1, ..., MAX_TRIES - 1`, `input || counter` is hashed
+/// with `H`, and the first `CAPACITY / 8` bytes of the output are packed, in
+/// little-endian order, into a candidate `x`-coordinate. The first candidate
+/// on the curve is used, with the square root `y` whose canonical
+/// representation is even.
+///
+/// The point is not multiplied by the cofactor, so on curves with a nontrivial
+/// cofactor it need not lie in the prime-order subgroup.
+pub struct TryAndIncrement<H: FixedLengthCRH, P: SWModelParameters> {
+    #[doc(hidden)]
+    _hash: PhantomData<H>,
+    #[doc(hidden)]
+    _params: PhantomData<P>,
+}
+
+impl<H: FixedLengthCRH, P: SWModelParameters> TryAndIncrement<H, P>
+where
+    P::BaseField: PrimeField,
+{
+    /// Each try succeeds with probability about 1/2, so hashing fails with
+    /// probability about `2^-MAX_TRIES`.
+    pub const MAX_TRIES: u8 = 32;
+
+    pub fn hash(parameters: &H::Parameters, input: &[u8]) -> Result<GroupAffine<P>, Error> {
+        let mut hash_input = input.to_vec();
+        hash_input.push(0u8);
+        for counter in 0..Self::MAX_TRIES {
+            *hash_input.last_mut().unwrap() = counter;
+            let output = H::evaluate(parameters, &hash_input)?;
+            let x = Self::candidate_x(&output)?;
+            let rhs = P::add_b(&((x.square() * &x) + &P::mul_by_a(&x)));
+            if let Some(y) = rhs.sqrt() {
+                let y = if y.into_repr().is_odd() { -y } else { y };
+                return Ok(GroupAffine::new(x, y, false));
+            }
+        }
+        Err(CryptoError::HashToCurveFailed.into())
+    }
+
+    fn candidate_x(output: &H::Output) -> Result<P::BaseField, Error> {
+        let mut bytes = to_bytes![output]?;
+        bytes.truncate((<P::BaseField as PrimeField>::Params::CAPACITY / 8) as usize);
+        let elements: Vec<P::BaseField> = bytes.to_field_elements()?;
+        Ok(elements.get(0).cloned().unwrap_or_else(P::BaseField::zero))
+    }
+}
@@ -17,6 +17,7 @@ pub(crate) use std::{borrow::ToOwned, boxed::Box, vec::Vec};
 
 pub mod commitment;
 pub mod crh;
+pub mod hash_to_curve;
 pub mod merkle_tree;
 pub mod nizk;
 pub mod prf;
@@ -35,7 +36,7 @@ pub use self::{
 pub use self::{
     commitment::CommitmentGadget, crh::FixedLengthCRHGadget,
     merkle_tree::constraints::MerkleTreePathGadget, nizk::NIZKVerifierGadget, prf::PRFGadget,
-    signature::SigRandomizePkGadget,
+    signature::{SigRandomizePkGadget, SigVerifyGadget},
 };
 
 pub type Error = Box<dyn algebra_core::Error>;
@@ -44,6 +45,8 @@ pub type Error = Box<dyn algebra_core::Error>;
 pub enum CryptoError {
     IncorrectInputLength(usize),
     NotPrimeOrder,
+    HashToCurveFailed,
+    Unsupported(&'static str),
 }
 
 impl core::fmt::Display for CryptoError {
@@ -51,6 +54,8 @@ impl core::fmt::Display for CryptoError {
         let msg = match self {
             CryptoError::IncorrectInputLength(len) => format!("input length is wrong: {}", len),
             CryptoError::NotPrimeOrder => "element is not prime order".to_owned(),
+            CryptoError::HashToCurveFailed => "failed to hash to the curve".to_owned(),
+            CryptoError::Unsupported(op) => format!("unsupported operation: {}", op),
         };
         write!(f, "{}", msg)
     }
@@ -24,7 +24,79 @@ where
     ConstraintF: Field,
     CRHGadget: FixedLengthCRHGadget<P::H, ConstraintF>,
 {
+    /// Returns whether the path proves that `leaf` is in the tree with the
+    /// given `root`, without enforcing it.
     pub fn check_membership<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        parameters: &CRHGadget::ParametersGadget,
+        root: &CRHGadget::OutputGadget,
+        leaf: impl ToBytesGadget<ConstraintF>,
+    ) -> Result<Boolean, SynthesisError> {
+        assert_eq!(self.path.len(), P::HEIGHT - 1);
+        let leaf_bytes = leaf.to_bytes(&mut cs.ns(|| "leaf_to_bytes"))?;
+        let mut previous_hash = CRHGadget::check_evaluation_gadget(
+            cs.ns(|| "check_evaluation_gadget"),
+            parameters,
+            &leaf_bytes,
+        )?;
+
+        for (i, &(ref left_hash, ref right_hash)) in self.path.iter().enumerate() {
+            let mut cs = cs.ns(|| format!("level_{}", i));
+            // Only the sibling of the previous hash is taken from the path; the
+            // direction bit decides on which side it goes.
+            let previous_is_left: Boolean =
+                AllocatedBit::alloc(&mut cs.ns(|| "previous_is_left"), || {
+                    Ok(&previous_hash == left_hash)
+                })?
+                .into();
+            let sibling = CRHGadget::OutputGadget::conditionally_select(
+                &mut cs.ns(|| "sibling"),
+                &previous_is_left,
+                right_hash,
+                left_hash,
+            )?;
+            let left = CRHGadget::OutputGadget::conditionally_select(
+                &mut cs.ns(|| "left"),
+                &previous_is_left,
+                &previous_hash,
+                &sibling,
+            )?;
+            let right = CRHGadget::OutputGadget::conditionally_select(
+                &mut cs.ns(|| "right"),
+                &previous_is_left,
+                &sibling,
+                &previous_hash,
+            )?;
+
+            previous_hash = hash_inner_node_gadget::<P::H, CRHGadget, ConstraintF, _>(
+                &mut cs.ns(|| "hash_inner_node"),
+                parameters,
+                &left,
+                &right,
+            )?;
+        }
+
+        // Compare the byte encodings, since `EqGadget` can only enforce.
+        let root_bytes = root.to_bytes(&mut cs.ns(|| "root_to_bytes"))?;
+        let computed_bytes = previous_hash.to_bytes(&mut cs.ns(|| "computed_root_to_bytes"))?;
+        let mut bits_equal = Vec::new();
+        for (i, (a, b)) in root_bytes
+            .iter()
+            .flat_map(UInt8::into_bits_le)
+            .zip(computed_bytes.iter().flat_map(UInt8::into_bits_le))
+            .enumerate()
+        {
+            bits_equal.push(Boolean::xnor(
+                &mut cs.ns(|| format!("root_bit_equal_{}", i)),
+                &a,
+                &b,
+            )?);
+        }
+        Boolean::kary_and(&mut cs.ns(|| "root_is_equal"), &bits_equal)
+    }
+
+    pub fn enforce_membership<CS: ConstraintSystem<ConstraintF>>(
         &self,
         cs: CS,
         parameters: &CRHGadget::ParametersGadget,
@@ -295,7 +367,7 @@ mod test {
                 - constraints_from_leaf;
             println!("constraints from path: {}", constraints_from_path);
             let leaf_g: &[UInt8] = leaf_g.as_slice();
-            cw.check_membership(
+            cw.enforce_membership(
                 &mut cs.ns(|| format!("new_witness_check_{}", i)),
                 &crh_parameters,
                 &root,
@@ -342,4 +414,81 @@ mod test {
         }
         generate_merkle_tree(&leaves, true);
     }
+
+    struct SmallMerkleTreeParams;
+
+    impl MerkleTreeConfig for SmallMerkleTreeParams {
+        const HEIGHT: usize = 4;
+        type H = H;
+    }
+
+    type SmallMerkleTree = MerkleHashTree<SmallMerkleTreeParams>;
+
+    fn check_small_tree_membership(
+        tamper_with_sibling: bool,
+    ) -> (TestConstraintSystem<Fq>, Boolean, bool) {
+        let mut rng = XorShiftRng::seed_from_u64(9174123u64);
+        let leaves = (0..4u8).map(|i| [i; 30]).collect::<Vec<_>>();
+
+        let crh_parameters = H::setup(&mut rng).unwrap();
+        let tree = SmallMerkleTree::new(crh_parameters.clone(), &leaves).unwrap();
+        let root = tree.root();
+        let mut proof = tree.generate_proof(2, &leaves[2]).unwrap();
+        if tamper_with_sibling {
+            proof.path[1] = Default::default();
+        }
+        let native_result = proof.verify(&crh_parameters, &root, &leaves[2]).unwrap();
+
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let root =
+            <HG as FixedLengthCRHGadget<H, _>>::OutputGadget::alloc(&mut cs.ns(|| "root"), || {
+                Ok(root)
+            })
+            .unwrap();
+        let crh_parameters = <HG as FixedLengthCRHGadget<H, Fq>>::ParametersGadget::alloc(
+            &mut cs.ns(|| "parameters"),
+            || Ok(crh_parameters),
+        )
+        .unwrap();
+        let leaf_g = UInt8::constant_vec(&leaves[2]);
+        let path =
+            MerkleTreePathGadget::<_, HG, _>::alloc(&mut cs.ns(|| "path"), || Ok(proof)).unwrap();
+        let is_member = path
+            .check_membership(
+                &mut cs.ns(|| "check_membership"),
+                &crh_parameters,
+                &root,
+                leaf_g.as_slice(),
+            )
+            .unwrap();
+        path.enforce_membership(
+            &mut cs.ns(|| "enforce_membership"),
+            &crh_parameters,
+            &root,
+            leaf_g.as_slice(),
+        )
+        .unwrap();
+        (cs, is_member, native_result)
+    }
+
+    #[test]
+    fn check_membership_test() {
+        let (cs, is_member, native_result) = check_small_tree_membership(false);
+        assert!(native_result);
+        assert_eq!(is_member.get_value(), Some(true));
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn wrong_sibling_test() {
+        let (cs, is_member, native_result) = check_small_tree_membership(true);
+        assert!(!native_result);
+        assert_eq!(is_member.get_value(), Some(false));
+        // Only `enforce_membership` makes the system unsatisfiable.
+        assert!(!cs.is_satisfied());
+        assert!(cs
+            .which_is_unsatisfied()
+            .unwrap()
+            .starts_with("enforce_membership"));
+    }
 }
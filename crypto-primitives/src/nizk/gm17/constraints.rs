@@ -175,7 +175,9 @@ where
             }
             // Check that the input and the query in the verification are of the
             // same length.
-            assert!(input_len == pvk.query.len() && public_inputs.next().is_none());
+            if input_len != pvk.query.len() || public_inputs.next().is_some() {
+                return Err(SynthesisError::MalformedVerifyingKey);
+            }
             g_psi
         };
 
@@ -208,7 +210,7 @@ where
             )?
         };
 
-        let test1 = P::final_exponentiation(cs.ns(|| "Final Exp 1"), &test1_exp).unwrap();
+        let test1 = P::final_exponentiation(cs.ns(|| "Final Exp 1"), &test1_exp)?;
 
         // e(A, H^{gamma}) = e(G^{gamma}, B)
         let test2_exp = {
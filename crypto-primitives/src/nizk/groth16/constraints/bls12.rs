@@ -0,0 +1,112 @@
+use super::{PreparedVerifyingKeyGadget, VerifyingKeyGadget};
+use crate::Vec;
+use algebra_core::{
+    curves::bls12::{Bls12, Bls12Parameters, G1Affine, G2Affine},
+    AffineCurve, BigInteger, FromBytes, PrimeField,
+};
+use groth16::VerifyingKey;
+use r1cs_core::{ConstraintSystem, SynthesisError};
+use r1cs_std::{
+    groups::bls12::G2PreparedGadget, pairing::bls12::PairingGadget as Bls12PairingGadget,
+    prelude::*,
+};
+
+impl<P: Bls12Parameters> PreparedVerifyingKeyGadget<Bls12<P>, P::Fp, Bls12PairingGadget<P>> {
+    /// Parses the `ToBytes` encoding of a `PreparedVerifyingKey`, so that the
+    /// key can be supplied to the circuit as bytes, for example allocated with
+    /// `UInt8::alloc_vec`.
+    ///
+    /// The prepared values in the encoding are not trusted. The verifying key
+    /// that begins it is allocated with `alloc_checked`, which checks that its
+    /// points lie in the prime-order subgroups, and is prepared in-circuit.
+    /// The encoded `alpha_g1_beta_g2` and `gamma_abc_g1` must equal the
+    /// recomputed ones, and the encoded line coefficients of `-gamma_g2` and
+    /// `-delta_g2` are ignored in favour of the recomputed ones. Bytes that
+    /// do not encode a consistent key make the constraint system
+    /// unsatisfiable.
+    ///
+    /// Whoever chooses `bytes` still chooses the key, so a caller that
+    /// receives them as a witness must bind them to the statement, e.g. by
+    /// comparing their hash with a public input. Bytes of the wrong length are
+    /// rejected with `SynthesisError::InvalidArgument`.
+    pub fn from_bytes<CS: ConstraintSystem<P::Fp>>(
+        mut cs: CS,
+        bytes: &[UInt8],
+    ) -> Result<Self, SynthesisError> {
+        let fp_size = <P::Fp as PrimeField>::BigInt::NUM_LIMBS * 8;
+        let g1_size = 2 * fp_size + 1;
+        let g2_size = 4 * fp_size + 1;
+        let gt_size = 12 * fp_size;
+        let g2_prepared_size = G2PreparedGadget::<P>::num_bytes();
+
+        // `gamma_abc_g1` is written twice, once as part of `vk`.
+        let fixed_size = g1_size + 3 * g2_size + gt_size + 2 * g2_prepared_size;
+        if bytes.len() < fixed_size || (bytes.len() - fixed_size) % (2 * g1_size) != 0 {
+            return Err(SynthesisError::InvalidArgument);
+        }
+        let num_queries = (bytes.len() - fixed_size) / (2 * g1_size);
+
+        let (vk_bytes, bytes) = bytes.split_at((1 + num_queries) * g1_size + 3 * g2_size);
+        let (gt_bytes, bytes) = bytes.split_at(gt_size);
+        let gamma_abc_bytes = &bytes[2 * g2_prepared_size..];
+
+        // Without values, or with bytes that do not decode, any key of the
+        // right shape yields the same constraints.
+        let vk = Self::verifying_key_from_bytes(vk_bytes, num_queries).unwrap_or_else(|| {
+            let g1 = G1Affine::<P>::prime_subgroup_generator();
+            let g2 = G2Affine::<P>::prime_subgroup_generator();
+            VerifyingKey {
+                alpha_g1: g1,
+                beta_g2: g2,
+                gamma_g2: g2,
+                delta_g2: g2,
+                gamma_abc_g1: vec![g1; num_queries + 1],
+            }
+        });
+        let vk = VerifyingKeyGadget::<Bls12<P>, P::Fp, Bls12PairingGadget<P>>::alloc_checked(
+            cs.ns(|| "alloc vk"),
+            || Ok(vk),
+        )?;
+
+        let mut gamma_abc_g1_bytes = Vec::new();
+        for (i, g) in vk.gamma_abc_g1.iter().enumerate() {
+            gamma_abc_g1_bytes.extend(g.to_bytes(cs.ns(|| format!("query_{} to bytes", i)))?);
+        }
+        let mut encoded_vk = vk.alpha_g1.to_bytes(cs.ns(|| "alpha_g1 to bytes"))?;
+        encoded_vk.extend(vk.beta_g2.to_bytes(cs.ns(|| "beta_g2 to bytes"))?);
+        encoded_vk.extend(vk.gamma_g2.to_bytes(cs.ns(|| "gamma_g2 to bytes"))?);
+        encoded_vk.extend(vk.delta_g2.to_bytes(cs.ns(|| "delta_g2 to bytes"))?);
+        encoded_vk.extend_from_slice(&gamma_abc_g1_bytes);
+        encoded_vk[..].enforce_equal(cs.ns(|| "vk bytes"), vk_bytes)?;
+
+        let pvk = vk.prepare(cs.ns(|| "prepare vk"))?;
+        let encoded_gt = pvk.alpha_g1_beta_g2.to_bytes(cs.ns(|| "alpha_g1_beta_g2 to bytes"))?;
+        encoded_gt[..].enforce_equal(cs.ns(|| "alpha_g1_beta_g2 bytes"), gt_bytes)?;
+        gamma_abc_g1_bytes[..].enforce_equal(cs.ns(|| "gamma_abc_g1 bytes"), gamma_abc_bytes)?;
+        Ok(pvk)
+    }
+
+    /// Decodes the values of the verifying key at the start of the encoding,
+    /// if they are known.
+    fn verifying_key_from_bytes(
+        bytes: &[UInt8],
+        num_queries: usize,
+    ) -> Option<VerifyingKey<Bls12<P>>> {
+        let values = UInt8::get_values(bytes)?;
+        let mut reader = &values[..];
+        let alpha_g1 = G1Affine::<P>::read(&mut reader).ok()?;
+        let beta_g2 = G2Affine::<P>::read(&mut reader).ok()?;
+        let gamma_g2 = G2Affine::<P>::read(&mut reader).ok()?;
+        let delta_g2 = G2Affine::<P>::read(&mut reader).ok()?;
+        let gamma_abc_g1 = (0..=num_queries)
+            .map(|_| G1Affine::<P>::read(&mut reader).ok())
+            .collect::<Option<Vec<_>>>()?;
+        Some(VerifyingKey {
+            alpha_g1,
+            beta_g2,
+            gamma_g2,
+            delta_g2,
+            gamma_abc_g1,
+        })
+    }
+}
@@ -0,0 +1,2132 @@
+use crate::{
+    nizk::{groth16::Groth16, NIZKVerifierGadget},
+    Vec,
+};
+use algebra_core::{
+    AffineCurve, Field, FpParameters, PairingEngine, PrimeField, ProjectiveCurve, ToConstraintField,
+};
+use r1cs_core::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+use r1cs_std::{fields::fp::FpGadget, prelude::*};
+
+use core::{borrow::Borrow, marker::PhantomData};
+use groth16::{PreparedVerifyingKey, Proof, VerifyingKey};
+
+mod bls12;
+
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "P::G1Gadget: Clone, P::G2Gadget: Clone"),
+    PartialEq(bound = "P::G1Gadget: PartialEq, P::G2Gadget: PartialEq"),
+    Eq(bound = "P::G1Gadget: Eq, P::G2Gadget: Eq")
+)]
+pub struct ProofGadget<
+    PairingE: PairingEngine,
+    ConstraintF: Field,
+    P: PairingGadget<PairingE, ConstraintF>,
+> {
+    pub a: P::G1Gadget,
+    pub b: P::G2Gadget,
+    pub c: P::G1Gadget,
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(
+    bound = "P::G1Gadget: Clone, P::GTGadget: Clone, P::G1PreparedGadget: Clone, \
+             P::G2PreparedGadget: Clone, "
+))]
+pub struct VerifyingKeyGadget<
+    PairingE: PairingEngine,
+    ConstraintF: Field,
+    P: PairingGadget<PairingE, ConstraintF>,
+> {
+    pub alpha_g1: P::G1Gadget,
+    pub beta_g2: P::G2Gadget,
+    pub gamma_g2: P::G2Gadget,
+    pub delta_g2: P::G2Gadget,
+    pub gamma_abc_g1: Vec<P::G1Gadget>,
+}
+
+impl<PairingE: PairingEngine, ConstraintF: Field, P: PairingGadget<PairingE, ConstraintF>>
+    VerifyingKeyGadget<PairingE, ConstraintF, P>
+{
+    /// Prepares the verifying key, computing `e(alpha_g1, beta_g2)` and the
+    /// line coefficients of `-gamma_g2` and `-delta_g2`.
+    ///
+    /// When verifying several proofs against the same key, call this once and
+    /// pass the result to `conditional_check_verify_prepared` for each proof,
+    /// so that this work is not repeated per proof.
+    pub fn prepare<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<PreparedVerifyingKeyGadget<PairingE, ConstraintF, P>, SynthesisError> {
+        let mut cs = cs.ns(|| "Preparing verifying key");
+        let alpha_g1_pc = P::prepare_g1(&mut cs.ns(|| "Prepare alpha_g1"), &self.alpha_g1)?;
+        let beta_g2_pc = P::prepare_g2(&mut cs.ns(|| "Prepare beta_g2"), &self.beta_g2)?;
+
+        let alpha_g1_beta_g2 = P::pairing(
+            &mut cs.ns(|| "Precompute e(alpha_g1, beta_g2)"),
+            alpha_g1_pc,
+            beta_g2_pc,
+        )?;
+
+        let gamma_g2_neg_pc =
+            P::prepare_g2_neg(&mut cs.ns(|| "Prepare gamma_g2_neg"), &self.gamma_g2)?;
+        let delta_g2_neg_pc =
+            P::prepare_g2_neg(&mut cs.ns(|| "Prepare delta_g2_neg"), &self.delta_g2)?;
+
+        Ok(PreparedVerifyingKeyGadget {
+            alpha_g1_beta_g2,
+            gamma_g2_neg_pc,
+            delta_g2_neg_pc,
+            gamma_abc_g1: self.gamma_abc_g1.clone(),
+        })
+    }
+}
+
+/// A verifying key whose G2 elements have their pairing line coefficients
+/// precomputed. It can be reused across any number of verifications.
+#[derive(Derivative)]
+#[derivative(Clone(
+    bound = "P::G1Gadget: Clone, P::GTGadget: Clone, P::G1PreparedGadget: Clone, \
+             P::G2PreparedGadget: Clone, "
+))]
+pub struct PreparedVerifyingKeyGadget<
+    PairingE: PairingEngine,
+    ConstraintF: Field,
+    P: PairingGadget<PairingE, ConstraintF>,
+> {
+    pub alpha_g1_beta_g2: P::GTGadget,
+    pub gamma_g2_neg_pc: P::G2PreparedGadget,
+    pub delta_g2_neg_pc: P::G2PreparedGadget,
+    pub gamma_abc_g1: Vec<P::G1Gadget>,
+}
+
+pub struct Groth16VerifierGadget<PairingE, ConstraintF, P>
+where
+    PairingE: PairingEngine,
+    ConstraintF: Field,
+    P: PairingGadget<PairingE, ConstraintF>,
+{
+    _pairing_engine: PhantomData<PairingE>,
+    _engine: PhantomData<ConstraintF>,
+    _pairing_gadget: PhantomData<P>,
+}
+
+impl<PairingE, ConstraintF, P> Groth16VerifierGadget<PairingE, ConstraintF, P>
+where
+    PairingE: PairingEngine,
+    ConstraintF: Field,
+    P: PairingGadget<PairingE, ConstraintF>,
+{
+    /// Computes `gamma_abc_g1[0] + sum_i public_inputs[i] * gamma_abc_g1[i + 1]`.
+    fn process_inputs<'a, CS, I, T>(
+        mut cs: CS,
+        gamma_abc_g1: &[P::G1Gadget],
+        mut public_inputs: I,
+    ) -> Result<P::G1Gadget, SynthesisError>
+    where
+        CS: ConstraintSystem<ConstraintF>,
+        I: Iterator<Item = &'a T>,
+        T: 'a + ToBitsGadget<ConstraintF> + ?Sized,
+    {
+        let mut g_ic = gamma_abc_g1[0].clone();
+        let mut input_len = 1;
+        for (i, (input, b)) in public_inputs
+            .by_ref()
+            .zip(gamma_abc_g1.iter().skip(1))
+            .enumerate()
+        {
+            let input_bits = input.to_bits(cs.ns(|| format!("Input {}", i)))?;
+            g_ic = b.mul_bits(cs.ns(|| format!("Mul {}", i)), &g_ic, input_bits.iter())?;
+            input_len += 1;
+        }
+        // Check that the input and the query in the verification are of the
+        // same length.
+        if input_len != gamma_abc_g1.len() || public_inputs.next().is_some() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+        Ok(g_ic)
+    }
+
+    /// Computes the same `g_ic` as `process_inputs` for a verifying key that
+    /// is known when generating constraints. Each term is computed with
+    /// `fixed_base_scalar_mul`, whose tables are computed natively, so unlike
+    /// `mul_bits` no doublings are enforced.
+    pub fn process_inputs_with_constant_key<'a, CS, I, T>(
+        mut cs: CS,
+        gamma_abc_g1: &[PairingE::G1Affine],
+        mut public_inputs: I,
+    ) -> Result<P::G1Gadget, SynthesisError>
+    where
+        CS: ConstraintSystem<ConstraintF>,
+        I: Iterator<Item = &'a T>,
+        T: 'a + ToBitsGadget<ConstraintF> + ?Sized,
+    {
+        let mut g_ic = P::G1Gadget::alloc_constant(
+            cs.ns(|| "gamma_abc_g1[0]"),
+            gamma_abc_g1[0].into_projective(),
+        )?;
+        let mut input_len = 1;
+        for (i, (input, b)) in public_inputs
+            .by_ref()
+            .zip(gamma_abc_g1.iter().skip(1))
+            .enumerate()
+        {
+            let input_bits = input.to_bits(cs.ns(|| format!("Input {}", i)))?;
+            let term = P::G1Gadget::fixed_base_scalar_mul(
+                cs.ns(|| format!("Mul {}", i)),
+                &b.into_projective(),
+                &input_bits,
+                3,
+            )?;
+            // A zero input gives the identity, so the sum must be complete.
+            g_ic = g_ic.add_unified(cs.ns(|| format!("Add {}", i)), &term)?;
+            input_len += 1;
+        }
+        // Check that the input and the query in the verification are of the
+        // same length.
+        if input_len != gamma_abc_g1.len() || public_inputs.next().is_some() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+        Ok(g_ic)
+    }
+
+    /// Shared by `conditional_check_verify_prepared` and
+    /// `verify_with_field_inputs`.
+    fn conditional_verify_prepared<'a, CS, I, T>(
+        mut cs: CS,
+        pvk: &PreparedVerifyingKeyGadget<PairingE, ConstraintF, P>,
+        public_inputs: I,
+        proof: &ProofGadget<PairingE, ConstraintF, P>,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError>
+    where
+        CS: ConstraintSystem<ConstraintF>,
+        I: Iterator<Item = &'a T>,
+        T: 'a + ToBitsGadget<ConstraintF> + ?Sized,
+    {
+        let g_ic =
+            Self::process_inputs(cs.ns(|| "Process input"), &pvk.gamma_abc_g1, public_inputs)?;
+        Self::conditional_check_pairings(&mut cs, pvk, &g_ic, proof, condition)
+    }
+
+    /// Like `conditional_check_verify_prepared`, but for a prepared verifying
+    /// key that is known when generating constraints, so that `g_ic` can be
+    /// computed with `process_inputs_with_constant_key`.
+    pub fn conditional_verify_with_constant_key<'a, CS, I, T>(
+        mut cs: CS,
+        pvk: &PreparedVerifyingKey<PairingE>,
+        public_inputs: I,
+        proof: &ProofGadget<PairingE, ConstraintF, P>,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError>
+    where
+        CS: ConstraintSystem<ConstraintF>,
+        I: Iterator<Item = &'a T>,
+        T: 'a + ToBitsGadget<ConstraintF> + ?Sized,
+        P::G2PreparedGadget: AllocGadget<PairingE::G2Prepared, ConstraintF>,
+    {
+        let pvk_gadget = PreparedVerifyingKeyGadget::alloc_constant(cs.ns(|| "Prepared vk"), pvk)?;
+        let g_ic = Self::process_inputs_with_constant_key(
+            cs.ns(|| "Process input"),
+            &pvk.gamma_abc_g1,
+            public_inputs,
+        )?;
+        Self::conditional_check_pairings(&mut cs, &pvk_gadget, &g_ic, proof, condition)
+    }
+
+    /// Checks `e(A, B) * e(g_ic, -gamma) * e(C, -delta) = e(alpha, beta)` if
+    /// `condition` is set.
+    fn conditional_check_pairings<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        pvk: &PreparedVerifyingKeyGadget<PairingE, ConstraintF, P>,
+        g_ic: &P::G1Gadget,
+        proof: &ProofGadget<PairingE, ConstraintF, P>,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError> {
+        let test_exp = {
+            let proof_a_prep = P::prepare_g1(cs.ns(|| "Prepare proof a"), &proof.a)?;
+            let proof_b_prep = P::prepare_g2(cs.ns(|| "Prepare proof b"), &proof.b)?;
+            let proof_c_prep = P::prepare_g1(cs.ns(|| "Prepare proof c"), &proof.c)?;
+
+            let g_ic_prep = P::prepare_g1(cs.ns(|| "Prepare g_ic"), g_ic)?;
+
+            P::miller_loop(
+                cs.ns(|| "Miller loop 1"),
+                &[proof_a_prep, g_ic_prep, proof_c_prep],
+                &[
+                    proof_b_prep,
+                    pvk.gamma_g2_neg_pc.clone(),
+                    pvk.delta_g2_neg_pc.clone(),
+                ],
+            )?
+        };
+
+        let test = P::final_exponentiation(cs.ns(|| "Final Exp"), &test_exp)?;
+
+        test.conditional_enforce_equal(cs.ns(|| "Test 1"), &pvk.alpha_g1_beta_g2, condition)?;
+        Ok(())
+    }
+
+    /// Verifies each of `proofs` against the corresponding entry of
+    /// `public_inputs`, using a single Miller loop and a single final
+    /// exponentiation.
+    ///
+    /// Proof `i` is valid iff
+    /// `e(A_i, B_i) * e(g_ic_i, -gamma) * e(C_i, -delta) * e(-alpha, beta) = 1`.
+    /// Instead of checking each of these equations, this checks their
+    /// product after raising the `i`-th one to `r_i`, whose little-endian
+    /// bits are `challenges[i]`. Scaling the G1 arguments by `r_i` lets the
+    /// `gamma`, `delta` and `beta` terms of all proofs be merged, so `N`
+    /// proofs cost `N + 3` pairings instead of `4 * N`.
+    ///
+    /// This is only sound if the challenges are unpredictable to whoever
+    /// produced the proofs, e.g. if they are derived from a hash of the
+    /// proofs and inputs. Unless there are as many entries in
+    /// `public_inputs` and `challenges` as there are proofs, this returns
+    /// `SynthesisError::InvalidArgument`.
+    pub fn batch_verify<CS, T>(
+        mut cs: CS,
+        vk: &VerifyingKeyGadget<PairingE, ConstraintF, P>,
+        public_inputs: &[Vec<T>],
+        proofs: &[ProofGadget<PairingE, ConstraintF, P>],
+        challenges: &[Vec<Boolean>],
+    ) -> Result<(), SynthesisError>
+    where
+        CS: ConstraintSystem<ConstraintF>,
+        T: ToBitsGadget<ConstraintF>,
+    {
+        if proofs.len() != public_inputs.len() || proofs.len() != challenges.len() {
+            return Err(SynthesisError::InvalidArgument);
+        }
+
+        // The addition formulas of `P::G1Gadget` need not handle the identity,
+        // so all linear combinations start from a fixed non-zero point, which
+        // is subtracted at the end.
+        let offset = PairingE::G1Projective::prime_subgroup_generator();
+        let offset_gadget = P::G1Gadget::alloc_constant(cs.ns(|| "offset"), offset)?;
+        let mut g_ic_sum = offset_gadget.clone();
+        let mut c_sum = offset_gadget.clone();
+        let mut alpha_sum = offset_gadget.clone();
+
+        let mut g1s = Vec::with_capacity(proofs.len() + 3);
+        let mut g2s = Vec::with_capacity(proofs.len() + 3);
+        for (i, ((inputs, proof), r)) in
+            public_inputs.iter().zip(proofs).zip(challenges).enumerate()
+        {
+            let mut cs = cs.ns(|| format!("Proof {}", i));
+            let g_ic =
+                Self::process_inputs(cs.ns(|| "Process input"), &vk.gamma_abc_g1, inputs.iter())?;
+
+            let r_a = proof
+                .a
+                .mul_bits(cs.ns(|| "offset + r * a"), &offset_gadget, r.iter())?
+                .sub_constant(cs.ns(|| "r * a"), &offset)?;
+            g1s.push(P::prepare_g1(cs.ns(|| "Prepare r * a"), &r_a)?);
+            g2s.push(P::prepare_g2(cs.ns(|| "Prepare b"), &proof.b)?);
+
+            g_ic_sum = g_ic.mul_bits(cs.ns(|| "Accumulate r * g_ic"), &g_ic_sum, r.iter())?;
+            c_sum = proof
+                .c
+                .mul_bits(cs.ns(|| "Accumulate r * c"), &c_sum, r.iter())?;
+            alpha_sum =
+                vk.alpha_g1
+                    .mul_bits(cs.ns(|| "Accumulate r * alpha"), &alpha_sum, r.iter())?;
+        }
+
+        let g_ic_sum = g_ic_sum.sub_constant(cs.ns(|| "Remove offset from g_ic"), &offset)?;
+        let c_sum = c_sum.sub_constant(cs.ns(|| "Remove offset from c"), &offset)?;
+        let neg_alpha_sum = alpha_sum
+            .sub_constant(cs.ns(|| "Remove offset from alpha"), &offset)?
+            .negate(cs.ns(|| "Negate alpha"))?;
+
+        g1s.push(P::prepare_g1(cs.ns(|| "Prepare g_ic"), &g_ic_sum)?);
+        g2s.push(P::prepare_g2_neg(
+            cs.ns(|| "Prepare gamma_g2_neg"),
+            &vk.gamma_g2,
+        )?);
+        g1s.push(P::prepare_g1(cs.ns(|| "Prepare c"), &c_sum)?);
+        g2s.push(P::prepare_g2_neg(
+            cs.ns(|| "Prepare delta_g2_neg"),
+            &vk.delta_g2,
+        )?);
+        g1s.push(P::prepare_g1(cs.ns(|| "Prepare alpha"), &neg_alpha_sum)?);
+        g2s.push(P::prepare_g2(cs.ns(|| "Prepare beta_g2"), &vk.beta_g2)?);
+
+        let product = P::product_of_pairings(cs.ns(|| "Product of pairings"), &g1s, &g2s)?;
+        let one = P::GTGadget::one(cs.ns(|| "one"))?;
+        product.enforce_equal(cs.ns(|| "Check product"), &one)
+    }
+}
+
+impl<PairingE, ConstraintF, P> Groth16VerifierGadget<PairingE, ConstraintF, P>
+where
+    PairingE: PairingEngine,
+    ConstraintF: PrimeField,
+    P: PairingGadget<PairingE, ConstraintF>,
+{
+    /// Verifies `proof` against `pvk` for public inputs given as elements of
+    /// the constraint field, rather than as the bits of `PairingE::Fr`
+    /// elements.
+    ///
+    /// Each input is decomposed into bits that are checked to encode an
+    /// integer smaller than both moduli, so that the input denotes the same
+    /// scalar that it does in the constraint field. If the scalar field is the
+    /// smaller one, an input that does not fit in it leaves the system
+    /// unsatisfied.
+    pub fn verify_with_field_inputs<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        pvk: &PreparedVerifyingKeyGadget<PairingE, ConstraintF, P>,
+        public_inputs: &[FpGadget<ConstraintF>],
+        proof: &ProofGadget<PairingE, ConstraintF, P>,
+    ) -> Result<(), SynthesisError> {
+        let scalar_field_is_smaller = is_smaller(
+            PairingE::Fr::characteristic(),
+            ConstraintF::characteristic(),
+        );
+
+        let mut input_bits = Vec::new();
+        for (i, input) in public_inputs.iter().enumerate() {
+            let mut cs = cs.ns(|| format!("Input {}", i));
+            let mut bits = input.to_non_unique_bits(cs.ns(|| "to bits"))?;
+            if scalar_field_is_smaller {
+                Boolean::enforce_in_field::<_, _, PairingE::Fr>(
+                    cs.ns(|| "in scalar field"),
+                    &bits,
+                )?;
+            } else {
+                Boolean::enforce_in_field::<_, _, ConstraintF>(cs.ns(|| "in field"), &bits)?;
+            }
+            // `to_non_unique_bits` is big-endian, but `mul_bits` expects
+            // little-endian bits.
+            bits.reverse();
+            input_bits.push(bits);
+        }
+
+        Self::conditional_verify_prepared(
+            cs.ns(|| "Verify"),
+            pvk,
+            input_bits.iter(),
+            proof,
+            &Boolean::constant(true),
+        )
+    }
+
+    /// Verifies `proof` against `pvk` for public inputs given as bytes. The
+    /// bytes are packed into scalars as `ToConstraintField<PairingE::Fr>` packs
+    /// a `[u8]`: each chunk of `CAPACITY / 8` bytes is read in little-endian
+    /// order, so every scalar is smaller than the modulus and no range check
+    /// is needed.
+    pub fn verify_from_bytes<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        pvk: &PreparedVerifyingKeyGadget<PairingE, ConstraintF, P>,
+        input_bytes: &[UInt8],
+        proof: &ProofGadget<PairingE, ConstraintF, P>,
+    ) -> Result<(), SynthesisError> {
+        let chunk_size = (<PairingE::Fr as PrimeField>::Params::CAPACITY / 8) as usize;
+        let input_bits = input_bytes
+            .chunks(chunk_size)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .flat_map(UInt8::into_bits_le)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        Self::conditional_verify_prepared(
+            cs.ns(|| "Verify"),
+            pvk,
+            input_bits.iter(),
+            proof,
+            &Boolean::constant(true),
+        )
+    }
+}
+
+/// Compares two integers given as little-endian limbs.
+fn is_smaller(a: &[u64], b: &[u64]) -> bool {
+    let limb = |x: &[u64], i: usize| x.get(i).copied().unwrap_or(0);
+    (0..a.len().max(b.len()))
+        .rev()
+        .map(|i| (limb(a, i), limb(b, i)))
+        .find(|(a, b)| a != b)
+        .map_or(false, |(a, b)| a < b)
+}
+
+impl<PairingE, ConstraintF, P, C, V> NIZKVerifierGadget<Groth16<PairingE, C, V>, ConstraintF>
+    for Groth16VerifierGadget<PairingE, ConstraintF, P>
+where
+    PairingE: PairingEngine,
+    ConstraintF: Field,
+    C: ConstraintSynthesizer<PairingE::Fr>,
+    V: ToConstraintField<PairingE::Fr>,
+    P: PairingGadget<PairingE, ConstraintF>,
+{
+    type PreparedVerificationKeyGadget = PreparedVerifyingKeyGadget<PairingE, ConstraintF, P>;
+    type VerificationKeyGadget = VerifyingKeyGadget<PairingE, ConstraintF, P>;
+    type ProofGadget = ProofGadget<PairingE, ConstraintF, P>;
+
+    fn check_verify<'a, CS, I, T>(
+        cs: CS,
+        vk: &Self::VerificationKeyGadget,
+        public_inputs: I,
+        proof: &Self::ProofGadget,
+    ) -> Result<(), SynthesisError>
+    where
+        CS: ConstraintSystem<ConstraintF>,
+        I: Iterator<Item = &'a T>,
+        T: 'a + ToBitsGadget<ConstraintF> + ?Sized,
+    {
+        <Self as NIZKVerifierGadget<Groth16<PairingE, C, V>, ConstraintF>>::conditional_check_verify(
+            cs,
+            vk,
+            public_inputs,
+            proof,
+            &Boolean::constant(true),
+        )
+    }
+
+    fn conditional_check_verify<'a, CS, I, T>(
+        mut cs: CS,
+        vk: &Self::VerificationKeyGadget,
+        public_inputs: I,
+        proof: &Self::ProofGadget,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError>
+    where
+        CS: ConstraintSystem<ConstraintF>,
+        I: Iterator<Item = &'a T>,
+        T: 'a + ToBitsGadget<ConstraintF> + ?Sized,
+    {
+        let pvk = vk.prepare(&mut cs.ns(|| "Prepare vk"))?;
+        <Self as NIZKVerifierGadget<Groth16<PairingE, C, V>, ConstraintF>>::conditional_check_verify_prepared(cs, &pvk, public_inputs, proof, condition)
+    }
+
+    fn conditional_check_verify_prepared<'a, CS, I, T>(
+        cs: CS,
+        pvk: &Self::PreparedVerificationKeyGadget,
+        public_inputs: I,
+        proof: &Self::ProofGadget,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError>
+    where
+        CS: ConstraintSystem<ConstraintF>,
+        I: Iterator<Item = &'a T>,
+        T: 'a + ToBitsGadget<ConstraintF> + ?Sized,
+    {
+        Self::conditional_verify_prepared(cs, pvk, public_inputs, proof, condition)
+    }
+}
+
+impl<PairingE, ConstraintF, P> AllocGadget<PreparedVerifyingKey<PairingE>, ConstraintF>
+    for PreparedVerifyingKeyGadget<PairingE, ConstraintF, P>
+where
+    PairingE: PairingEngine,
+    ConstraintF: Field,
+    P: PairingGadget<PairingE, ConstraintF>,
+    P::G2PreparedGadget: AllocGadget<PairingE::G2Prepared, ConstraintF>,
+{
+    fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        val: T,
+    ) -> Result<Self, SynthesisError>
+    where
+        T: Borrow<PreparedVerifyingKey<PairingE>>,
+    {
+        let pvk = val.borrow().clone();
+
+        let alpha_g1_beta_g2 =
+            P::GTGadget::alloc_constant(cs.ns(|| "alpha_g1_beta_g2"), pvk.alpha_g1_beta_g2)?;
+
+        let gamma_g2_neg_pc =
+            P::G2PreparedGadget::alloc_constant(cs.ns(|| "gamma_g2_neg_pc"), pvk.gamma_g2_neg_pc)?;
+
+        let delta_g2_neg_pc =
+            P::G2PreparedGadget::alloc_constant(cs.ns(|| "delta_g2_neg_pc"), pvk.delta_g2_neg_pc)?;
+
+        let mut gamma_abc_g1 = Vec::<P::G1Gadget>::new();
+        for (i, item) in pvk.gamma_abc_g1.iter().enumerate() {
+            gamma_abc_g1.push(P::G1Gadget::alloc_constant(
+                cs.ns(|| format!("query_{}", i)),
+                item.borrow().into_projective(),
+            )?);
+        }
+
+        Ok(Self {
+            alpha_g1_beta_g2,
+            gamma_g2_neg_pc,
+            delta_g2_neg_pc,
+            gamma_abc_g1,
+        })
+    }
+
+    fn alloc<F, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        f: F,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<PreparedVerifyingKey<PairingE>>,
+    {
+        let pvk = f()?.borrow().clone();
+
+        let alpha_g1_beta_g2 =
+            P::GTGadget::alloc(cs.ns(|| "alpha_g1_beta_g2"), || Ok(pvk.alpha_g1_beta_g2))?;
+
+        let gamma_g2_neg_pc =
+            P::G2PreparedGadget::alloc(cs.ns(|| "gamma_g2_neg_pc"), || Ok(&pvk.gamma_g2_neg_pc))?;
+
+        let delta_g2_neg_pc =
+            P::G2PreparedGadget::alloc(cs.ns(|| "delta_g2_neg_pc"), || Ok(&pvk.delta_g2_neg_pc))?;
+
+        let mut gamma_abc_g1 = Vec::<P::G1Gadget>::new();
+        for (i, item) in pvk.gamma_abc_g1.iter().enumerate() {
+            gamma_abc_g1.push(P::G1Gadget::alloc(
+                cs.ns(|| format!("query_{}", i)),
+                || Ok(item.borrow().into_projective()),
+            )?);
+        }
+
+        Ok(Self {
+            alpha_g1_beta_g2,
+            gamma_g2_neg_pc,
+            delta_g2_neg_pc,
+            gamma_abc_g1,
+        })
+    }
+
+    fn alloc_input<F, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        f: F,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<PreparedVerifyingKey<PairingE>>,
+    {
+        let pvk = f()?.borrow().clone();
+
+        let alpha_g1_beta_g2 =
+            P::GTGadget::alloc_input(cs.ns(|| "alpha_g1_beta_g2"), || Ok(pvk.alpha_g1_beta_g2))?;
+
+        let gamma_g2_neg_pc =
+            P::G2PreparedGadget::alloc_input(cs.ns(|| "gamma_g2_neg_pc"), || {
+                Ok(&pvk.gamma_g2_neg_pc)
+            })?;
+
+        let delta_g2_neg_pc =
+            P::G2PreparedGadget::alloc_input(cs.ns(|| "delta_g2_neg_pc"), || {
+                Ok(&pvk.delta_g2_neg_pc)
+            })?;
+
+        let mut gamma_abc_g1 = Vec::<P::G1Gadget>::new();
+        for (i, item) in pvk.gamma_abc_g1.iter().enumerate() {
+            gamma_abc_g1.push(P::G1Gadget::alloc_input(
+                cs.ns(|| format!("query_{}", i)),
+                || Ok(item.borrow().into_projective()),
+            )?);
+        }
+
+        Ok(Self {
+            alpha_g1_beta_g2,
+            gamma_g2_neg_pc,
+            delta_g2_neg_pc,
+            gamma_abc_g1,
+        })
+    }
+}
+
+impl<PairingE, ConstraintF, P> AllocGadget<VerifyingKey<PairingE>, ConstraintF>
+    for VerifyingKeyGadget<PairingE, ConstraintF, P>
+where
+    PairingE: PairingEngine,
+    ConstraintF: Field,
+    P: PairingGadget<PairingE, ConstraintF>,
+{
+    /// Embeds the verifying key as constants, without enforcing that its
+    /// points lie in the prime-order subgroups. This costs no constraints, but
+    /// is only sound when the verifying key is trusted, e.g. when it is fixed
+    /// at circuit setup time.
+    #[inline]
+    fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        val: T,
+    ) -> Result<Self, SynthesisError>
+    where
+        T: Borrow<VerifyingKey<PairingE>>,
+    {
+        let VerifyingKey {
+            alpha_g1,
+            beta_g2,
+            gamma_g2,
+            delta_g2,
+            gamma_abc_g1,
+        } = val.borrow().clone();
+        let alpha_g1 =
+            P::G1Gadget::alloc_constant(cs.ns(|| "alpha_g1"), alpha_g1.into_projective())?;
+        let beta_g2 = P::G2Gadget::alloc_constant(cs.ns(|| "beta_g2"), beta_g2.into_projective())?;
+        let gamma_g2 =
+            P::G2Gadget::alloc_constant(cs.ns(|| "gamma_g2"), gamma_g2.into_projective())?;
+        let delta_g2 =
+            P::G2Gadget::alloc_constant(cs.ns(|| "delta_g2"), delta_g2.into_projective())?;
+
+        let gamma_abc_g1 = gamma_abc_g1
+            .into_iter()
+            .enumerate()
+            .map(|(i, gamma_abc_i)| {
+                P::G1Gadget::alloc_constant(
+                    cs.ns(|| format!("gamma_abc_{}", i)),
+                    gamma_abc_i.into_projective(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            alpha_g1,
+            beta_g2,
+            gamma_g2,
+            delta_g2,
+            gamma_abc_g1,
+        })
+    }
+
+    #[inline]
+    fn alloc<FN, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        value_gen: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<VerifyingKey<PairingE>>,
+    {
+        value_gen().and_then(|vk| {
+            let VerifyingKey {
+                alpha_g1,
+                beta_g2,
+                gamma_g2,
+                delta_g2,
+                gamma_abc_g1,
+            } = vk.borrow().clone();
+            let alpha_g1 =
+                P::G1Gadget::alloc(cs.ns(|| "alpha_g1"), || Ok(alpha_g1.into_projective()))?;
+            let beta_g2 =
+                P::G2Gadget::alloc(cs.ns(|| "beta_g2"), || Ok(beta_g2.into_projective()))?;
+            let gamma_g2 =
+                P::G2Gadget::alloc(cs.ns(|| "gamma_g2"), || Ok(gamma_g2.into_projective()))?;
+            let delta_g2 =
+                P::G2Gadget::alloc(cs.ns(|| "delta_g2"), || Ok(delta_g2.into_projective()))?;
+
+            let gamma_abc_g1 = gamma_abc_g1
+                .into_iter()
+                .enumerate()
+                .map(|(i, gamma_abc_i)| {
+                    P::G1Gadget::alloc(cs.ns(|| format!("gamma_abc_{}", i)), || {
+                        Ok(gamma_abc_i.into_projective())
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .collect::<Result<_, _>>()?;
+            Ok(Self {
+                alpha_g1,
+                beta_g2,
+                gamma_g2,
+                delta_g2,
+                gamma_abc_g1,
+            })
+        })
+    }
+
+    /// Allocates the verifying key as a witness, enforcing that every point
+    /// lies in the prime-order subgroup of its curve. Use this instead of
+    /// `alloc` when the verifying key is not trusted.
+    #[inline]
+    fn alloc_checked<FN, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        value_gen: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<VerifyingKey<PairingE>>,
+    {
+        value_gen().and_then(|vk| {
+            let VerifyingKey {
+                alpha_g1,
+                beta_g2,
+                gamma_g2,
+                delta_g2,
+                gamma_abc_g1,
+            } = vk.borrow().clone();
+            let alpha_g1 = P::G1Gadget::alloc_checked(cs.ns(|| "alpha_g1"), || {
+                Ok(alpha_g1.into_projective())
+            })?;
+            let beta_g2 =
+                P::G2Gadget::alloc_checked(cs.ns(|| "beta_g2"), || Ok(beta_g2.into_projective()))?;
+            let gamma_g2 = P::G2Gadget::alloc_checked(cs.ns(|| "gamma_g2"), || {
+                Ok(gamma_g2.into_projective())
+            })?;
+            let delta_g2 = P::G2Gadget::alloc_checked(cs.ns(|| "delta_g2"), || {
+                Ok(delta_g2.into_projective())
+            })?;
+
+            let gamma_abc_g1 = gamma_abc_g1
+                .into_iter()
+                .enumerate()
+                .map(|(i, gamma_abc_i)| {
+                    P::G1Gadget::alloc_checked(cs.ns(|| format!("gamma_abc_{}", i)), || {
+                        Ok(gamma_abc_i.into_projective())
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .collect::<Result<_, _>>()?;
+            Ok(Self {
+                alpha_g1,
+                beta_g2,
+                gamma_g2,
+                delta_g2,
+                gamma_abc_g1,
+            })
+        })
+    }
+
+    #[inline]
+    fn alloc_input<FN, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        value_gen: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<VerifyingKey<PairingE>>,
+    {
+        value_gen().and_then(|vk| {
+            let VerifyingKey {
+                alpha_g1,
+                beta_g2,
+                gamma_g2,
+                delta_g2,
+                gamma_abc_g1,
+            } = vk.borrow().clone();
+            let alpha_g1 =
+                P::G1Gadget::alloc_input(cs.ns(|| "alpha_g1"), || Ok(alpha_g1.into_projective()))?;
+            let beta_g2 =
+                P::G2Gadget::alloc_input(cs.ns(|| "beta_g2"), || Ok(beta_g2.into_projective()))?;
+            let gamma_g2 =
+                P::G2Gadget::alloc_input(cs.ns(|| "gamma_g2"), || Ok(gamma_g2.into_projective()))?;
+            let delta_g2 =
+                P::G2Gadget::alloc_input(cs.ns(|| "delta_g2"), || Ok(delta_g2.into_projective()))?;
+
+            let gamma_abc_g1 = gamma_abc_g1
+                .into_iter()
+                .enumerate()
+                .map(|(i, gamma_abc_i)| {
+                    P::G1Gadget::alloc_input(cs.ns(|| format!("gamma_abc_{}", i)), || {
+                        Ok(gamma_abc_i.into_projective())
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .collect::<Result<_, _>>()?;
+
+            Ok(Self {
+                alpha_g1,
+                beta_g2,
+                gamma_g2,
+                delta_g2,
+                gamma_abc_g1,
+            })
+        })
+    }
+}
+
+impl<PairingE, ConstraintF, P> AllocGadget<Proof<PairingE>, ConstraintF>
+    for ProofGadget<PairingE, ConstraintF, P>
+where
+    PairingE: PairingEngine,
+    ConstraintF: Field,
+    P: PairingGadget<PairingE, ConstraintF>,
+{
+    #[inline]
+    fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        val: T,
+    ) -> Result<Self, SynthesisError>
+    where
+        T: Borrow<Proof<PairingE>>,
+    {
+        let Proof { a, b, c } = val.borrow().clone();
+        let a = P::G1Gadget::alloc_constant(cs.ns(|| "a"), a.into_projective())?;
+        let b = P::G2Gadget::alloc_constant(cs.ns(|| "b"), b.into_projective())?;
+        let c = P::G1Gadget::alloc_constant(cs.ns(|| "c"), c.into_projective())?;
+        Ok(Self { a, b, c })
+    }
+
+    #[inline]
+    fn alloc<FN, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        value_gen: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<Proof<PairingE>>,
+    {
+        value_gen().and_then(|proof| {
+            let Proof { a, b, c } = proof.borrow().clone();
+            let a = P::G1Gadget::alloc_checked(cs.ns(|| "a"), || Ok(a.into_projective()))?;
+            let b = P::G2Gadget::alloc_checked(cs.ns(|| "b"), || Ok(b.into_projective()))?;
+            let c = P::G1Gadget::alloc_checked(cs.ns(|| "c"), || Ok(c.into_projective()))?;
+            Ok(Self { a, b, c })
+        })
+    }
+
+    #[inline]
+    fn alloc_input<FN, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        value_gen: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<Proof<PairingE>>,
+    {
+        value_gen().and_then(|proof| {
+            let Proof { a, b, c } = proof.borrow().clone();
+            // We don't need to check here because the prime order check can be performed
+            // in plain.
+            let a = P::G1Gadget::alloc_input(cs.ns(|| "a"), || Ok(a.into_projective()))?;
+            let b = P::G2Gadget::alloc_input(cs.ns(|| "b"), || Ok(b.into_projective()))?;
+            let c = P::G1Gadget::alloc_input(cs.ns(|| "c"), || Ok(c.into_projective()))?;
+            Ok(Self { a, b, c })
+        })
+    }
+}
+
+impl<PairingE, ConstraintF, P> ConditionalEqGadget<ConstraintF>
+    for ProofGadget<PairingE, ConstraintF, P>
+where
+    PairingE: PairingEngine,
+    ConstraintF: Field,
+    P: PairingGadget<PairingE, ConstraintF>,
+{
+    #[inline]
+    fn conditional_enforce_equal<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError> {
+        self.a
+            .conditional_enforce_equal(&mut cs.ns(|| "a equality"), &other.a, condition)?;
+        self.b
+            .conditional_enforce_equal(&mut cs.ns(|| "b equality"), &other.b, condition)?;
+        self.c
+            .conditional_enforce_equal(&mut cs.ns(|| "c equality"), &other.c, condition)?;
+        Ok(())
+    }
+
+    fn cost() -> usize {
+        2 * <P::G1Gadget as ConditionalEqGadget<ConstraintF>>::cost()
+            + <P::G2Gadget as ConditionalEqGadget<ConstraintF>>::cost()
+    }
+}
+
+impl<PairingE, ConstraintF, P> EqGadget<ConstraintF> for ProofGadget<PairingE, ConstraintF, P>
+where
+    PairingE: PairingEngine,
+    ConstraintF: Field,
+    P: PairingGadget<PairingE, ConstraintF>,
+{
+}
+
+impl<PairingE, ConstraintF, P> CondSelectGadget<ConstraintF>
+    for ProofGadget<PairingE, ConstraintF, P>
+where
+    PairingE: PairingEngine,
+    ConstraintF: Field,
+    P: PairingGadget<PairingE, ConstraintF>,
+{
+    #[inline]
+    fn conditionally_select<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        cond: &Boolean,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let a = P::G1Gadget::conditionally_select(
+            &mut cs.ns(|| "Select a"),
+            cond,
+            &true_value.a,
+            &false_value.a,
+        )?;
+        let b = P::G2Gadget::conditionally_select(
+            &mut cs.ns(|| "Select b"),
+            cond,
+            &true_value.b,
+            &false_value.b,
+        )?;
+        let c = P::G1Gadget::conditionally_select(
+            &mut cs.ns(|| "Select c"),
+            cond,
+            &true_value.c,
+            &false_value.c,
+        )?;
+        Ok(Self { a, b, c })
+    }
+
+    fn cost() -> usize {
+        2 * <P::G1Gadget as CondSelectGadget<ConstraintF>>::cost()
+            + <P::G2Gadget as CondSelectGadget<ConstraintF>>::cost()
+    }
+}
+
+impl<PairingE, ConstraintF, P> ToBytesGadget<ConstraintF>
+    for VerifyingKeyGadget<PairingE, ConstraintF, P>
+where
+    PairingE: PairingEngine,
+    ConstraintF: Field,
+    P: PairingGadget<PairingE, ConstraintF>,
+{
+    #[inline]
+    fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Vec<UInt8>, SynthesisError> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&self.alpha_g1.to_bytes(&mut cs.ns(|| "alpha_g1 to bytes"))?);
+        bytes.extend_from_slice(&self.beta_g2.to_bytes(&mut cs.ns(|| "beta_g2 to bytes"))?);
+        bytes.extend_from_slice(&self.gamma_g2.to_bytes(&mut cs.ns(|| "gamma_g2 to bytes"))?);
+        bytes.extend_from_slice(&self.delta_g2.to_bytes(&mut cs.ns(|| "delta_g2 to bytes"))?);
+        for (i, g) in self.gamma_abc_g1.iter().enumerate() {
+            let mut cs = cs.ns(|| format!("Iteration {}", i));
+            bytes.extend_from_slice(&g.to_bytes(&mut cs.ns(|| "g"))?);
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use groth16::*;
+    use r1cs_core::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+
+    use super::*;
+    use algebra::{
+        bls12_377::{Bls12_377, Fq, Fr},
+        test_rng, BitIterator, PrimeField,
+    };
+    use r1cs_std::{
+        bls12_377::{Fq12Gadget, PairingGadget as Bls12_377PairingGadget},
+        boolean::Boolean,
+        test_constraint_system::TestConstraintSystem,
+    };
+    use rand::Rng;
+
+    type TestProofSystem = Groth16<Bls12_377, Bench<Fr>, Fr>;
+    type TestVerifierGadget = Groth16VerifierGadget<Bls12_377, Fq, Bls12_377PairingGadget>;
+    type TestProofGadget = ProofGadget<Bls12_377, Fq, Bls12_377PairingGadget>;
+    type TestVkGadget = VerifyingKeyGadget<Bls12_377, Fq, Bls12_377PairingGadget>;
+    type TestPvkGadget = PreparedVerifyingKeyGadget<Bls12_377, Fq, Bls12_377PairingGadget>;
+
+    struct Bench<F: Field> {
+        inputs: Vec<Option<F>>,
+        num_constraints: usize,
+    }
+
+    impl<F: Field> ConstraintSynthesizer<F> for Bench<F> {
+        fn generate_constraints<CS: ConstraintSystem<F>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            assert!(self.inputs.len() >= 2);
+            assert!(self.num_constraints >= self.inputs.len());
+
+            let mut variables: Vec<_> = Vec::with_capacity(self.inputs.len());
+            for (i, input) in self.inputs.into_iter().enumerate() {
+                let input_var = cs.alloc_input(
+                    || format!("Input {}", i),
+                    || input.ok_or(SynthesisError::AssignmentMissing),
+                )?;
+                variables.push((input, input_var));
+            }
+
+            for i in 0..self.num_constraints {
+                let new_entry = {
+                    let (input_1_val, input_1_var) = variables[i];
+                    let (input_2_val, input_2_var) = variables[i + 1];
+                    let result_val = input_1_val
+                        .and_then(|input_1| input_2_val.map(|input_2| input_1 * &input_2));
+                    let result_var = cs.alloc(
+                        || format!("Result {}", i),
+                        || result_val.ok_or(SynthesisError::AssignmentMissing),
+                    )?;
+                    cs.enforce(
+                        || format!("Enforce constraint {}", i),
+                        |lc| lc + input_1_var,
+                        |lc| lc + input_2_var,
+                        |lc| lc + result_var,
+                    );
+                    (result_val, result_var)
+                };
+                variables.push(new_entry);
+            }
+            Ok(())
+        }
+    }
+
+    /// Generates parameters for a `Bench` circuit with `num_inputs` inputs and
+    /// as many constraints.
+    fn setup_bench<R: Rng>(num_inputs: usize, rng: &mut R) -> Parameters<Bls12_377> {
+        let c = Bench::<Fr> {
+            inputs: vec![None; num_inputs],
+            num_constraints: num_inputs,
+        };
+        generate_random_parameters(c, rng).unwrap()
+    }
+
+    /// Proves the `Bench` circuit set up by `setup_bench` for `inputs`.
+    fn prove_bench<R: Rng>(
+        params: &Parameters<Bls12_377>,
+        inputs: &[Fr],
+        rng: &mut R,
+    ) -> Proof<Bls12_377> {
+        let c = Bench {
+            inputs: inputs.iter().cloned().map(Some).collect(),
+            num_constraints: inputs.len(),
+        };
+        create_random_proof(c, params, rng).unwrap()
+    }
+
+    /// Allocates each of `inputs` as public little-endian bits.
+    fn alloc_input_bits<CS: ConstraintSystem<Fq>>(mut cs: CS, inputs: &[Fr]) -> Vec<Vec<Boolean>> {
+        inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| {
+                let mut input_bits = BitIterator::new(input.into_repr()).collect::<Vec<_>>();
+                // Input must be in little-endian, but BitIterator outputs in big-endian.
+                input_bits.reverse();
+                Vec::<Boolean>::alloc_input(cs.ns(|| format!("Input {}", i)), || Ok(input_bits))
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn groth16_verifier_test() {
+        let num_inputs = 100;
+        let num_constraints = num_inputs;
+        let rng = &mut test_rng();
+        let mut inputs: Vec<Option<Fr>> = Vec::with_capacity(num_inputs);
+        for _ in 0..num_inputs {
+            inputs.push(Some(rng.gen()));
+        }
+        let params = {
+            let c = Bench::<Fr> {
+                inputs: vec![None; num_inputs],
+                num_constraints,
+            };
+
+            generate_random_parameters(c, rng).unwrap()
+        };
+
+        {
+            let proof = {
+                // Create an instance of our circuit (with the
+                // witness)
+                let c = Bench {
+                    inputs: inputs.clone(),
+                    num_constraints,
+                };
+                // Create a groth16 proof with our parameters.
+                create_random_proof(c, &params, rng).unwrap()
+            };
+
+            // assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
+            let mut cs = TestConstraintSystem::<Fq>::new();
+
+            let inputs: Vec<_> = inputs.into_iter().map(|input| input.unwrap()).collect();
+            let mut input_gadgets = Vec::new();
+
+            {
+                let mut cs = cs.ns(|| "Allocate Input");
+                for (i, input) in inputs.into_iter().enumerate() {
+                    let mut input_bits = BitIterator::new(input.into_repr()).collect::<Vec<_>>();
+                    // Input must be in little-endian, but BitIterator outputs in big-endian.
+                    input_bits.reverse();
+
+                    let input_bits =
+                        Vec::<Boolean>::alloc_input(cs.ns(|| format!("Input {}", i)), || {
+                            Ok(input_bits)
+                        })
+                        .unwrap();
+                    input_gadgets.push(input_bits);
+                }
+            }
+
+            let vk_gadget = TestVkGadget::alloc_input(cs.ns(|| "Vk"), || Ok(&params.vk)).unwrap();
+            let proof_gadget =
+                TestProofGadget::alloc(cs.ns(|| "Proof"), || Ok(proof.clone())).unwrap();
+            println!("Time to verify!\n\n\n\n");
+            <TestVerifierGadget as NIZKVerifierGadget<TestProofSystem, Fq>>::check_verify(
+                cs.ns(|| "Verify"),
+                &vk_gadget,
+                input_gadgets.iter(),
+                &proof_gadget,
+            )
+            .unwrap();
+            if !cs.is_satisfied() {
+                println!("=========================================================");
+                println!("Unsatisfied constraints:");
+                println!("{:?}", cs.which_is_unsatisfied().unwrap());
+                println!("=========================================================");
+            }
+
+            // cs.print_named_objects();
+            assert!(cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn groth16_constant_vk_test() {
+        let num_inputs = 2;
+        let rng = &mut test_rng();
+        let params = setup_bench(num_inputs, rng);
+
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let vk_gadget = TestVkGadget::alloc_constant(cs.ns(|| "Constant vk"), &params.vk).unwrap();
+        assert_eq!(cs.num_constraints(), 0);
+        assert_eq!(vk_gadget.gamma_abc_g1.len(), num_inputs + 1);
+
+        let num_constraints = cs.num_constraints();
+        TestVkGadget::alloc(cs.ns(|| "Vk"), || Ok(&params.vk)).unwrap();
+        let unchecked_cost = cs.num_constraints() - num_constraints;
+
+        let num_constraints = cs.num_constraints();
+        TestVkGadget::alloc_checked(cs.ns(|| "Checked vk"), || Ok(&params.vk)).unwrap();
+        let checked_cost = cs.num_constraints() - num_constraints;
+
+        assert!(cs.is_satisfied());
+        assert!(unchecked_cost > 0);
+        assert!(checked_cost > unchecked_cost);
+    }
+
+    #[test]
+    fn groth16_select_proof_test() {
+        let num_inputs = 2;
+        let rng = &mut test_rng();
+        let params = setup_bench(num_inputs, rng);
+
+        let mut instances = Vec::new();
+        for _ in 0..2 {
+            let inputs: Vec<Fr> = (0..num_inputs).map(|_| rng.gen()).collect();
+            let proof = prove_bench(&params, &inputs, rng);
+            instances.push((inputs, proof));
+        }
+
+        for &selector in &[true, false] {
+            let mut cs = TestConstraintSystem::<Fq>::new();
+            let vk_gadget = TestVkGadget::alloc_input(cs.ns(|| "Vk"), || Ok(&params.vk)).unwrap();
+            let first_gadget =
+                TestProofGadget::alloc(cs.ns(|| "First proof"), || Ok(&instances[0].1)).unwrap();
+            let second_gadget =
+                TestProofGadget::alloc(cs.ns(|| "Second proof"), || Ok(&instances[1].1)).unwrap();
+            let cond = Boolean::alloc(cs.ns(|| "Condition"), || Ok(selector)).unwrap();
+
+            let selected = TestProofGadget::conditionally_select(
+                cs.ns(|| "Select proof"),
+                &cond,
+                &first_gadget,
+                &second_gadget,
+            )
+            .unwrap();
+            let (expected_gadget, inputs) = if selector {
+                (&first_gadget, &instances[0].0)
+            } else {
+                (&second_gadget, &instances[1].0)
+            };
+            selected
+                .enforce_equal(cs.ns(|| "Selected equality"), expected_gadget)
+                .unwrap();
+
+            let input_gadgets = alloc_input_bits(&mut cs, inputs);
+            <TestVerifierGadget as NIZKVerifierGadget<TestProofSystem, Fq>>::check_verify(
+                cs.ns(|| "Verify"),
+                &vk_gadget,
+                input_gadgets.iter(),
+                &selected,
+            )
+            .unwrap();
+            assert!(cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn groth16_verifier_input_length_test() {
+        let num_inputs = 2;
+        let rng = &mut test_rng();
+        let params = setup_bench(num_inputs, rng);
+        let inputs: Vec<Fr> = (0..num_inputs).map(|_| rng.gen()).collect();
+        let proof = prove_bench(&params, &inputs, rng);
+
+        for &num_given in &[num_inputs - 1, num_inputs + 1] {
+            let mut cs = TestConstraintSystem::<Fq>::new();
+            let given_inputs = (0..num_given)
+                .map(|i| inputs.get(i).cloned().unwrap_or_else(|| rng.gen()))
+                .collect::<Vec<_>>();
+            let input_gadgets = alloc_input_bits(&mut cs, &given_inputs);
+            let vk_gadget = TestVkGadget::alloc_input(cs.ns(|| "Vk"), || Ok(&params.vk)).unwrap();
+            let proof_gadget =
+                TestProofGadget::alloc(cs.ns(|| "Proof"), || Ok(proof.clone())).unwrap();
+            match <TestVerifierGadget as NIZKVerifierGadget<TestProofSystem, Fq>>::check_verify(
+                cs.ns(|| "Verify"),
+                &vk_gadget,
+                input_gadgets.iter(),
+                &proof_gadget,
+            ) {
+                Err(SynthesisError::MalformedVerifyingKey) => {},
+                _ => panic!("expected MalformedVerifyingKey for {} inputs", num_given),
+            }
+        }
+    }
+
+    #[test]
+    fn groth16_verifier_propagates_errors_test() {
+        use alloc::string::String;
+        use r1cs_core::{LinearCombination, Variable};
+
+        /// Forwards to a `TestConstraintSystem`, but fails every allocation
+        /// made inside a namespace named `fail_in`.
+        struct FailingConstraintSystem {
+            inner: TestConstraintSystem<Fq>,
+            namespaces: Vec<String>,
+            fail_in: &'static str,
+        }
+
+        impl ConstraintSystem<Fq> for FailingConstraintSystem {
+            type Root = Self;
+
+            fn alloc<FN, A, AR>(&mut self, annotation: A, f: FN) -> Result<Variable, SynthesisError>
+            where
+                FN: FnOnce() -> Result<Fq, SynthesisError>,
+                A: FnOnce() -> AR,
+                AR: Into<String>,
+            {
+                if self.namespaces.iter().any(|n| n == self.fail_in) {
+                    return Err(SynthesisError::AssignmentMissing);
+                }
+                self.inner.alloc(annotation, f)
+            }
+
+            fn alloc_input<FN, A, AR>(
+                &mut self,
+                annotation: A,
+                f: FN,
+            ) -> Result<Variable, SynthesisError>
+            where
+                FN: FnOnce() -> Result<Fq, SynthesisError>,
+                A: FnOnce() -> AR,
+                AR: Into<String>,
+            {
+                self.inner.alloc_input(annotation, f)
+            }
+
+            fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
+            where
+                A: FnOnce() -> AR,
+                AR: Into<String>,
+                LA: FnOnce(LinearCombination<Fq>) -> LinearCombination<Fq>,
+                LB: FnOnce(LinearCombination<Fq>) -> LinearCombination<Fq>,
+                LC: FnOnce(LinearCombination<Fq>) -> LinearCombination<Fq>,
+            {
+                self.inner.enforce(annotation, a, b, c)
+            }
+
+            fn push_namespace<NR, N>(&mut self, name_fn: N)
+            where
+                NR: Into<String>,
+                N: FnOnce() -> NR,
+            {
+                let name = name_fn().into();
+                self.namespaces.push(name.clone());
+                self.inner.push_namespace(|| name);
+            }
+
+            fn pop_namespace(&mut self) {
+                self.namespaces.pop();
+                self.inner.pop_namespace();
+            }
+
+            fn get_root(&mut self) -> &mut Self::Root {
+                self
+            }
+
+            fn num_constraints(&self) -> usize {
+                self.inner.num_constraints()
+            }
+        }
+
+        let num_inputs = 2;
+        let rng = &mut test_rng();
+        let params = setup_bench(num_inputs, rng);
+        let inputs: Vec<Fr> = (0..num_inputs).map(|_| rng.gen()).collect();
+        let proof = prove_bench(&params, &inputs, rng);
+
+        // Make the final exponentiation fail.
+        let mut cs = FailingConstraintSystem {
+            inner: TestConstraintSystem::new(),
+            namespaces: Vec::new(),
+            fail_in: "Final Exp",
+        };
+        let input_gadgets = alloc_input_bits(&mut cs, &inputs);
+        let vk_gadget = TestVkGadget::alloc_input(cs.ns(|| "Vk"), || Ok(&params.vk)).unwrap();
+        let proof_gadget = TestProofGadget::alloc(cs.ns(|| "Proof"), || Ok(proof)).unwrap();
+        match <TestVerifierGadget as NIZKVerifierGadget<TestProofSystem, Fq>>::check_verify(
+            cs.ns(|| "Verify"),
+            &vk_gadget,
+            input_gadgets.iter(),
+            &proof_gadget,
+        ) {
+            Err(SynthesisError::AssignmentMissing) => {},
+            _ => panic!("expected the final exponentiation to fail"),
+        }
+    }
+
+    #[test]
+    fn groth16_verifier_shared_vk_test() {
+        let num_inputs = 2;
+        let rng = &mut test_rng();
+        let params = setup_bench(num_inputs, rng);
+
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let vk_gadget = TestVkGadget::alloc_input(cs.ns(|| "Vk"), || Ok(&params.vk)).unwrap();
+
+        let mut instances = Vec::new();
+        for i in 0..2 {
+            let inputs: Vec<Fr> = (0..num_inputs).map(|_| rng.gen()).collect();
+            let proof = prove_bench(&params, &inputs, rng);
+
+            let mut cs = cs.ns(|| format!("Instance {}", i));
+            let input_gadgets = alloc_input_bits(&mut cs, &inputs);
+            let proof_gadget = TestProofGadget::alloc(cs.ns(|| "Proof"), || Ok(proof)).unwrap();
+            instances.push((input_gadgets, proof_gadget));
+        }
+
+        // Prepare the verifying key once, and verify both proofs against it.
+        let num_constraints = cs.num_constraints();
+        let pvk_gadget = vk_gadget.prepare(cs.ns(|| "Prepare vk")).unwrap();
+        let prepare_cost = cs.num_constraints() - num_constraints;
+
+        let mut verify_costs = Vec::new();
+        for (i, (input_gadgets, proof_gadget)) in instances.iter().enumerate() {
+            let num_constraints = cs.num_constraints();
+            <TestVerifierGadget as NIZKVerifierGadget<TestProofSystem, Fq>>::conditional_check_verify_prepared(
+                cs.ns(|| format!("Verify prepared {}", i)),
+                &pvk_gadget,
+                input_gadgets.iter(),
+                proof_gadget,
+                &Boolean::constant(true),
+            )
+            .unwrap();
+            verify_costs.push(cs.num_constraints() - num_constraints);
+        }
+
+        // Verifying against the unprepared key prepares it again.
+        let (input_gadgets, proof_gadget) = &instances[0];
+        let num_constraints = cs.num_constraints();
+        <TestVerifierGadget as NIZKVerifierGadget<TestProofSystem, Fq>>::check_verify(
+            cs.ns(|| "Verify"),
+            &vk_gadget,
+            input_gadgets.iter(),
+            proof_gadget,
+        )
+        .unwrap();
+        let full_cost = cs.num_constraints() - num_constraints;
+
+        assert!(cs.is_satisfied());
+        assert_eq!(verify_costs[0], verify_costs[1]);
+        assert_eq!(full_cost, prepare_cost + verify_costs[0]);
+    }
+
+    #[test]
+    fn groth16_prepared_vk_from_bytes_test() {
+        use algebra::{to_bytes, ToBytes};
+        use r1cs_std::uint8::UInt8;
+
+        let num_inputs = 2;
+        let rng = &mut test_rng();
+        let params = setup_bench(num_inputs, rng);
+        let inputs: Vec<Fr> = (0..num_inputs).map(|_| rng.gen()).collect();
+        let proof = prove_bench(&params, &inputs, rng);
+
+        let mut pvk_bytes = Vec::new();
+        prepare_verifying_key(&params.vk)
+            .write(&mut pvk_bytes)
+            .unwrap();
+
+        let verify_from_bytes = |pvk_bytes: &[u8]| {
+            let mut cs = TestConstraintSystem::<Fq>::new();
+            let bytes = UInt8::alloc_vec(cs.ns(|| "Pvk bytes"), pvk_bytes).unwrap();
+            let pvk_gadget = TestPvkGadget::from_bytes(cs.ns(|| "Parse pvk"), &bytes).unwrap();
+
+            let input_gadgets = alloc_input_bits(&mut cs, &inputs);
+            let proof_gadget =
+                TestProofGadget::alloc(cs.ns(|| "Proof"), || Ok(proof.clone())).unwrap();
+            <TestVerifierGadget as NIZKVerifierGadget<TestProofSystem, Fq>>::conditional_check_verify_prepared(
+                cs.ns(|| "Verify"),
+                &pvk_gadget,
+                input_gadgets.iter(),
+                &proof_gadget,
+                &Boolean::constant(true),
+            )
+            .unwrap();
+            cs.is_satisfied()
+        };
+
+        assert!(verify_from_bytes(&pvk_bytes));
+
+        // The prepared values of another key, in place of the recomputed ones.
+        let other_pvk = prepare_verifying_key(&setup_bench(num_inputs, rng).vk);
+        let mut other_pvk_bytes = Vec::new();
+        other_pvk.write(&mut other_pvk_bytes).unwrap();
+        let vk_len = to_bytes![params.vk].unwrap().len();
+        let gt_len = to_bytes![other_pvk.alpha_g1_beta_g2].unwrap().len();
+        let mut tampered = pvk_bytes.clone();
+        tampered[vk_len..vk_len + gt_len]
+            .copy_from_slice(&other_pvk_bytes[vk_len..vk_len + gt_len]);
+        assert!(!verify_from_bytes(&tampered));
+
+        // Another verifying key in front of the original prepared values.
+        let mut tampered = pvk_bytes.clone();
+        tampered[..vk_len].copy_from_slice(&other_pvk_bytes[..vk_len]);
+        assert!(!verify_from_bytes(&tampered));
+
+        // An `alpha_g1` that is not on the curve.
+        let mut tampered = pvk_bytes.clone();
+        tampered[0] ^= 1;
+        assert!(!verify_from_bytes(&tampered));
+
+        // Tamper with the last point of `gamma_abc_g1`.
+        let len = pvk_bytes.len();
+        pvk_bytes[len - 2] ^= 1;
+        assert!(!verify_from_bytes(&pvk_bytes));
+
+        // Bytes of the wrong length are rejected.
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let bytes = UInt8::alloc_vec(cs.ns(|| "Pvk bytes"), &pvk_bytes[..len - 1]).unwrap();
+        match TestPvkGadget::from_bytes(cs.ns(|| "Parse pvk"), &bytes) {
+            Err(SynthesisError::InvalidArgument) => {},
+            _ => panic!("expected InvalidArgument for truncated bytes"),
+        }
+    }
+
+    #[test]
+    fn groth16_constant_key_inputs_test() {
+        let num_inputs = 32;
+        let rng = &mut test_rng();
+        let inputs: Vec<Fr> = (0..num_inputs).map(|_| rng.gen()).collect();
+        let params = setup_bench(num_inputs, rng);
+        let proof = prove_bench(&params, &inputs, rng);
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let input_gadgets = alloc_input_bits(&mut cs, &inputs);
+        let vk_gadget = TestVkGadget::alloc_constant(cs.ns(|| "Vk"), &params.vk).unwrap();
+
+        let before = cs.num_constraints();
+        let g_ic = TestVerifierGadget::process_inputs(
+            cs.ns(|| "Process input"),
+            &vk_gadget.gamma_abc_g1,
+            input_gadgets.iter(),
+        )
+        .unwrap();
+        let mul_bits_cost = cs.num_constraints() - before;
+
+        let before = cs.num_constraints();
+        let g_ic_constant_key = TestVerifierGadget::process_inputs_with_constant_key(
+            cs.ns(|| "Process input with constant key"),
+            &params.vk.gamma_abc_g1,
+            input_gadgets.iter(),
+        )
+        .unwrap();
+        let precomputed_cost = cs.num_constraints() - before;
+
+        assert!(precomputed_cost < mul_bits_cost);
+        assert_eq!(g_ic.get_value(), g_ic_constant_key.get_value());
+
+        let proof_gadget = TestProofGadget::alloc(cs.ns(|| "Proof"), || Ok(&proof)).unwrap();
+        TestVerifierGadget::conditional_verify_with_constant_key(
+            cs.ns(|| "Verify"),
+            &pvk,
+            input_gadgets.iter(),
+            &proof_gadget,
+            &Boolean::constant(true),
+        )
+        .unwrap();
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn groth16_verifier_byte_inputs_test() {
+        let rng = &mut test_rng();
+        // Three scalars, the last one partially filled.
+        let input_bytes = (0..70).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let inputs: Vec<Fr> = input_bytes.to_field_elements().unwrap();
+        assert_eq!(inputs.len(), 3);
+
+        let params = setup_bench(inputs.len(), rng);
+        let proof = prove_bench(&params, &inputs, rng);
+
+        let verify = |input_bytes: &[u8]| {
+            let mut cs = TestConstraintSystem::<Fq>::new();
+            let vk_gadget = TestVkGadget::alloc_input(cs.ns(|| "Vk"), || Ok(&params.vk)).unwrap();
+            let pvk_gadget = vk_gadget.prepare(cs.ns(|| "Prepare vk")).unwrap();
+            let proof_gadget =
+                TestProofGadget::alloc(cs.ns(|| "Proof"), || Ok(proof.clone())).unwrap();
+
+            // The bytes, verified directly.
+            let byte_gadgets = UInt8::alloc_input_vec(cs.ns(|| "Bytes"), input_bytes).unwrap();
+            TestVerifierGadget::verify_from_bytes(
+                cs.ns(|| "Verify bytes"),
+                &pvk_gadget,
+                &byte_gadgets,
+                &proof_gadget,
+            )
+            .unwrap();
+            let bytes_satisfied = cs.is_satisfied();
+
+            // The same inputs, packed natively and given as bits.
+            let inputs = ToConstraintField::<Fr>::to_field_elements(input_bytes).unwrap();
+            let input_gadgets = alloc_input_bits(&mut cs, &inputs);
+            <TestVerifierGadget as NIZKVerifierGadget<TestProofSystem, Fq>>::conditional_check_verify_prepared(
+                cs.ns(|| "Verify bits"),
+                &pvk_gadget,
+                input_gadgets.iter(),
+                &proof_gadget,
+                &Boolean::constant(true),
+            )
+            .unwrap();
+            (bytes_satisfied, cs.is_satisfied())
+        };
+
+        assert_eq!(verify(&input_bytes), (true, true));
+
+        let mut wrong_bytes = input_bytes.clone();
+        wrong_bytes[40] ^= 1;
+        assert_eq!(verify(&wrong_bytes), (false, false));
+    }
+
+    #[test]
+    fn groth16_verifier_field_inputs_test() {
+        use algebra::{FpParameters, One};
+
+        let num_inputs = 2;
+        let rng = &mut test_rng();
+        let params = setup_bench(num_inputs, rng);
+        let inputs: Vec<Fr> = (0..num_inputs).map(|_| rng.gen()).collect();
+        let proof = prove_bench(&params, &inputs, rng);
+
+        let prepare = |cs: &mut TestConstraintSystem<Fq>| {
+            let vk_gadget = TestVkGadget::alloc_input(cs.ns(|| "Vk"), || Ok(&params.vk)).unwrap();
+            let pvk_gadget = vk_gadget.prepare(cs.ns(|| "Prepare vk")).unwrap();
+            let proof_gadget =
+                TestProofGadget::alloc(cs.ns(|| "Proof"), || Ok(proof.clone())).unwrap();
+            (pvk_gadget, proof_gadget)
+        };
+
+        let verify_with_bits = |inputs: &[Fr]| {
+            let mut cs = TestConstraintSystem::<Fq>::new();
+            let (pvk_gadget, proof_gadget) = prepare(&mut cs);
+            let input_gadgets = alloc_input_bits(&mut cs, &inputs);
+            <TestVerifierGadget as NIZKVerifierGadget<TestProofSystem, Fq>>::conditional_check_verify_prepared(
+                cs.ns(|| "Verify"),
+                &pvk_gadget,
+                input_gadgets.iter(),
+                &proof_gadget,
+                &Boolean::constant(true),
+            )
+            .unwrap();
+            cs.is_satisfied()
+        };
+
+        let verify_with_field_elements = |inputs: &[Fq]| {
+            let mut cs = TestConstraintSystem::<Fq>::new();
+            let (pvk_gadget, proof_gadget) = prepare(&mut cs);
+            let mut input_gadgets = Vec::new();
+            for (i, input) in inputs.iter().enumerate() {
+                input_gadgets.push(
+                    FpGadget::alloc_input(cs.ns(|| format!("Input {}", i)), || Ok(input)).unwrap(),
+                );
+            }
+            TestVerifierGadget::verify_with_field_inputs(
+                cs.ns(|| "Verify"),
+                &pvk_gadget,
+                &input_gadgets,
+                &proof_gadget,
+            )
+            .unwrap();
+            cs.is_satisfied()
+        };
+
+        // The scalar field is smaller than the constraint field, so each
+        // scalar has the same representation in both.
+        let fq_from_limbs = |limbs: &[u64]| {
+            let mut repr = <Fq as PrimeField>::BigInt::default();
+            repr.as_mut()[..limbs.len()].copy_from_slice(limbs);
+            Fq::from_repr(repr).unwrap()
+        };
+        let to_fq = |inputs: &[Fr]| {
+            inputs
+                .iter()
+                .map(|input| fq_from_limbs(input.into_repr().as_ref()))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(verify_with_bits(&inputs));
+        assert!(verify_with_field_elements(&to_fq(&inputs)));
+
+        let mut wrong_inputs = inputs.clone();
+        wrong_inputs[0] += &Fr::one();
+        assert!(!verify_with_bits(&wrong_inputs));
+        assert!(!verify_with_field_elements(&to_fq(&wrong_inputs)));
+
+        // `inputs[0] + r` is the same scalar as `inputs[0]`, but is rejected
+        // because it is not a canonical scalar.
+        let mut unreduced_inputs = to_fq(&inputs);
+        unreduced_inputs[0] += &fq_from_limbs(<Fr as PrimeField>::Params::MODULUS.as_ref());
+        assert!(!verify_with_field_elements(&unreduced_inputs));
+    }
+
+    #[test]
+    fn groth16_batch_verifier_test() {
+        type P = Bls12_377PairingGadget;
+
+        let num_proofs = 3;
+        let num_inputs = 2;
+        let rng = &mut test_rng();
+        let params = setup_bench(num_inputs, rng);
+
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let vk_gadget = TestVkGadget::alloc_input(cs.ns(|| "Vk"), || Ok(&params.vk)).unwrap();
+        let pvk_gadget = vk_gadget.prepare(cs.ns(|| "Prepare vk")).unwrap();
+
+        let mut instances = Vec::new();
+        for i in 0..num_proofs {
+            let inputs: Vec<Fr> = (0..num_inputs).map(|_| rng.gen()).collect();
+            let proof = prove_bench(&params, &inputs, rng);
+
+            let mut cs = cs.ns(|| format!("Instance {}", i));
+            let input_gadgets = alloc_input_bits(&mut cs, &inputs);
+            let proof_gadget = TestProofGadget::alloc(cs.ns(|| "Proof"), || Ok(proof)).unwrap();
+            instances.push((input_gadgets, proof_gadget));
+        }
+
+        let num_constraints = cs.num_constraints();
+        for (i, (input_gadgets, proof_gadget)) in instances.iter().enumerate() {
+            <TestVerifierGadget as NIZKVerifierGadget<TestProofSystem, Fq>>::conditional_check_verify_prepared(
+                cs.ns(|| format!("Verify {}", i)),
+                &pvk_gadget,
+                input_gadgets.iter(),
+                proof_gadget,
+                &Boolean::constant(true),
+            )
+            .unwrap();
+        }
+        let independent_cost = cs.num_constraints() - num_constraints;
+
+        // Raise the easy part of each verification equation to a random
+        // coefficient, and share the hard part between all of them.
+        let coefficients: Vec<u64> = (0..num_proofs).map(|_| rng.gen::<u32>().into()).collect();
+        let num_constraints = cs.num_constraints();
+        let mut batch = {
+            let mut cs = cs.ns(|| "Batch");
+            let mut batch = Fq12Gadget::one(cs.ns(|| "one")).unwrap();
+            for (i, ((input_gadgets, proof), coefficient)) in
+                instances.iter().zip(&coefficients).enumerate()
+            {
+                let mut cs = cs.ns(|| format!("Proof {}", i));
+                let mut g_ic = pvk_gadget.gamma_abc_g1[0].clone();
+                for (j, (input, b)) in input_gadgets
+                    .iter()
+                    .zip(pvk_gadget.gamma_abc_g1.iter().skip(1))
+                    .enumerate()
+                {
+                    g_ic = b
+                        .mul_bits(cs.ns(|| format!("Mul {}", j)), &g_ic, input.iter())
+                        .unwrap();
+                }
+
+                let proof_a_prep = P::prepare_g1(cs.ns(|| "Prepare proof a"), &proof.a).unwrap();
+                let proof_b_prep = P::prepare_g2(cs.ns(|| "Prepare proof b"), &proof.b).unwrap();
+                let proof_c_prep = P::prepare_g1(cs.ns(|| "Prepare proof c"), &proof.c).unwrap();
+                let g_ic_prep = P::prepare_g1(cs.ns(|| "Prepare g_ic"), &g_ic).unwrap();
+                let miller_loop = P::miller_loop(
+                    cs.ns(|| "Miller loop"),
+                    &[proof_a_prep, g_ic_prep, proof_c_prep],
+                    &[
+                        proof_b_prep,
+                        pvk_gadget.gamma_g2_neg_pc.clone(),
+                        pvk_gadget.delta_g2_neg_pc.clone(),
+                    ],
+                )
+                .unwrap();
+                let first_chunk =
+                    P::final_exponentiation_first_chunk(cs.ns(|| "First chunk"), &miller_loop)
+                        .unwrap();
+                let term = first_chunk
+                    .cyclotomic_exp(cs.ns(|| "Exp by coefficient"), [*coefficient])
+                    .unwrap();
+                batch.mul_in_place(cs.ns(|| "Accumulate"), &term).unwrap();
+            }
+            batch
+        };
+        batch = P::final_exponentiation_last_chunk(cs.ns(|| "Last chunk"), &batch).unwrap();
+        let expected = pvk_gadget
+            .alpha_g1_beta_g2
+            .cyclotomic_exp(cs.ns(|| "Expected"), [coefficients.iter().sum::<u64>()])
+            .unwrap();
+        batch
+            .enforce_equal(cs.ns(|| "Batch check"), &expected)
+            .unwrap();
+        let batch_cost = cs.num_constraints() - num_constraints;
+
+        assert!(cs.is_satisfied());
+        assert!(batch_cost < independent_cost);
+    }
+
+    #[test]
+    fn groth16_batch_verify_test() {
+        let num_proofs = 4;
+        let num_inputs = 2;
+        let rng = &mut test_rng();
+        let params = setup_bench(num_inputs, rng);
+
+        let mut instances = Vec::new();
+        for _ in 0..num_proofs {
+            let inputs: Vec<Fr> = (0..num_inputs).map(|_| rng.gen()).collect();
+            let proof = prove_bench(&params, &inputs, rng);
+            instances.push((inputs, proof));
+        }
+
+        let mut batch_verify = |instances: &[(Vec<Fr>, Proof<Bls12_377>)]| {
+            let mut cs = TestConstraintSystem::<Fq>::new();
+            let vk_gadget = TestVkGadget::alloc_input(cs.ns(|| "Vk"), || Ok(&params.vk)).unwrap();
+
+            let mut input_gadgets = Vec::new();
+            let mut proof_gadgets = Vec::new();
+            let mut challenges = Vec::new();
+            for (i, (inputs, proof)) in instances.iter().enumerate() {
+                let mut cs = cs.ns(|| format!("Instance {}", i));
+                input_gadgets.push(alloc_input_bits(&mut cs, inputs));
+                proof_gadgets
+                    .push(TestProofGadget::alloc(cs.ns(|| "Proof"), || Ok(proof.clone())).unwrap());
+
+                let challenge: Vec<bool> = (0..32).map(|_| rng.gen()).collect();
+                challenges
+                    .push(Vec::<Boolean>::alloc(cs.ns(|| "Challenge"), || Ok(challenge)).unwrap());
+            }
+
+            TestVerifierGadget::batch_verify(
+                cs.ns(|| "Batch verify"),
+                &vk_gadget,
+                &input_gadgets,
+                &proof_gadgets,
+                &challenges,
+            )
+            .unwrap();
+            cs.is_satisfied()
+        };
+
+        assert!(batch_verify(&instances));
+
+        // Verifying one of the proofs against the wrong inputs fails.
+        let mut wrong_inputs = instances.clone();
+        wrong_inputs[2].0 = instances[1].0.clone();
+        assert!(!batch_verify(&wrong_inputs));
+
+        // Swapping `a` between two proofs corrupts both of them.
+        let mut tampered = instances.clone();
+        tampered[1].1.a = instances[3].1.a;
+        tampered[3].1.a = instances[1].1.a;
+        assert!(!batch_verify(&tampered));
+
+        // Every proof needs its own inputs and challenge.
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let vk_gadget = TestVkGadget::alloc_input(cs.ns(|| "Vk"), || Ok(&params.vk)).unwrap();
+        let proof_gadget =
+            TestProofGadget::alloc(cs.ns(|| "Proof"), || Ok(instances[0].1.clone())).unwrap();
+        let no_inputs: Vec<Vec<Vec<Boolean>>> = Vec::new();
+        match TestVerifierGadget::batch_verify(
+            cs.ns(|| "Batch verify"),
+            &vk_gadget,
+            &no_inputs,
+            &[proof_gadget],
+            &[],
+        ) {
+            Err(SynthesisError::InvalidArgument) => {},
+            _ => panic!("expected InvalidArgument for a proof without inputs"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_recursive {
+    use groth16::*;
+    use r1cs_core::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+
+    use super::*;
+    use algebra::{
+        fields::{FftParameters, FpParameters},
+        mnt4_298::{Fq as MNT4Fq, FqParameters as MNT4FqParameters, Fr as MNT4Fr, MNT4_298},
+        mnt6_298::{Fq as MNT6Fq, FqParameters as MNT6FqParameters, Fr as MNT6Fr, MNT6_298},
+        test_rng, BigInteger, PrimeField,
+    };
+    use r1cs_std::{
+        fields::fp::FpGadget, mnt4_298::PairingGadget as MNT4_298PairingGadget,
+        mnt6_298::PairingGadget as MNT6_298PairingGadget,
+        test_constraint_system::TestConstraintSystem, uint8::UInt8,
+    };
+    use rand::Rng;
+
+    type TestProofSystem1 = Groth16<MNT6_298, Bench<MNT4Fq>, MNT6Fr>;
+    type TestVerifierGadget1 = Groth16VerifierGadget<MNT6_298, MNT6Fq, MNT6_298PairingGadget>;
+    type TestProofGadget1 = ProofGadget<MNT6_298, MNT6Fq, MNT6_298PairingGadget>;
+    type TestVkGadget1 = VerifyingKeyGadget<MNT6_298, MNT6Fq, MNT6_298PairingGadget>;
+
+    type TestProofSystem2 = Groth16<MNT4_298, Wrapper, MNT4Fr>;
+    type TestVerifierGadget2 = Groth16VerifierGadget<MNT4_298, MNT4Fq, MNT4_298PairingGadget>;
+    type TestProofGadget2 = ProofGadget<MNT4_298, MNT4Fq, MNT4_298PairingGadget>;
+    type TestVkGadget2 = VerifyingKeyGadget<MNT4_298, MNT4Fq, MNT4_298PairingGadget>;
+
+    #[derive(Clone)]
+    struct Bench<F: Field> {
+        inputs: Vec<Option<F>>,
+        num_constraints: usize,
+    }
+
+    impl<F: Field> ConstraintSynthesizer<F> for Bench<F> {
+        fn generate_constraints<CS: ConstraintSystem<F>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            assert!(self.inputs.len() >= 2);
+            assert!(self.num_constraints >= self.inputs.len());
+
+            let mut variables: Vec<_> = Vec::with_capacity(self.inputs.len());
+            for (i, input) in self.inputs.into_iter().enumerate() {
+                let input_var = cs.alloc_input(
+                    || format!("Input {}", i),
+                    || input.ok_or(SynthesisError::AssignmentMissing),
+                )?;
+                variables.push((input, input_var));
+            }
+
+            for i in 0..self.num_constraints {
+                let new_entry = {
+                    let (input_1_val, input_1_var) = variables[i];
+                    let (input_2_val, input_2_var) = variables[i + 1];
+                    let result_val = input_1_val
+                        .and_then(|input_1| input_2_val.map(|input_2| input_1 * &input_2));
+                    let result_var = cs.alloc(
+                        || format!("Result {}", i),
+                        || result_val.ok_or(SynthesisError::AssignmentMissing),
+                    )?;
+                    cs.enforce(
+                        || format!("Enforce constraint {}", i),
+                        |lc| lc + input_1_var,
+                        |lc| lc + input_2_var,
+                        |lc| lc + result_var,
+                    );
+                    (result_val, result_var)
+                };
+                variables.push(new_entry);
+            }
+            Ok(())
+        }
+    }
+
+    struct Wrapper {
+        inputs: Vec<Option<MNT4Fq>>,
+        params: Parameters<MNT6_298>,
+        proof: Proof<MNT6_298>,
+    }
+
+    impl ConstraintSynthesizer<MNT6Fq> for Wrapper {
+        fn generate_constraints<CS: ConstraintSystem<MNT6Fq>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let params = self.params;
+            let proof = self.proof;
+            let inputs: Vec<_> = self
+                .inputs
+                .into_iter()
+                .map(|input| input.unwrap())
+                .collect();
+            let input_gadgets;
+
+            {
+                let mut cs = cs.ns(|| "Allocate Input");
+                // Chain all input values in one large byte array.
+                let input_bytes = inputs
+                    .clone()
+                    .into_iter()
+                    .flat_map(|input| {
+                        input
+                            .into_repr()
+                            .as_ref()
+                            .iter()
+                            .flat_map(|l| l.to_le_bytes().to_vec())
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+
+                // Allocate this byte array as input packed into field elements.
+                let input_bytes = UInt8::alloc_input_vec(cs.ns(|| "Input"), &input_bytes[..])?;
+                // 40 byte
+                let element_size = <MNT4FqParameters as FftParameters>::BigInt::NUM_LIMBS * 8;
+                input_gadgets = input_bytes
+                    .chunks(element_size)
+                    .map(|chunk| {
+                        chunk
+                            .iter()
+                            .flat_map(|byte| byte.into_bits_le())
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+            }
+
+            let vk_gadget = TestVkGadget1::alloc(cs.ns(|| "Vk"), || Ok(&params.vk))?;
+            let proof_gadget =
+                TestProofGadget1::alloc(cs.ns(|| "Proof"), || Ok(proof.clone())).unwrap();
+            <TestVerifierGadget1 as NIZKVerifierGadget<TestProofSystem1, MNT6Fq>>::check_verify(
+                cs.ns(|| "Verify"),
+                &vk_gadget,
+                input_gadgets.iter(),
+                &proof_gadget,
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn groth16_recursive_verifier_test() {
+        let num_inputs = 5;
+        let num_constraints = num_inputs;
+        let rng = &mut test_rng();
+        let mut inputs: Vec<Option<MNT4Fq>> = Vec::with_capacity(num_inputs);
+        for _ in 0..num_inputs {
+            inputs.push(Some(rng.gen()));
+        }
+
+        // Generate inner params and proof.
+        let inner_params = {
+            let c = Bench::<MNT4Fq> {
+                inputs: vec![None; num_inputs],
+                num_constraints,
+            };
+
+            generate_random_parameters(c, rng).unwrap()
+        };
+
+        let inner_proof = {
+            // Create an instance of our circuit (with the
+            // witness)
+            let c = Bench {
+                inputs: inputs.clone(),
+                num_constraints,
+            };
+            // Create a groth16 proof with our parameters.
+            create_random_proof(c, &inner_params, rng).unwrap()
+        };
+
+        // Generate outer params and proof.
+        let params = {
+            let c = Wrapper {
+                inputs: inputs.clone(),
+                params: inner_params.clone(),
+                proof: inner_proof.clone(),
+            };
+
+            generate_random_parameters(c, rng).unwrap()
+        };
+
+        {
+            let proof = {
+                // Create an instance of our circuit (with the
+                // witness)
+                let c = Wrapper {
+                    inputs: inputs.clone(),
+                    params: inner_params.clone(),
+                    proof: inner_proof.clone(),
+                };
+                // Create a groth16 proof with our parameters.
+                create_random_proof(c, &params, rng).unwrap()
+            };
+
+            let mut cs = TestConstraintSystem::<MNT4Fq>::new();
+
+            let inputs: Vec<_> = inputs.into_iter().map(|input| input.unwrap()).collect();
+            let mut input_gadgets = Vec::new();
+
+            {
+                let bigint_size = <MNT4FqParameters as FftParameters>::BigInt::NUM_LIMBS * 64;
+                let mut input_bits = Vec::new();
+                let mut cs = cs.ns(|| "Allocate Input");
+                for (i, input) in inputs.into_iter().enumerate() {
+                    let input_gadget =
+                        FpGadget::alloc_input(cs.ns(|| format!("Input {}", i)), || Ok(input))
+                            .unwrap();
+                    let mut fp_bits = input_gadget
+                        .to_bits(cs.ns(|| format!("To bits {}", i)))
+                        .unwrap();
+
+                    // FpGadget::to_bits outputs a big-endian binary representation of
+                    // fe_gadget's value, so we have to reverse it to get the little-endian
+                    // form.
+                    fp_bits.reverse();
+
+                    // Use 320 bits per element.
+                    for _ in fp_bits.len()..bigint_size {
+                        fp_bits.push(Boolean::constant(false));
+                    }
+                    input_bits.extend_from_slice(&fp_bits);
+                }
+
+                // Pack input bits into field elements of the underlying circuit.
+                let max_size = 8 * (<MNT6FqParameters as FpParameters>::CAPACITY / 8) as usize;
+                let max_size = max_size as usize;
+                let bigint_size = <MNT6FqParameters as FftParameters>::BigInt::NUM_LIMBS * 64;
+                for chunk in input_bits.chunks(max_size) {
+                    let mut chunk = chunk.to_vec();
+                    let len = chunk.len();
+                    for _ in len..bigint_size {
+                        chunk.push(Boolean::constant(false));
+                    }
+                    input_gadgets.push(chunk);
+                }
+                // assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
+            }
+
+            let vk_gadget = TestVkGadget2::alloc_input(cs.ns(|| "Vk"), || Ok(&params.vk)).unwrap();
+            let proof_gadget =
+                TestProofGadget2::alloc(cs.ns(|| "Proof"), || Ok(proof.clone())).unwrap();
+            println!("Time to verify!\n\n\n\n");
+            <TestVerifierGadget2 as NIZKVerifierGadget<TestProofSystem2, MNT4Fq>>::check_verify(
+                cs.ns(|| "Verify"),
+                &vk_gadget,
+                input_gadgets.iter(),
+                &proof_gadget,
+            )
+            .unwrap();
+            if !cs.is_satisfied() {
+                println!("=========================================================");
+                println!("Unsatisfied constraints:");
+                println!("{:?}", cs.which_is_unsatisfied().unwrap());
+                println!("=========================================================");
+            }
+
+            // cs.print_named_objects();
+            assert!(cs.is_satisfied());
+        }
+    }
+}
@@ -439,6 +439,30 @@ impl<ConstraintF: PrimeField> ConditionalEqGadget<ConstraintF> for Blake2sOutput
     }
 }
 
+impl<ConstraintF: PrimeField> CondSelectGadget<ConstraintF> for Blake2sOutputGadget {
+    fn conditionally_select<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        cond: &Boolean,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let mut bytes = Vec::with_capacity(32);
+        for (i, (t, f)) in true_value.0.iter().zip(&false_value.0).enumerate() {
+            bytes.push(UInt8::conditionally_select(
+                cs.ns(|| format!("blake2s_select_{}", i)),
+                cond,
+                t,
+                f,
+            )?);
+        }
+        Ok(Blake2sOutputGadget(bytes))
+    }
+
+    fn cost() -> usize {
+        32 * <UInt8 as CondSelectGadget<ConstraintF>>::cost()
+    }
+}
+
 impl<ConstraintF: PrimeField> ToBytesGadget<ConstraintF> for Blake2sOutputGadget {
     #[inline]
     fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
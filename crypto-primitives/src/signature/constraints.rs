@@ -1,8 +1,13 @@
-use algebra_core::Field;
+use algebra_core::{Field, PrimeField};
+use digest::Digest;
 use r1cs_core::{ConstraintSystem, SynthesisError};
 use r1cs_std::prelude::*;
 
-use crate::signature::SignatureScheme;
+use crate::{
+    prf::blake2s::constraints::{blake2s_gadget, Blake2sGadget},
+    signature::SignatureScheme,
+    Vec,
+};
 
 pub trait SigRandomizePkGadget<S: SignatureScheme, ConstraintF: Field> {
     type ParametersGadget: AllocGadget<S::Parameters, ConstraintF> + Clone;
@@ -19,3 +24,54 @@ pub trait SigRandomizePkGadget<S: SignatureScheme, ConstraintF: Field> {
         randomness: &[UInt8],
     ) -> Result<Self::PublicKeyGadget, SynthesisError>;
 }
+
+pub trait SigVerifyGadget<S: SignatureScheme, ConstraintF: Field> {
+    type ParametersGadget: AllocGadget<S::Parameters, ConstraintF> + Clone;
+
+    type PublicKeyGadget: ToBytesGadget<ConstraintF>
+        + EqGadget<ConstraintF>
+        + AllocGadget<S::PublicKey, ConstraintF>
+        + Clone;
+
+    type SignatureGadget: AllocGadget<S::Signature, ConstraintF> + Clone;
+
+    /// Returns whether `signature` is a valid signature on `message` under
+    /// `public_key`, without enforcing it.
+    fn verify<CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        parameters: &Self::ParametersGadget,
+        public_key: &Self::PublicKeyGadget,
+        message: &[UInt8],
+        signature: &Self::SignatureGadget,
+    ) -> Result<Boolean, SynthesisError>;
+}
+
+/// The in-circuit counterpart of the digest `D`, used to recompute the
+/// verifier challenge.
+pub trait DigestGadget<D: Digest, ConstraintF: Field> {
+    fn digest<CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        input: &[UInt8],
+    ) -> Result<Vec<UInt8>, SynthesisError>;
+}
+
+impl<ConstraintF: PrimeField> DigestGadget<blake2::Blake2s, ConstraintF> for Blake2sGadget {
+    fn digest<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        input: &[UInt8],
+    ) -> Result<Vec<UInt8>, SynthesisError> {
+        let input_bits = input
+            .iter()
+            .flat_map(UInt8::into_bits_le)
+            .collect::<Vec<_>>();
+        let mut result = Vec::with_capacity(32);
+        for (i, int) in blake2s_gadget(cs.ns(|| "Blake2s Eval"), &input_bits)?
+            .into_iter()
+            .enumerate()
+        {
+            let chunk = int.to_bytes(&mut cs.ns(|| format!("Result ToBytes {}", i)))?;
+            result.extend_from_slice(&chunk);
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,315 @@
+use crate::{
+    signature::{
+        eddsa::{EdDSA, EdDSAParameters, EdDSASig},
+        DigestGadget, SigVerifyGadget,
+    },
+    Vec,
+};
+use algebra_core::{
+    curves::{models::TEModelParameters, twisted_edwards_extended::GroupAffine as TEAffine},
+    to_bytes, Field,
+};
+use digest::Digest;
+use r1cs_core::{ConstraintSystem, SynthesisError};
+use r1cs_std::{groups::curves::twisted_edwards::AffineGadget, prelude::*};
+
+use core::{borrow::Borrow, marker::PhantomData};
+
+#[derive(Derivative)]
+#[derivative(Clone(
+    bound = "P: TEModelParameters, ConstraintF: Field, F: FieldGadget<P::BaseField, ConstraintF>"
+))]
+pub struct EdDSAParametersGadget<P, ConstraintF, F>
+where
+    P: TEModelParameters,
+    ConstraintF: Field,
+    F: FieldGadget<P::BaseField, ConstraintF>,
+{
+    generator: AffineGadget<P, ConstraintF, F>,
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(
+    bound = "P: TEModelParameters, ConstraintF: Field, F: FieldGadget<P::BaseField, ConstraintF>"
+))]
+pub struct EdDSASigGadget<P, ConstraintF, F>
+where
+    P: TEModelParameters,
+    ConstraintF: Field,
+    F: FieldGadget<P::BaseField, ConstraintF>,
+{
+    prover_commitment: AffineGadget<P, ConstraintF, F>,
+    /// The little-endian byte encoding of the prover response.
+    prover_response: Vec<UInt8>,
+}
+
+pub struct EdDSAVerifyGadget<P, ConstraintF, F, DG>
+where
+    P: TEModelParameters,
+    ConstraintF: Field,
+    F: FieldGadget<P::BaseField, ConstraintF>,
+{
+    #[doc(hidden)]
+    _curve: PhantomData<*const P>,
+    #[doc(hidden)]
+    _field_gadget: PhantomData<*const F>,
+    #[doc(hidden)]
+    _digest_gadget: PhantomData<*const DG>,
+    #[doc(hidden)]
+    _engine: PhantomData<*const ConstraintF>,
+}
+
+impl<P, ConstraintF, F, D, DG> SigVerifyGadget<EdDSA<P, D>, ConstraintF>
+    for EdDSAVerifyGadget<P, ConstraintF, F, DG>
+where
+    P: TEModelParameters,
+    ConstraintF: Field,
+    F: FieldGadget<P::BaseField, ConstraintF>,
+    D: Digest + Send + Sync,
+    DG: DigestGadget<D, ConstraintF>,
+{
+    type ParametersGadget = EdDSAParametersGadget<P, ConstraintF, F>;
+    type PublicKeyGadget = AffineGadget<P, ConstraintF, F>;
+    type SignatureGadget = EdDSASigGadget<P, ConstraintF, F>;
+
+    /// Mirrors `EdDSA::verify`, by checking that `S * B - c * A == R`.
+    fn verify<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        parameters: &Self::ParametersGadget,
+        public_key: &Self::PublicKeyGadget,
+        message: &[UInt8],
+        signature: &Self::SignatureGadget,
+    ) -> Result<Boolean, SynthesisError> {
+        let prover_commitment = &signature.prover_commitment;
+
+        let mut hash_input = prover_commitment.to_bytes(cs.ns(|| "commitment to bytes"))?;
+        hash_input.extend(public_key.to_bytes(cs.ns(|| "public key to bytes"))?);
+        hash_input.extend_from_slice(message);
+        let mut challenge = DG::digest(cs.ns(|| "digest"), &hash_input)?;
+        challenge.resize(EdDSA::<P, D>::challenge_num_bytes(), UInt8::constant(0u8));
+
+        let prover_response_bits = signature
+            .prover_response
+            .iter()
+            .flat_map(UInt8::into_bits_le)
+            .collect::<Vec<_>>();
+        let challenge_bits = challenge
+            .iter()
+            .flat_map(UInt8::into_bits_le)
+            .collect::<Vec<_>>();
+        let neg_public_key =
+            GroupGadget::<TEAffine<P>, _>::negate(public_key, cs.ns(|| "negate public key"))?;
+        let claimed_prover_commitment = GroupGadget::<TEAffine<P>, _>::mul_bits_2(
+            cs.ns(|| "claimed prover commitment"),
+            &parameters.generator,
+            &prover_response_bits,
+            &neg_public_key,
+            &challenge_bits,
+        )?;
+
+        let expected_bytes = prover_commitment.to_bytes(cs.ns(|| "expected to bytes"))?;
+        let claimed_bytes = claimed_prover_commitment.to_bytes(cs.ns(|| "claimed to bytes"))?;
+        let mut bits_equal = Vec::new();
+        for (i, (a, b)) in expected_bytes
+            .iter()
+            .flat_map(UInt8::into_bits_le)
+            .zip(claimed_bytes.iter().flat_map(UInt8::into_bits_le))
+            .enumerate()
+        {
+            bits_equal.push(Boolean::xnor(
+                cs.ns(|| format!("commitment bit {} equal", i)),
+                &a,
+                &b,
+            )?);
+        }
+        Boolean::kary_and(cs.ns(|| "commitment equal"), &bits_equal)
+    }
+}
+
+impl<P, ConstraintF, F, D> AllocGadget<EdDSAParameters<P, D>, ConstraintF>
+    for EdDSAParametersGadget<P, ConstraintF, F>
+where
+    P: TEModelParameters,
+    ConstraintF: Field,
+    F: FieldGadget<P::BaseField, ConstraintF>,
+    D: Digest,
+{
+    fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        val: T,
+    ) -> Result<Self, SynthesisError>
+    where
+        T: Borrow<EdDSAParameters<P, D>>,
+    {
+        let generator = AffineGadget::alloc_constant(cs, val.borrow().generator)?;
+        Ok(Self { generator })
+    }
+
+    fn alloc<FN, T, CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        f: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<EdDSAParameters<P, D>>,
+    {
+        let generator = AffineGadget::alloc_checked(cs, || f().map(|pp| pp.borrow().generator))?;
+        Ok(Self { generator })
+    }
+
+    fn alloc_input<FN, T, CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        f: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<EdDSAParameters<P, D>>,
+    {
+        let generator = AffineGadget::alloc_input(cs, || f().map(|pp| pp.borrow().generator))?;
+        Ok(Self { generator })
+    }
+}
+
+impl<P, ConstraintF, F> AllocGadget<EdDSASig<P>, ConstraintF> for EdDSASigGadget<P, ConstraintF, F>
+where
+    P: TEModelParameters,
+    ConstraintF: Field,
+    F: FieldGadget<P::BaseField, ConstraintF>,
+{
+    fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        val: T,
+    ) -> Result<Self, SynthesisError>
+    where
+        T: Borrow<EdDSASig<P>>,
+    {
+        let sig = val.borrow();
+        Ok(Self {
+            prover_commitment: AffineGadget::alloc_constant(
+                cs.ns(|| "prover commitment"),
+                sig.prover_commitment,
+            )?,
+            prover_response: UInt8::constant_vec(&to_bytes![sig.prover_response]?),
+        })
+    }
+
+    fn alloc<FN, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        f: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<EdDSASig<P>>,
+    {
+        let sig = f().map(|sig| sig.borrow().clone());
+        let prover_response = match &sig {
+            Ok(sig) => to_bytes![sig.prover_response]?,
+            Err(_) => to_bytes![EdDSASig::<P>::default().prover_response]?,
+        };
+        Ok(Self {
+            prover_commitment: AffineGadget::alloc(cs.ns(|| "prover commitment"), || {
+                sig.map(|sig| sig.prover_commitment)
+            })?,
+            prover_response: UInt8::alloc_vec(cs.ns(|| "prover response"), &prover_response)?,
+        })
+    }
+
+    fn alloc_input<FN, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        f: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<EdDSASig<P>>,
+    {
+        let sig = f().map(|sig| sig.borrow().clone());
+        let prover_response = match &sig {
+            Ok(sig) => to_bytes![sig.prover_response]?,
+            Err(_) => to_bytes![EdDSASig::<P>::default().prover_response]?,
+        };
+        Ok(Self {
+            prover_commitment: AffineGadget::alloc_input(cs.ns(|| "prover commitment"), || {
+                sig.map(|sig| sig.prover_commitment)
+            })?,
+            prover_response: UInt8::alloc_input_vec(cs.ns(|| "prover response"), &prover_response)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        prf::blake2s::constraints::Blake2sGadget,
+        signature::{
+            eddsa::{constraints::EdDSAVerifyGadget, EdDSA},
+            SigVerifyGadget, SignatureScheme,
+        },
+    };
+    use algebra::{
+        ed_on_bls12_381::{EdwardsParameters, Fq},
+        test_rng,
+    };
+    use blake2::Blake2s;
+    use r1cs_core::ConstraintSystem;
+    use r1cs_std::{
+        fields::fp::FpGadget, prelude::*, test_constraint_system::TestConstraintSystem,
+    };
+
+    type TestSig = EdDSA<EdwardsParameters, Blake2s>;
+    type TestSigGadget = EdDSAVerifyGadget<EdwardsParameters, Fq, FpGadget<Fq>, Blake2sGadget>;
+
+    fn verify_in_circuit(flip_message_bit: bool) -> (TestConstraintSystem<Fq>, Boolean) {
+        let rng = &mut test_rng();
+        let mut message = b"Hi, I am an EdDSA signature!".to_vec();
+        let parameters = TestSig::setup(rng).unwrap();
+        let (pk, sk) = TestSig::keygen(&parameters, rng).unwrap();
+        let sig = TestSig::sign(&parameters, &sk, &message, rng).unwrap();
+        if flip_message_bit {
+            message[3] ^= 1;
+        }
+        let native_result = TestSig::verify(&parameters, &pk, &message, &sig).unwrap();
+        assert_eq!(native_result, !flip_message_bit);
+
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let parameters_gadget =
+            <TestSigGadget as SigVerifyGadget<TestSig, Fq>>::ParametersGadget::alloc(
+                cs.ns(|| "parameters"),
+                || Ok(&parameters),
+            )
+            .unwrap();
+        let pk_gadget = <TestSigGadget as SigVerifyGadget<TestSig, Fq>>::PublicKeyGadget::alloc(
+            cs.ns(|| "public key"),
+            || Ok(&pk),
+        )
+        .unwrap();
+        let sig_gadget = <TestSigGadget as SigVerifyGadget<TestSig, Fq>>::SignatureGadget::alloc(
+            cs.ns(|| "signature"),
+            || Ok(&sig),
+        )
+        .unwrap();
+        let message_gadget = UInt8::alloc_vec(cs.ns(|| "message"), &message).unwrap();
+        let result = TestSigGadget::verify(
+            cs.ns(|| "verify"),
+            &parameters_gadget,
+            &pk_gadget,
+            &message_gadget,
+            &sig_gadget,
+        )
+        .unwrap();
+        (cs, result)
+    }
+
+    #[test]
+    fn eddsa_verify_gadget_test() {
+        let (cs, result) = verify_in_circuit(false);
+        assert_eq!(result.get_value(), Some(true));
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn eddsa_verify_gadget_flipped_message_bit_test() {
+        let (cs, result) = verify_in_circuit(true);
+        assert_eq!(result.get_value(), Some(false));
+        assert!(cs.is_satisfied());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{CryptoError, Error, SignatureScheme, Vec};
+use algebra_core::{
+    bytes::{FromBytes, ToBytes},
+    curves::{
+        models::TEModelParameters,
+        twisted_edwards_extended::{GroupAffine as TEAffine, GroupProjective as TEProjective},
+        AffineCurve, ProjectiveCurve,
+    },
+    fields::PrimeField,
+    io::{Result as IoResult, Write},
+    to_bytes, BigInteger, UniformRand, Zero,
+};
+use core::marker::PhantomData;
+use digest::Digest;
+use rand::Rng;
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// EdDSA over the twisted Edwards curve `P`, with the digest `D`. Since `P`
+/// is arbitrary, this does not follow the encodings of RFC 8032: points are
+/// encoded with `ToBytes`, and the challenge is the integer encoded by the
+/// first `NUM_LIMBS * 8` bytes of `D(R || A || M)` in little-endian order.
+pub struct EdDSA<P: TEModelParameters, D: Digest> {
+    _curve: PhantomData<P>,
+    _hash: PhantomData<D>,
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = "P: TEModelParameters"))]
+pub struct EdDSAParameters<P: TEModelParameters, D: Digest> {
+    _hash: PhantomData<D>,
+    /// The base point `B`.
+    pub generator: TEAffine<P>,
+}
+
+pub type EdDSAPublicKey<P> = TEAffine<P>;
+
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "P: TEModelParameters"),
+    Default(bound = "P: TEModelParameters")
+)]
+pub struct EdDSASecretKey<P: TEModelParameters> {
+    pub secret_scalar: P::ScalarField,
+    /// Hashed with the message to derive the nonce deterministically.
+    pub prefix: [u8; 32],
+}
+
+impl<P: TEModelParameters> ToBytes for EdDSASecretKey<P> {
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.secret_scalar.write(&mut writer)?;
+        self.prefix.write(&mut writer)
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "P: TEModelParameters"),
+    Default(bound = "P: TEModelParameters")
+)]
+pub struct EdDSASig<P: TEModelParameters> {
+    /// `R = r * B`.
+    pub prover_commitment: TEAffine<P>,
+    /// `S = r + c * a`.
+    pub prover_response: P::ScalarField,
+}
+
+impl<P: TEModelParameters, D: Digest> EdDSA<P, D> {
+    /// The challenge `c` as an integer, which is not reduced modulo the order
+    /// of `B`, so that it can be used as is in-circuit.
+    pub fn challenge(
+        prover_commitment: &TEAffine<P>,
+        public_key: &TEAffine<P>,
+        message: &[u8],
+    ) -> Result<<P::ScalarField as PrimeField>::BigInt, Error> {
+        let mut hash_input = Vec::new();
+        hash_input.extend_from_slice(&to_bytes![prover_commitment, public_key]?);
+        hash_input.extend_from_slice(message);
+        let mut digest = D::digest(&hash_input).to_vec();
+        digest.resize(Self::challenge_num_bytes(), 0u8);
+        Ok(<P::ScalarField as PrimeField>::BigInt::read(&digest[..])?)
+    }
+
+    pub fn challenge_num_bytes() -> usize {
+        <P::ScalarField as PrimeField>::BigInt::NUM_LIMBS * 8
+    }
+
+    /// Reduces an integer encoded in little-endian bytes into a scalar.
+    fn scalar_from_le_bytes(bytes: &[u8]) -> P::ScalarField {
+        let base = P::ScalarField::from(256u64);
+        bytes
+            .iter()
+            .rev()
+            .fold(P::ScalarField::zero(), |acc, byte| {
+                acc * &base + &P::ScalarField::from(*byte)
+            })
+    }
+}
+
+impl<P: TEModelParameters, D: Digest + Send + Sync> SignatureScheme for EdDSA<P, D> {
+    type Parameters = EdDSAParameters<P, D>;
+    type PublicKey = EdDSAPublicKey<P>;
+    type SecretKey = EdDSASecretKey<P>;
+    type Signature = EdDSASig<P>;
+
+    fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+        let setup_time = start_timer!(|| "EdDSA::Setup");
+
+        let generator = TEProjective::<P>::rand(rng).into_affine();
+
+        end_timer!(setup_time);
+        Ok(EdDSAParameters {
+            _hash: PhantomData,
+            generator,
+        })
+    }
+
+    fn keygen<R: Rng>(
+        parameters: &Self::Parameters,
+        rng: &mut R,
+    ) -> Result<(Self::PublicKey, Self::SecretKey), Error> {
+        let keygen_time = start_timer!(|| "EdDSA::KeyGen");
+
+        let secret_scalar = P::ScalarField::rand(rng);
+        let mut prefix = [0u8; 32];
+        rng.fill_bytes(&mut prefix);
+        let public_key = parameters.generator.mul(secret_scalar).into_affine();
+
+        end_timer!(keygen_time);
+        Ok((
+            public_key,
+            EdDSASecretKey {
+                secret_scalar,
+                prefix,
+            },
+        ))
+    }
+
+    fn sign<R: Rng>(
+        parameters: &Self::Parameters,
+        sk: &Self::SecretKey,
+        message: &[u8],
+        _rng: &mut R,
+    ) -> Result<Self::Signature, Error> {
+        let sign_time = start_timer!(|| "EdDSA::Sign");
+
+        // r := D(prefix || M)
+        let mut hash_input = Vec::new();
+        hash_input.extend_from_slice(&sk.prefix);
+        hash_input.extend_from_slice(message);
+        let nonce = Self::scalar_from_le_bytes(&D::digest(&hash_input));
+        let prover_commitment = parameters.generator.mul(nonce).into_affine();
+
+        // S := r + c * a
+        let public_key = parameters.generator.mul(sk.secret_scalar).into_affine();
+        let challenge = Self::challenge(&prover_commitment, &public_key, message)?;
+        let challenge = Self::scalar_from_le_bytes(&to_bytes![challenge]?);
+        let prover_response = nonce + &(challenge * &sk.secret_scalar);
+
+        end_timer!(sign_time);
+        Ok(EdDSASig {
+            prover_commitment,
+            prover_response,
+        })
+    }
+
+    fn verify(
+        parameters: &Self::Parameters,
+        pk: &Self::PublicKey,
+        message: &[u8],
+        signature: &Self::Signature,
+    ) -> Result<bool, Error> {
+        let verify_time = start_timer!(|| "EdDSA::Verify");
+
+        let EdDSASig {
+            prover_commitment,
+            prover_response,
+        } = signature;
+        let challenge = Self::challenge(prover_commitment, pk, message)?;
+
+        // S * B == R + c * A
+        let lhs = parameters.generator.mul(*prover_response);
+        let rhs = pk.mul(challenge) + &prover_commitment.into_projective();
+
+        end_timer!(verify_time);
+        Ok(lhs == rhs)
+    }
+
+    /// The challenge commits to the public key, so a signature cannot be
+    /// randomized along with it.
+    fn randomize_public_key(
+        _: &Self::Parameters,
+        _: &Self::PublicKey,
+        _: &[u8],
+    ) -> Result<Self::PublicKey, Error> {
+        Err(CryptoError::Unsupported("EdDSA public key randomization").into())
+    }
+
+    fn randomize_signature(
+        _: &Self::Parameters,
+        _: &Self::Signature,
+        _: &[u8],
+    ) -> Result<Self::Signature, Error> {
+        Err(CryptoError::Unsupported("EdDSA signature randomization").into())
+    }
+}
@@ -8,6 +8,7 @@ pub mod constraints;
 #[cfg(feature = "r1cs")]
 pub use constraints::*;
 
+pub mod eddsa;
 pub mod schnorr;
 
 pub trait SignatureScheme {
@@ -52,10 +53,14 @@ pub trait SignatureScheme {
 
 #[cfg(test)]
 mod test {
-    use crate::{signature::schnorr::SchnorrSignature, SignatureScheme};
+    use crate::{
+        signature::{eddsa::EdDSA, schnorr::SchnorrSignature},
+        SignatureScheme,
+    };
     use algebra::{
-        ed_on_bls12_381::EdwardsAffine as JubJub, groups::Group, test_rng, to_bytes, ToBytes,
-        UniformRand,
+        ed_on_bls12_381::{EdwardsAffine as JubJub, EdwardsParameters},
+        groups::Group,
+        test_rng, to_bytes, ToBytes, UniformRand,
     };
     use blake2::Blake2s;
 
@@ -101,4 +106,14 @@ mod test {
             &random_scalar.as_slice(),
         );
     }
+
+    #[test]
+    fn eddsa_signature_test() {
+        let message = "Hi, I am an EdDSA signature!";
+        sign_and_verify::<EdDSA<EdwardsParameters, Blake2s>>(message.as_bytes());
+        failed_verification::<EdDSA<EdwardsParameters, Blake2s>>(
+            message.as_bytes(),
+            "Bad message".as_bytes(),
+        );
+    }
 }
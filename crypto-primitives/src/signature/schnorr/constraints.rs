@@ -1,18 +1,21 @@
 use crate::Vec;
-use algebra_core::{groups::Group, Field};
+use algebra_core::{groups::Group, to_bytes, Field, FpParameters, PrimeField};
 use r1cs_core::{ConstraintSystem, SynthesisError};
 use r1cs_std::prelude::*;
 
-use crate::signature::SigRandomizePkGadget;
+use crate::signature::{DigestGadget, SigRandomizePkGadget, SigVerifyGadget};
 
-use core::{borrow::Borrow, marker::PhantomData};
+use core::{borrow::Borrow, hash::Hash, marker::PhantomData};
 
-use crate::signature::schnorr::{SchnorrPublicKey, SchnorrSigParameters, SchnorrSignature};
+use crate::signature::schnorr::{
+    SchnorrPublicKey, SchnorrSig, SchnorrSigParameters, SchnorrSignature,
+};
 use digest::Digest;
 
 pub struct SchnorrSigGadgetParameters<G: Group, ConstraintF: Field, GG: GroupGadget<G, ConstraintF>>
 {
     generator: GG,
+    salt: Vec<UInt8>,
     _group: PhantomData<*const G>,
     _engine: PhantomData<*const ConstraintF>,
 }
@@ -23,6 +26,7 @@ impl<G: Group, ConstraintF: Field, GG: GroupGadget<G, ConstraintF>> Clone
     fn clone(&self) -> Self {
         Self {
             generator: self.generator.clone(),
+            salt: self.salt.clone(),
             _group: PhantomData,
             _engine: PhantomData,
         }
@@ -53,6 +57,72 @@ pub struct SchnorrRandomizePkGadget<G: Group, ConstraintF: Field, GG: GroupGadge
     _engine: PhantomData<*const ConstraintF>,
 }
 
+impl<G, ConstraintF, GG> SchnorrRandomizePkGadget<G, ConstraintF, GG>
+where
+    G: Group,
+    ConstraintF: Field,
+    GG: GroupGadget<G, ConstraintF>,
+{
+    /// Rerandomizes the generator to `generator + randomness * generator`,
+    /// reading `randomness` as a little-endian integer like
+    /// `check_randomization_gadget` does.
+    ///
+    /// A key pair stays valid under the new parameters only once its public
+    /// key has been mapped through `randomize_public_key_for_parameters`
+    /// with the same randomness. This commutes with
+    /// `check_randomization_gadget`: shifting the public key by `r` under
+    /// the original parameters and then rescaling it gives the same key as
+    /// rescaling it first and then shifting by `r` under the new parameters.
+    ///
+    /// Signatures are not carried over, since the verifier challenge is
+    /// bound to the prover commitment, which the rescaling changes; they must
+    /// be produced under the new parameters.
+    pub fn randomize_parameters<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        parameters: &SchnorrSigGadgetParameters<G, ConstraintF, GG>,
+        randomness: &[UInt8],
+    ) -> Result<SchnorrSigGadgetParameters<G, ConstraintF, GG>, SynthesisError> {
+        let randomness = randomness
+            .iter()
+            .flat_map(|b| b.into_bits_le())
+            .collect::<Vec<_>>();
+        let generator = parameters.generator.mul_bits(
+            &mut cs.ns(|| "Randomize Generator"),
+            &parameters.generator,
+            randomness.iter(),
+        )?;
+        Ok(SchnorrSigGadgetParameters {
+            generator,
+            salt: parameters.salt.clone(),
+            _group: PhantomData,
+            _engine: PhantomData,
+        })
+    }
+
+    /// Maps `public_key` to the public key of the same secret key under the
+    /// parameters returned by `randomize_parameters` with `randomness`.
+    pub fn randomize_public_key_for_parameters<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        public_key: &SchnorrSigGadgetPk<G, ConstraintF, GG>,
+        randomness: &[UInt8],
+    ) -> Result<SchnorrSigGadgetPk<G, ConstraintF, GG>, SynthesisError> {
+        let randomness = randomness
+            .iter()
+            .flat_map(|b| b.into_bits_le())
+            .collect::<Vec<_>>();
+        let pub_key = public_key.pub_key.mul_bits(
+            &mut cs.ns(|| "Rescale PubKey"),
+            &public_key.pub_key,
+            randomness.iter(),
+        )?;
+        Ok(SchnorrSigGadgetPk {
+            pub_key,
+            _group: PhantomData,
+            _engine: PhantomData,
+        })
+    }
+}
+
 impl<G, GG, D, ConstraintF> SigRandomizePkGadget<SchnorrSignature<G, D>, ConstraintF>
     for SchnorrRandomizePkGadget<G, ConstraintF, GG>
 where
@@ -106,35 +176,58 @@ where
         let generator = GG::alloc_constant(cs, val.borrow().generator)?;
         Ok(Self {
             generator,
+            salt: UInt8::constant_vec(&val.borrow().salt),
             _engine: PhantomData,
             _group: PhantomData,
         })
     }
 
-    fn alloc<F, T, CS: ConstraintSystem<ConstraintF>>(cs: CS, f: F) -> Result<Self, SynthesisError>
+    fn alloc<F, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        f: F,
+    ) -> Result<Self, SynthesisError>
     where
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<SchnorrSigParameters<G, D>>,
     {
-        let generator = GG::alloc_checked(cs, || f().map(|pp| pp.borrow().generator))?;
+        let pp = f();
+        let salt: Vec<Option<u8>> = match &pp {
+            Ok(pp) => pp.borrow().salt.iter().map(|b| Some(*b)).collect(),
+            Err(_) => vec![None; 32],
+        };
+        let salt = UInt8::alloc_vec(cs.ns(|| "salt"), &salt)?;
+        let generator =
+            GG::alloc_checked(cs.ns(|| "generator"), || pp.map(|pp| pp.borrow().generator))?;
         Ok(Self {
             generator,
+            salt,
             _engine: PhantomData,
             _group: PhantomData,
         })
     }
 
     fn alloc_input<F, T, CS: ConstraintSystem<ConstraintF>>(
-        cs: CS,
+        mut cs: CS,
         f: F,
     ) -> Result<Self, SynthesisError>
     where
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<SchnorrSigParameters<G, D>>,
     {
-        let generator = GG::alloc_input(cs, || f().map(|pp| pp.borrow().generator))?;
+        let pp = f();
+        let salt_value = pp.as_ref().ok().map(|pp| pp.borrow().salt);
+        let mut salt = Vec::with_capacity(32);
+        for i in 0..32 {
+            salt.push(UInt8::alloc_input(
+                cs.ns(|| format!("salt byte {}", i)),
+                || salt_value.map(|s| s[i]).ok_or(SynthesisError::AssignmentMissing),
+            )?);
+        }
+        let generator =
+            GG::alloc_input(cs.ns(|| "generator"), || pp.map(|pp| pp.borrow().generator))?;
         Ok(Self {
             generator,
+            salt,
             _engine: PhantomData,
             _group: PhantomData,
         })
@@ -240,3 +333,492 @@ where
         self.pub_key.to_bytes(&mut cs.ns(|| "PubKey To Bytes"))
     }
 }
+
+/// The little-endian byte encodings of the prover response and of the
+/// verifier challenge.
+#[derive(Derivative)]
+#[derivative(Clone(bound = "G: Group, ConstraintF: Field"))]
+pub struct SchnorrSigGadget<G: Group, ConstraintF: Field> {
+    prover_response: Vec<UInt8>,
+    verifier_challenge: Vec<UInt8>,
+    #[doc(hidden)]
+    _group: PhantomData<*const G>,
+    #[doc(hidden)]
+    _engine: PhantomData<*const ConstraintF>,
+}
+
+impl<G, ConstraintF> SchnorrSigGadget<G, ConstraintF>
+where
+    G: Group,
+    G::ScalarField: PrimeField,
+    ConstraintF: Field,
+{
+    /// The native verifier only ever sees the challenge as a field element,
+    /// so its encoding must be canonical.
+    fn enforce_challenge_in_field<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        cs: CS,
+    ) -> Result<(), SynthesisError> {
+        let mut challenge_bits = self
+            .verifier_challenge
+            .iter()
+            .flat_map(UInt8::into_bits_le)
+            .collect::<Vec<_>>();
+        challenge_bits.reverse();
+        Boolean::enforce_in_field::<_, _, G::ScalarField>(cs, &challenge_bits)
+    }
+}
+
+impl<G, ConstraintF> AllocGadget<SchnorrSig<G>, ConstraintF> for SchnorrSigGadget<G, ConstraintF>
+where
+    G: Group,
+    G::ScalarField: PrimeField,
+    ConstraintF: Field,
+{
+    fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
+        _cs: CS,
+        val: T,
+    ) -> Result<Self, SynthesisError>
+    where
+        T: Borrow<SchnorrSig<G>>,
+    {
+        let sig = val.borrow();
+        Ok(Self {
+            prover_response: UInt8::constant_vec(&to_bytes![sig.prover_response]?),
+            verifier_challenge: UInt8::constant_vec(&to_bytes![sig.verifier_challenge]?),
+            _group: PhantomData,
+            _engine: PhantomData,
+        })
+    }
+
+    fn alloc<F, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        f: F,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<SchnorrSig<G>>,
+    {
+        let sig = match f() {
+            Ok(sig) => sig.borrow().clone(),
+            Err(_) => SchnorrSig::default(),
+        };
+        let sig = Self {
+            prover_response: UInt8::alloc_vec(
+                cs.ns(|| "prover response"),
+                &to_bytes![sig.prover_response]?,
+            )?,
+            verifier_challenge: UInt8::alloc_vec(
+                cs.ns(|| "verifier challenge"),
+                &to_bytes![sig.verifier_challenge]?,
+            )?,
+            _group: PhantomData,
+            _engine: PhantomData,
+        };
+        sig.enforce_challenge_in_field(cs.ns(|| "challenge in field"))?;
+        Ok(sig)
+    }
+
+    fn alloc_input<F, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        f: F,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<SchnorrSig<G>>,
+    {
+        let sig = match f() {
+            Ok(sig) => sig.borrow().clone(),
+            Err(_) => SchnorrSig::default(),
+        };
+        let sig = Self {
+            prover_response: UInt8::alloc_input_vec(
+                cs.ns(|| "prover response"),
+                &to_bytes![sig.prover_response]?,
+            )?,
+            verifier_challenge: UInt8::alloc_input_vec(
+                cs.ns(|| "verifier challenge"),
+                &to_bytes![sig.verifier_challenge]?,
+            )?,
+            _group: PhantomData,
+            _engine: PhantomData,
+        };
+        sig.enforce_challenge_in_field(cs.ns(|| "challenge in field"))?;
+        Ok(sig)
+    }
+}
+
+pub struct SchnorrVerifyGadget<G, ConstraintF, GG, DG>
+where
+    G: Group,
+    ConstraintF: Field,
+    GG: GroupGadget<G, ConstraintF>,
+{
+    #[doc(hidden)]
+    _group: PhantomData<*const G>,
+    #[doc(hidden)]
+    _group_gadget: PhantomData<*const GG>,
+    #[doc(hidden)]
+    _digest_gadget: PhantomData<*const DG>,
+    #[doc(hidden)]
+    _engine: PhantomData<*const ConstraintF>,
+}
+
+impl<G, GG, D, DG, ConstraintF> SigVerifyGadget<SchnorrSignature<G, D>, ConstraintF>
+    for SchnorrVerifyGadget<G, ConstraintF, GG, DG>
+where
+    G: Group + Hash,
+    G::ScalarField: PrimeField,
+    GG: GroupGadget<G, ConstraintF>,
+    D: Digest + Send + Sync,
+    DG: DigestGadget<D, ConstraintF>,
+    ConstraintF: Field,
+{
+    type ParametersGadget = SchnorrSigGadgetParameters<G, ConstraintF, GG>;
+    type PublicKeyGadget = SchnorrSigGadgetPk<G, ConstraintF, GG>;
+    type SignatureGadget = SchnorrSigGadget<G, ConstraintF>;
+
+    /// Mirrors `SchnorrSignature::verify`. The commitment is computed with
+    /// `GroupGadget::mul_bits_2`, whose default needs a complete addition
+    /// law; gadgets without one, like the short Weierstrass `AffineGadget`,
+    /// override it.
+    ///
+    /// The commitment is hashed as `GG::to_bytes`, that is, in affine form,
+    /// so this only accepts the signatures of `SchnorrSignature` for groups
+    /// whose `ToBytes` is the affine encoding too, like twisted Edwards
+    /// affine points. For projective groups the signer must hash the affine
+    /// commitment instead.
+    fn verify<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        parameters: &Self::ParametersGadget,
+        public_key: &Self::PublicKeyGadget,
+        message: &[UInt8],
+        signature: &Self::SignatureGadget,
+    ) -> Result<Boolean, SynthesisError> {
+        let prover_response_bits = signature
+            .prover_response
+            .iter()
+            .flat_map(UInt8::into_bits_le)
+            .collect::<Vec<_>>();
+        let verifier_challenge_bits = signature
+            .verifier_challenge
+            .iter()
+            .flat_map(UInt8::into_bits_le)
+            .collect::<Vec<_>>();
+        let claimed_prover_commitment = GG::mul_bits_2(
+            cs.ns(|| "claimed prover commitment"),
+            &parameters.generator,
+            &prover_response_bits,
+            &public_key.pub_key,
+            &verifier_challenge_bits,
+        )?;
+
+        let mut hash_input = parameters.salt.clone();
+        hash_input.extend(claimed_prover_commitment.to_bytes(cs.ns(|| "commitment to bytes"))?);
+        hash_input.extend_from_slice(message);
+        let digest = DG::digest(cs.ns(|| "digest"), &hash_input)?;
+
+        // `from_random_bytes` keeps the low `MODULUS_BITS` bits of the digest,
+        // and the challenge is known to be smaller than the modulus.
+        let num_bits = <G::ScalarField as PrimeField>::Params::MODULUS_BITS as usize;
+        let digest_bits = digest
+            .iter()
+            .flat_map(UInt8::into_bits_le)
+            .chain(core::iter::repeat(Boolean::constant(false)))
+            .take(num_bits);
+        let mut bits_equal = Vec::with_capacity(num_bits);
+        for (i, (a, b)) in digest_bits.zip(&verifier_challenge_bits).enumerate() {
+            bits_equal.push(Boolean::xnor(
+                cs.ns(|| format!("challenge bit {} equal", i)),
+                &a,
+                b,
+            )?);
+        }
+        Boolean::kary_and(cs.ns(|| "challenge equal"), &bits_equal)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        prf::blake2s::constraints::Blake2sGadget,
+        signature::{
+            schnorr::{
+                constraints::{SchnorrRandomizePkGadget, SchnorrVerifyGadget},
+                SchnorrSig, SchnorrSigParameters, SchnorrSignature,
+            },
+            SigVerifyGadget, SignatureScheme,
+        },
+    };
+    use algebra::{
+        ed_on_bls12_381::{EdwardsAffine as JubJub, Fq, Fr},
+        groups::Group,
+        test_rng, to_bytes, One, ToBytes, UniformRand,
+    };
+    use blake2::Blake2s;
+    use r1cs_core::{ConstraintSystem, SynthesisError};
+    use r1cs_std::{
+        ed_on_bls12_381::EdwardsGadget, prelude::*, test_constraint_counter::ConstraintCounter,
+        test_constraint_system::TestConstraintSystem,
+    };
+
+    type TestSig = SchnorrSignature<JubJub, Blake2s>;
+    type TestSigGadget = SchnorrVerifyGadget<JubJub, Fq, EdwardsGadget, Blake2sGadget>;
+    type TestRandomizeGadget = SchnorrRandomizePkGadget<JubJub, Fq, EdwardsGadget>;
+
+    fn verify_in_circuit(tamper_with_signature: bool) -> (TestConstraintSystem<Fq>, Boolean) {
+        let rng = &mut test_rng();
+        let message = b"Hi, I am a Schnorr signature!";
+        let parameters = TestSig::setup(rng).unwrap();
+        let (pk, sk) = TestSig::keygen(&parameters, rng).unwrap();
+        let mut sig = TestSig::sign(&parameters, &sk, message, rng).unwrap();
+        if tamper_with_signature {
+            sig.prover_response += &Fr::one();
+        }
+        let native_result = TestSig::verify(&parameters, &pk, message, &sig).unwrap();
+        assert_eq!(native_result, !tamper_with_signature);
+
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let parameters_gadget =
+            <TestSigGadget as SigVerifyGadget<TestSig, Fq>>::ParametersGadget::alloc(
+                cs.ns(|| "parameters"),
+                || Ok(&parameters),
+            )
+            .unwrap();
+        let pk_gadget = <TestSigGadget as SigVerifyGadget<TestSig, Fq>>::PublicKeyGadget::alloc(
+            cs.ns(|| "public key"),
+            || Ok(&pk),
+        )
+        .unwrap();
+        let sig_gadget = <TestSigGadget as SigVerifyGadget<TestSig, Fq>>::SignatureGadget::alloc(
+            cs.ns(|| "signature"),
+            || Ok(&sig),
+        )
+        .unwrap();
+        let message_gadget = UInt8::alloc_vec(cs.ns(|| "message"), message).unwrap();
+        let result = TestSigGadget::verify(
+            cs.ns(|| "verify"),
+            &parameters_gadget,
+            &pk_gadget,
+            &message_gadget,
+            &sig_gadget,
+        )
+        .unwrap();
+        (cs, result)
+    }
+
+    #[test]
+    fn schnorr_verify_gadget_test() {
+        let (cs, result) = verify_in_circuit(false);
+        assert_eq!(result.get_value(), Some(true));
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn schnorr_verify_gadget_tampered_signature_test() {
+        let (cs, result) = verify_in_circuit(true);
+        assert_eq!(result.get_value(), Some(false));
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn schnorr_verify_gadget_setup_mode_test() {
+        let message_len = b"Hi, I am a Schnorr signature!".len();
+        // The counter never calls the value closures, like a key generator.
+        let mut cs = ConstraintCounter::new();
+        let parameters_gadget =
+            <TestSigGadget as SigVerifyGadget<TestSig, Fq>>::ParametersGadget::alloc(
+                cs.ns(|| "parameters"),
+                || Err::<SchnorrSigParameters<_, Blake2s>, _>(SynthesisError::AssignmentMissing),
+            )
+            .unwrap();
+        let pk_gadget = <TestSigGadget as SigVerifyGadget<TestSig, Fq>>::PublicKeyGadget::alloc(
+            cs.ns(|| "public key"),
+            || Err::<JubJub, _>(SynthesisError::AssignmentMissing),
+        )
+        .unwrap();
+        let sig_gadget = <TestSigGadget as SigVerifyGadget<TestSig, Fq>>::SignatureGadget::alloc(
+            cs.ns(|| "signature"),
+            || Err::<SchnorrSig<JubJub>, _>(SynthesisError::AssignmentMissing),
+        )
+        .unwrap();
+        let message_gadget =
+            UInt8::alloc_vec(cs.ns(|| "message"), &vec![None::<u8>; message_len]).unwrap();
+        let result = TestSigGadget::verify(
+            cs.ns(|| "verify"),
+            &parameters_gadget,
+            &pk_gadget,
+            &message_gadget,
+            &sig_gadget,
+        )
+        .unwrap();
+        assert_eq!(result.get_value(), None);
+
+        let (proving_cs, _) = verify_in_circuit(false);
+        assert_eq!(cs.num_constraints(), proving_cs.num_constraints());
+    }
+
+    #[test]
+    fn schnorr_verify_gadget_short_weierstrass_test() {
+        use algebra::{
+            bls12_377::{Fq, Fr, G1Affine, G1Projective},
+            Field, Zero,
+        };
+        use digest::Digest;
+        use r1cs_std::bls12_377::G1Gadget;
+
+        type SwSig = SchnorrSignature<G1Projective, Blake2s>;
+        type SwSigGadget = SchnorrVerifyGadget<G1Projective, Fq, G1Gadget, Blake2sGadget>;
+
+        let rng = &mut test_rng();
+        let message = b"Hi, I am a Schnorr signature!";
+        let parameters = SwSig::setup(rng).unwrap();
+        let sk = Fr::rand(rng);
+        // `SchnorrSignature::sign` hashes the projective commitment, while the
+        // gadget hashes its affine form.
+        let mut sign = |sk: &Fr| loop {
+            let random_scalar = Fr::rand(rng);
+            let prover_commitment = G1Affine::from(parameters.generator.mul(&random_scalar));
+            let mut hash_input = parameters.salt.to_vec();
+            hash_input.extend_from_slice(&to_bytes![prover_commitment].unwrap());
+            hash_input.extend_from_slice(message);
+            if let Some(verifier_challenge) = Fr::from_random_bytes(&Blake2s::digest(&hash_input)) {
+                break SchnorrSig::<G1Projective> {
+                    prover_response: random_scalar - &(verifier_challenge * sk),
+                    verifier_challenge,
+                };
+            }
+        };
+        let sig = sign(&sk);
+        let mut tampered_sig = sig.clone();
+        tampered_sig.prover_response += &Fr::one();
+        // A zero secret key makes the public key the identity.
+        let zero_sk_sig = sign(&Fr::zero());
+        let cases = [
+            (parameters.generator.mul(&sk), sig, true),
+            (parameters.generator.mul(&sk), tampered_sig, false),
+            (G1Projective::zero(), zero_sk_sig, true),
+        ];
+
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let parameters_gadget =
+            <SwSigGadget as SigVerifyGadget<SwSig, Fq>>::ParametersGadget::alloc(
+                cs.ns(|| "parameters"),
+                || Ok(&parameters),
+            )
+            .unwrap();
+        let message_gadget = UInt8::alloc_vec(cs.ns(|| "message"), message).unwrap();
+        for (i, (pk, sig, expected)) in cases.iter().enumerate() {
+            let mut cs = cs.ns(|| format!("Case {}", i));
+            let pk_gadget = <SwSigGadget as SigVerifyGadget<SwSig, Fq>>::PublicKeyGadget::alloc(
+                cs.ns(|| "public key"),
+                || Ok(pk),
+            )
+            .unwrap();
+            let sig_gadget = <SwSigGadget as SigVerifyGadget<SwSig, Fq>>::SignatureGadget::alloc(
+                cs.ns(|| "signature"),
+                || Ok(sig),
+            )
+            .unwrap();
+            let result = SwSigGadget::verify(
+                cs.ns(|| "verify"),
+                &parameters_gadget,
+                &pk_gadget,
+                &message_gadget,
+                &sig_gadget,
+            )
+            .unwrap();
+            assert_eq!(result.get_value(), Some(*expected), "case {}", i);
+        }
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn schnorr_randomize_parameters_test() {
+        let rng = &mut test_rng();
+        let message = b"Hi, I am a Schnorr signature!";
+        let parameters = TestSig::setup(rng).unwrap();
+        let (pk, sk) = TestSig::keygen(&parameters, rng).unwrap();
+        let sig = TestSig::sign(&parameters, &sk, message, rng).unwrap();
+
+        // Natively, rescaling the generator by `1 + randomness` and signing
+        // with the same secret key.
+        let randomness = Fr::rand(rng);
+        let scale = Fr::one() + &randomness;
+        let randomized_parameters = SchnorrSigParameters {
+            generator: parameters.generator.mul(&scale),
+            ..parameters.clone()
+        };
+        let randomized_pk = pk.mul(&scale);
+        let randomized_sig = TestSig::sign(&randomized_parameters, &sk, message, rng).unwrap();
+        assert!(TestSig::verify(
+            &randomized_parameters,
+            &randomized_pk,
+            message,
+            &randomized_sig
+        )
+        .unwrap());
+
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let parameters_gadget =
+            <TestSigGadget as SigVerifyGadget<TestSig, Fq>>::ParametersGadget::alloc(
+                cs.ns(|| "parameters"),
+                || Ok(&parameters),
+            )
+            .unwrap();
+        let pk_gadget = <TestSigGadget as SigVerifyGadget<TestSig, Fq>>::PublicKeyGadget::alloc(
+            cs.ns(|| "public key"),
+            || Ok(&pk),
+        )
+        .unwrap();
+        let randomness_gadget =
+            UInt8::alloc_vec(cs.ns(|| "randomness"), &to_bytes![randomness].unwrap()).unwrap();
+        let randomized_parameters_gadget = TestRandomizeGadget::randomize_parameters(
+            cs.ns(|| "randomize parameters"),
+            &parameters_gadget,
+            &randomness_gadget,
+        )
+        .unwrap();
+        let randomized_pk_gadget = TestRandomizeGadget::randomize_public_key_for_parameters(
+            cs.ns(|| "randomize public key"),
+            &pk_gadget,
+            &randomness_gadget,
+        )
+        .unwrap();
+        let message_gadget = UInt8::alloc_vec(cs.ns(|| "message"), message).unwrap();
+
+        let randomized_sig_gadget =
+            <TestSigGadget as SigVerifyGadget<TestSig, Fq>>::SignatureGadget::alloc(
+                cs.ns(|| "randomized signature"),
+                || Ok(&randomized_sig),
+            )
+            .unwrap();
+        let result = TestSigGadget::verify(
+            cs.ns(|| "verify randomized signature"),
+            &randomized_parameters_gadget,
+            &randomized_pk_gadget,
+            &message_gadget,
+            &randomized_sig_gadget,
+        )
+        .unwrap();
+        assert_eq!(result.get_value(), Some(true));
+
+        // The challenge binds the prover commitment, so signatures made under
+        // the original parameters do not carry over.
+        let sig_gadget = <TestSigGadget as SigVerifyGadget<TestSig, Fq>>::SignatureGadget::alloc(
+            cs.ns(|| "signature"),
+            || Ok(&sig),
+        )
+        .unwrap();
+        let result = TestSigGadget::verify(
+            cs.ns(|| "verify original signature"),
+            &randomized_parameters_gadget,
+            &randomized_pk_gadget,
+            &message_gadget,
+            &sig_gadget,
+        )
+        .unwrap();
+        assert_eq!(result.get_value(), Some(false));
+        assert!(cs.is_satisfied());
+    }
+}
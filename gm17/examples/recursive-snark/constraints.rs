@@ -1,8 +1,5 @@
-use algebra::{
-    fields::{FftParameters, FpParameters},
-    BigInteger, Field, PrimeField,
-};
-use algebra_core::{PairingEngine, ToConstraintField};
+use algebra::Field;
+use algebra_core::PairingEngine;
 use crypto_primitives::nizk::{
     constraints::NIZKVerifierGadget,
     gm17::{
@@ -13,8 +10,11 @@ use crypto_primitives::nizk::{
 use gm17::{Parameters, Proof};
 use r1cs_core::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
 use r1cs_std::{
-    alloc::AllocGadget, bits::ToBitsGadget, boolean::Boolean, fields::fp::FpGadget,
-    pairing::PairingGadget as PG, uint8::UInt8,
+    alloc::{alloc_input_vec, AllocGadget},
+    bits::ToBitsGadget,
+    boolean::Boolean,
+    fields::{fp::FpGadget, nonnative::NonNativeFieldGadget},
+    pairing::PairingGadget as PG,
 };
 use std::marker::PhantomData;
 
@@ -56,6 +56,12 @@ type InnerVkGadget<C> = VerifyingKeyGadget<
     <C as CurvePair>::PairingGadgetTick,
 >;
 
+// The inputs of InnerCircuit, emulated in MiddleCircuit
+type InnerInputGadget<C> = NonNativeFieldGadget<
+    <<C as CurvePair>::PairingEngineTick as PairingEngine>::Fr,
+    <<C as CurvePair>::PairingEngineTock as PairingEngine>::Fr,
+>;
+
 // Verifying MiddleCircuit in OuterCircuit
 type MiddleProofSystem<C> = Gm17<
     <C as CurvePair>::PairingEngineTock,
@@ -150,19 +156,10 @@ impl<C: CurvePair> MiddleCircuit<C> {
     pub fn inputs(
         inputs: &[<C::PairingEngineTick as PairingEngine>::Fr],
     ) -> Vec<<C::PairingEngineTock as PairingEngine>::Fr> {
-        let input_bytes = inputs
+        inputs
             .iter()
-            .flat_map(|input| {
-                input
-                    .into_repr()
-                    .as_ref()
-                    .iter()
-                    .flat_map(|l| l.to_le_bytes().to_vec())
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
-
-        input_bytes[..].to_field_elements().unwrap()
+            .flat_map(InnerInputGadget::<C>::get_limbs_representations)
+            .collect()
     }
 }
 
@@ -180,33 +177,22 @@ impl<C: CurvePair> ConstraintSynthesizer<<C::PairingEngineTock as PairingEngine>
 
         {
             let mut cs = cs.ns(|| "Allocate Input");
-            // Chain all input values in one large byte array.
-            let input_bytes = inputs
+            // Allocate each input as a non-native field element, whose limbs
+            // are the public inputs of this circuit.
+            input_gadgets = inputs
                 .into_iter()
-                .flat_map(|input| {
-                    input
-                        .into_repr()
-                        .as_ref()
-                        .iter()
-                        .flat_map(|l| l.to_le_bytes().to_vec())
-                        .collect::<Vec<_>>()
+                .enumerate()
+                .map(|(i, input)| {
+                    let input_gadget = InnerInputGadget::<C>::alloc_input(
+                        cs.ns(|| format!("Input {}", i)),
+                        || Ok(input),
+                    )?;
+                    // The sub-SNARK verifier expects little-endian bits.
+                    let mut bits = input_gadget.to_bits(cs.ns(|| format!("To bits {}", i)))?;
+                    bits.reverse();
+                    Ok(bits)
                 })
-                .collect::<Vec<_>>();
-
-            // Allocate this byte array as input packed into field elements.
-            let input_bytes = UInt8::alloc_input_vec(cs.ns(|| "Input"), &input_bytes[..])?;
-            // 40 byte
-            let element_size =
-                <<<C::PairingEngineTick as PairingEngine>::Fr as PrimeField>::Params as FftParameters>::BigInt::NUM_LIMBS * 8;
-            input_gadgets = input_bytes
-                .chunks(element_size)
-                .map(|chunk| {
-                    chunk
-                        .iter()
-                        .flat_map(|byte| byte.into_bits_le())
-                        .collect::<Vec<_>>()
-                })
-                .collect::<Vec<_>>();
+                .collect::<Result<Vec<_>, SynthesisError>>()?;
         }
         println!("|---- Num inputs for sub-SNARK: {}", input_gadgets.len());
         let num_constraints = cs.num_constraints();
@@ -269,13 +255,10 @@ impl<C: CurvePair> ConstraintSynthesizer<<C::PairingEngineTick as PairingEngine>
         let mut input_gadgets = Vec::new();
 
         {
-            let bigint_size =
-                <<<C::PairingEngineTick as PairingEngine>::Fr as PrimeField>::Params as FftParameters>::BigInt::NUM_LIMBS * 64;
-            let mut input_bits = Vec::new();
+            let (bits_per_limb, num_limbs) = InnerInputGadget::<C>::limb_parameters();
             let mut cs = cs.ns(|| "Allocate Input");
-            for (i, input) in inputs.into_iter().enumerate() {
-                let input_gadget =
-                    FpGadget::alloc_input(cs.ns(|| format!("Input {}", i)), || Ok(input))?;
+            let input_fes: Vec<FpGadget<_>> = alloc_input_vec(cs.ns(|| "Input"), inputs)?;
+            for (i, input_gadget) in input_fes.iter().enumerate() {
                 let mut fp_bits = input_gadget.to_bits(cs.ns(|| format!("To bits {}", i)))?;
 
                 // FpGadget::to_bits outputs a big-endian binary representation of
@@ -283,26 +266,12 @@ impl<C: CurvePair> ConstraintSynthesizer<<C::PairingEngineTick as PairingEngine>
                 // form.
                 fp_bits.reverse();
 
-                // Use 320 bits per element.
-                for _ in fp_bits.len()..bigint_size {
-                    fp_bits.push(Boolean::constant(false));
-                }
-                input_bits.extend_from_slice(&fp_bits);
-            }
-
-            // Pack input bits into field elements of the underlying circuit.
-            let max_size = 8
-                * (<<<C::PairingEngineTock as PairingEngine>::Fr as PrimeField>::Params as FpParameters>::CAPACITY / 8)
-                    as usize;
-            let bigint_size =
-                <<<C::PairingEngineTock as PairingEngine>::Fr as PrimeField>::Params as FftParameters>::BigInt::NUM_LIMBS * 64;
-            for chunk in input_bits.chunks(max_size) {
-                let mut chunk = chunk.to_vec();
-                let len = chunk.len();
-                for _ in len..bigint_size {
-                    chunk.push(Boolean::constant(false));
+                // The inputs of the sub-SNARK are the limbs of each element;
+                // see `MiddleCircuit::inputs`.
+                fp_bits.resize(num_limbs * bits_per_limb, Boolean::constant(false));
+                for limb_bits in fp_bits.chunks(bits_per_limb) {
+                    input_gadgets.push(limb_bits.to_vec());
                 }
-                input_gadgets.push(chunk);
             }
         }
         println!("|---- Num inputs for sub-SNARK: {}", input_gadgets.len());
@@ -21,6 +21,8 @@ pub enum SynthesisError {
     MalformedVerifyingKey,
     /// During CRS generation, we observed an unconstrained auxiliary variable
     UnconstrainedVariable,
+    /// During synthesis, a gadget was called with arguments it does not accept.
+    InvalidArgument,
 }
 
 impl From<io::Error> for SynthesisError {
@@ -53,6 +55,7 @@ impl fmt::Display for SynthesisError {
             SynthesisError::UnconstrainedVariable => {
                 write!(f, "auxiliary variable was unconstrained")
             }
+            SynthesisError::InvalidArgument => write!(f, "invalid argument to a gadget"),
         }
     }
 }
@@ -0,0 +1,261 @@
+//! Derives for the gadget traits of `r1cs-std`, which apply the trait to each
+//! field of a struct in turn.
+//!
+//! Like the derives of `algebra-core`, the generated code refers to the traits
+//! and types it uses by their bare names, so these must be in scope: the
+//! `r1cs-std` prelude together with `ConstraintSystem` and `SynthesisError`
+//! from `r1cs-core` is enough, plus `Vec` for `ToBytesGadget`. The constraint field is the struct's type
+//! parameter named `ConstraintF` if it has one; otherwise a new parameter
+//! `ConstraintF: Field` is introduced, and `Field` must be in scope too.
+//!
+//! `PhantomData` fields are skipped, and are recreated by `CondSelectGadget`.
+extern crate proc_macro;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, GenericParam, Generics, Index, Member, Type,
+};
+
+/// Derives `ConditionalEqGadget` and `EqGadget`, enforcing each field to be
+/// equal under the same condition. The struct must also implement `Eq`.
+#[proc_macro_derive(EqGadget)]
+pub fn derive_eq_gadget(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    proc_macro::TokenStream::from(impl_eq_gadget(&ast))
+}
+
+/// Derives `ToBytesGadget`, concatenating the bytes of each field.
+#[proc_macro_derive(ToBytesGadget)]
+pub fn derive_to_bytes_gadget(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    proc_macro::TokenStream::from(impl_to_bytes_gadget(&ast))
+}
+
+/// Derives `CondSelectGadget`, selecting each field with the same condition.
+#[proc_macro_derive(CondSelectGadget)]
+pub fn derive_cond_select_gadget(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    proc_macro::TokenStream::from(impl_cond_select_gadget(&ast))
+}
+
+struct GadgetField {
+    member: Member,
+    /// The name of the namespace in which the field is handled.
+    name: String,
+    ty: Type,
+    is_phantom: bool,
+}
+
+fn gadget_fields(ast: &DeriveInput, derive: &str) -> Vec<GadgetField> {
+    match ast.data {
+        Data::Struct(ref data_struct) => data_struct
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let member = match field.ident {
+                    Some(ref ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(i)),
+                };
+                let name = match field.ident {
+                    Some(ref ident) => ident.to_string(),
+                    None => i.to_string(),
+                };
+                GadgetField {
+                    member,
+                    name,
+                    ty: field.ty.clone(),
+                    is_phantom: is_phantom_data(&field.ty),
+                }
+            })
+            .collect(),
+        _ => panic!(
+            "{} can only be derived for structs, {} is not a struct",
+            derive, ast.ident
+        ),
+    }
+}
+
+fn is_phantom_data(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map_or(false, |segment| segment.ident == "PhantomData"),
+        _ => false,
+    }
+}
+
+/// Returns the generics of the impl, which add a `ConstraintF` parameter if
+/// the struct does not have one, and require each non-phantom field to
+/// implement `bound`.
+fn impl_generics(ast: &DeriveInput, fields: &[GadgetField], bound: TokenStream) -> Generics {
+    let mut generics = ast.generics.clone();
+    let has_constraint_field = generics.params.iter().any(|param| match param {
+        GenericParam::Type(ty) => ty.ident == "ConstraintF",
+        _ => false,
+    });
+    if !has_constraint_field {
+        generics.params.push(parse_quote!(ConstraintF: Field));
+    }
+    let where_clause = generics.make_where_clause();
+    for field in fields.iter().filter(|field| !field.is_phantom) {
+        let ty = &field.ty;
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: #bound<ConstraintF>));
+    }
+    generics
+}
+
+fn impl_eq_gadget(ast: &DeriveInput) -> TokenStream {
+    let name = &ast.ident;
+    let fields = gadget_fields(ast, "EqGadget");
+    let generics = impl_generics(ast, &fields, quote!(ConditionalEqGadget));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+
+    let mut enforce_body = Vec::<TokenStream>::new();
+    let mut cost_body = Vec::<TokenStream>::new();
+    for field in fields.iter().filter(|field| !field.is_phantom) {
+        let GadgetField {
+            member, name, ty, ..
+        } = field;
+        enforce_body.push(quote! {
+            <#ty as ConditionalEqGadget<ConstraintF>>::conditional_enforce_equal(
+                &self.#member,
+                cs.ns(|| #name),
+                &other.#member,
+                condition,
+            )?;
+        });
+        cost_body.push(quote! { + <#ty as ConditionalEqGadget<ConstraintF>>::cost() });
+    }
+
+    quote! {
+        impl #impl_generics ConditionalEqGadget<ConstraintF> for #name #ty_generics #where_clause {
+            #[allow(unused_mut, unused_variables)]
+            fn conditional_enforce_equal<CS: ConstraintSystem<ConstraintF>>(
+                &self,
+                mut cs: CS,
+                other: &Self,
+                condition: &Boolean,
+            ) -> Result<(), SynthesisError> {
+                #(#enforce_body)*
+                Ok(())
+            }
+
+            fn cost() -> usize {
+                0 #(#cost_body)*
+            }
+        }
+
+        impl #impl_generics EqGadget<ConstraintF> for #name #ty_generics #where_clause {}
+    }
+}
+
+fn impl_to_bytes_gadget(ast: &DeriveInput) -> TokenStream {
+    let name = &ast.ident;
+    let fields = gadget_fields(ast, "ToBytesGadget");
+    let generics = impl_generics(ast, &fields, quote!(ToBytesGadget));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+
+    let mut to_bytes_body = Vec::<TokenStream>::new();
+    let mut to_non_unique_bytes_body = Vec::<TokenStream>::new();
+    for field in fields.iter().filter(|field| !field.is_phantom) {
+        let GadgetField {
+            member, name, ty, ..
+        } = field;
+        to_bytes_body.push(quote! {
+            bytes.extend(<#ty as ToBytesGadget<ConstraintF>>::to_bytes(
+                &self.#member,
+                cs.ns(|| #name),
+            )?);
+        });
+        to_non_unique_bytes_body.push(quote! {
+            bytes.extend(<#ty as ToBytesGadget<ConstraintF>>::to_non_unique_bytes(
+                &self.#member,
+                cs.ns(|| #name),
+            )?);
+        });
+    }
+
+    quote! {
+        impl #impl_generics ToBytesGadget<ConstraintF> for #name #ty_generics #where_clause {
+            #[allow(unused_mut, unused_variables)]
+            fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
+                &self,
+                mut cs: CS,
+            ) -> Result<Vec<UInt8>, SynthesisError> {
+                let mut bytes = Vec::new();
+                #(#to_bytes_body)*
+                Ok(bytes)
+            }
+
+            #[allow(unused_mut, unused_variables)]
+            fn to_non_unique_bytes<CS: ConstraintSystem<ConstraintF>>(
+                &self,
+                mut cs: CS,
+            ) -> Result<Vec<UInt8>, SynthesisError> {
+                let mut bytes = Vec::new();
+                #(#to_non_unique_bytes_body)*
+                Ok(bytes)
+            }
+        }
+    }
+}
+
+fn impl_cond_select_gadget(ast: &DeriveInput) -> TokenStream {
+    let name = &ast.ident;
+    let fields = gadget_fields(ast, "CondSelectGadget");
+    let generics = impl_generics(ast, &fields, quote!(CondSelectGadget));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+
+    let mut select_body = Vec::<TokenStream>::new();
+    let mut cost_body = Vec::<TokenStream>::new();
+    for field in &fields {
+        let GadgetField {
+            member,
+            name,
+            ty,
+            is_phantom,
+        } = field;
+        if *is_phantom {
+            select_body.push(quote! { #member: ::core::marker::PhantomData, });
+        } else {
+            select_body.push(quote! {
+                #member: <#ty as CondSelectGadget<ConstraintF>>::conditionally_select(
+                    cs.ns(|| #name),
+                    cond,
+                    &true_value.#member,
+                    &false_value.#member,
+                )?,
+            });
+            cost_body.push(quote! { + <#ty as CondSelectGadget<ConstraintF>>::cost() });
+        }
+    }
+
+    quote! {
+        impl #impl_generics CondSelectGadget<ConstraintF> for #name #ty_generics #where_clause {
+            #[allow(unused_mut, unused_variables)]
+            fn conditionally_select<CS: ConstraintSystem<ConstraintF>>(
+                mut cs: CS,
+                cond: &Boolean,
+                true_value: &Self,
+                false_value: &Self,
+            ) -> Result<Self, SynthesisError> {
+                Ok(Self {
+                    #(#select_body)*
+                })
+            }
+
+            fn cost() -> usize {
+                0 #(#cost_body)*
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use algebra::{bls12_381::Fr, PrimeField, UniformRand};
+use core::marker::PhantomData;
+use r1cs_core::{ConstraintSystem, SynthesisError};
+use r1cs_derive::{CondSelectGadget, EqGadget, ToBytesGadget};
+use r1cs_std::{prelude::*, test_constraint_system::TestConstraintSystem};
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+#[derive(Clone, PartialEq, Eq, EqGadget, ToBytesGadget, CondSelectGadget)]
+struct Pair<ConstraintF: PrimeField> {
+    element: FpGadget<ConstraintF>,
+    byte: UInt8,
+    _field: PhantomData<ConstraintF>,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+struct HandWrittenPair<ConstraintF: PrimeField> {
+    element: FpGadget<ConstraintF>,
+    byte: UInt8,
+    _field: PhantomData<ConstraintF>,
+}
+
+impl<ConstraintF: PrimeField> ConditionalEqGadget<ConstraintF> for HandWrittenPair<ConstraintF> {
+    fn conditional_enforce_equal<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError> {
+        self.element
+            .conditional_enforce_equal(cs.ns(|| "element"), &other.element, condition)?;
+        self.byte
+            .conditional_enforce_equal(cs.ns(|| "byte"), &other.byte, condition)?;
+        Ok(())
+    }
+
+    fn cost() -> usize {
+        <FpGadget<ConstraintF> as ConditionalEqGadget<ConstraintF>>::cost()
+            + <UInt8 as ConditionalEqGadget<ConstraintF>>::cost()
+    }
+}
+
+impl<ConstraintF: PrimeField> EqGadget<ConstraintF> for HandWrittenPair<ConstraintF> {}
+
+fn alloc_pair<CS: ConstraintSystem<Fr>>(mut cs: CS, element: Fr, byte: u8) -> Pair<Fr> {
+    Pair {
+        element: FpGadget::alloc(cs.ns(|| "element"), || Ok(element)).unwrap(),
+        byte: UInt8::alloc(cs.ns(|| "byte"), || Ok(byte)).unwrap(),
+        _field: PhantomData,
+    }
+}
+
+#[test]
+fn derived_eq_gadget_matches_hand_written() {
+    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+    let element = Fr::rand(&mut rng);
+    let other_element = Fr::rand(&mut rng);
+
+    for &(other_element, other_byte) in &[(element, 7u8), (other_element, 7), (element, 8)] {
+        for &condition in &[true, false] {
+            let mut derived_cs = TestConstraintSystem::<Fr>::new();
+            let a = alloc_pair(derived_cs.ns(|| "a"), element, 7);
+            let b = alloc_pair(derived_cs.ns(|| "b"), other_element, other_byte);
+            let condition_gadget =
+                Boolean::alloc(derived_cs.ns(|| "condition"), || Ok(condition)).unwrap();
+            a.conditional_enforce_equal(derived_cs.ns(|| "enforce"), &b, &condition_gadget)
+                .unwrap();
+
+            let mut hand_written_cs = TestConstraintSystem::<Fr>::new();
+            let to_hand_written = |pair: Pair<Fr>| HandWrittenPair {
+                element: pair.element,
+                byte: pair.byte,
+                _field: PhantomData,
+            };
+            let a = to_hand_written(alloc_pair(hand_written_cs.ns(|| "a"), element, 7));
+            let b = to_hand_written(alloc_pair(
+                hand_written_cs.ns(|| "b"),
+                other_element,
+                other_byte,
+            ));
+            let condition_gadget =
+                Boolean::alloc(hand_written_cs.ns(|| "condition"), || Ok(condition)).unwrap();
+            a.conditional_enforce_equal(hand_written_cs.ns(|| "enforce"), &b, &condition_gadget)
+                .unwrap();
+
+            let equal = other_element == element && other_byte == 7;
+            assert_eq!(derived_cs.is_satisfied(), equal || !condition);
+            assert_eq!(derived_cs.is_satisfied(), hand_written_cs.is_satisfied());
+            assert_eq!(
+                derived_cs.num_constraints(),
+                hand_written_cs.num_constraints()
+            );
+            assert_eq!(
+                <Pair<Fr> as ConditionalEqGadget<Fr>>::cost(),
+                <HandWrittenPair<Fr> as ConditionalEqGadget<Fr>>::cost()
+            );
+        }
+    }
+}
+
+#[test]
+fn derived_to_bytes_and_cond_select_gadgets() {
+    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+    let mut cs = TestConstraintSystem::<Fr>::new();
+    let a_element = Fr::rand(&mut rng);
+    let b_element = Fr::rand(&mut rng);
+    let a = alloc_pair(cs.ns(|| "a"), a_element, 1);
+    let b = alloc_pair(cs.ns(|| "b"), b_element, 2);
+
+    let bytes = a.to_bytes(cs.ns(|| "to bytes")).unwrap();
+    let mut expected = a.element.to_bytes(cs.ns(|| "element to bytes")).unwrap();
+    expected.push(a.byte.clone());
+    assert_eq!(bytes, expected);
+
+    for &cond in &[true, false] {
+        let cond_gadget = Boolean::constant(cond);
+        let selected =
+            Pair::conditionally_select(cs.ns(|| format!("select {}", cond)), &cond_gadget, &a, &b)
+                .unwrap();
+        let (element, byte) = if cond { (a_element, 1) } else { (b_element, 2) };
+        assert_eq!(selected.element.get_value(), Some(element));
+        assert_eq!(selected.byte.get_value(), Some(byte));
+    }
+    assert!(cs.is_satisfied());
+}
@@ -1,8 +1,29 @@
-use crate::Vec;
+use crate::{bits::boolean::Boolean, Assignment, Vec};
 use algebra::Field;
 use core::borrow::Borrow;
 use r1cs_core::{ConstraintSystem, SynthesisError};
 
+/// How a variable is allocated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AllocationMode {
+    /// A constant, which allocates no variables.
+    Constant,
+    /// A public input.
+    Input,
+    /// A private witness.
+    Witness,
+}
+
+impl AllocationMode {
+    pub fn is_constant(self) -> bool {
+        self == AllocationMode::Constant
+    }
+
+    pub fn is_input(self) -> bool {
+        self == AllocationMode::Input
+    }
+}
+
 pub trait AllocGadget<V, ConstraintF: Field>
 where
     Self: Sized,
@@ -49,6 +70,43 @@ where
     {
         Self::alloc_input(cs, f)
     }
+
+    /// Dispatches to `alloc_constant`, `alloc_input` or `alloc` according to
+    /// `mode`. A constant needs its value, so `f` is called immediately in that
+    /// case.
+    fn alloc_with_mode<F, T, CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        f: F,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<V>,
+    {
+        match mode {
+            AllocationMode::Constant => Self::alloc_constant(cs, f()?),
+            AllocationMode::Input => Self::alloc_input(cs, f),
+            AllocationMode::Witness => Self::alloc(cs, f),
+        }
+    }
+
+    /// Allocates a public input if `is_input` is set, and a witness otherwise.
+    fn alloc_with_mode_from_bool<F, T, CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        f: F,
+        is_input: bool,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<V>,
+    {
+        let mode = if is_input {
+            AllocationMode::Input
+        } else {
+            AllocationMode::Witness
+        };
+        Self::alloc_with_mode(cs, f, mode)
+    }
 }
 
 impl<I, ConstraintF: Field, A: AllocGadget<I, ConstraintF>> AllocGadget<[I], ConstraintF>
@@ -104,6 +162,15 @@ impl<I, ConstraintF: Field, A: AllocGadget<I, ConstraintF>> AllocGadget<[I], Con
         Ok(vec)
     }
 
+    /// Checks every element individually.
+    ///
+    /// For group elements there is no cheaper batched check: proving that a
+    /// random linear combination of the elements lies in the prime-order
+    /// subgroup is only sound if the coefficients are chosen after the
+    /// elements are fixed, which a non-interactive circuit cannot do without
+    /// hashing them in-circuit. Even then, multiplying each element by a
+    /// variable coefficient costs more than the cofactor clearing that
+    /// `alloc_checked` performs for each element.
     fn alloc_checked<F, T, CS: ConstraintSystem<ConstraintF>>(
         mut cs: CS,
         f: F,
@@ -140,3 +207,224 @@ impl<I, ConstraintF: Field, A: AllocGadget<I, ConstraintF>> AllocGadget<[I], Con
         Ok(vec)
     }
 }
+
+/// Allocates each of `values` as a witness in its own numbered namespace, as
+/// the `AllocGadget<[I]>` impl for `Vec<A>` does, but from any iterator of
+/// values or references to them.
+pub fn alloc_vec<I, A, V, ConstraintF, CS>(mut cs: CS, values: I) -> Result<Vec<A>, SynthesisError>
+where
+    I: IntoIterator,
+    I::Item: Borrow<V>,
+    A: AllocGadget<V, ConstraintF>,
+    ConstraintF: Field,
+    CS: ConstraintSystem<ConstraintF>,
+{
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| A::alloc(cs.ns(|| format!("value_{}", i)), || Ok(value)))
+        .collect()
+}
+
+/// Allocates each of `values` as a public input in its own numbered
+/// namespace. See [`alloc_vec`].
+pub fn alloc_input_vec<I, A, V, ConstraintF, CS>(
+    mut cs: CS,
+    values: I,
+) -> Result<Vec<A>, SynthesisError>
+where
+    I: IntoIterator,
+    I::Item: Borrow<V>,
+    A: AllocGadget<V, ConstraintF>,
+    ConstraintF: Field,
+    CS: ConstraintSystem<ConstraintF>,
+{
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| A::alloc_input(cs.ns(|| format!("value_{}", i)), || Ok(value)))
+        .collect()
+}
+
+/// Allocates a possibly absent value together with a flag signalling whether
+/// it is present. When the value is absent, the inner gadget is allocated from
+/// `I::default()`; nothing constrains it to that value, so constraints on it
+/// should be conditioned on the flag.
+impl<I, ConstraintF, A> AllocGadget<Option<I>, ConstraintF> for (Boolean, A)
+where
+    I: Default + Clone,
+    ConstraintF: Field,
+    A: AllocGadget<I, ConstraintF>,
+{
+    fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        t: T,
+    ) -> Result<Self, SynthesisError>
+    where
+        T: Borrow<Option<I>>,
+    {
+        let value = t.borrow();
+        let is_some = Boolean::constant(value.is_some());
+        let value = A::alloc_constant(cs.ns(|| "value"), value.clone().unwrap_or_default())?;
+        Ok((is_some, value))
+    }
+
+    fn alloc<F, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        f: F,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<Option<I>>,
+    {
+        let value = f().ok().map(|value| value.borrow().clone());
+        let is_some = Boolean::alloc(cs.ns(|| "is some"), || {
+            value.as_ref().map(Option::is_some).get()
+        })?;
+        let value = A::alloc(cs.ns(|| "value"), || {
+            value.map(Option::unwrap_or_default).get()
+        })?;
+        Ok((is_some, value))
+    }
+
+    fn alloc_checked<F, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        f: F,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<Option<I>>,
+    {
+        let value = f().ok().map(|value| value.borrow().clone());
+        let is_some = Boolean::alloc(cs.ns(|| "is some"), || {
+            value.as_ref().map(Option::is_some).get()
+        })?;
+        let value = A::alloc_checked(cs.ns(|| "value"), || {
+            value.map(Option::unwrap_or_default).get()
+        })?;
+        Ok((is_some, value))
+    }
+
+    fn alloc_input<F, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        f: F,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<Option<I>>,
+    {
+        let value = f().ok().map(|value| value.borrow().clone());
+        let is_some = Boolean::alloc_input(cs.ns(|| "is some"), || {
+            value.as_ref().map(Option::is_some).get()
+        })?;
+        let value = A::alloc_input(cs.ns(|| "value"), || {
+            value.map(Option::unwrap_or_default).get()
+        })?;
+        Ok((is_some, value))
+    }
+
+    fn alloc_input_checked<F, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        f: F,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<Option<I>>,
+    {
+        let value = f().ok().map(|value| value.borrow().clone());
+        let is_some = Boolean::alloc_input(cs.ns(|| "is some"), || {
+            value.as_ref().map(Option::is_some).get()
+        })?;
+        let value = A::alloc_input_checked(cs.ns(|| "value"), || {
+            value.map(Option::unwrap_or_default).get()
+        })?;
+        Ok((is_some, value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        alloc::{alloc_input_vec, alloc_vec, AllocGadget, AllocationMode},
+        bits::boolean::Boolean,
+        fields::fp::FpGadget,
+        test_constraint_system::TestConstraintSystem,
+        Vec,
+    };
+    use algebra::{bls12_381::Fr, UniformRand, Zero};
+    use r1cs_core::ConstraintSystem;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_alloc_option() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let value = Fr::rand(&mut rng);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let (is_some, some) =
+            <(Boolean, FpGadget<Fr>)>::alloc(cs.ns(|| "some"), || Ok(Some(value))).unwrap();
+        assert_eq!(is_some.get_value(), Some(true));
+        assert_eq!(some.value, Some(value));
+
+        let (is_some, none) =
+            <(Boolean, FpGadget<Fr>)>::alloc_input(cs.ns(|| "none"), || Ok(None::<Fr>)).unwrap();
+        assert_eq!(is_some.get_value(), Some(false));
+        assert_eq!(none.value, Some(Fr::zero()));
+
+        let (is_some, constant) =
+            <(Boolean, FpGadget<Fr>)>::alloc_constant(cs.ns(|| "constant"), Some(value)).unwrap();
+        assert_eq!(is_some.get_value(), Some(true));
+        assert_eq!(constant.value, Some(value));
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_alloc_vec() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let values = (0..5).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let witnesses: Vec<FpGadget<Fr>> =
+            alloc_vec(cs.ns(|| "witnesses"), values.iter().cloned()).unwrap();
+        let inputs: Vec<FpGadget<Fr>> = alloc_input_vec(cs.ns(|| "inputs"), &values).unwrap();
+        for ((value, witness), input) in values.iter().zip(&witnesses).zip(&inputs) {
+            assert_eq!(witness.value, Some(*value));
+            assert_eq!(input.value, Some(*value));
+        }
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_alloc_with_mode() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let value = Fr::rand(&mut rng);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let modes = [
+            AllocationMode::Constant,
+            AllocationMode::Input,
+            AllocationMode::Witness,
+        ];
+        for (i, &mode) in modes.iter().enumerate() {
+            let (num_inputs, num_aux) = (cs.num_inputs(), cs.num_aux_variables());
+            let gadget = if mode.is_constant() {
+                FpGadget::alloc_with_mode(cs.ns(|| format!("mode {}", i)), || Ok(value), mode)
+            } else {
+                FpGadget::alloc_with_mode_from_bool(
+                    cs.ns(|| format!("mode {}", i)),
+                    || Ok(value),
+                    mode.is_input(),
+                )
+            }
+            .unwrap();
+            assert_eq!(gadget.value, Some(value));
+            assert_eq!(cs.num_inputs() - num_inputs, mode.is_input() as usize);
+            assert_eq!(
+                cs.num_aux_variables() - num_aux,
+                (mode == AllocationMode::Witness) as usize
+            );
+        }
+        assert!(cs.is_satisfied());
+    }
+}
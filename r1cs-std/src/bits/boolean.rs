@@ -1,4 +1,4 @@
-use algebra::{BitIterator, Field, PrimeField};
+use algebra::{BitIterator, Field, FpParameters, PrimeField};
 
 use crate::{prelude::*, Assignment, Vec};
 use core::borrow::Borrow;
@@ -6,12 +6,24 @@ use r1cs_core::{ConstraintSystem, ConstraintVar, LinearCombination, SynthesisErr
 
 /// Represents a variable in the constraint system which is guaranteed
 /// to be either zero or one.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone)]
+#[cfg_attr(not(feature = "debug_values"), derive(Debug))]
 pub struct AllocatedBit {
     variable: Variable,
     value: Option<bool>,
 }
 
+/// Prints the variable followed by its assignment, or `?` if it is missing.
+#[cfg(feature = "debug_values")]
+impl core::fmt::Debug for AllocatedBit {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.value {
+            Some(value) => write!(f, "{:?} = {:?}", self.variable.get_unchecked(), value),
+            None => write!(f, "{:?} = ?", self.variable.get_unchecked()),
+        }
+    }
+}
+
 impl AllocatedBit {
     pub fn get_value(&self) -> Option<bool> {
         self.value
@@ -348,10 +360,18 @@ fn cond_select_helper<F: PrimeField, CS: ConstraintSystem<F>>(
     let result_var = cs.alloc(
         || "cond_select_result",
         || {
-            result_val = cond
+            #[cfg(feature = "constant_time")]
+            let value = {
+                let (c, a, b) = (cond.get_value().get()?, first.0.get()?, second.0.get()?);
+                (c & a) | (!c & b)
+            };
+            #[cfg(not(feature = "constant_time"))]
+            let value = cond
                 .get_value()
-                .and_then(|c| if c { first.0 } else { second.0 });
-            result_val.get().map(|v| F::from(v as u8))
+                .and_then(|c| if c { first.0 } else { second.0 })
+                .get()?;
+            result_val = Some(value);
+            Ok(F::from(value as u8))
         },
     )?;
 
@@ -379,7 +399,8 @@ fn cond_select_helper<F: PrimeField, CS: ConstraintSystem<F>>(
 
 /// This is a boolean value which may be either a constant or
 /// an interpretation of an `AllocatedBit`.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone)]
+#[cfg_attr(not(feature = "debug_values"), derive(Debug))]
 pub enum Boolean {
     /// Existential view of the boolean variable
     Is(AllocatedBit),
@@ -389,6 +410,18 @@ pub enum Boolean {
     Constant(bool),
 }
 
+/// Prints negated bits with a leading `!`, and constants as their value.
+#[cfg(feature = "debug_values")]
+impl core::fmt::Debug for Boolean {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Boolean::Is(bit) => write!(f, "{:?}", bit),
+            Boolean::Not(bit) => write!(f, "!{:?}", bit),
+            Boolean::Constant(c) => write!(f, "{:?}", c),
+        }
+    }
+}
+
 impl Boolean {
     pub fn get_value(&self) -> Option<bool> {
         match *self {
@@ -398,6 +431,11 @@ impl Boolean {
         }
     }
 
+    /// Returns the values of `bits`, or `None` if any of them is missing.
+    pub fn get_values(bits: &[Self]) -> Option<Vec<bool>> {
+        bits.iter().map(Self::get_value).collect()
+    }
+
     pub fn lc<ConstraintF: Field>(
         &self,
         one: Variable,
@@ -533,36 +571,119 @@ impl Boolean {
         }
     }
 
+    /// Perform NAND over two boolean operands
+    pub fn nand<'a, ConstraintF, CS>(
+        cs: CS,
+        a: &'a Self,
+        b: &'a Self,
+    ) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: Field,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        // Negation only flips the view of the AND result, so this costs
+        // exactly as much as `and`, and constants fold the same way.
+        Ok(Boolean::and(cs, a, b)?.not())
+    }
+
+    /// Perform NOR over two boolean operands
+    pub fn nor<'a, ConstraintF, CS>(cs: CS, a: &'a Self, b: &'a Self) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: Field,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        Ok(Boolean::or(cs, a, b)?.not())
+    }
+
+    /// Perform XNOR over two boolean operands
+    pub fn xnor<'a, ConstraintF, CS>(
+        cs: CS,
+        a: &'a Self,
+        b: &'a Self,
+    ) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: Field,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        Ok(Boolean::xor(cs, a, b)?.not())
+    }
+
+    /// Perform AND over all the operands, combining them pairwise in a
+    /// balanced tree so that the result has logarithmic depth. Returns `true`
+    /// for an empty slice.
     pub fn kary_and<ConstraintF, CS>(mut cs: CS, bits: &[Self]) -> Result<Self, SynthesisError>
     where
         ConstraintF: Field,
         CS: ConstraintSystem<ConstraintF>,
     {
-        assert!(!bits.is_empty());
-        let mut bits = bits.iter();
+        let mut layer = Vec::with_capacity(bits.len());
+        for bit in bits {
+            match *bit {
+                // false AND x is always false
+                Boolean::Constant(false) => return Ok(Boolean::Constant(false)),
+                // true AND x is always x
+                Boolean::Constant(true) => {}
+                _ => layer.push(*bit),
+            }
+        }
 
-        let mut cur: Self = *bits.next().unwrap();
-        for (i, next) in bits.enumerate() {
-            cur = Boolean::and(cs.ns(|| format!("AND {}", i)), &cur, next)?;
+        let mut depth = 0;
+        while layer.len() > 1 {
+            let mut cs = cs.ns(|| format!("layer {}", depth));
+            layer = layer
+                .chunks(2)
+                .enumerate()
+                .map(|(i, pair)| {
+                    if pair.len() == 2 {
+                        Boolean::and(cs.ns(|| format!("AND {}", i)), &pair[0], &pair[1])
+                    } else {
+                        Ok(pair[0])
+                    }
+                })
+                .collect::<Result<_, _>>()?;
+            depth += 1;
         }
 
-        Ok(cur)
+        Ok(layer.pop().unwrap_or(Boolean::Constant(true)))
     }
 
+    /// Perform OR over all the operands, combining them pairwise in a
+    /// balanced tree so that the result has logarithmic depth. Returns `false`
+    /// for an empty slice.
     pub fn kary_or<ConstraintF, CS>(mut cs: CS, bits: &[Self]) -> Result<Self, SynthesisError>
     where
         ConstraintF: Field,
         CS: ConstraintSystem<ConstraintF>,
     {
-        assert!(!bits.is_empty());
-        let mut bits = bits.iter();
+        let mut layer = Vec::with_capacity(bits.len());
+        for bit in bits {
+            match *bit {
+                // true OR x is always true
+                Boolean::Constant(true) => return Ok(Boolean::Constant(true)),
+                // false OR x is always x
+                Boolean::Constant(false) => {}
+                _ => layer.push(*bit),
+            }
+        }
 
-        let mut cur: Self = *bits.next().unwrap();
-        for (i, next) in bits.enumerate() {
-            cur = Boolean::or(cs.ns(|| format!("OR {}", i)), &cur, next)?;
+        let mut depth = 0;
+        while layer.len() > 1 {
+            let mut cs = cs.ns(|| format!("layer {}", depth));
+            layer = layer
+                .chunks(2)
+                .enumerate()
+                .map(|(i, pair)| {
+                    if pair.len() == 2 {
+                        Boolean::or(cs.ns(|| format!("OR {}", i)), &pair[0], &pair[1])
+                    } else {
+                        Ok(pair[0])
+                    }
+                })
+                .collect::<Result<_, _>>()?;
+            depth += 1;
         }
 
-        Ok(cur)
+        Ok(layer.pop().unwrap_or(Boolean::Constant(false)))
     }
 
     /// Asserts that at least one operand is false.
@@ -599,6 +720,81 @@ impl Boolean {
         }
     }
 
+    /// Asserts that `self` equals the constant `value`, with a single linear
+    /// constraint on the underlying variable, or none if `self` is itself a
+    /// constant.
+    pub fn enforce_constant<ConstraintF, CS>(
+        &self,
+        mut cs: CS,
+        value: bool,
+    ) -> Result<(), SynthesisError>
+    where
+        ConstraintF: Field,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        match *self {
+            Boolean::Constant(c) if c == value => Ok(()),
+            Boolean::Constant(_) => Err(SynthesisError::Unsatisfiable),
+            _ => {
+                let one = CS::one();
+                let expected = Boolean::constant(value).lc(one, ConstraintF::one());
+                cs.enforce(
+                    || "enforce constant",
+                    |lc| lc + one,
+                    |lc| self.lc(one, ConstraintF::one()) + &lc,
+                    |lc| expected + &lc,
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Converts `x` into a `Boolean`, enforcing that it is `0` or `1`; see
+    /// `FpGadget::from_boolean` for the converse.
+    ///
+    /// An allocated `x` is reused as the bit, with a single booleanity
+    /// constraint, and a constant `x` is checked at synthesis time. Any other
+    /// linear combination is copied into a new bit.
+    pub fn try_from_fp<ConstraintF, CS>(
+        mut cs: CS,
+        x: &FpGadget<ConstraintF>,
+    ) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        let value = x.value.map(|v| !v.is_zero());
+        match &x.variable {
+            ConstraintVar::Var(var) => {
+                let var = *var;
+                // Constrain: (1 - x) * x = 0
+                cs.enforce(
+                    || "boolean constraint",
+                    |lc| lc + CS::one() - var,
+                    |lc| lc + var,
+                    |lc| lc,
+                );
+                Ok(AllocatedBit {
+                    variable: var,
+                    value,
+                }
+                .into())
+            }
+            ConstraintVar::LC(lc) if lc.is_constant() => match x.value {
+                Some(v) if v.is_zero() => Ok(Boolean::Constant(false)),
+                Some(v) if v.is_one() => Ok(Boolean::Constant(true)),
+                Some(_) => Err(SynthesisError::Unsatisfiable),
+                None => Err(SynthesisError::AssignmentMissing),
+            },
+            ConstraintVar::LC(_) => {
+                let bit: Boolean = AllocatedBit::alloc(cs.ns(|| "bit"), || value.get())?.into();
+                FpGadget::from_boolean(cs.ns(|| "bit to field"), &bit)?
+                    .enforce_equal(cs.ns(|| "x == bit"), x)?;
+                Ok(bit)
+            }
+        }
+    }
+
     /// Asserts that this bit_gadget representation is "in
     /// the field" when interpreted in big endian.
     pub fn enforce_in_field<ConstraintF, CS, F: PrimeField>(
@@ -787,12 +983,20 @@ impl<ConstraintF: Field> ConditionalEqGadget<ConstraintF> for Boolean {
         CS: ConstraintSystem<ConstraintF>,
     {
         use self::Boolean::*;
+        if let Constant(false) = condition {
+            return Ok(());
+        }
         let one = CS::one();
         let difference: LinearCombination<ConstraintF> = match (self, other) {
             // 1 - 1 = 0 - 0 = 0
             (Constant(true), Constant(true)) | (Constant(false), Constant(false)) => return Ok(()),
-            // false != true
-            (Constant(_), Constant(_)) => return Err(SynthesisError::AssignmentMissing),
+            // false != true, so this only holds if the condition is false
+            (Constant(_), Constant(_)) => {
+                if let Constant(true) = condition {
+                    return Err(SynthesisError::Unsatisfiable);
+                }
+                LinearCombination::zero() + one
+            }
             // 1 - a
             (Constant(true), Is(a)) | (Is(a), Constant(true)) => {
                 LinearCombination::zero() + one - a.get_variable()
@@ -819,17 +1023,13 @@ impl<ConstraintF: Field> ConditionalEqGadget<ConstraintF> for Boolean {
             (Not(a), Not(b)) => LinearCombination::zero() + a.get_variable() - b.get_variable(),
         };
 
-        if let Constant(false) = condition {
-            Ok(())
-        } else {
-            cs.enforce(
-                || "conditional_equals",
-                |lc| difference + &lc,
-                |lc| condition.lc(one, ConstraintF::one()) + &lc,
-                |lc| lc,
-            );
-            Ok(())
-        }
+        cs.enforce(
+            || "conditional_equals",
+            |lc| difference + &lc,
+            |lc| condition.lc(one, ConstraintF::one()) + &lc,
+            |lc| lc,
+        );
+        Ok(())
     }
 
     fn cost() -> usize {
@@ -857,58 +1057,25 @@ impl<ConstraintF: PrimeField> ToConstraintFieldGadget<ConstraintF> for Boolean {
         &self,
         mut cs: CS,
     ) -> Result<Vec<FpGadget<ConstraintF>>, SynthesisError> {
-        let gadget = match self {
-            Boolean::Constant(cond) => {
-                if *cond {
-                    FpGadget::one(&mut cs.ns(|| "one"))?
-                } else {
-                    FpGadget::zero(&mut cs.ns(|| "zero"))?
-                }
-            }
-            Boolean::Is(allocated_bit) => {
-                let value = match self.get_value() {
-                    None => None,
-                    Some(bool) => {
-                        if bool {
-                            Some(ConstraintF::one())
-                        } else {
-                            Some(ConstraintF::zero())
-                        }
-                    }
-                };
-
-                FpGadget::<ConstraintF> {
-                    value,
-                    variable: ConstraintVar::Var(allocated_bit.get_variable()),
-                }
-            }
-            Boolean::Not(allocated_bit) => {
-                let value = match self.get_value() {
-                    None => None,
-                    Some(bool) => {
-                        if bool {
-                            Some(ConstraintF::zero())
-                        } else {
-                            Some(ConstraintF::one())
-                        }
-                    }
-                };
-
-                let mut lc = LinearCombination::<ConstraintF>::zero();
-                lc += (ConstraintF::one(), CS::one());
-                lc += (-ConstraintF::one(), allocated_bit.get_variable());
-
-                FpGadget::<ConstraintF> {
-                    value,
-                    variable: ConstraintVar::LC(lc),
-                }
-            }
-        };
-
+        let gadget = FpGadget::from_boolean(cs.ns(|| "from boolean"), self)?;
         Ok(vec![gadget])
     }
 }
 
+/// Packs little-endian bits into field elements, `CAPACITY` bits at a time.
+impl<ConstraintF: PrimeField> ToConstraintFieldGadget<ConstraintF> for [Boolean] {
+    fn to_constraint_field<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Vec<FpGadget<ConstraintF>>, SynthesisError> {
+        let max_size = ConstraintF::Params::CAPACITY as usize;
+        self.chunks(max_size)
+            .enumerate()
+            .map(|(i, chunk)| FpGadget::from_bits_le(cs.ns(|| format!("chunk {}", i)), chunk))
+            .collect()
+    }
+}
+
 impl<ConstraintF: PrimeField> CondSelectGadget<ConstraintF> for Boolean {
     fn conditionally_select<CS>(
         mut cs: CS,
@@ -954,10 +1121,12 @@ impl<ConstraintF: PrimeField> CondSelectGadget<ConstraintF> for Boolean {
 mod test {
     use super::{AllocatedBit, Boolean};
     use crate::{prelude::*, test_constraint_system::TestConstraintSystem};
-    use algebra::{bls12_381::Fr, BitIterator, Field, One, PrimeField, UniformRand, Zero};
+    use algebra::{
+        bls12_381::Fr, BitIterator, Field, FpParameters, One, PrimeField, UniformRand, Zero,
+    };
     use core::str::FromStr;
-    use r1cs_core::ConstraintSystem;
-    use rand::SeedableRng;
+    use r1cs_core::{ConstraintSystem, SynthesisError};
+    use rand::{Rng, SeedableRng};
     use rand_xorshift::XorShiftRng;
 
     #[test]
@@ -1164,6 +1333,44 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_enforce_constant() {
+        for a_bool in [false, true].iter().cloned() {
+            for a_neg in [false, true].iter().cloned() {
+                for value in [false, true].iter().cloned() {
+                    let mut cs = TestConstraintSystem::<Fr>::new();
+
+                    let mut a: Boolean = AllocatedBit::alloc(cs.ns(|| "a"), || Ok(a_bool))
+                        .unwrap()
+                        .into();
+                    if a_neg {
+                        a = a.not();
+                    }
+                    let num_variables = cs.num_aux_variables();
+                    let num_constraints = cs.num_constraints();
+
+                    a.enforce_constant(&mut cs, value).unwrap();
+
+                    assert_eq!(cs.num_aux_variables(), num_variables);
+                    assert_eq!(cs.num_constraints(), num_constraints + 1);
+                    assert_eq!(cs.is_satisfied(), (a_bool ^ a_neg) == value);
+                }
+            }
+        }
+
+        for a_bool in [false, true].iter().cloned() {
+            for value in [false, true].iter().cloned() {
+                let mut cs = TestConstraintSystem::<Fr>::new();
+                match Boolean::constant(a_bool).enforce_constant(&mut cs, value) {
+                    Ok(()) => assert_eq!(a_bool, value),
+                    Err(SynthesisError::Unsatisfiable) => assert_ne!(a_bool, value),
+                    Err(e) => panic!("unexpected error: {:?}", e),
+                }
+                assert_eq!(cs.num_constraints(), 0);
+            }
+        }
+    }
+
     #[test]
     fn test_conditional_enforce_equal() {
         for a_bool in [false, true].iter().cloned() {
@@ -1224,6 +1431,34 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_conditional_enforce_equal_constants() {
+        for a_bool in [false, true].iter().cloned() {
+            for b_bool in [false, true].iter().cloned() {
+                let a = Boolean::constant(a_bool);
+                let b = Boolean::constant(b_bool);
+
+                // Constant conditions never add a constraint.
+                let mut cs = TestConstraintSystem::<Fr>::new();
+                let result = a.conditional_enforce_equal(&mut cs, &b, &Boolean::constant(true));
+                assert_eq!(result.is_ok(), a_bool == b_bool);
+                a.conditional_enforce_equal(&mut cs, &b, &Boolean::constant(false))
+                    .unwrap();
+                assert_eq!(cs.num_constraints(), 0);
+
+                // A variable condition must be false for unequal constants.
+                for cond_bool in [false, true].iter().cloned() {
+                    let mut cs = TestConstraintSystem::<Fr>::new();
+                    let cond: Boolean = AllocatedBit::alloc(cs.ns(|| "cond"), || Ok(cond_bool))
+                        .unwrap()
+                        .into();
+                    a.conditional_enforce_equal(&mut cs, &b, &cond).unwrap();
+                    assert_eq!(cs.is_satisfied(), a_bool == b_bool || !cond_bool);
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_boolean_negation() {
         let mut cs = TestConstraintSystem::<Fr>::new();
@@ -1271,7 +1506,7 @@ mod test {
         }
     }
 
-    #[derive(Copy, Clone, Debug)]
+    #[derive(Copy, Clone, Debug, PartialEq)]
     enum OperandType {
         True,
         False,
@@ -1490,6 +1725,111 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_boolean_nand_nor_xnor() {
+        let variants = [
+            OperandType::True,
+            OperandType::False,
+            OperandType::AllocatedTrue,
+            OperandType::AllocatedFalse,
+            OperandType::NegatedAllocatedTrue,
+            OperandType::NegatedAllocatedFalse,
+        ];
+
+        let value = |operand: OperandType| {
+            operand == OperandType::True
+                || operand == OperandType::AllocatedTrue
+                || operand == OperandType::NegatedAllocatedFalse
+        };
+        let is_true = |operand: OperandType| operand == OperandType::True;
+        let is_false = |operand: OperandType| operand == OperandType::False;
+        let is_constant = |operand: OperandType| is_true(operand) || is_false(operand);
+
+        for first_operand in variants.iter().cloned() {
+            for second_operand in variants.iter().cloned() {
+                let a_val = value(first_operand);
+                let b_val = value(second_operand);
+
+                for gate in 0..3 {
+                    let mut cs = TestConstraintSystem::<Fr>::new();
+
+                    let a;
+                    let b;
+
+                    {
+                        let mut dyn_construct = |operand, name| {
+                            let cs = cs.ns(|| name);
+
+                            match operand {
+                                OperandType::True => Boolean::constant(true),
+                                OperandType::False => Boolean::constant(false),
+                                OperandType::AllocatedTrue => {
+                                    Boolean::from(AllocatedBit::alloc(cs, || Ok(true)).unwrap())
+                                }
+                                OperandType::AllocatedFalse => {
+                                    Boolean::from(AllocatedBit::alloc(cs, || Ok(false)).unwrap())
+                                }
+                                OperandType::NegatedAllocatedTrue => {
+                                    Boolean::from(AllocatedBit::alloc(cs, || Ok(true)).unwrap())
+                                        .not()
+                                }
+                                OperandType::NegatedAllocatedFalse => {
+                                    Boolean::from(AllocatedBit::alloc(cs, || Ok(false)).unwrap())
+                                        .not()
+                                }
+                            }
+                        };
+
+                        a = dyn_construct(first_operand, "a");
+                        b = dyn_construct(second_operand, "b");
+                    }
+
+                    let num_constraints = cs.num_constraints();
+
+                    let (c, expected, forced) = match gate {
+                        0 => (
+                            Boolean::nand(cs.ns(|| "nand"), &a, &b).unwrap(),
+                            !(a_val & b_val),
+                            is_false(first_operand) || is_false(second_operand),
+                        ),
+                        1 => (
+                            Boolean::nor(cs.ns(|| "nor"), &a, &b).unwrap(),
+                            !(a_val | b_val),
+                            is_true(first_operand) || is_true(second_operand),
+                        ),
+                        _ => (
+                            Boolean::xnor(cs.ns(|| "xnor"), &a, &b).unwrap(),
+                            !(a_val ^ b_val),
+                            false,
+                        ),
+                    };
+                    let fused_constraints = cs.num_constraints() - num_constraints;
+
+                    let composed = match gate {
+                        0 => Boolean::and(cs.ns(|| "and"), &a, &b).unwrap().not(),
+                        1 => Boolean::or(cs.ns(|| "or"), &a, &b).unwrap().not(),
+                        _ => Boolean::xor(cs.ns(|| "xor"), &a, &b).unwrap().not(),
+                    };
+                    let composed_constraints =
+                        cs.num_constraints() - num_constraints - fused_constraints;
+
+                    assert!(cs.is_satisfied());
+                    assert_eq!(c.get_value(), Some(expected));
+                    assert_eq!(composed.get_value(), Some(expected));
+                    assert_eq!(fused_constraints, composed_constraints);
+
+                    if forced || (is_constant(first_operand) && is_constant(second_operand)) {
+                        match c {
+                            Boolean::Constant(v) => assert_eq!(v, expected),
+                            _ => panic!("expected a constant result"),
+                        }
+                        assert_eq!(fused_constraints, 0);
+                    }
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_boolean_cond_select() {
         let variants = [
@@ -1564,6 +1904,34 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_boolean_cond_select_reads_both_branches() {
+        // With `constant_time`, the result is computed from both branches, so
+        // a missing witness in either of them fails the select, whichever one
+        // is selected. Otherwise only the selected branch must be assigned.
+        for &condition in &[true, false] {
+            for &missing_first in &[true, false] {
+                let mut cs = TestConstraintSystem::<Fr>::new();
+                let cond = Boolean::alloc(cs.ns(|| "cond"), || Ok(condition)).unwrap();
+                let mut a = AllocatedBit::alloc(cs.ns(|| "a"), || Ok(true)).unwrap();
+                let mut b = AllocatedBit::alloc(cs.ns(|| "b"), || Ok(false)).unwrap();
+                if missing_first {
+                    a.value = None;
+                } else {
+                    b.value = None;
+                }
+
+                let (a, b) = (Boolean::from(a), Boolean::from(b).not());
+                let result = Boolean::conditionally_select(cs.ns(|| "select"), &cond, &a, &b);
+                let selected_is_missing = condition == missing_first;
+                assert_eq!(
+                    result.is_err(),
+                    cfg!(feature = "constant_time") || selected_is_missing
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_boolean_or() {
         let variants = [
@@ -2140,4 +2508,197 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_kary_or() {
+        // test different numbers of operands
+        for i in 1..15 {
+            // with every possible assignment for them
+            for mut b in 0..(1 << i) {
+                let mut cs = TestConstraintSystem::<Fr>::new();
+
+                let mut expected = false;
+
+                let mut bits = vec![];
+                for j in 0..i {
+                    expected |= b & 1 == 1;
+
+                    bits.push(Boolean::from(
+                        AllocatedBit::alloc(cs.ns(|| format!("bit_gadget {}", j)), || {
+                            Ok(b & 1 == 1)
+                        })
+                        .unwrap(),
+                    ));
+                    b >>= 1;
+                }
+
+                let r = Boolean::kary_or(&mut cs, &bits).unwrap();
+
+                assert!(cs.is_satisfied());
+                assert_eq!(r.get_value(), Some(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_kary_constants() {
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        assert_eq!(
+            Boolean::kary_and(cs.ns(|| "empty and"), &[]).unwrap(),
+            Boolean::constant(true)
+        );
+        assert_eq!(
+            Boolean::kary_or(cs.ns(|| "empty or"), &[]).unwrap(),
+            Boolean::constant(false)
+        );
+
+        let mut bits = (0..8)
+            .map(|i| {
+                Boolean::from(
+                    AllocatedBit::alloc(cs.ns(|| format!("bit_gadget {}", i)), || Ok(i % 2 == 0))
+                        .unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let num_constraints = cs.num_constraints();
+
+        // A single forcing constant short-circuits the whole tree
+        bits[5] = Boolean::constant(false);
+        match Boolean::kary_and(cs.ns(|| "forced and"), &bits).unwrap() {
+            Boolean::Constant(false) => {}
+            _ => panic!("expected a constant result"),
+        }
+        bits[5] = Boolean::constant(true);
+        match Boolean::kary_or(cs.ns(|| "forced or"), &bits).unwrap() {
+            Boolean::Constant(true) => {}
+            _ => panic!("expected a constant result"),
+        }
+        assert_eq!(cs.num_constraints(), num_constraints);
+
+        // Identity constants are dropped rather than costing a gate
+        let r = Boolean::kary_and(cs.ns(|| "and"), &bits).unwrap();
+        assert_eq!(cs.num_constraints(), num_constraints + 6);
+        assert_eq!(r.get_value(), Some(false));
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_kary_and_depth() {
+        for log_n in 1..6 {
+            let n = 1 << log_n;
+            let mut cs = TestConstraintSystem::<Fr>::new();
+
+            let bits = (0..n)
+                .map(|i| {
+                    Boolean::from(
+                        AllocatedBit::alloc(cs.ns(|| format!("bit_gadget {}", i)), || Ok(true))
+                            .unwrap(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let num_constraints = cs.num_constraints();
+
+            let r = Boolean::kary_and(cs.ns(|| "kary and"), &bits).unwrap();
+            assert_eq!(r.get_value(), Some(true));
+            assert!(cs.is_satisfied());
+
+            // A balanced tree still needs one gate per operand but one, and
+            // the root sits on layer `log_n - 1`.
+            assert_eq!(cs.num_constraints(), num_constraints + n - 1);
+            assert!(cs
+                .get(&format!("kary and/layer {}/AND 0/and result", log_n - 1))
+                .is_one());
+        }
+    }
+
+    #[test]
+    fn test_fp_conversions() {
+        use crate::fields::fp::FpGadget;
+
+        for (i, value) in [false, true].iter().cloned().enumerate() {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let expected = if value { Fr::one() } else { Fr::zero() };
+
+            // Boolean -> FpGadget costs nothing, for every kind of Boolean.
+            let bit = Boolean::alloc(cs.ns(|| format!("bit {}", i)), || Ok(value)).unwrap();
+            let num_constraints = cs.num_constraints();
+            for (j, b) in [bit, Boolean::constant(value)].iter().enumerate() {
+                let x = FpGadget::from_boolean(cs.ns(|| format!("from {}", j)), b).unwrap();
+                assert_eq!(x.get_value(), Some(expected));
+            }
+            let negated = FpGadget::from_boolean(cs.ns(|| "from not"), &bit.not()).unwrap();
+            assert_eq!(negated.get_value(), Some(Fr::one() - &expected));
+            assert_eq!(cs.num_constraints(), num_constraints);
+
+            // FpGadget -> Boolean round-trips allocated, linear and constant
+            // field elements.
+            let x = FpGadget::alloc(cs.ns(|| "x"), || Ok(expected)).unwrap();
+            let from_var = Boolean::try_from_fp(cs.ns(|| "from var"), &x).unwrap();
+            let from_lc = Boolean::try_from_fp(cs.ns(|| "from lc"), &negated)
+                .unwrap()
+                .not();
+            let constant = FpGadget::alloc_constant(cs.ns(|| "constant"), expected).unwrap();
+            let from_constant = Boolean::try_from_fp(cs.ns(|| "from constant"), &constant).unwrap();
+            for (j, b) in [from_var, from_lc, from_constant].iter().enumerate() {
+                assert_eq!(b.get_value(), Some(value));
+                b.enforce_equal(cs.ns(|| format!("round trip {}", j)), &bit)
+                    .unwrap();
+            }
+            assert!(cs.is_satisfied());
+        }
+
+        // A value of 2 is rejected.
+        let two = Fr::one().double();
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let constant = FpGadget::alloc_constant(cs.ns(|| "constant"), two).unwrap();
+        assert!(Boolean::try_from_fp(cs.ns(|| "from constant"), &constant).is_err());
+        let x = FpGadget::alloc(cs.ns(|| "x"), || Ok(two)).unwrap();
+        let _ = Boolean::try_from_fp(cs.ns(|| "from var"), &x).unwrap();
+        assert!(!cs.is_satisfied());
+
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let x = FpGadget::alloc(cs.ns(|| "x"), || Ok(Fr::one()))
+            .unwrap()
+            .double(cs.ns(|| "2x"))
+            .unwrap();
+        let _ = Boolean::try_from_fp(cs.ns(|| "from lc"), &x).unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_to_constraint_field() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let bit_vals = (0..600).map(|_| rng.gen()).collect::<Vec<bool>>();
+        let bits = bit_vals
+            .iter()
+            .enumerate()
+            .map(|(i, b)| Boolean::alloc(cs.ns(|| format!("bit {}", i)), || Ok(*b)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let field_elements = bits
+            .to_constraint_field(cs.ns(|| "to constraint field"))
+            .unwrap();
+
+        let capacity = <Fr as PrimeField>::Params::CAPACITY as usize;
+        let expected = bit_vals
+            .chunks(capacity)
+            .map(|chunk| {
+                chunk.iter().rev().fold(Fr::zero(), |acc, bit| {
+                    let acc = acc.double();
+                    if *bit {
+                        acc + &Fr::one()
+                    } else {
+                        acc
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(field_elements.len(), 3);
+        for (fe, expected) in field_elements.iter().zip(&expected) {
+            assert_eq!(fe.get_value(), Some(*expected));
+        }
+        assert!(cs.is_satisfied());
+    }
 }
@@ -6,10 +6,44 @@ use algebra::Field;
 use r1cs_core::{ConstraintSystem, SynthesisError};
 
 pub mod boolean;
-pub mod uint32;
-pub mod uint64;
 pub mod uint8;
 
+#[macro_use]
+mod uint;
+
+impl_uint!(
+    /// Represents an interpretation of 16 `Boolean` objects as an
+    /// unsigned integer.
+    UInt16,
+    16,
+    u16,
+    uint16
+);
+impl_uint!(
+    /// Represents an interpretation of 32 `Boolean` objects as an
+    /// unsigned integer.
+    UInt32,
+    32,
+    u32,
+    uint32
+);
+impl_uint!(
+    /// Represents an interpretation of 64 `Boolean` objects as an
+    /// unsigned integer.
+    UInt64,
+    64,
+    u64,
+    uint64
+);
+impl_uint!(
+    /// Represents an interpretation of 128 `Boolean` objects as an
+    /// unsigned integer.
+    UInt128,
+    128,
+    u128,
+    uint128
+);
+
 pub trait ToBitsGadget<ConstraintF: Field> {
     /// Outputs the canonical bit-wise representation of `self`.
     ///
@@ -114,6 +148,15 @@ pub trait ToBytesGadget<ConstraintF: Field> {
     }
 }
 
+impl<ConstraintF: Field> ToBytesGadget<ConstraintF> for UInt8 {
+    fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        _cs: CS,
+    ) -> Result<Vec<UInt8>, SynthesisError> {
+        Ok(vec![self.clone()])
+    }
+}
+
 impl<ConstraintF: Field> ToBytesGadget<ConstraintF> for [UInt8] {
     fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
         &self,
@@ -142,3 +185,93 @@ impl<'a, ConstraintF: Field> ToBytesGadget<ConstraintF> for &'a [UInt8] {
         Ok(self.to_vec())
     }
 }
+
+macro_rules! array_gadgets {
+    ($N:expr) => {
+        impl<ConstraintF: Field, T: ToBitsGadget<ConstraintF>> ToBitsGadget<ConstraintF>
+            for [T; $N]
+        {
+            fn to_bits<CS: ConstraintSystem<ConstraintF>>(
+                &self,
+                mut cs: CS,
+            ) -> Result<Vec<Boolean>, SynthesisError> {
+                let mut result = Vec::new();
+                for (i, elem) in self.iter().enumerate() {
+                    result.extend(elem.to_bits(cs.ns(|| format!("elem {}", i)))?);
+                }
+                Ok(result)
+            }
+
+            fn to_non_unique_bits<CS: ConstraintSystem<ConstraintF>>(
+                &self,
+                mut cs: CS,
+            ) -> Result<Vec<Boolean>, SynthesisError> {
+                let mut result = Vec::new();
+                for (i, elem) in self.iter().enumerate() {
+                    result.extend(elem.to_non_unique_bits(cs.ns(|| format!("elem {}", i)))?);
+                }
+                Ok(result)
+            }
+        }
+
+        impl<ConstraintF: Field, T: ToBytesGadget<ConstraintF>> ToBytesGadget<ConstraintF>
+            for [T; $N]
+        {
+            fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
+                &self,
+                mut cs: CS,
+            ) -> Result<Vec<UInt8>, SynthesisError> {
+                let mut result = Vec::new();
+                for (i, elem) in self.iter().enumerate() {
+                    result.extend(elem.to_bytes(cs.ns(|| format!("elem {}", i)))?);
+                }
+                Ok(result)
+            }
+
+            fn to_non_unique_bytes<CS: ConstraintSystem<ConstraintF>>(
+                &self,
+                mut cs: CS,
+            ) -> Result<Vec<UInt8>, SynthesisError> {
+                let mut result = Vec::new();
+                for (i, elem) in self.iter().enumerate() {
+                    result.extend(elem.to_non_unique_bytes(cs.ns(|| format!("elem {}", i)))?);
+                }
+                Ok(result)
+            }
+        }
+    };
+}
+
+array_gadgets!(0);
+array_gadgets!(1);
+array_gadgets!(2);
+array_gadgets!(3);
+array_gadgets!(4);
+array_gadgets!(5);
+array_gadgets!(6);
+array_gadgets!(7);
+array_gadgets!(8);
+array_gadgets!(9);
+array_gadgets!(10);
+array_gadgets!(11);
+array_gadgets!(12);
+array_gadgets!(13);
+array_gadgets!(14);
+array_gadgets!(15);
+array_gadgets!(16);
+array_gadgets!(17);
+array_gadgets!(18);
+array_gadgets!(19);
+array_gadgets!(20);
+array_gadgets!(21);
+array_gadgets!(22);
+array_gadgets!(23);
+array_gadgets!(24);
+array_gadgets!(25);
+array_gadgets!(26);
+array_gadgets!(27);
+array_gadgets!(28);
+array_gadgets!(29);
+array_gadgets!(30);
+array_gadgets!(31);
+array_gadgets!(32);
@@ -0,0 +1,1373 @@
+macro_rules! impl_uint {
+    ($(#[$attr:meta])* $name:ident, $size:expr, $native:ident, $mod_name:ident) => {
+        pub mod $mod_name {
+            use algebra::{BigInteger, Field, FpParameters, PrimeField};
+
+            use r1cs_core::{ConstraintSystem, LinearCombination, SynthesisError};
+
+            use crate::{
+                boolean::{AllocatedBit, Boolean},
+                prelude::*,
+                Assignment, Vec,
+            };
+            use core::{borrow::Borrow, cmp::Ordering};
+
+            $(#[$attr])*
+            #[derive(Clone, Debug)]
+            pub struct $name {
+                // Least significant bit_gadget first
+                bits: Vec<Boolean>,
+                pub value: Option<$native>,
+            }
+
+            impl $name {
+                pub fn get_value(&self) -> Option<$native> {
+                    self.value
+                }
+
+                /// Construct a constant from a native integer
+                pub fn constant(value: $native) -> Self {
+                    let mut bits = Vec::with_capacity($size);
+
+                    let mut tmp = value;
+                    for _ in 0..$size {
+                        if tmp & 1 == 1 {
+                            bits.push(Boolean::constant(true))
+                        } else {
+                            bits.push(Boolean::constant(false))
+                        }
+
+                        tmp >>= 1;
+                    }
+
+                    $name {
+                        bits,
+                        value: Some(value),
+                    }
+                }
+
+                /// Allocate a witness in the constraint system
+                pub fn alloc<ConstraintF, CS>(
+                    mut cs: CS,
+                    value: Option<$native>,
+                ) -> Result<Self, SynthesisError>
+                where
+                    ConstraintF: Field,
+                    CS: ConstraintSystem<ConstraintF>,
+                {
+                    let bits = Self::bit_values(value)
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, v)| {
+                            Ok(Boolean::from(AllocatedBit::alloc(
+                                cs.ns(|| format!("allocated bit_gadget {}", i)),
+                                || v.get(),
+                            )?))
+                        })
+                        .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+                    Ok($name { bits, value })
+                }
+
+                fn is_constant(&self) -> bool {
+                    self.bits.iter().all(|bit| match bit {
+                        Boolean::Constant(_) => true,
+                        _ => false,
+                    })
+                }
+
+                fn bit_values(value: Option<$native>) -> Vec<Option<bool>> {
+                    match value {
+                        Some(mut val) => {
+                            let mut v = Vec::with_capacity($size);
+
+                            for _ in 0..$size {
+                                v.push(Some(val & 1 == 1));
+                                val >>= 1;
+                            }
+
+                            v
+                        }
+                        None => vec![None; $size],
+                    }
+                }
+
+                /// Turns this integer into its little-endian bit order representation.
+                pub fn to_bits_le(&self) -> Vec<Boolean> {
+                    self.bits.clone()
+                }
+
+                /// Converts a little-endian bit order representation of bits into
+                /// an integer.
+                pub fn from_bits_le(bits: &[Boolean]) -> Self {
+                    assert_eq!(bits.len(), $size);
+
+                    let bits = bits.to_vec();
+
+                    let mut value: Option<$native> = Some(0);
+                    for b in bits.iter().rev() {
+                        value.as_mut().map(|v| *v <<= 1);
+
+                        match b {
+                            &Boolean::Constant(b) => {
+                                if b {
+                                    value.as_mut().map(|v| *v |= 1);
+                                }
+                            }
+                            &Boolean::Is(ref b) => match b.get_value() {
+                                Some(true) => {
+                                    value.as_mut().map(|v| *v |= 1);
+                                }
+                                Some(false) => {}
+                                None => value = None,
+                            },
+                            &Boolean::Not(ref b) => match b.get_value() {
+                                Some(false) => {
+                                    value.as_mut().map(|v| *v |= 1);
+                                }
+                                Some(true) => {}
+                                None => value = None,
+                            },
+                        }
+                    }
+
+                    Self { value, bits }
+                }
+
+                /// Outputs the bytes of this integer in *big-endian* order, i.e.
+                /// the reverse of `to_bytes`. This only regroups the existing bits
+                /// and costs no constraints.
+                pub fn to_bytes_be(&self) -> Vec<UInt8> {
+                    let value_chunks = self.value.map(|val| val.to_be_bytes());
+                    self.bits
+                        .chunks(8)
+                        .rev()
+                        .enumerate()
+                        .map(|(i, chunk8)| UInt8 {
+                            bits: chunk8.to_vec(),
+                            value: value_chunks.map(|chunks| chunks[i]),
+                        })
+                        .collect()
+                }
+
+                /// Converts a *big-endian* byte order representation into an
+                /// integer; the inverse of `to_bytes_be`.
+                pub fn from_bytes_be(bytes: &[UInt8]) -> Self {
+                    assert_eq!(bytes.len(), $size / 8);
+
+                    let bits = bytes
+                        .iter()
+                        .rev()
+                        .flat_map(UInt8::into_bits_le)
+                        .collect::<Vec<_>>();
+                    Self::from_bits_le(&bits)
+                }
+
+                pub fn rotr(&self, by: usize) -> Self {
+                    let by = by % $size;
+
+                    let new_bits = self
+                        .bits
+                        .iter()
+                        .skip(by)
+                        .chain(self.bits.iter())
+                        .take($size)
+                        .cloned()
+                        .collect();
+
+                    $name {
+                        bits: new_bits,
+                        value: self.value.map(|v| v.rotate_right(by as u32)),
+                    }
+                }
+
+                pub fn rotl(&self, by: usize) -> Self {
+                    let by = by % $size;
+
+                    let new_bits = self
+                        .bits
+                        .iter()
+                        .skip($size - by)
+                        .chain(self.bits.iter())
+                        .take($size)
+                        .cloned()
+                        .collect();
+
+                    $name {
+                        bits: new_bits,
+                        value: self.value.map(|v| v.rotate_left(by as u32)),
+                    }
+                }
+
+                /// Logical right shift; the vacated high bits are filled with
+                /// constant zeros.
+                pub fn shr(&self, by: usize) -> Self {
+                    let by = core::cmp::min(by, $size);
+
+                    let new_bits = self
+                        .bits
+                        .iter()
+                        .skip(by)
+                        .cloned()
+                        .chain(core::iter::repeat(Boolean::constant(false)))
+                        .take($size)
+                        .collect();
+
+                    $name {
+                        bits: new_bits,
+                        value: self
+                            .value
+                            .map(|v| v.checked_shr(by as u32).unwrap_or(0)),
+                    }
+                }
+
+                /// Logical left shift; the vacated low bits are filled with
+                /// constant zeros, and bits shifted past the top are dropped.
+                pub fn shl(&self, by: usize) -> Self {
+                    let by = core::cmp::min(by, $size);
+
+                    let new_bits = core::iter::repeat(Boolean::constant(false))
+                        .take(by)
+                        .chain(self.bits.iter().cloned())
+                        .take($size)
+                        .collect();
+
+                    $name {
+                        bits: new_bits,
+                        value: self
+                            .value
+                            .map(|v| v.checked_shl(by as u32).unwrap_or(0)),
+                    }
+                }
+
+                /// XOR this integer with another integer of the same width
+                pub fn xor<ConstraintF, CS>(
+                    &self,
+                    mut cs: CS,
+                    other: &Self,
+                ) -> Result<Self, SynthesisError>
+                where
+                    ConstraintF: Field,
+                    CS: ConstraintSystem<ConstraintF>,
+                {
+                    let new_value = match (self.value, other.value) {
+                        (Some(a), Some(b)) => Some(a ^ b),
+                        _ => None,
+                    };
+
+                    let bits = self
+                        .bits
+                        .iter()
+                        .zip(other.bits.iter())
+                        .enumerate()
+                        .map(|(i, (a, b))| {
+                            Boolean::xor(cs.ns(|| format!("xor of bit_gadget {}", i)), a, b)
+                        })
+                        .collect::<Result<_, _>>()?;
+
+                    Ok($name {
+                        bits,
+                        value: new_value,
+                    })
+                }
+
+                /// Perform modular addition of several integers of the same
+                /// width.
+                pub fn addmany<ConstraintF, CS>(
+                    mut cs: CS,
+                    operands: &[Self],
+                ) -> Result<Self, SynthesisError>
+                where
+                    ConstraintF: PrimeField,
+                    CS: ConstraintSystem<ConstraintF>,
+                {
+                    // Make some arbitrary bounds for ourselves to avoid overflows
+                    // in the scalar field: the sum of ten operands needs at most
+                    // `$size + 4` bits.
+                    assert!(ConstraintF::Params::MODULUS_BITS as usize >= $size + 5);
+
+                    assert!(operands.len() >= 1);
+                    assert!(operands.len() <= 10);
+
+                    if operands.len() == 1 {
+                        return Ok(operands[0].clone());
+                    }
+
+                    // Constant operands only matter modulo 2^$size, so fold them
+                    // into a single constant; fewer operands need fewer carry bits.
+                    let (constants, witnesses): (Vec<&Self>, Vec<&Self>) =
+                        operands.iter().partition(|op| op.is_constant());
+                    if constants.len() > 1 {
+                        let sum = constants
+                            .iter()
+                            .filter_map(|op| op.value)
+                            .fold(0, |acc: $native, v| acc.wrapping_add(v));
+                        let mut folded = witnesses.into_iter().cloned().collect::<Vec<_>>();
+                        folded.push($name::constant(sum));
+                        return Self::addmany(cs, &folded);
+                    }
+
+                    // Compute the number of bits of the maximum value of the
+                    // sum, so we allocate enough bits for the result
+                    let mut num_bits = $size;
+                    let mut max_carry = operands.len() - 1;
+                    while max_carry != 0 {
+                        max_carry >>= 1;
+                        num_bits += 1;
+                    }
+
+                    // Keep track of the resulting value, both as a field element
+                    // (from which we read off the result bits) and modulo
+                    // 2^$size
+                    let mut result_value = Some(ConstraintF::zero());
+                    let mut modular_value: Option<$native> = Some(0);
+
+                    // This is a linear combination that we will enforce to be "zero"
+                    let mut lc = LinearCombination::zero();
+
+                    let mut all_constants = true;
+
+                    // Iterate over the operands
+                    for op in operands {
+                        // Accumulate the value
+                        match op.value {
+                            Some(val) => {
+                                result_value
+                                    .as_mut()
+                                    .map(|v| *v += &ConstraintF::from(val));
+                                modular_value
+                                    .as_mut()
+                                    .map(|v| *v = v.wrapping_add(val));
+                            }
+                            None => {
+                                // If any of our operands have unknown value, we won't
+                                // know the value of the result
+                                result_value = None;
+                                modular_value = None;
+                            }
+                        }
+
+                        // Iterate over each bit_gadget of the operand and add the operand to
+                        // the linear combination
+                        let mut coeff = ConstraintF::one();
+                        for bit in &op.bits {
+                            match *bit {
+                                Boolean::Is(ref bit) => {
+                                    all_constants = false;
+
+                                    // Add coeff * bit_gadget
+                                    lc += (coeff, bit.get_variable());
+                                }
+                                Boolean::Not(ref bit) => {
+                                    all_constants = false;
+
+                                    // Add coeff * (1 - bit_gadget) = coeff * ONE - coeff * bit_gadget
+                                    lc = lc + (coeff, CS::one()) - (coeff, bit.get_variable());
+                                }
+                                Boolean::Constant(bit) => {
+                                    if bit {
+                                        lc += (coeff, CS::one());
+                                    }
+                                }
+                            }
+
+                            coeff.double_in_place();
+                        }
+                    }
+
+                    if all_constants && modular_value.is_some() {
+                        // We can just return a constant, rather than
+                        // unpacking the result into allocated bits.
+
+                        return Ok($name::constant(modular_value.unwrap()));
+                    }
+
+                    let result_repr = result_value.map(|v| v.into_repr());
+
+                    // Storage area for the resulting bits
+                    let mut result_bits = vec![];
+
+                    // Allocate each bit_gadget of the result
+                    let mut coeff = ConstraintF::one();
+                    for i in 0..num_bits {
+                        // Allocate the bit_gadget
+                        let b = AllocatedBit::alloc(
+                            cs.ns(|| format!("result bit_gadget {}", i)),
+                            || result_repr.map(|v| v.get_bit(i)).get(),
+                        )?;
+
+                        // Subtract this bit_gadget from the linear combination to ensure the sums
+                        // balance out
+                        lc = lc - (coeff, b.get_variable());
+
+                        result_bits.push(b.into());
+
+                        coeff.double_in_place();
+                    }
+
+                    // Enforce that the linear combination equals zero
+                    cs.enforce(|| "modular addition", |lc| lc, |lc| lc, |_| lc);
+
+                    // Discard carry bits that we don't care about
+                    result_bits.truncate($size);
+
+                    Ok($name {
+                        bits: result_bits,
+                        value: modular_value,
+                    })
+                }
+
+                /// Perform modular subtraction of `other` from `self`, i.e.
+                /// compute `self - other mod 2^$size`.
+                pub fn sub<ConstraintF, CS>(
+                    &self,
+                    cs: CS,
+                    other: &Self,
+                ) -> Result<Self, SynthesisError>
+                where
+                    ConstraintF: PrimeField,
+                    CS: ConstraintSystem<ConstraintF>,
+                {
+                    self.sub_with_borrow(cs, other).map(|(result, _)| result)
+                }
+
+                /// Compute `self - other mod 2^$size` together with the borrow
+                /// out of the top bit, which is set exactly when `self < other`.
+                fn sub_with_borrow<ConstraintF, CS>(
+                    &self,
+                    mut cs: CS,
+                    other: &Self,
+                ) -> Result<(Self, Boolean), SynthesisError>
+                where
+                    ConstraintF: PrimeField,
+                    CS: ConstraintSystem<ConstraintF>,
+                {
+                    // `self - other + borrow * 2^$size - result` lies strictly
+                    // between `-2^($size + 1)` and `2^($size + 1)`, so it can only
+                    // be zero in the field if it is zero over the integers.
+                    assert!(ConstraintF::Params::MODULUS_BITS as usize >= $size + 2);
+
+                    let (modular_value, borrow_value) = match (self.value, other.value) {
+                        (Some(a), Some(b)) => (Some(a.wrapping_sub(b)), Some(a < b)),
+                        _ => (None, None),
+                    };
+
+                    // This is a linear combination that we will enforce to be "zero"
+                    let mut lc = LinearCombination::zero();
+
+                    let mut all_constants = true;
+
+                    // Add `self` and subtract `other`
+                    for (op, negate) in &[(self, false), (other, true)] {
+                        let mut coeff = ConstraintF::one();
+                        if *negate {
+                            coeff = -coeff;
+                        }
+
+                        for bit in &op.bits {
+                            match *bit {
+                                Boolean::Is(ref bit) => {
+                                    all_constants = false;
+
+                                    // Add coeff * bit_gadget
+                                    lc += (coeff, bit.get_variable());
+                                }
+                                Boolean::Not(ref bit) => {
+                                    all_constants = false;
+
+                                    // Add coeff * (1 - bit_gadget) = coeff * ONE - coeff * bit_gadget
+                                    lc = lc + (coeff, CS::one()) - (coeff, bit.get_variable());
+                                }
+                                Boolean::Constant(bit) => {
+                                    if bit {
+                                        lc += (coeff, CS::one());
+                                    }
+                                }
+                            }
+
+                            coeff.double_in_place();
+                        }
+                    }
+
+                    if all_constants && modular_value.is_some() {
+                        // We can just return a constant, rather than
+                        // unpacking the result into allocated bits.
+
+                        return Ok((
+                            $name::constant(modular_value.unwrap()),
+                            Boolean::constant(borrow_value.unwrap()),
+                        ));
+                    }
+
+                    // Storage area for the resulting bits
+                    let mut result_bits = Vec::with_capacity($size);
+
+                    // Allocate each bit_gadget of the result
+                    let mut coeff = ConstraintF::one();
+                    for (i, v) in Self::bit_values(modular_value).into_iter().enumerate() {
+                        let b = AllocatedBit::alloc(
+                            cs.ns(|| format!("result bit_gadget {}", i)),
+                            || v.get(),
+                        )?;
+
+                        lc = lc - (coeff, b.get_variable());
+
+                        result_bits.push(b.into());
+
+                        coeff.double_in_place();
+                    }
+
+                    // The borrow is set exactly when `other > self`, and adds
+                    // back the `2^$size` that the result wrapped around by
+                    let borrow =
+                        AllocatedBit::alloc(cs.ns(|| "borrow bit_gadget"), || borrow_value.get())?;
+                    lc += (coeff, borrow.get_variable());
+
+                    // Enforce that the linear combination equals zero
+                    cs.enforce(|| "modular subtraction", |lc| lc, |lc| lc, |_| lc);
+
+                    Ok((
+                        $name {
+                            bits: result_bits,
+                            value: modular_value,
+                        },
+                        borrow.into(),
+                    ))
+                }
+
+                /// Outputs whether `self < other`, interpreting both as
+                /// unsigned integers.
+                ///
+                /// The comparison reads off the borrow of `self - other`, which
+                /// is computed over the constraint field. This is only sound
+                /// because `$size` bits is well below the size of the field
+                /// modulus, so that the difference can never wrap around in the
+                /// field; this is checked at synthesis time.
+                pub fn is_lt<ConstraintF, CS>(
+                    &self,
+                    mut cs: CS,
+                    other: &Self,
+                ) -> Result<Boolean, SynthesisError>
+                where
+                    ConstraintF: PrimeField,
+                    CS: ConstraintSystem<ConstraintF>,
+                {
+                    self.sub_with_borrow(cs.ns(|| "self - other"), other)
+                        .map(|(_, borrow)| borrow)
+                }
+
+                /// Outputs whether `self <= other`. See [`Self::is_lt`].
+                pub fn is_le<ConstraintF, CS>(
+                    &self,
+                    cs: CS,
+                    other: &Self,
+                ) -> Result<Boolean, SynthesisError>
+                where
+                    ConstraintF: PrimeField,
+                    CS: ConstraintSystem<ConstraintF>,
+                {
+                    other.is_lt(cs, self).map(|is_gt| is_gt.not())
+                }
+
+                /// Outputs whether `self > other`. See [`Self::is_lt`].
+                pub fn is_gt<ConstraintF, CS>(
+                    &self,
+                    cs: CS,
+                    other: &Self,
+                ) -> Result<Boolean, SynthesisError>
+                where
+                    ConstraintF: PrimeField,
+                    CS: ConstraintSystem<ConstraintF>,
+                {
+                    other.is_lt(cs, self)
+                }
+
+                /// Outputs whether `self >= other`. See [`Self::is_lt`].
+                pub fn is_ge<ConstraintF, CS>(
+                    &self,
+                    cs: CS,
+                    other: &Self,
+                ) -> Result<Boolean, SynthesisError>
+                where
+                    ConstraintF: PrimeField,
+                    CS: ConstraintSystem<ConstraintF>,
+                {
+                    self.is_lt(cs, other).map(|is_lt| is_lt.not())
+                }
+
+                /// This function checks the ordering between `self` and `other`.
+                /// It outputs a `Boolean` that contains the result - `1` if true,
+                /// `0` otherwise. If `self` should also be checked for equality,
+                /// e.g. `a <= b` instead of `a < b`, set
+                /// `should_also_check_equality` to `true`. `Ordering::Equal` is
+                /// rejected with `SynthesisError::InvalidArgument`.
+                pub fn is_cmp<ConstraintF, CS>(
+                    &self,
+                    cs: CS,
+                    other: &Self,
+                    ordering: Ordering,
+                    should_also_check_equality: bool,
+                ) -> Result<Boolean, SynthesisError>
+                where
+                    ConstraintF: PrimeField,
+                    CS: ConstraintSystem<ConstraintF>,
+                {
+                    match (ordering, should_also_check_equality) {
+                        (Ordering::Less, false) => self.is_lt(cs, other),
+                        (Ordering::Less, true) => self.is_le(cs, other),
+                        (Ordering::Greater, false) => self.is_gt(cs, other),
+                        (Ordering::Greater, true) => self.is_ge(cs, other),
+                        (Ordering::Equal, _) => Err(SynthesisError::InvalidArgument),
+                    }
+                }
+
+                /// This function enforces the ordering between `self` and
+                /// `other`. The constraint system will not be satisfied
+                /// otherwise. See [`Self::is_cmp`].
+                pub fn enforce_cmp<ConstraintF, CS>(
+                    &self,
+                    mut cs: CS,
+                    other: &Self,
+                    ordering: Ordering,
+                    should_also_check_equality: bool,
+                ) -> Result<(), SynthesisError>
+                where
+                    ConstraintF: PrimeField,
+                    CS: ConstraintSystem<ConstraintF>,
+                {
+                    let is_cmp = self.is_cmp(
+                        cs.ns(|| "is cmp"),
+                        other,
+                        ordering,
+                        should_also_check_equality,
+                    )?;
+                    if let Boolean::Constant(false) = is_cmp {
+                        return Err(SynthesisError::Unsatisfiable);
+                    }
+                    is_cmp.enforce_equal(cs.ns(|| "enforce cmp"), &Boolean::constant(true))
+                }
+            }
+
+            impl<ConstraintF: Field> AllocGadget<$native, ConstraintF> for $name {
+                fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
+                    _cs: CS,
+                    t: T,
+                ) -> Result<Self, SynthesisError>
+                where
+                    T: Borrow<$native>,
+                {
+                    Ok($name::constant(*t.borrow()))
+                }
+
+                fn alloc<F, T, CS: ConstraintSystem<ConstraintF>>(
+                    cs: CS,
+                    value_gen: F,
+                ) -> Result<Self, SynthesisError>
+                where
+                    F: FnOnce() -> Result<T, SynthesisError>,
+                    T: Borrow<$native>,
+                {
+                    $name::alloc(cs, value_gen().map(|val| *val.borrow()).ok())
+                }
+
+                fn alloc_input<F, T, CS: ConstraintSystem<ConstraintF>>(
+                    mut cs: CS,
+                    value_gen: F,
+                ) -> Result<Self, SynthesisError>
+                where
+                    F: FnOnce() -> Result<T, SynthesisError>,
+                    T: Borrow<$native>,
+                {
+                    let value = value_gen().map(|val| *val.borrow()).ok();
+                    let bits = Self::bit_values(value)
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, v)| {
+                            Ok(Boolean::from(AllocatedBit::alloc_input(
+                                cs.ns(|| format!("allocated bit_gadget {}", i)),
+                                || v.get(),
+                            )?))
+                        })
+                        .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+                    Ok($name { bits, value })
+                }
+            }
+
+            impl<ConstraintF: Field> ToBitsGadget<ConstraintF> for $name {
+                fn to_bits<CS: ConstraintSystem<ConstraintF>>(
+                    &self,
+                    _cs: CS,
+                ) -> Result<Vec<Boolean>, SynthesisError> {
+                    Ok(self.to_bits_le())
+                }
+            }
+
+            impl<ConstraintF: Field> ToBytesGadget<ConstraintF> for $name {
+                #[inline]
+                fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
+                    &self,
+                    _cs: CS,
+                ) -> Result<Vec<UInt8>, SynthesisError> {
+                    let value_chunks = self.value.map(|val| val.to_le_bytes());
+                    let mut bytes = Vec::with_capacity($size / 8);
+                    for (i, chunk8) in self.to_bits_le().chunks(8).enumerate() {
+                        let byte = UInt8 {
+                            bits: chunk8.to_vec(),
+                            value: value_chunks.map(|chunks| chunks[i]),
+                        };
+                        bytes.push(byte);
+                    }
+
+                    Ok(bytes)
+                }
+            }
+
+            impl PartialEq for $name {
+                fn eq(&self, other: &Self) -> bool {
+                    self.value.is_some() && other.value.is_some() && self.value == other.value
+                }
+            }
+
+            impl Eq for $name {}
+
+            impl<ConstraintF: Field> ConditionalEqGadget<ConstraintF> for $name {
+                fn conditional_enforce_equal<CS: ConstraintSystem<ConstraintF>>(
+                    &self,
+                    mut cs: CS,
+                    other: &Self,
+                    condition: &Boolean,
+                ) -> Result<(), SynthesisError> {
+                    for (i, (a, b)) in self.bits.iter().zip(&other.bits).enumerate() {
+                        a.conditional_enforce_equal(
+                            &mut cs.ns(|| format!("{}_equal_{}", stringify!($mod_name), i)),
+                            b,
+                            condition,
+                        )?;
+                    }
+                    Ok(())
+                }
+
+                fn cost() -> usize {
+                    $size * <Boolean as ConditionalEqGadget<ConstraintF>>::cost()
+                }
+            }
+
+            #[cfg(test)]
+            mod test {
+                use super::$name;
+                use crate::{
+                    alloc::AllocGadget,
+                    bits::{boolean::Boolean, uint8::UInt8, ToBytesGadget},
+                    test_constraint_system::TestConstraintSystem,
+                    Vec,
+                };
+                use algebra::{bls12_381::Fr, One, Zero};
+                use core::cmp::Ordering;
+                use r1cs_core::{ConstraintSystem, SynthesisError};
+                use rand::{Rng, SeedableRng};
+                use rand_xorshift::XorShiftRng;
+
+                #[test]
+                fn test_from_bits() {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+                    for _ in 0..1000 {
+                        let v = (0..$size)
+                            .map(|_| Boolean::constant(rng.gen()))
+                            .collect::<Vec<_>>();
+
+                        let b = $name::from_bits_le(&v);
+
+                        for (i, bit_gadget) in b.bits.iter().enumerate() {
+                            match bit_gadget {
+                                &Boolean::Constant(bit_gadget) => {
+                                    assert!(bit_gadget == ((b.value.unwrap() >> i) & 1 == 1));
+                                }
+                                _ => unreachable!(),
+                            }
+                        }
+
+                        let expected_to_be_same = b.to_bits_le();
+
+                        for x in v.iter().zip(expected_to_be_same.iter()) {
+                            match x {
+                                (&Boolean::Constant(true), &Boolean::Constant(true)) => {}
+                                (&Boolean::Constant(false), &Boolean::Constant(false)) => {}
+                                _ => unreachable!(),
+                            }
+                        }
+                    }
+                }
+
+                #[test]
+                fn test_from_bits_to_bits_witness() {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+                    for i in 0..100 {
+                        let mut cs = TestConstraintSystem::<Fr>::new();
+
+                        let a: $native = rng.gen();
+                        let a_bit = if i % 2 == 0 {
+                            $name::alloc(cs.ns(|| "a_bit"), Some(a)).unwrap()
+                        } else {
+                            $name::alloc_input(cs.ns(|| "a_bit"), || Ok(a)).unwrap()
+                        };
+
+                        let bits = a_bit.to_bits_le();
+                        for (j, bit) in bits.iter().enumerate() {
+                            assert_eq!(bit.get_value().unwrap(), (a >> j) & 1 == 1);
+                        }
+
+                        let b_bit = $name::from_bits_le(&bits);
+                        assert_eq!(b_bit.get_value(), Some(a));
+                        assert!(b_bit.to_bits_le() == bits);
+                        assert!(cs.is_satisfied());
+                    }
+                }
+
+                #[test]
+                fn test_to_bytes_be() {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+                    for _ in 0..100 {
+                        let mut cs = TestConstraintSystem::<Fr>::new();
+
+                        let a: $native = rng.gen();
+                        let a_bit = $name::alloc(cs.ns(|| "a_bit"), Some(a)).unwrap();
+                        let num_constraints = cs.num_constraints();
+
+                        let be = a_bit.to_bytes_be();
+                        let mut le = a_bit.to_bytes(cs.ns(|| "to_bytes")).unwrap();
+                        le.reverse();
+                        assert_eq!(UInt8::get_values(&be), Some(a.to_be_bytes().to_vec()));
+                        assert_eq!(UInt8::get_values(&be), UInt8::get_values(&le));
+                        for (be_byte, le_byte) in be.iter().zip(&le) {
+                            assert_eq!(be_byte.into_bits_le(), le_byte.into_bits_le());
+                        }
+
+                        let round_tripped = $name::from_bytes_be(&be);
+                        assert_eq!(round_tripped.value, Some(a));
+                        assert_eq!(round_tripped.to_bits_le(), a_bit.to_bits_le());
+                        assert_eq!(cs.num_constraints(), num_constraints);
+                        assert!(cs.is_satisfied());
+                    }
+                }
+
+                #[test]
+                fn test_xor() {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+                    for _ in 0..1000 {
+                        let mut cs = TestConstraintSystem::<Fr>::new();
+
+                        let a: $native = rng.gen();
+                        let b: $native = rng.gen();
+                        let c: $native = rng.gen();
+
+                        let mut expected = a ^ b ^ c;
+
+                        let a_bit = $name::alloc(cs.ns(|| "a_bit"), Some(a)).unwrap();
+                        let b_bit = $name::constant(b);
+                        let c_bit = $name::alloc(cs.ns(|| "c_bit"), Some(c)).unwrap();
+
+                        let r = a_bit.xor(cs.ns(|| "first xor"), &b_bit).unwrap();
+                        let r = r.xor(cs.ns(|| "second xor"), &c_bit).unwrap();
+
+                        assert!(cs.is_satisfied());
+
+                        assert!(r.value == Some(expected));
+
+                        for b in r.bits.iter() {
+                            match b {
+                                &Boolean::Is(ref b) => {
+                                    assert!(b.get_value().unwrap() == (expected & 1 == 1));
+                                }
+                                &Boolean::Not(ref b) => {
+                                    assert!(!b.get_value().unwrap() == (expected & 1 == 1));
+                                }
+                                &Boolean::Constant(b) => {
+                                    assert!(b == (expected & 1 == 1));
+                                }
+                            }
+
+                            expected >>= 1;
+                        }
+                    }
+                }
+
+                #[test]
+                fn test_addmany_constants() {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+                    for _ in 0..1000 {
+                        let mut cs = TestConstraintSystem::<Fr>::new();
+
+                        let a: $native = rng.gen();
+                        let b: $native = rng.gen();
+                        let c: $native = rng.gen();
+
+                        let a_bit = $name::constant(a);
+                        let b_bit = $name::constant(b);
+                        let c_bit = $name::constant(c);
+
+                        let mut expected = a.wrapping_add(b).wrapping_add(c);
+
+                        let r = $name::addmany(cs.ns(|| "addition"), &[a_bit, b_bit, c_bit])
+                            .unwrap();
+
+                        assert!(r.value == Some(expected));
+                        assert_eq!(cs.num_constraints(), 0);
+
+                        for b in r.bits.iter() {
+                            match b {
+                                &Boolean::Is(_) => panic!(),
+                                &Boolean::Not(_) => panic!(),
+                                &Boolean::Constant(b) => {
+                                    assert!(b == (expected & 1 == 1));
+                                }
+                            }
+
+                            expected >>= 1;
+                        }
+                    }
+                }
+
+                #[test]
+                fn test_addmany() {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+                    for _ in 0..1000 {
+                        let mut cs = TestConstraintSystem::<Fr>::new();
+
+                        let a: $native = rng.gen();
+                        let b: $native = rng.gen();
+                        let c: $native = rng.gen();
+                        let d: $native = rng.gen();
+
+                        let mut expected = (a ^ b).wrapping_add(c).wrapping_add(d);
+
+                        let a_bit = $name::alloc(cs.ns(|| "a_bit"), Some(a)).unwrap();
+                        let b_bit = $name::constant(b);
+                        let c_bit = $name::constant(c);
+                        let d_bit = $name::alloc(cs.ns(|| "d_bit"), Some(d)).unwrap();
+
+                        let r = a_bit.xor(cs.ns(|| "xor"), &b_bit).unwrap();
+                        let r = $name::addmany(cs.ns(|| "addition"), &[r, c_bit, d_bit]).unwrap();
+
+                        assert!(cs.is_satisfied());
+
+                        assert!(r.value == Some(expected));
+
+                        for b in r.bits.iter() {
+                            match b {
+                                &Boolean::Is(ref b) => {
+                                    assert!(b.get_value().unwrap() == (expected & 1 == 1));
+                                }
+                                &Boolean::Not(ref b) => {
+                                    assert!(!b.get_value().unwrap() == (expected & 1 == 1));
+                                }
+                                &Boolean::Constant(_) => unreachable!(),
+                            }
+
+                            expected >>= 1;
+                        }
+
+                        // Flip a bit_gadget and see if the addition constraint still works
+                        if cs.get("addition/result bit_gadget 0/boolean").is_zero() {
+                            cs.set("addition/result bit_gadget 0/boolean", Fr::one());
+                        } else {
+                            cs.set("addition/result bit_gadget 0/boolean", Fr::zero());
+                        }
+
+                        assert!(!cs.is_satisfied());
+                    }
+                }
+
+                #[test]
+                fn test_addmany_constraint_count() {
+                    let mut cs = TestConstraintSystem::<Fr>::new();
+
+                    let a_bit = $name::alloc(cs.ns(|| "a_bit"), Some(3)).unwrap();
+                    let b_bit = $name::constant(5);
+                    let c_bit = $name::alloc(cs.ns(|| "c_bit"), Some(7)).unwrap();
+                    let d_bit = $name::alloc(cs.ns(|| "d_bit"), Some(11)).unwrap();
+                    assert_eq!(cs.num_constraints(), 3 * $size);
+
+                    let r = a_bit.xor(cs.ns(|| "xor"), &b_bit).unwrap();
+                    let _ = $name::addmany(cs.ns(|| "addition"), &[r, b_bit, c_bit, d_bit])
+                        .unwrap();
+
+                    // Four operands need two carry bits; each result bit costs a
+                    // booleanity constraint, plus one for the sum itself. For
+                    // `UInt32` this is the same 131 constraints as the original
+                    // hand-written gadget.
+                    assert_eq!(cs.num_constraints(), 3 * $size + ($size + 2) + 1);
+                    assert!(cs.is_satisfied());
+                }
+
+                #[test]
+                fn test_constant_folding() {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+                    let mut cs = TestConstraintSystem::<Fr>::new();
+
+                    let a: $native = rng.gen();
+                    let b: $native = rng.gen();
+                    let c: $native = rng.gen();
+                    let a_bit = $name::alloc(cs.ns(|| "a_bit"), Some(a)).unwrap();
+                    let b_bit = $name::constant(b);
+                    let c_bit = $name::constant(c);
+                    let num_constraints = cs.num_constraints();
+                    let num_variables = cs.num_aux_variables();
+
+                    // Xoring with a constant only negates bits.
+                    let r = a_bit.xor(cs.ns(|| "a xor b"), &b_bit).unwrap();
+                    assert_eq!(r.value, Some(a ^ b));
+                    let r = a_bit
+                        .rotr(7)
+                        .xor(cs.ns(|| "rotr xor"), &b_bit)
+                        .unwrap()
+                        .xor(cs.ns(|| "rotr xor xor"), &c_bit.rotr(3))
+                        .unwrap();
+                    assert_eq!(r.value, Some(a.rotate_right(7) ^ b ^ c.rotate_right(3)));
+                    assert_eq!(cs.num_constraints(), num_constraints);
+                    assert_eq!(cs.num_aux_variables(), num_variables);
+
+                    // Xoring two constants gives a constant.
+                    let r = b_bit.xor(cs.ns(|| "b xor c"), &c_bit).unwrap();
+                    assert_eq!(r.value, Some(b ^ c));
+                    assert!(r.bits.iter().all(|bit| match bit {
+                        Boolean::Constant(_) => true,
+                        _ => false,
+                    }));
+                    assert_eq!(cs.num_constraints(), num_constraints);
+
+                    // Constant operands of addmany are folded into one.
+                    let r = $name::addmany(
+                        cs.ns(|| "a + b + c + b"),
+                        &[a_bit.clone(), b_bit.clone(), c_bit.clone(), b_bit.clone()],
+                    )
+                    .unwrap();
+                    let expected = a.wrapping_add(b).wrapping_add(c).wrapping_add(b);
+                    assert_eq!(r.value, Some(expected));
+                    // Two operands need one carry bit.
+                    assert_eq!(cs.num_constraints(), num_constraints + ($size + 1) + 1);
+                    assert!(cs.is_satisfied());
+                }
+
+                #[test]
+                fn test_sub_constants() {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+                    for _ in 0..1000 {
+                        let mut cs = TestConstraintSystem::<Fr>::new();
+
+                        let a: $native = rng.gen();
+                        let b: $native = rng.gen();
+
+                        let a_bit = $name::constant(a);
+                        let b_bit = $name::constant(b);
+
+                        let r = a_bit.sub(cs.ns(|| "subtraction"), &b_bit).unwrap();
+
+                        assert_eq!(r.get_value(), Some(a.wrapping_sub(b)));
+                        assert_eq!(cs.num_constraints(), 0);
+                        assert_eq!(
+                            $name::from_bits_le(&r.to_bits_le()).get_value(),
+                            Some(a.wrapping_sub(b))
+                        );
+                        for b in r.bits.iter() {
+                            match b {
+                                &Boolean::Constant(_) => {}
+                                _ => panic!(),
+                            }
+                        }
+                    }
+                }
+
+                #[test]
+                fn test_sub() {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+                    for i in 0..1000 {
+                        let mut cs = TestConstraintSystem::<Fr>::new();
+
+                        let a: $native = rng.gen();
+                        let b: $native = rng.gen();
+
+                        let expected = a.wrapping_sub(b);
+
+                        let a_bit = $name::alloc(cs.ns(|| "a_bit"), Some(a)).unwrap();
+                        let b_bit = if i % 2 == 0 {
+                            $name::alloc(cs.ns(|| "b_bit"), Some(b)).unwrap()
+                        } else {
+                            $name::constant(b)
+                        };
+
+                        let r = a_bit.sub(cs.ns(|| "subtraction"), &b_bit).unwrap();
+
+                        assert!(cs.is_satisfied());
+                        assert_eq!(r.get_value(), Some(expected));
+                        assert_eq!(
+                            $name::from_bits_le(&r.to_bits_le()).get_value(),
+                            Some(expected)
+                        );
+
+                        // Flip a bit_gadget and see if the subtraction constraint still works
+                        if cs.get("subtraction/result bit_gadget 0/boolean").is_zero() {
+                            cs.set("subtraction/result bit_gadget 0/boolean", Fr::one());
+                        } else {
+                            cs.set("subtraction/result bit_gadget 0/boolean", Fr::zero());
+                        }
+
+                        assert!(!cs.is_satisfied());
+                    }
+                }
+
+                #[test]
+                fn test_sub_underflow() {
+                    let mut cs = TestConstraintSystem::<Fr>::new();
+
+                    let a_bit = $name::alloc(cs.ns(|| "a_bit"), Some(3)).unwrap();
+                    let b_bit = $name::alloc(cs.ns(|| "b_bit"), Some(5)).unwrap();
+                    assert_eq!(cs.num_constraints(), 2 * $size);
+
+                    let r = a_bit.sub(cs.ns(|| "subtraction"), &b_bit).unwrap();
+
+                    // One booleanity constraint per result bit and for the
+                    // borrow, plus one for the difference itself.
+                    assert_eq!(cs.num_constraints(), 2 * $size + ($size + 1) + 1);
+                    assert!(cs.is_satisfied());
+                    assert_eq!(r.get_value(), Some($native::max_value() - 1));
+                    assert_eq!(
+                        $name::from_bits_le(&r.to_bits_le()).get_value(),
+                        Some($native::max_value() - 1)
+                    );
+                    assert!(cs.get("subtraction/borrow bit_gadget/boolean").is_one());
+                }
+
+                #[test]
+                fn test_cmp() {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+                    for i in 0..1000 {
+                        let mut cs = TestConstraintSystem::<Fr>::new();
+
+                        let a: $native = rng.gen();
+                        // Make sure equal and adjacent values are covered too
+                        let b: $native = match i % 4 {
+                            0 => a,
+                            1 => a.wrapping_add(1),
+                            _ => rng.gen(),
+                        };
+
+                        let a_bit = $name::alloc(cs.ns(|| "a_bit"), Some(a)).unwrap();
+                        let b_bit = if i % 3 == 0 {
+                            $name::constant(b)
+                        } else {
+                            $name::alloc(cs.ns(|| "b_bit"), Some(b)).unwrap()
+                        };
+
+                        let lt = a_bit.is_lt(cs.ns(|| "lt"), &b_bit).unwrap();
+                        let le = a_bit.is_le(cs.ns(|| "le"), &b_bit).unwrap();
+                        let gt = a_bit.is_gt(cs.ns(|| "gt"), &b_bit).unwrap();
+                        let ge = a_bit.is_ge(cs.ns(|| "ge"), &b_bit).unwrap();
+
+                        assert!(cs.is_satisfied());
+                        assert_eq!(lt.get_value(), Some(a < b));
+                        assert_eq!(le.get_value(), Some(a <= b));
+                        assert_eq!(gt.get_value(), Some(a > b));
+                        assert_eq!(ge.get_value(), Some(a >= b));
+
+                        for &(ordering, check_eq, holds) in &[
+                            (Ordering::Less, false, a < b),
+                            (Ordering::Less, true, a <= b),
+                            (Ordering::Greater, false, a > b),
+                            (Ordering::Greater, true, a >= b),
+                        ] {
+                            let mut cs = TestConstraintSystem::<Fr>::new();
+                            let a_bit = $name::alloc(cs.ns(|| "a_bit"), Some(a)).unwrap();
+                            let b_bit = $name::alloc(cs.ns(|| "b_bit"), Some(b)).unwrap();
+                            a_bit
+                                .enforce_cmp(cs.ns(|| "cmp"), &b_bit, ordering, check_eq)
+                                .unwrap();
+                            assert_eq!(cs.is_satisfied(), holds);
+                        }
+                    }
+                }
+
+                #[test]
+                fn test_cmp_constants() {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+                    for _ in 0..1000 {
+                        let mut cs = TestConstraintSystem::<Fr>::new();
+
+                        let a: $native = rng.gen();
+                        let b: $native = rng.gen();
+
+                        let a_bit = $name::constant(a);
+                        let b_bit = $name::constant(b);
+
+                        let lt = a_bit.is_lt(cs.ns(|| "lt"), &b_bit).unwrap();
+                        let ge = a_bit.is_ge(cs.ns(|| "ge"), &b_bit).unwrap();
+
+                        assert_eq!(lt, Boolean::constant(a < b));
+                        assert_eq!(ge, Boolean::constant(a >= b));
+                        assert_eq!(cs.num_constraints(), 0);
+
+                        let ordering = if a < b {
+                            Ordering::Less
+                        } else {
+                            Ordering::Greater
+                        };
+                        a_bit
+                            .enforce_cmp(cs.ns(|| "cmp"), &b_bit, ordering, true)
+                            .unwrap();
+                        assert!(a_bit
+                            .enforce_cmp(cs.ns(|| "bad cmp"), &b_bit, ordering.reverse(), false)
+                            .is_err());
+                        match a_bit.is_cmp(cs.ns(|| "eq"), &b_bit, Ordering::Equal, true) {
+                            Err(SynthesisError::InvalidArgument) => {},
+                            _ => panic!("expected InvalidArgument for Ordering::Equal"),
+                        }
+                    }
+                }
+
+                #[test]
+                fn test_addmany_wraparound() {
+                    let mut cs = TestConstraintSystem::<Fr>::new();
+
+                    let a = $native::max_value();
+                    let b = 5;
+                    let c = $native::max_value() - 1;
+
+                    let expected = a.wrapping_add(b).wrapping_add(c);
+
+                    let a_bit = $name::alloc(cs.ns(|| "a_bit"), Some(a)).unwrap();
+                    let b_bit = $name::alloc(cs.ns(|| "b_bit"), Some(b)).unwrap();
+                    let c_bit = $name::constant(c);
+
+                    let r = $name::addmany(cs.ns(|| "addition"), &[a_bit, b_bit, c_bit]).unwrap();
+
+                    assert!(cs.is_satisfied());
+                    assert_eq!(r.get_value(), Some(expected));
+                    assert_eq!(r.bits.len(), $size);
+                    assert_eq!(
+                        $name::from_bits_le(&r.to_bits_le()).get_value(),
+                        Some(expected)
+                    );
+                }
+
+                #[test]
+                fn test_rotr() {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+                    let mut num = rng.gen();
+
+                    let a = $name::constant(num);
+
+                    for i in 0..$size {
+                        let b = a.rotr(i);
+
+                        assert!(b.value.unwrap() == num);
+
+                        let mut tmp = num;
+                        for b in &b.bits {
+                            match b {
+                                &Boolean::Constant(b) => {
+                                    assert_eq!(b, tmp & 1 == 1);
+                                }
+                                _ => unreachable!(),
+                            }
+
+                            tmp >>= 1;
+                        }
+
+                        num = num.rotate_right(1);
+                    }
+                }
+
+                #[test]
+                fn test_rotl() {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+                    for &i in &[0, 1, 7, $size / 2, $size - 1, $size, $size + 3] {
+                        let mut cs = TestConstraintSystem::<Fr>::new();
+
+                        let num: $native = rng.gen();
+                        let a = $name::alloc(cs.ns(|| "a"), Some(num)).unwrap();
+                        let num_constraints = cs.num_constraints();
+
+                        let b = a.rotl(i);
+                        let expected = num.rotate_left(i as u32);
+
+                        assert_eq!(cs.num_constraints(), num_constraints);
+                        assert_eq!(b.get_value(), Some(expected));
+                        assert_eq!(
+                            $name::from_bits_le(&b.to_bits_le()).get_value(),
+                            Some(expected)
+                        );
+                        assert!(b == a.rotr($size - i % $size));
+                    }
+                }
+
+                #[test]
+                fn test_shl() {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+                    for i in 0..$size {
+                        let mut cs = TestConstraintSystem::<Fr>::new();
+
+                        let num: $native = rng.gen();
+                        let a = $name::alloc(cs.ns(|| "a"), Some(num)).unwrap();
+                        let num_constraints = cs.num_constraints();
+
+                        let b = a.shl(i);
+                        let expected = num.wrapping_shl(i as u32);
+
+                        assert_eq!(cs.num_constraints(), num_constraints);
+                        assert_eq!(b.get_value(), Some(expected));
+                        assert_eq!(
+                            $name::from_bits_le(&b.to_bits_le()).get_value(),
+                            Some(expected)
+                        );
+                    }
+
+                    // Unlike `wrapping_shl`, shifting by the full width clears
+                    // every bit.
+                    let mut cs = TestConstraintSystem::<Fr>::new();
+                    let a = $name::alloc(cs.ns(|| "a"), Some(rng.gen())).unwrap();
+                    let b = a.shl($size);
+                    assert_eq!(b.get_value(), Some(0));
+                    for bit in b.to_bits_le() {
+                        assert_eq!(bit, Boolean::constant(false));
+                    }
+                }
+
+                #[test]
+                fn test_shr() {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+                    for i in 0..=$size {
+                        let mut cs = TestConstraintSystem::<Fr>::new();
+
+                        let num: $native = rng.gen();
+                        let a = $name::alloc(cs.ns(|| "a"), Some(num)).unwrap();
+                        let num_constraints = cs.num_constraints();
+
+                        let b = a.shr(i);
+                        let expected = num.checked_shr(i as u32).unwrap_or(0);
+
+                        assert_eq!(cs.num_constraints(), num_constraints);
+                        assert_eq!(b.get_value(), Some(expected));
+                        assert_eq!(
+                            $name::from_bits_le(&b.to_bits_le()).get_value(),
+                            Some(expected)
+                        );
+                    }
+                }
+            }
+        }
+    };
+}
@@ -7,18 +7,37 @@ use core::borrow::Borrow;
 
 /// Represents an interpretation of 8 `Boolean` objects as an
 /// unsigned integer.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
+#[cfg_attr(not(feature = "debug_values"), derive(Debug))]
 pub struct UInt8 {
     // Least significant bit_gadget first
     pub(crate) bits: Vec<Boolean>,
     pub(crate) value: Option<u8>,
 }
 
+/// Prints the bits, least significant first, followed by the assignment, or
+/// `?` if it is missing.
+#[cfg(feature = "debug_values")]
+impl core::fmt::Debug for UInt8 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(&self.bits).finish()?;
+        match self.value {
+            Some(value) => write!(f, " = {:#04x}", value),
+            None => write!(f, " = ?"),
+        }
+    }
+}
+
 impl UInt8 {
     pub fn get_value(&self) -> Option<u8> {
         self.value
     }
 
+    /// Returns the values of `bytes`, or `None` if any of them is missing.
+    pub fn get_values(bytes: &[Self]) -> Option<Vec<u8>> {
+        bytes.iter().map(Self::get_value).collect()
+    }
+
     /// Construct a constant vector of `UInt8` from a vector of `u8`
     pub fn constant_vec(values: &[u8]) -> Vec<Self> {
         let mut result = Vec::new();
@@ -80,35 +99,66 @@ impl UInt8 {
         ConstraintF: PrimeField,
         CS: ConstraintSystem<ConstraintF>,
     {
-        let values_len = values.len();
         let field_elements: Vec<ConstraintF> =
             ToConstraintField::<ConstraintF>::to_field_elements(values).unwrap();
 
-        let max_size = 8 * (ConstraintF::Params::CAPACITY / 8) as usize;
-        let mut allocated_bits = Vec::new();
-        for (i, field_element) in field_elements.into_iter().enumerate() {
+        // `values.to_field_elements()` packs `CAPACITY / 8` bytes into each
+        // field element, so each one decomposes into the bytes of one chunk.
+        let max_size = (ConstraintF::Params::CAPACITY / 8) as usize;
+        let mut bytes = Vec::with_capacity(values.len());
+        for (i, (field_element, chunk)) in field_elements
+            .into_iter()
+            .zip(values.chunks(max_size))
+            .enumerate()
+        {
             let fe = FpGadget::alloc_input(&mut cs.ns(|| format!("Field element {}", i)), || {
                 Ok(field_element)
             })?;
-            let mut fe_bits = fe.to_bits(cs.ns(|| format!("Convert fe to bits {}", i)))?;
-            // FpGadget::to_bits outputs a big-endian binary representation of
-            // fe_gadget's value, so we have to reverse it to get the little-endian
-            // form.
-            fe_bits.reverse();
-
-            // Remove the most significant bit, because we know it should be zero
-            // because `values.to_field_elements()` only
-            // packs field elements up to the penultimate bit.
-            // That is, the most significant bit (`ConstraintF::NUM_BITS`-th bit) is
-            // unset, so we can just pop it off.
-            allocated_bits.extend_from_slice(&fe_bits[0..max_size]);
+            bytes.extend(Self::from_field(
+                cs.ns(|| format!("Convert fe to bytes {}", i)),
+                &fe,
+                chunk.len(),
+            )?);
         }
+        Ok(bytes)
+    }
 
-        // Chunk up slices of 8 bit into bytes.
-        Ok(allocated_bits[0..8 * values_len]
-            .chunks(8)
-            .map(Self::from_bits_le)
-            .collect())
+    /// Packs `bytes`, in little-endian order, into a single field element.
+    /// At most `CAPACITY / 8` bytes fit, so that the packing is injective;
+    /// longer inputs are rejected with `SynthesisError::Unsatisfiable`. This
+    /// costs no constraints.
+    pub fn pack_to_field<ConstraintF, CS>(
+        cs: CS,
+        bytes: &[Self],
+    ) -> Result<FpGadget<ConstraintF>, SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        if bytes.len() > (ConstraintF::Params::CAPACITY / 8) as usize {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        FpGadget::from_bytes_le(cs, bytes)
+    }
+
+    /// Unpacks `field` into `num_bytes` bytes in little-endian order,
+    /// enforcing that `field < 2^(8 * num_bytes)`; this is the inverse of
+    /// `pack_to_field`. At most `CAPACITY / 8` bytes are supported; longer
+    /// outputs are rejected with `SynthesisError::Unsatisfiable`.
+    pub fn from_field<ConstraintF, CS>(
+        cs: CS,
+        field: &FpGadget<ConstraintF>,
+        num_bytes: usize,
+    ) -> Result<Vec<Self>, SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        if num_bytes > (ConstraintF::Params::CAPACITY / 8) as usize {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let bits = field.to_bits_le_enforce_length(cs, 8 * num_bytes)?;
+        Ok(bits.chunks(8).map(Self::from_bits_le).collect())
     }
 
     /// Turns this `UInt8` into its little-endian byte order representation.
@@ -179,6 +229,75 @@ impl UInt8 {
             value: new_value,
         })
     }
+
+    /// AND this `UInt8` with another `UInt8`
+    pub fn and<ConstraintF, CS>(&self, mut cs: CS, other: &Self) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: Field,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        let new_value = match (self.value, other.value) {
+            (Some(a), Some(b)) => Some(a & b),
+            _ => None,
+        };
+
+        let bits = self
+            .bits
+            .iter()
+            .zip(other.bits.iter())
+            .enumerate()
+            .map(|(i, (a, b))| Boolean::and(cs.ns(|| format!("and of bit_gadget {}", i)), a, b))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            bits,
+            value: new_value,
+        })
+    }
+
+    /// XORs `a` and `b` byte by byte, erroring if their lengths differ.
+    /// Bits that are constant in either operand are folded, so XORing two
+    /// constant arrays adds no constraints.
+    pub fn xor_bytes<ConstraintF, CS>(
+        mut cs: CS,
+        a: &[Self],
+        b: &[Self],
+    ) -> Result<Vec<Self>, SynthesisError>
+    where
+        ConstraintF: Field,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        if a.len() != b.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        a.iter()
+            .zip(b)
+            .enumerate()
+            .map(|(i, (a, b))| a.xor(cs.ns(|| format!("xor of byte {}", i)), b))
+            .collect()
+    }
+
+    /// ANDs `a` and `b` byte by byte, erroring if their lengths differ.
+    /// Bits that are constant in either operand are folded, so ANDing two
+    /// constant arrays adds no constraints.
+    pub fn and_bytes<ConstraintF, CS>(
+        mut cs: CS,
+        a: &[Self],
+        b: &[Self],
+    ) -> Result<Vec<Self>, SynthesisError>
+    where
+        ConstraintF: Field,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        if a.len() != b.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        a.iter()
+            .zip(b)
+            .enumerate()
+            .map(|(i, (a, b))| a.and(cs.ns(|| format!("and of byte {}", i)), b))
+            .collect()
+    }
 }
 
 impl PartialEq for UInt8 {
@@ -213,6 +332,45 @@ impl<ConstraintF: Field> ConditionalEqGadget<ConstraintF> for UInt8 {
 
 impl<ConstraintF: Field> EqGadget<ConstraintF> for UInt8 {}
 
+impl<ConstraintF: PrimeField> CondSelectGadget<ConstraintF> for UInt8 {
+    fn conditionally_select<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        cond: &Boolean,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let mut bits = Vec::with_capacity(8);
+        for (i, (t, f)) in true_value.bits.iter().zip(&false_value.bits).enumerate() {
+            bits.push(Boolean::conditionally_select(
+                cs.ns(|| format!("select bit {}", i)),
+                cond,
+                t,
+                f,
+            )?);
+        }
+        Ok(Self::from_bits_le(&bits))
+    }
+
+    fn cost() -> usize {
+        8 * <Boolean as CondSelectGadget<ConstraintF>>::cost()
+    }
+}
+
+/// Mirrors the native `ToConstraintField` implementation for `[u8]`, packing
+/// little-endian bytes into field elements, `CAPACITY / 8` bytes at a time.
+impl<ConstraintF: PrimeField> ToConstraintFieldGadget<ConstraintF> for [UInt8] {
+    fn to_constraint_field<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Vec<FpGadget<ConstraintF>>, SynthesisError> {
+        let max_size = (ConstraintF::Params::CAPACITY / 8) as usize;
+        self.chunks(max_size)
+            .enumerate()
+            .map(|(i, chunk)| UInt8::pack_to_field(cs.ns(|| format!("chunk {}", i)), chunk))
+            .collect()
+    }
+}
+
 impl<ConstraintF: Field> AllocGadget<u8, ConstraintF> for UInt8 {
     fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
         _cs: CS,
@@ -308,11 +466,26 @@ impl<ConstraintF: Field> AllocGadget<u8, ConstraintF> for UInt8 {
 mod test {
     use super::UInt8;
     use crate::{prelude::*, test_constraint_system::TestConstraintSystem, Vec};
-    use algebra::bls12_381::Fr;
+    use algebra::{bls12_381::Fr, FpParameters, PrimeField, ToConstraintField};
     use r1cs_core::ConstraintSystem;
     use rand::{Rng, SeedableRng};
     use rand_xorshift::XorShiftRng;
 
+    #[test]
+    fn test_uint8_enforce_equal_constants() {
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let a = UInt8::constant(0x5a);
+        let b = UInt8::constant(0xa5);
+
+        a.enforce_equal(cs.ns(|| "a == a"), &a).unwrap();
+        a.conditional_enforce_equal(cs.ns(|| "skipped"), &b, &Boolean::constant(false))
+            .unwrap();
+        assert_eq!(cs.num_constraints(), 0);
+
+        assert!(a.enforce_equal(cs.ns(|| "a == b"), &b).is_err());
+        assert_eq!(cs.num_constraints(), 0);
+    }
+
     #[test]
     fn test_uint8_from_bits_to_bits() {
         let mut cs = TestConstraintSystem::<Fr>::new();
@@ -337,6 +510,89 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_uint8_array_to_bits_to_bytes() {
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let byte_vals = [0x01u8, 0x23, 0x45, 0x67];
+        let bytes = UInt8::alloc_vec(cs.ns(|| "alloc value"), &byte_vals).unwrap();
+        let array = [
+            bytes[0].clone(),
+            bytes[1].clone(),
+            bytes[2].clone(),
+            bytes[3].clone(),
+        ];
+
+        let bits = array.to_bits(cs.ns(|| "to bits")).unwrap();
+        assert_eq!(bits.len(), 32);
+        let round_tripped = bits.chunks(8).map(UInt8::from_bits_le).collect::<Vec<_>>();
+        for (native_byte, gadget_byte) in byte_vals.iter().zip(&round_tripped) {
+            assert_eq!(gadget_byte.get_value(), Some(*native_byte));
+        }
+
+        let round_tripped = array.to_bytes(cs.ns(|| "to bytes")).unwrap();
+        assert_eq!(round_tripped.len(), 4);
+        for (native_byte, gadget_byte) in byte_vals.iter().zip(&round_tripped) {
+            assert_eq!(gadget_byte.get_value(), Some(*native_byte));
+        }
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_uint8_to_constraint_field() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let byte_vals = (0..100).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let bytes = UInt8::alloc_vec(cs.ns(|| "alloc value"), &byte_vals).unwrap();
+        let field_elements = bytes
+            .to_constraint_field(cs.ns(|| "to constraint field"))
+            .unwrap();
+        let native_field_elements: Vec<Fr> = byte_vals.to_field_elements().unwrap();
+        assert_eq!(field_elements.len(), native_field_elements.len());
+        for (fe, native_fe) in field_elements.iter().zip(&native_field_elements) {
+            assert_eq!(fe.get_value(), Some(*native_fe));
+        }
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_uint8_pack_to_field_round_trip() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let max_size = (<Fr as PrimeField>::Params::CAPACITY / 8) as usize;
+
+        let mut byte_vals = (0..max_size).map(|_| rng.gen()).collect::<Vec<u8>>();
+        byte_vals[max_size - 1] = u8::max_value();
+        let bytes = UInt8::alloc_vec(cs.ns(|| "alloc value"), &byte_vals).unwrap();
+        let packed = UInt8::pack_to_field(cs.ns(|| "pack"), &bytes).unwrap();
+        let native_packed: Vec<Fr> = byte_vals.to_field_elements().unwrap();
+        assert_eq!(packed.get_value(), Some(native_packed[0]));
+
+        let unpacked = UInt8::from_field(cs.ns(|| "unpack"), &packed, max_size).unwrap();
+        assert_eq!(UInt8::get_values(&unpacked), Some(byte_vals.clone()));
+        assert!(cs.is_satisfied());
+
+        // One byte more than the capacity is rejected in both directions.
+        let mut too_long = bytes.clone();
+        too_long.push(UInt8::constant(0));
+        assert!(UInt8::pack_to_field(cs.ns(|| "pack too long"), &too_long).is_err());
+        assert!(UInt8::from_field(cs.ns(|| "unpack too long"), &packed, max_size + 1).is_err());
+
+        // The packed value does not fit in one byte less.
+        let _ = UInt8::from_field(cs.ns(|| "unpack short"), &packed, max_size - 1).unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_uint8_get_values() {
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let byte_vals = (64u8..128u8).collect::<Vec<_>>();
+        let bytes = UInt8::alloc_vec(cs.ns(|| "alloc value"), &byte_vals).unwrap();
+        assert_eq!(UInt8::get_values(&bytes), Some(byte_vals));
+
+        let missing = UInt8::alloc_vec(cs.ns(|| "alloc missing"), &[Some(1u8), None]).unwrap();
+        assert_eq!(UInt8::get_values(&missing), None);
+    }
+
     #[test]
     fn test_uint8_from_bits() {
         let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
@@ -410,4 +666,52 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_uint8_xor_and_bytes() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+        for _ in 0..10 {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+
+            let a = (0..16).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let b = (0..16).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let expected_xor = a.iter().zip(&b).map(|(a, b)| a ^ b).collect::<Vec<_>>();
+            let expected_and = a.iter().zip(&b).map(|(a, b)| a & b).collect::<Vec<_>>();
+
+            let a_constant = UInt8::constant_vec(&a);
+            let b_constant = UInt8::constant_vec(&b);
+            let a_bytes = UInt8::alloc_vec(cs.ns(|| "a"), &a).unwrap();
+            let b_bytes = UInt8::alloc_vec(cs.ns(|| "b"), &b).unwrap();
+
+            // Operations on constants are folded.
+            let num_constraints = cs.num_constraints();
+            let xor = UInt8::xor_bytes(cs.ns(|| "constant xor"), &a_constant, &b_constant).unwrap();
+            let and = UInt8::and_bytes(cs.ns(|| "constant and"), &a_constant, &b_constant).unwrap();
+            assert_eq!(cs.num_constraints(), num_constraints);
+            assert_eq!(UInt8::get_values(&xor), Some(expected_xor.clone()));
+            assert_eq!(UInt8::get_values(&and), Some(expected_and.clone()));
+
+            for (i, (a, b)) in [(&a_bytes, &b_bytes), (&a_bytes, &b_constant)]
+                .iter()
+                .enumerate()
+            {
+                let xor = UInt8::xor_bytes(cs.ns(|| format!("xor {}", i)), a, b).unwrap();
+                let and = UInt8::and_bytes(cs.ns(|| format!("and {}", i)), a, b).unwrap();
+                for (result, expected) in [(xor, &expected_xor), (and, &expected_and)].iter() {
+                    assert_eq!(UInt8::get_values(result), Some(expected.to_vec()));
+                    for (byte, expected) in result.iter().zip(expected.iter()) {
+                        let bits = (0..8)
+                            .map(|j| (*expected >> j) & 1 == 1)
+                            .collect::<Vec<_>>();
+                        assert_eq!(Boolean::get_values(&byte.bits), Some(bits));
+                    }
+                }
+            }
+            assert!(cs.is_satisfied());
+
+            assert!(UInt8::xor_bytes(cs.ns(|| "short xor"), &a_bytes, &b_bytes[1..]).is_err());
+            assert!(UInt8::and_bytes(cs.ns(|| "short and"), &a_bytes[1..], &b_bytes).is_err());
+        }
+    }
 }
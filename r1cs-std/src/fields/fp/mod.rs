@@ -1,22 +1,37 @@
-use algebra::{bytes::ToBytes, FpParameters, PrimeField};
+use algebra::{bytes::ToBytes, FpParameters, PrimeField, SquareRootField};
 use r1cs_core::{
     ConstraintSystem,
     ConstraintVar::{self, *},
-    LinearCombination, SynthesisError,
+    Index, LinearCombination, SynthesisError, Variable,
 };
 
 use core::borrow::Borrow;
 
-use crate::{boolean::AllocatedBit, prelude::*, Assignment, Vec};
+use crate::{boolean::AllocatedBit, prelude::*, select::select_value, Assignment, Vec};
 
 pub mod cmp;
 
-#[derive(Debug)]
+#[cfg_attr(not(feature = "debug_values"), derive(Debug))]
 pub struct FpGadget<F: PrimeField> {
     pub value: Option<F>,
     pub variable: ConstraintVar<F>,
 }
 
+/// Prints the variable followed by its assignment, or `?` if it is missing.
+#[cfg(feature = "debug_values")]
+impl<F: PrimeField> core::fmt::Debug for FpGadget<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self.variable {
+            Var(v) => write!(f, "{:?}", v.get_unchecked())?,
+            LC(lc) => write!(f, "{:?}", lc)?,
+        }
+        match self.value {
+            Some(value) => write!(f, " = {:?}", value),
+            None => write!(f, " = ?"),
+        }
+    }
+}
+
 impl<F: PrimeField> FpGadget<F> {
     #[inline]
     pub fn from<CS: ConstraintSystem<F>>(mut cs: CS, value: &F) -> Self {
@@ -35,8 +50,430 @@ impl<F: PrimeField> FpGadget<F> {
             // this would not recognize that z is in fact a constant.
             // Since this is an edge case, this is left as a TODO.
             Var(_v) => false,
-            LC(l) => l.is_constant(),
+            // Arithmetic on linear combinations does not merge terms, so a
+            // constant may be spread over several terms of the one variable.
+            LC(l) => {
+                let one = Variable::new_unchecked(Index::Input(0));
+                l.as_ref().iter().all(|(v, _)| *v == one)
+            },
+        }
+    }
+
+    /// Converts `bit` into the field element `0` or `1`. The booleanity of
+    /// `bit` is already enforced, so this costs no constraints; see
+    /// `Boolean::try_from_fp` for the converse.
+    pub fn from_boolean<CS: ConstraintSystem<F>>(
+        _cs: CS,
+        bit: &Boolean,
+    ) -> Result<Self, SynthesisError> {
+        Ok(FpGadget {
+            value: bit.get_value().map(|b| if b { F::one() } else { F::zero() }),
+            variable: LC(bit.lc(CS::one(), F::one())),
+        })
+    }
+
+    /// Computes `3 * self`. Like `double`, this only rescales the underlying
+    /// linear combination and costs no constraints.
+    pub fn triple<CS: ConstraintSystem<F>>(&self, _cs: CS) -> Result<Self, SynthesisError> {
+        let three = F::one().double() + &F::one();
+        Ok(FpGadget {
+            value: self.value.map(|val| val * &three),
+            variable: self.variable.clone() * three,
+        })
+    }
+
+    /// Packs `bits`, given in big-endian order, into a field element. If there
+    /// are enough bits to encode a value that is not smaller than the modulus,
+    /// the packed value is also checked to be in the field, so that `bits` is
+    /// its unique decomposition.
+    pub fn from_bits_be<CS: ConstraintSystem<F>>(
+        mut cs: CS,
+        bits: &[Boolean],
+    ) -> Result<Self, SynthesisError> {
+        if bits.len() >= F::Params::MODULUS_BITS as usize {
+            Boolean::enforce_in_field::<_, _, F>(cs.ns(|| "check in field"), bits)?;
+        }
+
+        let mut value = Some(F::zero());
+        let mut lc = LinearCombination::zero();
+        let mut coeff = F::one();
+        for bit in bits.iter().rev() {
+            value = match (value, bit.get_value()) {
+                (Some(v), Some(true)) => Some(v + &coeff),
+                (Some(v), Some(false)) => Some(v),
+                _ => None,
+            };
+            lc = lc + &bit.lc(CS::one(), coeff);
+
+            coeff.double_in_place();
+        }
+
+        Ok(FpGadget {
+            value,
+            variable: LC(lc),
+        })
+    }
+
+    /// Packs `bits`, given in little-endian order, into a field element. See
+    /// [`Self::from_bits_be`].
+    pub fn from_bits_le<CS: ConstraintSystem<F>>(
+        cs: CS,
+        bits: &[Boolean],
+    ) -> Result<Self, SynthesisError> {
+        let bits = bits.iter().rev().cloned().collect::<Vec<_>>();
+        Self::from_bits_be(cs, &bits)
+    }
+
+    /// Packs `bytes`, given in little-endian order as produced by the native
+    /// `ToBytes` implementation, into a field element, checking that they
+    /// encode a value smaller than the modulus.
+    pub fn from_bytes_le<CS: ConstraintSystem<F>>(
+        cs: CS,
+        bytes: &[UInt8],
+    ) -> Result<Self, SynthesisError> {
+        let bits = bytes
+            .iter()
+            .flat_map(UInt8::into_bits_le)
+            .collect::<Vec<_>>();
+        Self::from_bits_le(cs, &bits)
+    }
+
+    /// Outputs the `length` least significant bits of `self` in
+    /// *little-endian* form, enforcing that `self < 2^length`.
+    ///
+    /// When `length < MODULUS_BITS`, the packed bits cannot wrap around the
+    /// modulus, so this costs only `length + 1` constraints instead of the
+    /// full decomposition and in-field check of `to_bits`. Otherwise it falls
+    /// back to `to_bits`, padding the result with zeros.
+    pub fn to_bits_le_enforce_length<CS: ConstraintSystem<F>>(
+        &self,
+        mut cs: CS,
+        length: usize,
+    ) -> Result<Vec<Boolean>, SynthesisError> {
+        if length >= F::Params::MODULUS_BITS as usize {
+            let mut bits = self.to_bits(cs.ns(|| "to bits"))?;
+            bits.reverse();
+            bits.resize(length, Boolean::constant(false));
+            return Ok(bits);
+        }
+
+        let repr = self.value.map(|v| v.into_repr());
+        let mut bits = Vec::with_capacity(length);
+        let mut lc = LinearCombination::zero();
+        let mut coeff = F::one();
+        for i in 0..length {
+            let bit = AllocatedBit::alloc(cs.ns(|| format!("bit {}", i)), || {
+                let limbs = repr.get()?;
+                Ok((limbs.as_ref()[i / 64] >> (i % 64)) & 1 == 1)
+            })?;
+            lc += (coeff, bit.get_variable());
+            coeff.double_in_place();
+            bits.push(Boolean::from(bit));
+        }
+
+        lc = &self.variable - lc;
+        cs.enforce(|| "unpacking_constraint", |lc| lc, |lc| lc, |_| lc);
+
+        Ok(bits)
+    }
+
+    /// Enforces that `self` equals the constant `c`, folding `c` into the
+    /// single equality constraint instead of building a constant gadget.
+    pub fn enforce_equal_constant<CS: ConstraintSystem<F>>(
+        &self,
+        mut cs: CS,
+        c: F,
+    ) -> Result<(), SynthesisError> {
+        let one = CS::one();
+        cs.enforce(
+            || "enforce_equal_constant",
+            |lc| &self.variable + lc + (-c, one),
+            |lc| lc + one,
+            |lc| lc,
+        );
+        Ok(())
+    }
+
+    /// Outputs whether `self` equals the constant `c`.
+    pub fn is_eq_constant<CS: ConstraintSystem<F>>(
+        &self,
+        mut cs: CS,
+        c: F,
+    ) -> Result<Boolean, SynthesisError> {
+        self.sub_constant(cs.ns(|| "self - c"), &c)?
+            .is_zero(cs.ns(|| "is zero"))
+    }
+
+    /// Enforces that `self` is one of the constants in `set`, by enforcing
+    /// that the product of the differences `self - v` is zero. This costs
+    /// `set.len() - 1` constraints, or one for a single-element set. An empty
+    /// set is unsatisfiable.
+    pub fn enforce_in_set<CS: ConstraintSystem<F>>(
+        &self,
+        mut cs: CS,
+        set: &[F],
+    ) -> Result<(), SynthesisError> {
+        match set.split_last() {
+            None => Err(SynthesisError::Unsatisfiable),
+            Some((last, [])) => self.enforce_equal_constant(cs, *last),
+            Some((last, rest)) => {
+                let product = self.product_of_differences(cs.ns(|| "product"), rest)?;
+                let difference = self.sub_constant(cs.ns(|| "last difference"), last)?;
+                let zero = Self::zero(cs.ns(|| "zero"))?;
+                product.mul_equals(cs.ns(|| "product is zero"), &difference, &zero)
+            },
+        }
+    }
+
+    /// Outputs whether `self` is one of the constants in `set`, which is
+    /// always false for an empty set.
+    pub fn is_in_set<CS: ConstraintSystem<F>>(
+        &self,
+        mut cs: CS,
+        set: &[F],
+    ) -> Result<Boolean, SynthesisError> {
+        match set {
+            [] => Ok(Boolean::constant(false)),
+            [value] => self.is_eq_constant(cs, *value),
+            _ => self
+                .product_of_differences(cs.ns(|| "product"), set)?
+                .is_zero(cs.ns(|| "is zero")),
+        }
+    }
+
+    /// Computes the product of `self - v` over `v` in `values`, which must be
+    /// non-empty.
+    fn product_of_differences<CS: ConstraintSystem<F>>(
+        &self,
+        mut cs: CS,
+        values: &[F],
+    ) -> Result<Self, SynthesisError> {
+        let mut product = self.sub_constant(cs.ns(|| "difference 0"), &values[0])?;
+        for (i, value) in values.iter().enumerate().skip(1) {
+            let difference = self.sub_constant(cs.ns(|| format!("difference {}", i)), value)?;
+            product = product.mul(cs.ns(|| format!("multiply {}", i)), &difference)?;
+        }
+        Ok(product)
+    }
+
+    /// Outputs whether the canonical integer representative of `self` is odd.
+    pub fn is_odd<CS: ConstraintSystem<F>>(&self, cs: CS) -> Result<Boolean, SynthesisError> {
+        // `to_bits` returns the unique big-endian decomposition.
+        let bits = self.to_bits(cs)?;
+        Ok(bits[bits.len() - 1])
+    }
+
+    /// Outputs whether `self` is zero.
+    pub fn is_zero<CS: ConstraintSystem<F>>(&self, cs: CS) -> Result<Boolean, SynthesisError> {
+        self.is_zero_with_inverse(cs).map(|(is_zero, _)| is_zero)
+    }
+
+    /// Outputs the inverse of `self` if it is non-zero, and zero otherwise.
+    pub fn inverse_or_zero<CS: ConstraintSystem<F>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Self, SynthesisError> {
+        let (is_zero, inverse) = self.is_zero_with_inverse(cs.ns(|| "is zero"))?;
+
+        // inverse * is_zero = 0, which pins down the inverse of zero
+        let one = CS::one();
+        cs.enforce(
+            || "inverse of zero is zero",
+            |lc| &inverse.variable + lc,
+            |_| is_zero.lc(one, F::one()),
+            |lc| lc,
+        );
+
+        Ok(inverse)
+    }
+
+    /// Returns a bit that is set iff `self` is zero, together with a witness
+    /// that equals the inverse of `self` whenever `self` is non-zero.
+    fn is_zero_with_inverse<CS: ConstraintSystem<F>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<(Boolean, Self), SynthesisError> {
+        let is_zero_value = self.value.map(|v| v.is_zero());
+
+        let is_zero = Boolean::from(AllocatedBit::alloc(cs.ns(|| "is zero"), || {
+            is_zero_value.get()
+        })?);
+        let inverse = Self::alloc(cs.ns(|| "inverse"), || {
+            Ok(self.value.get()?.inverse().unwrap_or_else(F::zero))
+        })?;
+
+        // self * inverse = 1 - is_zero, so a non-zero `self` forces `is_zero`
+        // to be unset...
+        let one = CS::one();
+        cs.enforce(
+            || "inverse unless zero",
+            |lc| &self.variable + lc,
+            |lc| &inverse.variable + lc,
+            |_| is_zero.not().lc(one, F::one()),
+        );
+        // ...and self * is_zero = 0 only allows it to be set for a zero `self`.
+        cs.enforce(
+            || "zero if is_zero",
+            |lc| &self.variable + lc,
+            |_| is_zero.lc(one, F::one()),
+            |lc| lc,
+        );
+
+        Ok((is_zero, inverse))
+    }
+
+    /// Inverts every element of `values` with Montgomery's trick, returning
+    /// `DivisionByZero` if any of them is known to be zero.
+    ///
+    /// Only the product of all the values is inverted, with a single field
+    /// inversion and a single `inverse()` constraint; every individual inverse
+    /// is then recovered from it and the prefix products with two
+    /// multiplications. Together with the `values.len() - 1` multiplications
+    /// for the prefix products, this costs `3 * values.len() - 2` constraints.
+    pub fn batch_inverse<CS: ConstraintSystem<F>>(
+        mut cs: CS,
+        values: &[Self],
+    ) -> Result<Vec<Self>, SynthesisError> {
+        if values.iter().any(|v| v.value.map_or(false, |v| v.is_zero())) {
+            return Err(SynthesisError::DivisionByZero);
+        }
+        let (first, rest) = match values.split_first() {
+            Some(split) => split,
+            None => return Ok(Vec::new()),
+        };
+
+        // prefix_products[i] = values[0] * ... * values[i]
+        let mut prefix_products = Vec::with_capacity(values.len());
+        prefix_products.push(first.clone());
+        for (i, value) in rest.iter().enumerate() {
+            let product = prefix_products[i].mul(cs.ns(|| format!("product {}", i + 1)), value)?;
+            prefix_products.push(product);
+        }
+
+        // The product, and hence every value, must be non-zero to be inverted.
+        let mut acc = prefix_products[values.len() - 1].inverse(cs.ns(|| "invert product"))?;
+        let mut inverses = Vec::with_capacity(values.len());
+        for i in (1..values.len()).rev() {
+            // acc is the inverse of prefix_products[i].
+            let inverse = acc.mul(cs.ns(|| format!("inverse {}", i)), &prefix_products[i - 1])?;
+            acc = acc.mul(cs.ns(|| format!("strip {}", i)), &values[i])?;
+            inverses.push(inverse);
+        }
+        inverses.push(acc);
+        inverses.reverse();
+        Ok(inverses)
+    }
+
+    /// Selects `table[i]`, where `i` is the integer encoded by `index_bits` in
+    /// big-endian order, as `conditionally_select_power_of_two_vector` does.
+    /// Since the entries are constants, the selections by the least
+    /// significant bit are linear, so a witness index costs
+    /// `table.len() / 2 - 1` constraints; constant index bits cost nothing.
+    /// Returns `SynthesisError::InvalidArgument` unless `table.len()` is
+    /// `2^index_bits.len()`.
+    pub fn lookup<CS: ConstraintSystem<F>>(
+        mut cs: CS,
+        index_bits: &[Boolean],
+        table: &[F],
+    ) -> Result<Self, SynthesisError> {
+        if !table.len().is_power_of_two()
+            || table.len().trailing_zeros() as usize != index_bits.len()
+        {
+            return Err(SynthesisError::InvalidArgument);
+        }
+        let (lsb, high_bits) = match index_bits.split_last() {
+            Some(split) => split,
+            None => return Self::alloc_constant(cs.ns(|| "constant"), table[0]),
+        };
+
+        // pair[0] + lsb * (pair[1] - pair[0])
+        let one = CS::one();
+        let layer = table
+            .chunks(2)
+            .map(|pair| FpGadget {
+                value: lsb
+                    .get_value()
+                    .map(|bit| if bit { pair[1] } else { pair[0] }),
+                variable: (lsb.lc(one, pair[1] - &pair[0]) + (pair[0], one)).into(),
+            })
+            .collect::<Vec<_>>();
+        conditionally_select_power_of_two_vector(cs.ns(|| "select"), high_bits, &layer)
+    }
+
+    /// Computes the inner product of `a` and `b` by accumulating with
+    /// `mul_add`, at one constraint per term. Slices of different lengths are
+    /// rejected with `SynthesisError::InvalidArgument`.
+    pub fn inner_product<CS: ConstraintSystem<F>>(
+        mut cs: CS,
+        a: &[Self],
+        b: &[Self],
+    ) -> Result<Self, SynthesisError> {
+        if a.len() != b.len() {
+            return Err(SynthesisError::InvalidArgument);
+        }
+        let mut result = Self::zero(cs.ns(|| "zero"))?;
+        for (i, (a, b)) in a.iter().zip(b).enumerate() {
+            result = a.mul_add(cs.ns(|| format!("term {}", i)), b, &result)?;
         }
+        Ok(result)
+    }
+}
+
+impl<F: PrimeField + SquareRootField> FpGadget<F> {
+    /// Outputs whether `self` is a quadratic residue; zero counts as one.
+    ///
+    /// The Legendre symbol is computed outside the circuit, and the resulting
+    /// bit `b` is justified by a witness `s` with `s^2 = self` if `b` is set,
+    /// and `s^2 = self * g` otherwise, where the multiplicative generator `g`
+    /// is a non-residue. Clearing `b` also requires `self` to be invertible, so
+    /// zero can only be reported as a residue.
+    pub fn is_quadratic_residue<CS: ConstraintSystem<F>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Boolean, SynthesisError> {
+        let non_residue = F::multiplicative_generator();
+        let is_qr_value = self.value.map(|v| !v.legendre().is_qnr());
+
+        let is_qr = Boolean::from(AllocatedBit::alloc(cs.ns(|| "is residue"), || {
+            is_qr_value.get()
+        })?);
+        let root = Self::alloc(cs.ns(|| "root"), || {
+            let value = self.value.get()?;
+            let root = if is_qr_value.get()? {
+                value.sqrt()
+            } else {
+                (value * &non_residue).sqrt()
+            };
+            root.get()
+        })?;
+
+        // square = b ? self : self * g
+        let zero = Self::zero(cs.ns(|| "zero"))?;
+        let residue_part =
+            Self::conditionally_select(cs.ns(|| "select residue"), &is_qr, self, &zero)?;
+        let square = self
+            .sub(cs.ns(|| "non-residue part"), &residue_part)?
+            .mul_by_constant(cs.ns(|| "scale by non-residue"), &non_residue)?
+            .add(cs.ns(|| "add residue part"), &residue_part)?;
+        root.square_equals(cs.ns(|| "check root"), &square)?;
+
+        // self * inv = 1 - b, so that b = 0 implies self != 0
+        let inv = Self::alloc(cs.ns(|| "inverse if non-residue"), || {
+            if is_qr_value.get()? {
+                Ok(F::zero())
+            } else {
+                self.value.get()?.inverse().get()
+            }
+        })?;
+        let one = CS::one();
+        cs.enforce(
+            || "check non-zero if non-residue",
+            |lc| &self.variable + lc,
+            |lc| &inv.variable + lc,
+            |_| is_qr.not().lc(one, F::one()),
+        );
+
+        Ok(is_qr)
     }
 }
 
@@ -214,6 +651,30 @@ impl<F: PrimeField> FieldGadget<F, F> for FpGadget<F> {
         Ok(self)
     }
 
+    #[inline]
+    fn sub_constant<CS: ConstraintSystem<F>>(
+        &self,
+        _cs: CS,
+        other: &F,
+    ) -> Result<Self, SynthesisError> {
+        let value = self.value.map(|val| val - other);
+        Ok(FpGadget {
+            value,
+            variable: self.variable.clone() + (-*other, CS::one()),
+        })
+    }
+
+    #[inline]
+    fn sub_constant_in_place<CS: ConstraintSystem<F>>(
+        &mut self,
+        _cs: CS,
+        other: &F,
+    ) -> Result<&mut Self, SynthesisError> {
+        self.value.as_mut().map(|val| *val -= other);
+        self.variable += (-*other, CS::one());
+        Ok(self)
+    }
+
     #[inline]
     fn mul_by_constant<CS: ConstraintSystem<F>>(
         &self,
@@ -270,6 +731,35 @@ impl<F: PrimeField> FieldGadget<F, F> for FpGadget<F> {
         Ok(self)
     }
 
+    /// Enforces `self * other = result - addend` for a newly allocated
+    /// `result`. This is the same single constraint as `mul` followed by
+    /// `add`, but the output is one variable rather than a linear combination,
+    /// so it does not grow when results are accumulated, as in
+    /// `inner_product`.
+    fn mul_add<CS: ConstraintSystem<F>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        addend: &Self,
+    ) -> Result<Self, SynthesisError> {
+        if self.is_constant() || other.is_constant() {
+            return self
+                .mul(cs.ns(|| "mul"), other)?
+                .add(cs.ns(|| "add"), addend);
+        }
+
+        let result = Self::alloc(cs.ns(|| "mul_add"), || {
+            Ok(self.value.get()? * &other.value.get()? + &addend.value.get()?)
+        })?;
+        cs.enforce(
+            || "mul_add_constraint",
+            |lc| &self.variable + lc,
+            |lc| &other.variable + lc,
+            |lc| (&result.variable - &addend.variable) + lc,
+        );
+        Ok(result)
+    }
+
     fn mul_equals<CS: ConstraintSystem<F>>(
         &self,
         mut cs: CS,
@@ -330,7 +820,20 @@ impl<F: PrimeField> ConditionalEqGadget<F> for FpGadget<F> {
         other: &Self,
         condition: &Boolean,
     ) -> Result<(), SynthesisError> {
+        if let Boolean::Constant(false) = condition {
+            return Ok(());
+        }
         let difference = self.sub(cs.ns(|| "difference"), other)?;
+        // For constant operands the check is decided now: equal constants
+        // need no constraint, and unequal ones are unsatisfiable unless a
+        // variable condition is false.
+        if difference.is_constant() {
+            match (difference.value, condition) {
+                (Some(d), _) if d.is_zero() => return Ok(()),
+                (Some(_), Boolean::Constant(true)) => return Err(SynthesisError::Unsatisfiable),
+                _ => {},
+            }
+        }
         let one = CS::one();
         let one_const = F::one();
         cs.enforce(
@@ -427,7 +930,10 @@ impl<F: PrimeField> ToBitsGadget<F> for FpGadget<F> {
 
 impl<F: PrimeField> ToBytesGadget<F> for FpGadget<F> {
     /// Outputs the unique byte decomposition of `self` in *little-endian*
-    /// form.
+    /// form, matching `algebra`'s `ToBytes`. The first
+    /// `(MODULUS_BITS + 7) / 8` bytes are also the `CanonicalSerialize`
+    /// output of the value; any remaining bytes pad it to whole limbs and are
+    /// zero.
     fn to_bytes<CS: ConstraintSystem<F>>(&self, mut cs: CS) -> Result<Vec<UInt8>, SynthesisError> {
         let bytes = self.to_non_unique_bytes(&mut cs)?;
         Boolean::enforce_in_field::<_, _, F>(
@@ -500,9 +1006,7 @@ impl<F: PrimeField> CondSelectGadget<F> for FpGadget<F> {
             }
         } else {
             let result = Self::alloc(cs.ns(|| ""), || {
-                cond.get_value()
-                    .and_then(|cond| if cond { true_value } else { false_value }.get_value())
-                    .get()
+                select_value(cond.get_value(), true_value.value, false_value.value).get()
             })?;
             // a = self; b = other; c = cond;
             //
@@ -677,3 +1181,611 @@ impl<F: PrimeField> AllocGadget<F, F> for FpGadget<F> {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        alloc::AllocGadget,
+        bits::{boolean::Boolean, uint8::UInt8, ToBitsGadget, ToBytesGadget},
+        eq::{ConditionalEqGadget, EqGadget},
+        fields::{fp::FpGadget, FieldGadget},
+        test_constraint_counter::{assert_constraint_count, constraint_delta},
+        test_constraint_system::TestConstraintSystem,
+        Vec,
+    };
+    use algebra::{
+        bls12_381::Fr, CanonicalSerialize, FftField, Field, One, PrimeField, UniformRand, Zero,
+    };
+    use r1cs_core::{ConstraintSystem, SynthesisError};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_mul_constraint_count() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let a = FpGadget::alloc(cs.ns(|| "a"), || Ok(Fr::rand(&mut rng))).unwrap();
+        let b = FpGadget::alloc(cs.ns(|| "b"), || Ok(Fr::rand(&mut rng))).unwrap();
+        assert_constraint_count(&cs, 0);
+
+        let (product, num_constraints) =
+            constraint_delta(&mut cs, |cs| a.mul(cs.ns(|| "a * b"), &b).unwrap());
+        assert_eq!(num_constraints, 1);
+        assert_eq!(product.value, Some(a.value.unwrap() * &b.value.unwrap()));
+        assert_constraint_count(&cs, 1);
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_inner_product() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let mut alloc_vec = |cs: &mut TestConstraintSystem<Fr>, name: &str| {
+            (0..8)
+                .map(|i| {
+                    FpGadget::alloc(cs.ns(|| format!("{} {}", name, i)), || {
+                        Ok(Fr::rand(&mut rng))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+        let a = alloc_vec(&mut cs, "a");
+        let b = alloc_vec(&mut cs, "b");
+        let expected = a
+            .iter()
+            .zip(&b)
+            .map(|(a, b)| a.value.unwrap() * &b.value.unwrap())
+            .sum::<Fr>();
+
+        let (naive, naive_constraints) = constraint_delta(&mut cs, |cs| {
+            let mut sum = FpGadget::zero(cs.ns(|| "naive zero")).unwrap();
+            for (i, (a, b)) in a.iter().zip(&b).enumerate() {
+                let product = a.mul(cs.ns(|| format!("naive mul {}", i)), b).unwrap();
+                sum = sum
+                    .add(cs.ns(|| format!("naive add {}", i)), &product)
+                    .unwrap();
+            }
+            sum
+        });
+        let (result, constraints) = constraint_delta(&mut cs, |cs| {
+            FpGadget::inner_product(cs.ns(|| "inner product"), &a, &b).unwrap()
+        });
+
+        assert_eq!(naive.value, Some(expected));
+        assert_eq!(result.value, Some(expected));
+        assert_eq!(constraints, a.len());
+        assert_eq!(constraints, naive_constraints);
+        assert!(cs.is_satisfied());
+
+        // The result is pinned down by the last constraint.
+        cs.set("inner product/term 7/mul_add/alloc", Fr::zero());
+        assert!(!cs.is_satisfied());
+
+        match FpGadget::inner_product(cs.ns(|| "mismatched lengths"), &a, &b[1..]) {
+            Err(SynthesisError::InvalidArgument) => {},
+            _ => panic!("expected InvalidArgument for slices of different lengths"),
+        }
+    }
+
+    #[test]
+    fn test_debug_shows_assignment() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let value = Fr::rand(&mut rng);
+        let a = FpGadget::alloc(cs.ns(|| "a"), || Ok(value)).unwrap();
+
+        let debug = format!("{:?}", a);
+        assert!(debug.contains(&format!("{:?}", value)));
+    }
+
+    #[test]
+    fn test_batch_inverse() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let values = (0..10)
+            .map(|i| FpGadget::alloc(cs.ns(|| format!("value {}", i)), || Ok(Fr::rand(&mut rng))))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let num_constraints = cs.num_constraints();
+
+        let batched = FpGadget::batch_inverse(cs.ns(|| "batch inverse"), &values).unwrap();
+        assert_eq!(cs.num_constraints(), num_constraints + 3 * values.len() - 2);
+
+        for (i, (value, batched)) in values.iter().zip(&batched).enumerate() {
+            let inverse = value.inverse(cs.ns(|| format!("inverse {}", i))).unwrap();
+            assert_eq!(batched.value, inverse.value);
+            assert_eq!(batched.value, value.value.unwrap().inverse());
+        }
+        assert!(cs.is_satisfied());
+
+        // A wrong inverse must not satisfy the constraint system
+        cs.set("batch inverse/inverse 3/mul", Fr::zero());
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_lookup() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let table = (0..16).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+
+        for index in 0..16 {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let index_bits = (0..4)
+                .rev()
+                .map(|i| {
+                    Boolean::alloc(cs.ns(|| format!("index bit {}", i)), || {
+                        Ok((index >> i) & 1 == 1)
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            let (result, num_constraints) = constraint_delta(&mut cs, |cs| {
+                FpGadget::lookup(cs.ns(|| "lookup"), &index_bits, &table).unwrap()
+            });
+            assert_eq!(result.value, Some(table[index]));
+            assert_eq!(num_constraints, 7);
+            assert!(cs.is_satisfied());
+
+            // Constant index bits select without any constraints.
+            let constant_bits = index_bits
+                .iter()
+                .map(|bit| Boolean::constant(bit.get_value().unwrap()))
+                .collect::<Vec<_>>();
+            let (result, num_constraints) = constraint_delta(&mut cs, |cs| {
+                FpGadget::lookup(cs.ns(|| "constant lookup"), &constant_bits, &table).unwrap()
+            });
+            assert_eq!(result.value, Some(table[index]));
+            assert_eq!(num_constraints, 0);
+        }
+
+        // The table must have exactly one entry per index.
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let index_bits = vec![Boolean::constant(false); 3];
+        match FpGadget::lookup(cs.ns(|| "short table"), &index_bits, &table) {
+            Err(SynthesisError::InvalidArgument) => {},
+            _ => panic!("expected InvalidArgument"),
+        }
+    }
+
+    #[test]
+    fn test_in_set() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let set = (0..5).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let outsider = Fr::rand(&mut rng);
+
+        for (i, (value, expected)) in [(set[0], true), (set[4], true), (outsider, false)]
+            .iter()
+            .enumerate()
+        {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let a = FpGadget::alloc(cs.ns(|| "a"), || Ok(*value)).unwrap();
+
+            let is_in_set = a.is_in_set(cs.ns(|| "is in set"), &set).unwrap();
+            assert_eq!(is_in_set.get_value(), Some(*expected), "value {}", i);
+            assert!(cs.is_satisfied());
+
+            let (_, num_constraints) = constraint_delta(&mut cs, |cs| {
+                a.enforce_in_set(cs.ns(|| "enforce in set"), &set).unwrap()
+            });
+            assert_eq!(num_constraints, set.len() - 1);
+            assert_eq!(cs.is_satisfied(), *expected, "value {}", i);
+        }
+    }
+
+    #[test]
+    fn test_in_set_edge_cases() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let value = Fr::rand(&mut rng);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let a = FpGadget::alloc(cs.ns(|| "a"), || Ok(value)).unwrap();
+
+        // Nothing is in the empty set.
+        let is_in_empty = a.is_in_set(cs.ns(|| "is in empty set"), &[]).unwrap();
+        assert_eq!(is_in_empty.get_value(), Some(false));
+        match a.enforce_in_set(cs.ns(|| "enforce in empty set"), &[]) {
+            Err(SynthesisError::Unsatisfiable) => {},
+            _ => panic!("expected Unsatisfiable"),
+        }
+
+        // A single-element set is an equality check.
+        let is_in_single = a.is_in_set(cs.ns(|| "is in single"), &[value]).unwrap();
+        assert_eq!(is_in_single.get_value(), Some(true));
+        let (_, num_constraints) = constraint_delta(&mut cs, |cs| {
+            a.enforce_in_set(cs.ns(|| "enforce in single"), &[value])
+                .unwrap()
+        });
+        assert_eq!(num_constraints, 1);
+        assert!(cs.is_satisfied());
+
+        let other = value + &Fr::one();
+        let is_in_other = a.is_in_set(cs.ns(|| "is in other"), &[other]).unwrap();
+        assert_eq!(is_in_other.get_value(), Some(false));
+        assert!(cs.is_satisfied());
+        a.enforce_in_set(cs.ns(|| "enforce in other"), &[other])
+            .unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_is_quadratic_residue() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+        let mut elements = vec![
+            Fr::zero(),
+            Fr::one(),
+            Fr::from(2u64),
+            Fr::from(3u64),
+            Fr::from(4u64),
+            Fr::from(5u64),
+            -Fr::one(),
+            Fr::multiplicative_generator(),
+        ];
+        elements.extend((0..10).map(|_| Fr::rand(&mut rng)));
+
+        for (i, element) in elements.into_iter().enumerate() {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+
+            // Euler's criterion
+            let power = element.pow(Fr::modulus_minus_one_div_two());
+            let expected = power.is_zero() || power.is_one();
+
+            let a = FpGadget::alloc(cs.ns(|| "a"), || Ok(element)).unwrap();
+            let is_qr = a.is_quadratic_residue(cs.ns(|| "is qr")).unwrap();
+
+            assert_eq!(is_qr.get_value(), Some(expected), "element {}", i);
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 4);
+
+            // Claiming the opposite answer must not satisfy the constraints
+            let flipped = if expected { Fr::zero() } else { Fr::one() };
+            cs.set("is qr/is residue/boolean", flipped);
+            assert!(!cs.is_satisfied(), "element {}", i);
+        }
+    }
+
+    #[test]
+    fn test_inverse_or_zero() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+        let mut elements = vec![Fr::zero(), Fr::one(), -Fr::one(), Fr::from(2u64)];
+        elements.extend((0..5).map(|_| Fr::rand(&mut rng)));
+
+        for element in elements {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+
+            let a = FpGadget::alloc(cs.ns(|| "a"), || Ok(element)).unwrap();
+            let is_zero = a.is_zero(cs.ns(|| "is zero")).unwrap();
+            let inverse = a.inverse_or_zero(cs.ns(|| "inverse or zero")).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(is_zero.get_value(), Some(element.is_zero()));
+            assert_eq!(inverse.value, Some(element.inverse().unwrap_or_else(Fr::zero)));
+
+            // Neither the zero bit nor the inverse can be forged
+            let (correct, flipped) = if element.is_zero() {
+                (Fr::one(), Fr::zero())
+            } else {
+                (Fr::zero(), Fr::one())
+            };
+            cs.set("is zero/is zero/boolean", flipped);
+            assert!(!cs.is_satisfied());
+            cs.set("is zero/is zero/boolean", correct);
+            assert!(cs.is_satisfied());
+
+            cs.set("inverse or zero/is zero/inverse/alloc", Fr::from(7u64));
+            assert!(!cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn test_from_bits() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+        for i in 0..10 {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+
+            let x = Fr::rand(&mut rng);
+            let x_gadget = FpGadget::alloc(cs.ns(|| "x"), || Ok(x)).unwrap();
+            let bits = x_gadget.to_bits(cs.ns(|| "to bits")).unwrap();
+
+            let from_be = FpGadget::from_bits_be(cs.ns(|| "from bits be"), &bits).unwrap();
+            let bits_le = bits.iter().rev().cloned().collect::<Vec<_>>();
+            let from_le = FpGadget::from_bits_le(cs.ns(|| "from bits le"), &bits_le).unwrap();
+
+            assert_eq!(from_be.value, Some(x), "element {}", i);
+            assert_eq!(from_le.value, Some(x), "element {}", i);
+            from_be
+                .enforce_equal(cs.ns(|| "be round trip"), &x_gadget)
+                .unwrap();
+            from_le
+                .enforce_equal(cs.ns(|| "le round trip"), &x_gadget)
+                .unwrap();
+            assert!(cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn test_from_bits_range_check() {
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        // Short inputs can never overflow, so they are packed for free
+        let bits = (0..8)
+            .map(|i| Boolean::alloc(cs.ns(|| format!("bit {}", i)), || Ok(i % 3 == 0)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let num_constraints = cs.num_constraints();
+        let packed = FpGadget::from_bits_le(cs.ns(|| "short"), &bits).unwrap();
+        assert_eq!(cs.num_constraints(), num_constraints);
+        assert_eq!(packed.value, Some(Fr::from(0b0100_1001u64)));
+        assert!(cs.is_satisfied());
+
+        // All ones is larger than the modulus
+        let bits = (0..Fr::size_in_bits())
+            .map(|i| Boolean::alloc(cs.ns(|| format!("long bit {}", i)), || Ok(true)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let _ = FpGadget::from_bits_be(cs.ns(|| "long"), &bits).unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_to_bits_le_enforce_length() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let x = u64::rand(&mut rng);
+        let x_gadget = FpGadget::alloc(cs.ns(|| "x"), || Ok(Fr::from(x))).unwrap();
+        let (bits, short_cost) = constraint_delta(&mut cs, |cs| {
+            x_gadget
+                .to_bits_le_enforce_length(cs.ns(|| "short"), 64)
+                .unwrap()
+        });
+        let (_, full_cost) =
+            constraint_delta(&mut cs, |cs| x_gadget.to_bits(cs.ns(|| "full")).unwrap());
+        let expected = (0..64).map(|i| (x >> i) & 1 == 1).collect::<Vec<_>>();
+        assert_eq!(Boolean::get_values(&bits), Some(expected.clone()));
+        assert_eq!(short_cost, 65);
+        assert!(short_cost < full_cost);
+        assert!(cs.is_satisfied());
+
+        // Lengths of at least MODULUS_BITS fall back to the full decomposition.
+        let long = x_gadget
+            .to_bits_le_enforce_length(cs.ns(|| "long"), 300)
+            .unwrap();
+        let long_values = Boolean::get_values(&long).unwrap();
+        assert_eq!(long_values.len(), 300);
+        assert_eq!(long_values[..64], expected[..]);
+        assert!(long_values[64..].iter().all(|b| !b));
+        assert!(cs.is_satisfied());
+
+        // 2^64 does not fit in 64 bits.
+        let y = Fr::from(u64::max_value()) + &Fr::one();
+        let y_gadget = FpGadget::alloc(cs.ns(|| "y"), || Ok(y)).unwrap();
+        let _ = y_gadget
+            .to_bits_le_enforce_length(cs.ns(|| "overflow"), 64)
+            .unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_to_bytes_matches_serialization() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        for i in 0..10 {
+            let x = Fr::rand(&mut rng);
+            let mut native_bytes = vec![];
+            x.serialize_uncompressed(&mut native_bytes).unwrap();
+
+            let x_gadget = FpGadget::alloc(cs.ns(|| format!("x {}", i)), || Ok(x)).unwrap();
+            let bytes = x_gadget
+                .to_bytes(cs.ns(|| format!("to bytes {}", i)))
+                .unwrap();
+            let bytes = UInt8::get_values(&bytes).unwrap();
+            assert_eq!(bytes[..native_bytes.len()], native_bytes[..]);
+            assert!(bytes[native_bytes.len()..].iter().all(|b| *b == 0));
+        }
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_eq_constant() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+        let x = Fr::rand(&mut rng);
+        let y = Fr::rand(&mut rng);
+
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let x_gadget = FpGadget::alloc(cs.ns(|| "x"), || Ok(x)).unwrap();
+
+        let num_constraints = cs.num_constraints();
+        x_gadget
+            .enforce_equal_constant(cs.ns(|| "folded"), x)
+            .unwrap();
+        let folded_cost = cs.num_constraints() - num_constraints;
+
+        let num_constraints = cs.num_constraints();
+        let x_constant = FpGadget::alloc_constant(cs.ns(|| "x constant"), x).unwrap();
+        x_gadget
+            .enforce_equal(cs.ns(|| "general"), &x_constant)
+            .unwrap();
+        let general_cost = cs.num_constraints() - num_constraints;
+
+        assert_eq!(folded_cost, 1);
+        assert!(folded_cost <= general_cost);
+        assert!(cs.is_satisfied());
+
+        let is_x = x_gadget.is_eq_constant(cs.ns(|| "is x"), x).unwrap();
+        let is_y = x_gadget.is_eq_constant(cs.ns(|| "is y"), y).unwrap();
+        assert_eq!(is_x.get_value(), Some(true));
+        assert_eq!(is_y.get_value(), Some(false));
+        assert!(cs.is_satisfied());
+
+        x_gadget
+            .enforce_equal_constant(cs.ns(|| "wrong constant"), y)
+            .unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_enforce_equal_constants() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+        let x = Fr::rand(&mut rng);
+        let y = Fr::rand(&mut rng);
+
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let x_constant = FpGadget::alloc_constant(cs.ns(|| "x"), x).unwrap();
+        let x_sum = FpGadget::alloc_constant(cs.ns(|| "x - y"), x - &y)
+            .unwrap()
+            .add_constant(cs.ns(|| "x - y + y"), &y)
+            .unwrap();
+        let y_constant = FpGadget::alloc_constant(cs.ns(|| "y"), y).unwrap();
+
+        // Equal constants, and any constants under a false condition, need
+        // no constraint.
+        x_constant
+            .enforce_equal(cs.ns(|| "x == x - y + y"), &x_sum)
+            .unwrap();
+        x_constant
+            .conditional_enforce_equal(cs.ns(|| "skipped"), &y_constant, &Boolean::constant(false))
+            .unwrap();
+        assert_eq!(cs.num_constraints(), 0);
+
+        // Unequal constants fail at synthesis time.
+        match x_constant.enforce_equal(cs.ns(|| "x == y"), &y_constant) {
+            Err(SynthesisError::Unsatisfiable) => {},
+            _ => panic!("expected Unsatisfiable"),
+        }
+        assert_eq!(cs.num_constraints(), 0);
+
+        // Under a variable condition, they force the condition to be false.
+        for (i, cond_value) in [false, true].iter().enumerate() {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let x_constant = FpGadget::alloc_constant(cs.ns(|| "x"), x).unwrap();
+            let y_constant = FpGadget::alloc_constant(cs.ns(|| "y"), y).unwrap();
+            let cond = Boolean::alloc(cs.ns(|| format!("cond {}", i)), || Ok(*cond_value)).unwrap();
+            x_constant
+                .conditional_enforce_equal(cs.ns(|| "x == y if cond"), &y_constant, &cond)
+                .unwrap();
+            assert_eq!(cs.is_satisfied(), !cond_value);
+        }
+    }
+
+    #[test]
+    fn test_linear_scalings() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let x = Fr::rand(&mut rng);
+        let x_gadget = FpGadget::alloc(cs.ns(|| "x"), || Ok(x)).unwrap();
+
+        let quadrupled = x_gadget
+            .double(cs.ns(|| "double"))
+            .unwrap()
+            .double(cs.ns(|| "double again"))
+            .unwrap();
+        let tripled = x_gadget.triple(cs.ns(|| "triple")).unwrap();
+        let mut negated = x_gadget.clone();
+        negated.negate_in_place(cs.ns(|| "negate")).unwrap();
+        assert_eq!(cs.num_constraints(), 0);
+
+        assert_eq!(quadrupled.value, Some(x * &Fr::from(4u64)));
+        assert_eq!(tripled.value, Some(x * &Fr::from(3u64)));
+        assert_eq!(negated.value, Some(-x));
+
+        quadrupled
+            .enforce_equal_constant(cs.ns(|| "check quadrupled"), x * &Fr::from(4u64))
+            .unwrap();
+        tripled
+            .enforce_equal_constant(cs.ns(|| "check tripled"), x * &Fr::from(3u64))
+            .unwrap();
+        negated
+            .enforce_equal_constant(cs.ns(|| "check negated"), -x)
+            .unwrap();
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_batch_inverse_of_zero() {
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let values = [Fr::from(3u64), Fr::zero(), Fr::from(5u64)]
+            .iter()
+            .enumerate()
+            .map(|(i, v)| FpGadget::alloc(cs.ns(|| format!("value {}", i)), || Ok(*v)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        match FpGadget::batch_inverse(cs.ns(|| "batch inverse"), &values) {
+            Err(SynthesisError::DivisionByZero) => {}
+            _ => panic!("expected DivisionByZero"),
+        }
+    }
+
+    #[test]
+    fn test_sub_constant_allocates_nothing() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let x = Fr::rand(&mut rng);
+        let c = Fr::rand(&mut rng);
+        let x_gadget = FpGadget::alloc(cs.ns(|| "x"), || Ok(x)).unwrap();
+        let num_variables = cs.num_aux_variables();
+
+        let folded = x_gadget.sub_constant(cs.ns(|| "x - c"), &c).unwrap();
+        let mut folded_in_place = x_gadget.clone();
+        folded_in_place
+            .sub_constant_in_place(cs.ns(|| "x -= c"), &c)
+            .unwrap();
+        assert_eq!(cs.num_aux_variables(), num_variables);
+        assert_eq!(cs.num_constraints(), 0);
+
+        let c_gadget = FpGadget::from(cs.ns(|| "c"), &c);
+        let allocated = x_gadget.sub(cs.ns(|| "x - c gadget"), &c_gadget).unwrap();
+        assert_eq!(cs.num_aux_variables(), num_variables + 1);
+
+        assert_eq!(folded.value, Some(x - &c));
+        assert_eq!(folded.value, allocated.value);
+        assert_eq!(folded_in_place.value, allocated.value);
+        folded
+            .enforce_equal(cs.ns(|| "check folded"), &allocated)
+            .unwrap();
+        folded_in_place
+            .enforce_equal(cs.ns(|| "check folded in place"), &allocated)
+            .unwrap();
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_mul_by_constant_constraint_count() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let x = FpGadget::alloc(cs.ns(|| "x"), || Ok(Fr::rand(&mut rng))).unwrap();
+        let c = Fr::rand(&mut rng);
+
+        let (scaled, num_constraints) = constraint_delta(&mut cs, |cs| {
+            x.mul_by_constant(cs.ns(|| "x * c"), &c).unwrap()
+        });
+        assert_eq!(num_constraints, 0);
+
+        let (multiplied, num_constraints) = constraint_delta(&mut cs, |cs| {
+            let c_gadget = FpGadget::from(cs.ns(|| "c"), &c);
+            x.mul(cs.ns(|| "x * c gadget"), &c_gadget).unwrap()
+        });
+        assert_eq!(num_constraints, FpGadget::<Fr>::cost_of_mul());
+
+        assert_eq!(scaled.value, Some(x.value.unwrap() * &c));
+        assert_eq!(scaled.value, multiplied.value);
+        scaled
+            .enforce_equal(cs.ns(|| "check scaled"), &multiplied)
+            .unwrap();
+        assert!(cs.is_satisfied());
+    }
+}
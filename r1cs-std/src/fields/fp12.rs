@@ -23,6 +23,12 @@ type Fp6GadgetVariable<P, ConstraintF> = <Fp6Gadget<P, ConstraintF> as FieldGadg
     ConstraintF,
 >>::Variable;
 
+/// A gadget for `Fp12`, represented as `c0 + c1 * w` over `Fp6`.
+///
+/// The base-field coefficients are native variables of the constraint system,
+/// so there is no modular reduction to defer between multiplications: every
+/// `Fp` product inside `mul` is already exactly one constraint, and additions
+/// and multiplications by constants are free linear combinations.
 #[derive(Derivative)]
 #[derivative(Debug(bound = "ConstraintF: PrimeField"))]
 #[must_use]
@@ -72,9 +78,9 @@ where
         }
     }
 
-    /// Multiply by quadratic nonresidue v.
+    /// Multiply by quadratic nonresidue v. This costs no constraints.
     #[inline]
-    pub(crate) fn mul_fp6_by_nonresidue<CS: ConstraintSystem<ConstraintF>>(
+    pub fn mul_fp6_by_nonresidue<CS: ConstraintSystem<ConstraintF>>(
         cs: CS,
         fe: &Fp6Gadget<P, ConstraintF>,
     ) -> Result<Fp6Gadget<P, ConstraintF>, SynthesisError> {
@@ -84,6 +90,19 @@ where
         Ok(Fp6Gadget::<P, ConstraintF>::new(new_c0, new_c1, new_c2))
     }
 
+    /// Multiply a Fp12Gadget by a Fp6Gadget, using 2 Fp6 multiplications
+    /// instead of the 3 of a full multiplication.
+    #[inline]
+    pub fn mul_by_base_field<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        fe: &Fp6Gadget<P, ConstraintF>,
+    ) -> Result<Self, SynthesisError> {
+        let c0 = self.c0.mul(cs.ns(|| "c0"), fe)?;
+        let c1 = self.c1.mul(cs.ns(|| "c1"), fe)?;
+        Ok(Self::new(c0, c1))
+    }
+
     #[inline]
     pub fn conjugate_in_place<CS: ConstraintSystem<ConstraintF>>(
         &mut self,
@@ -289,6 +308,28 @@ where
         }
         Ok(res)
     }
+
+    /// Computes `self^exp`, where `exp` is given by its big-endian `bits`, like
+    /// `FieldGadget::pow` but squaring with `cyclotomic_square`. `self` must be
+    /// in the cyclotomic subgroup, e.g. an output of a pairing.
+    pub fn cyclotomic_pow<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        bits: &[Boolean],
+    ) -> Result<Self, SynthesisError> {
+        let mut res = Self::one(cs.ns(|| "Alloc result"))?;
+        for (i, bit) in bits.iter().enumerate() {
+            res = res.cyclotomic_square(cs.ns(|| format!("Double {}", i)))?;
+            let tmp = res.mul(cs.ns(|| format!("Add {}-th base power", i)), self)?;
+            res = Self::conditionally_select(
+                cs.ns(|| format!("Conditional Select {}", i)),
+                bit,
+                &tmp,
+                &res,
+            )?;
+        }
+        Ok(res)
+    }
 }
 
 impl<P, ConstraintF: PrimeField> FieldGadget<Fp12<P>, ConstraintF> for Fp12Gadget<P, ConstraintF>
@@ -623,7 +664,9 @@ where
     }
 
     fn cost_of_mul_equals() -> usize {
-        Self::cost_of_mul()
+        // One product `v1` and two product checks over Fp6.
+        Fp6Gadget::<P, ConstraintF>::cost_of_mul()
+            + 2 * Fp6Gadget::<P, ConstraintF>::cost_of_mul_equals()
     }
 }
 
@@ -77,6 +77,19 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> Fp2Gadget<P, C
         result.mul_by_fp_constant_in_place(cs, fe)?;
         Ok(result)
     }
+
+    /// Multiply a Fp2Gadget by a FpGadget, using 2 constraints instead of the
+    /// 3 of a full multiplication.
+    #[inline]
+    pub fn mul_by_base_field<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        fe: &FpGadget<ConstraintF>,
+    ) -> Result<Self, SynthesisError> {
+        let c0 = self.c0.mul(cs.ns(|| "c0"), fe)?;
+        let c1 = self.c1.mul(cs.ns(|| "c1"), fe)?;
+        Ok(Self::new(c0, c1))
+    }
 }
 
 impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> FieldGadget<Fp2<P>, ConstraintF>
@@ -519,6 +532,9 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> ToBitsGadget<C
 impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> ToBytesGadget<ConstraintF>
     for Fp2Gadget<P, ConstraintF>
 {
+    /// Outputs the bytes of `c0` followed by those of `c1`, as in `algebra`'s
+    /// `ToBytes` and `CanonicalSerialize`; see `FpGadget::to_bytes` for how
+    /// the two differ.
     fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
         &self,
         mut cs: CS,
@@ -11,6 +11,7 @@ pub mod fp3;
 pub mod fp4;
 pub mod fp6_2over3;
 pub mod fp6_3over2;
+pub mod nonnative;
 
 use crate::fields::fp::FpGadget;
 pub trait ToConstraintFieldGadget<ConstraintF: PrimeField> {
@@ -119,6 +120,17 @@ pub trait FieldGadget<F: Field, ConstraintF: Field>:
         Ok(self)
     }
 
+    /// Computes `self * other + addend`.
+    fn mul_add<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        addend: &Self,
+    ) -> Result<Self, SynthesisError> {
+        self.mul(cs.ns(|| "mul"), other)?
+            .add(cs.ns(|| "add"), addend)
+    }
+
     fn square<CS: ConstraintSystem<ConstraintF>>(&self, cs: CS) -> Result<Self, SynthesisError> {
         self.mul(cs, &self)
     }
@@ -181,6 +193,10 @@ pub trait FieldGadget<F: Field, ConstraintF: Field>:
         self.add_constant_in_place(cs, &(-(*other)))
     }
 
+    /// Multiplies `self` by a constant. Prefer this over `mul` with an
+    /// allocated constant: for `FpGadget` it only rescales the linear
+    /// combination, and extension fields reduce it to such rescalings of
+    /// their coefficients, so it costs no multiplication constraints.
     fn mul_by_constant<CS: ConstraintSystem<ConstraintF>>(
         &self,
         _: CS,
@@ -196,6 +212,10 @@ pub trait FieldGadget<F: Field, ConstraintF: Field>:
         Ok(self)
     }
 
+    /// Witnesses the inverse and checks its product with `self`. For
+    /// extension fields this single product check is cheaper than the
+    /// native reduction to a base field inversion via the norm, which needs
+    /// the same check in the base field plus several extension products.
     fn inverse<CS: ConstraintSystem<ConstraintF>>(
         &self,
         mut cs: CS,
@@ -231,6 +251,10 @@ pub trait FieldGadget<F: Field, ConstraintF: Field>:
         Ok(result)
     }
 
+    /// Computes `self^(p^power)`, where `p` is the characteristic. This only
+    /// multiplies by the precomputed Frobenius coefficients, so it allocates
+    /// no witnesses and costs no constraints; for prime fields it is the
+    /// identity.
     fn frobenius_map<CS: ConstraintSystem<ConstraintF>>(
         &self,
         _: CS,
@@ -268,6 +292,9 @@ pub trait FieldGadget<F: Field, ConstraintF: Field>:
         Ok(res)
     }
 
+    /// Computes `self^exp` for a constant `exp`, given as little-endian `u64`
+    /// limbs, by square-and-multiply over its big-endian bits. Leading zeros
+    /// are skipped, so an exponent of `1` costs no constraints.
     fn pow_by_constant<S: AsRef<[u64]>, CS: ConstraintSystem<ConstraintF>>(
         &self,
         mut cs: CS,
@@ -289,6 +316,11 @@ pub trait FieldGadget<F: Field, ConstraintF: Field>:
             }
         }
 
+        // x^0 = 1
+        if !found_one {
+            return Self::one(cs.ns(|| "one"));
+        }
+
         Ok(res)
     }
 
@@ -303,6 +335,34 @@ pub trait FieldGadget<F: Field, ConstraintF: Field>:
     }
 }
 
+/// Returns a `Boolean` that is set iff `value` is zero.
+pub(crate) fn is_zero<F, ConstraintF, FG, CS>(
+    mut cs: CS,
+    value: &FG,
+) -> Result<Boolean, SynthesisError>
+where
+    F: Field,
+    ConstraintF: Field,
+    FG: FieldGadget<F, ConstraintF>,
+    CS: ConstraintSystem<ConstraintF>,
+{
+    let is_zero = Boolean::alloc(cs.ns(|| "is zero"), || {
+        value.get_value().map(|v| v.is_zero()).get()
+    })?;
+    let inverse = FG::alloc(cs.ns(|| "inverse"), || {
+        Ok(value.get_value().get()?.inverse().unwrap_or_else(F::zero))
+    })?;
+    let zero = FG::zero(cs.ns(|| "zero"))?;
+    let one = FG::one(cs.ns(|| "one"))?;
+    let is_zero_f = FG::conditionally_select(cs.ns(|| "as field"), &is_zero, &one, &zero)?;
+    let is_not_zero_f = one.sub(cs.ns(|| "1 - is zero"), &is_zero_f)?;
+    // value * inverse = 1 - is_zero forces is_zero = 1 when value = 0, and
+    // value * is_zero = 0 forces is_zero = 0 otherwise.
+    value.mul_equals(cs.ns(|| "check inverse"), &inverse, &is_not_zero_f)?;
+    value.mul_equals(cs.ns(|| "check is zero"), &is_zero_f, &zero)?;
+    Ok(is_zero)
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use rand::{self, SeedableRng};
@@ -506,6 +566,40 @@ pub(crate) mod tests {
                 .unwrap()
         );
 
+        let num_constraints = cs.num_constraints();
+        assert_eq!(
+            a_native,
+            a.pow_by_constant(cs.ns(|| "test_constant_pow_one"), &[1])
+                .unwrap()
+                .get_value()
+                .unwrap()
+        );
+        assert_eq!(cs.num_constraints(), num_constraints);
+        assert_eq!(
+            FE::one(),
+            a.pow_by_constant(cs.ns(|| "test_constant_pow_zero"), &[0])
+                .unwrap()
+                .get_value()
+                .unwrap()
+        );
+        for exp in &[[5u64], [17u64]] {
+            assert_eq!(
+                a_native.pow(exp),
+                a.pow_by_constant(cs.ns(|| format!("test_constant_pow_{}", exp[0])), exp)
+                    .unwrap()
+                    .get_value()
+                    .unwrap()
+            );
+        }
+        let exp: [u64; 4] = rand::Rng::gen(&mut test_rng());
+        assert_eq!(
+            a_native.pow(&exp),
+            a.pow_by_constant(cs.ns(|| "test_constant_pow_random"), &exp)
+                .unwrap()
+                .get_value()
+                .unwrap()
+        );
+
         // a * a * a = a^3
         let mut constants = [FE::zero(); 4];
         for c in &mut constants {
@@ -569,6 +663,112 @@ pub(crate) mod tests {
             assert_eq!(a_gadget.get_value().unwrap(), a);
         }
 
+        let a = FE::rand(&mut rng);
+        let a_gadget = F::alloc(cs.ns(|| "a_gadget"), || Ok(a)).unwrap();
+        assert_eq!(
+            a_gadget
+                .frobenius_map(cs.ns(|| "frob_map_zero"), 0)
+                .unwrap()
+                .get_value()
+                .unwrap(),
+            a
+        );
+
+        // Applying the `p`-power Frobenius `i` times is the same as applying the
+        // `p^i`-power one; once `i` reaches the extension degree this is the
+        // identity again.
+        let mut iterated = a_gadget.clone();
+        let mut returned_to_start = false;
+        for i in 1..=maxpower {
+            iterated = iterated
+                .frobenius_map(cs.ns(|| format!("iterated_frob_map_{}", i)), 1)
+                .unwrap();
+            let mut expected = a;
+            expected.frobenius_map(i);
+            assert_eq!(iterated.get_value().unwrap(), expected);
+            returned_to_start |= expected == a;
+        }
+        assert!(returned_to_start);
+
+        assert_eq!(cs.num_constraints(), 0);
+        assert!(cs.is_satisfied());
+    }
+
+    /// Checks `frobenius_map` against its definition, i.e. raising to the
+    /// `i`-th power of the characteristic with `pow`, rather than against the
+    /// native `frobenius_map`, which shares the same Frobenius coefficients.
+    #[allow(dead_code)]
+    pub(crate) fn frobenius_pow_tests<
+        FE: Field,
+        ConstraintF: Field,
+        F: FieldGadget<FE, ConstraintF>,
+    >(
+        maxpower: usize,
+    ) {
+        let mut cs = TestConstraintSystem::<ConstraintF>::new();
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        for j in 0..3 {
+            let a = FE::rand(&mut rng);
+            let a_gadget = F::alloc(cs.ns(|| format!("a_gadget_{}", j)), || Ok(a)).unwrap();
+
+            let mut expected = a;
+            for i in 1..=maxpower {
+                expected = expected.pow(FE::characteristic());
+                let frob = a_gadget
+                    .frobenius_map(cs.ns(|| format!("frob_map_{}_{}", j, i)), i)
+                    .unwrap();
+                assert_eq!(frob.get_value().unwrap(), expected, "power {}", i);
+            }
+        }
+
+        assert_eq!(cs.num_constraints(), 0);
+        assert!(cs.is_satisfied());
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn lookup_tests<FE: Field, ConstraintF: Field, F: FieldGadget<FE, ConstraintF>>() {
+        let mut cs = TestConstraintSystem::<ConstraintF>::new();
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+        let mut table = [FE::zero(); 4];
+        for c in &mut table {
+            *c = FE::rand(&mut rng);
+        }
+
+        // `b[0]` is the least significant bit of the table index, and `b[2]`
+        // selects the negation.
+        for i in 0..8usize {
+            let bits = (0..3)
+                .map(|j| {
+                    Boolean::alloc(cs.ns(|| format!("bit {} of {}", j, i)), || {
+                        Ok((i >> j) & 1 == 1)
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let expected = table[i % 4];
+
+            let two_bit = F::two_bit_lookup(
+                cs.ns(|| format!("two bit lookup {}", i)),
+                &bits[..2],
+                &table,
+            )
+            .unwrap();
+            assert_eq!(two_bit.get_value().unwrap(), expected);
+
+            let b0b1 =
+                Boolean::and(cs.ns(|| format!("b0 and b1 {}", i)), &bits[0], &bits[1]).unwrap();
+            let three_bit = F::three_bit_cond_neg_lookup(
+                cs.ns(|| format!("three bit lookup {}", i)),
+                &bits,
+                &b0b1,
+                &table,
+            )
+            .unwrap();
+            let expected = if i >= 4 { -expected } else { expected };
+            assert_eq!(three_bit.get_value().unwrap(), expected);
+        }
+
         assert!(cs.is_satisfied());
     }
 }
@@ -0,0 +1,972 @@
+use algebra::{BigInteger, FpParameters, PrimeField};
+use core::{borrow::Borrow, cmp::max};
+use r1cs_core::{ConstraintSystem, ConstraintVar, SynthesisError};
+
+use crate::{prelude::*, select::select_value, Assignment, Vec};
+
+/// Emulates arithmetic in `TargetField` inside a constraint system over
+/// `BaseField`.
+///
+/// An element is represented by `num_limbs` limbs of `bits_per_limb` bits
+/// each, least significant first (see [`Self::limb_parameters`]). Every
+/// gadget is kept in canonical form: each limb is range-checked and the
+/// integer they encode is smaller than the modulus of `TargetField`. Hence
+/// equality is checked limb-wise, and the bit decomposition of the limbs is
+/// unique.
+///
+/// Each operation witnesses the reduced result `r` together with a quotient
+/// `q`, and checks the integer identity `lhs = q * p + r` limb-wise, moving
+/// the difference between neighbouring limbs through range-checked carries.
+#[derive(Derivative)]
+#[derivative(
+    Debug(bound = "TargetField: PrimeField, BaseField: PrimeField"),
+    Clone(bound = "TargetField: PrimeField, BaseField: PrimeField")
+)]
+#[must_use]
+pub struct NonNativeFieldGadget<TargetField: PrimeField, BaseField: PrimeField> {
+    pub limbs: Vec<FpGadget<BaseField>>,
+    pub value: Option<TargetField>,
+}
+
+impl<TargetField: PrimeField, BaseField: PrimeField> NonNativeFieldGadget<TargetField, BaseField> {
+    /// Returns `(bits_per_limb, num_limbs)`. This is the smallest number of
+    /// limbs for which the coefficients of the product of two elements, and
+    /// the carries between them, fit into `BaseField` without wrapping
+    /// around.
+    pub fn limb_parameters() -> (usize, usize) {
+        let target_bits = TargetField::Params::MODULUS_BITS as usize;
+        let capacity = BaseField::Params::CAPACITY as usize;
+        for num_limbs in 1..=target_bits {
+            let bits_per_limb = (target_bits + num_limbs - 1) / num_limbs;
+            if 2 * bits_per_limb + log2_ceil(num_limbs + 1) + 2 <= capacity {
+                return (bits_per_limb, num_limbs);
+            }
+        }
+        panic!("BaseField is too small to emulate TargetField");
+    }
+
+    /// Returns the limbs of `value`, as they are allocated by
+    /// [`AllocGadget::alloc_input`]. Use this to compute the public input of a
+    /// circuit that allocates non-native inputs.
+    pub fn get_limbs_representations(value: &TargetField) -> Vec<BaseField> {
+        Self::limbs_of(value.into_repr().as_ref())
+    }
+
+    fn limbs_of(words: &[u64]) -> Vec<BaseField> {
+        let (bits_per_limb, num_limbs) = Self::limb_parameters();
+        (0..num_limbs)
+            .map(|i| {
+                let mut repr = BaseField::BigInt::default();
+                for j in 0..bits_per_limb {
+                    if get_bit(words, i * bits_per_limb + j) {
+                        repr.as_mut()[j / 64] |= 1 << (j % 64);
+                    }
+                }
+                BaseField::from_repr(repr).unwrap()
+            })
+            .collect()
+    }
+
+    fn modulus_limbs() -> Vec<BaseField> {
+        Self::limbs_of(TargetField::characteristic())
+    }
+
+    fn words(&self) -> Option<Vec<u64>> {
+        self.value.map(|v| v.into_repr().as_ref().to_vec())
+    }
+
+    /// Allocates `value` as a witness directly from its bits, and checks that
+    /// it is canonical.
+    fn alloc_canonical<CS: ConstraintSystem<BaseField>>(
+        mut cs: CS,
+        value: Option<TargetField>,
+    ) -> Result<Self, SynthesisError> {
+        let (bits_per_limb, num_limbs) = Self::limb_parameters();
+        let words = value.map(|v| v.into_repr().as_ref().to_vec());
+        let bits = alloc_bits(cs.ns(|| "bits"), words, num_limbs * bits_per_limb)?;
+        let limbs = bits
+            .chunks(bits_per_limb)
+            .enumerate()
+            .map(|(i, chunk)| FpGadget::from_bits_le(cs.ns(|| format!("limb {}", i)), chunk))
+            .collect::<Result<Vec<_>, _>>()?;
+        let result = Self { limbs, value };
+        result.enforce_in_field(cs.ns(|| "in field"))?;
+        Ok(result)
+    }
+
+    /// Enforces that the integer encoded by the (range-checked) limbs is
+    /// smaller than the modulus, by witnessing `s = p - 1 - self` as a
+    /// non-negative integer.
+    fn enforce_in_field<CS: ConstraintSystem<BaseField>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<(), SynthesisError> {
+        let (bits_per_limb, num_limbs) = Self::limb_parameters();
+        let mut p_minus_one = TargetField::characteristic().to_vec();
+        p_minus_one[0] -= 1;
+
+        let s_words = self.words().map(|w| bigint_sub(&p_minus_one, &w));
+        let s_bits = alloc_bits(cs.ns(|| "s bits"), s_words, num_limbs * bits_per_limb)?;
+        let lhs = s_bits
+            .chunks(bits_per_limb)
+            .zip(&self.limbs)
+            .enumerate()
+            .map(|(i, (chunk, limb))| {
+                FpGadget::from_bits_le(cs.ns(|| format!("s limb {}", i)), chunk)?.add(&mut cs, limb)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let rhs = Self::limbs_of(&p_minus_one)
+            .into_iter()
+            .map(|c| FpGadget::alloc_constant(&mut cs, c))
+            .collect::<Result<Vec<_>, _>>()?;
+        enforce_equal_integers(
+            cs.ns(|| "s + self = p - 1"),
+            &lhs,
+            &rhs,
+            bits_per_limb,
+            bits_per_limb + 1,
+        )
+    }
+
+    /// Returns `lhs mod p`, where `lhs` is an integer given by its limb-wise
+    /// coefficients. `lhs_words` is its value, `num_q_bits` bounds the quotient
+    /// and `bound_bits` bounds the absolute value of every coefficient of
+    /// both sides of `lhs = q * p + r`.
+    fn reduce<CS: ConstraintSystem<BaseField>>(
+        mut cs: CS,
+        lhs: &[FpGadget<BaseField>],
+        lhs_words: Option<Vec<u64>>,
+        value: Option<TargetField>,
+        num_q_bits: usize,
+        bound_bits: usize,
+    ) -> Result<Self, SynthesisError> {
+        let (bits_per_limb, _) = Self::limb_parameters();
+        let result = Self::alloc_canonical(cs.ns(|| "alloc result"), value)?;
+
+        let q_words = lhs_words.map(|w| bigint_div(&w, TargetField::characteristic()));
+        let q_bits = alloc_bits(cs.ns(|| "q bits"), q_words, num_q_bits)?;
+        let q_limbs = q_bits
+            .chunks(bits_per_limb)
+            .enumerate()
+            .map(|(i, chunk)| FpGadget::from_bits_le(cs.ns(|| format!("q limb {}", i)), chunk))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let p_limbs = Self::modulus_limbs();
+        let mut rhs = result.limbs.clone();
+        for (i, q_limb) in q_limbs.iter().enumerate() {
+            for (j, p_limb) in p_limbs.iter().enumerate() {
+                let term = q_limb.mul_by_constant(&mut cs, p_limb)?;
+                if i + j < rhs.len() {
+                    rhs[i + j] = rhs[i + j].add(&mut cs, &term)?;
+                } else {
+                    rhs.push(term);
+                }
+            }
+        }
+
+        enforce_equal_integers(
+            cs.ns(|| "lhs = q * p + r"),
+            lhs,
+            &rhs,
+            bits_per_limb,
+            bound_bits,
+        )?;
+        Ok(result)
+    }
+
+    /// Reduces `a + b`, `a + p - b` or `p - a` for canonical `a` and `b`. All
+    /// of these lie in `[0, 2p)`, so the quotient is a single bit.
+    fn reduce_linear<CS: ConstraintSystem<BaseField>>(
+        cs: CS,
+        lhs: &[FpGadget<BaseField>],
+        lhs_words: Option<Vec<u64>>,
+        value: Option<TargetField>,
+    ) -> Result<Self, SynthesisError> {
+        let (bits_per_limb, _) = Self::limb_parameters();
+        Self::reduce(cs, lhs, lhs_words, value, 1, bits_per_limb + 2)
+    }
+
+    /// Decomposes each limb into `bits_per_limb` bits, in little-endian order.
+    /// Since the limbs are range-checked, this decomposition is unique.
+    fn limb_bits<CS: ConstraintSystem<BaseField>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Vec<Boolean>, SynthesisError> {
+        let (bits_per_limb, _) = Self::limb_parameters();
+        let mut bits = Vec::with_capacity(self.limbs.len() * bits_per_limb);
+        for (i, limb) in self.limbs.iter().enumerate() {
+            let words = limb.value.map(|v| v.into_repr().as_ref().to_vec());
+            let limb_bits = alloc_bits(cs.ns(|| format!("limb {} bits", i)), words, bits_per_limb)?;
+            let packed = FpGadget::from_bits_le(cs.ns(|| format!("pack limb {}", i)), &limb_bits)?;
+            packed.enforce_equal(cs.ns(|| format!("limb {} equals packed bits", i)), limb)?;
+            bits.extend(limb_bits);
+        }
+        Ok(bits)
+    }
+
+    /// Returns the number of constraints used to check
+    /// `lhs = q * p + r` for a freshly allocated `r`.
+    fn cost_of_reduce(q_bits: usize, bound_bits: usize) -> usize {
+        let (bits_per_limb, num_limbs) = Self::limb_parameters();
+        let q_limbs = (q_bits + bits_per_limb - 1) / bits_per_limb;
+        let len = max(num_limbs, q_limbs + num_limbs - 1);
+        Self::cost_of_alloc() + q_bits + cost_of_equal_integers(len, bits_per_limb, bound_bits)
+    }
+
+    fn cost_of_alloc() -> usize {
+        let (bits_per_limb, num_limbs) = Self::limb_parameters();
+        2 * num_limbs * bits_per_limb
+            + cost_of_equal_integers(num_limbs, bits_per_limb, bits_per_limb + 1)
+    }
+
+    fn cost_of_linear() -> usize {
+        let (bits_per_limb, _) = Self::limb_parameters();
+        Self::cost_of_reduce(1, bits_per_limb + 2)
+    }
+}
+
+impl<TargetField: PrimeField, BaseField: PrimeField> ToConstraintFieldGadget<BaseField>
+    for NonNativeFieldGadget<TargetField, BaseField>
+{
+    fn to_constraint_field<CS: ConstraintSystem<BaseField>>(
+        &self,
+        _cs: CS,
+    ) -> Result<Vec<FpGadget<BaseField>>, SynthesisError> {
+        Ok(self.limbs.clone())
+    }
+}
+
+impl<TargetField: PrimeField, BaseField: PrimeField> FieldGadget<TargetField, BaseField>
+    for NonNativeFieldGadget<TargetField, BaseField>
+{
+    type Variable = Vec<ConstraintVar<BaseField>>;
+
+    #[inline]
+    fn get_value(&self) -> Option<TargetField> {
+        self.value
+    }
+
+    #[inline]
+    fn get_variable(&self) -> Self::Variable {
+        self.limbs.iter().map(|limb| limb.get_variable()).collect()
+    }
+
+    #[inline]
+    fn zero<CS: ConstraintSystem<BaseField>>(cs: CS) -> Result<Self, SynthesisError> {
+        Self::alloc_constant(cs, TargetField::zero())
+    }
+
+    #[inline]
+    fn one<CS: ConstraintSystem<BaseField>>(cs: CS) -> Result<Self, SynthesisError> {
+        Self::alloc_constant(cs, TargetField::one())
+    }
+
+    #[inline]
+    fn conditionally_add_constant<CS: ConstraintSystem<BaseField>>(
+        &self,
+        mut cs: CS,
+        bit: &Boolean,
+        coeff: TargetField,
+    ) -> Result<Self, SynthesisError> {
+        let sum = self.add_constant(cs.ns(|| "add constant"), &coeff)?;
+        Self::conditionally_select(cs.ns(|| "select"), bit, &sum, self)
+    }
+
+    fn add<CS: ConstraintSystem<BaseField>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let lhs = self
+            .limbs
+            .iter()
+            .zip(&other.limbs)
+            .map(|(a, b)| a.add(&mut cs, b))
+            .collect::<Result<Vec<_>, _>>()?;
+        let lhs_words = match (self.words(), other.words()) {
+            (Some(a), Some(b)) => Some(bigint_add(&a, &b)),
+            _ => None,
+        };
+        let value = match (self.value, other.value) {
+            (Some(a), Some(b)) => Some(a + &b),
+            _ => None,
+        };
+        Self::reduce_linear(cs.ns(|| "reduce"), &lhs, lhs_words, value)
+    }
+
+    fn sub<CS: ConstraintSystem<BaseField>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+    ) -> Result<Self, SynthesisError> {
+        // Compute `self + p - other`, which is non-negative.
+        let lhs = self
+            .limbs
+            .iter()
+            .zip(&other.limbs)
+            .zip(Self::modulus_limbs())
+            .map(|((a, b), p)| a.add_constant(&mut cs, &p)?.sub(&mut cs, b))
+            .collect::<Result<Vec<_>, _>>()?;
+        let lhs_words = match (self.words(), other.words()) {
+            (Some(a), Some(b)) => Some(bigint_sub(
+                &bigint_add(&a, TargetField::characteristic()),
+                &b,
+            )),
+            _ => None,
+        };
+        let value = match (self.value, other.value) {
+            (Some(a), Some(b)) => Some(a - &b),
+            _ => None,
+        };
+        Self::reduce_linear(cs.ns(|| "reduce"), &lhs, lhs_words, value)
+    }
+
+    fn negate<CS: ConstraintSystem<BaseField>>(&self, mut cs: CS) -> Result<Self, SynthesisError> {
+        // Compute `p - self`, which is non-negative.
+        let lhs = self
+            .limbs
+            .iter()
+            .zip(Self::modulus_limbs())
+            .map(|(a, p)| a.negate(&mut cs)?.add_constant(&mut cs, &p))
+            .collect::<Result<Vec<_>, _>>()?;
+        let lhs_words = self
+            .words()
+            .map(|a| bigint_sub(TargetField::characteristic(), &a));
+        let value = self.value.map(|a| -a);
+        Self::reduce_linear(cs.ns(|| "reduce"), &lhs, lhs_words, value)
+    }
+
+    fn mul<CS: ConstraintSystem<BaseField>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let (bits_per_limb, num_limbs) = Self::limb_parameters();
+
+        // The coefficients of the product of the two limb polynomials.
+        let mut lhs: Vec<FpGadget<BaseField>> = Vec::with_capacity(2 * num_limbs - 1);
+        for (i, a) in self.limbs.iter().enumerate() {
+            for (j, b) in other.limbs.iter().enumerate() {
+                let term = a.mul(cs.ns(|| format!("a_{} * b_{}", i, j)), b)?;
+                if i + j < lhs.len() {
+                    lhs[i + j] = lhs[i + j].add(&mut cs, &term)?;
+                } else {
+                    lhs.push(term);
+                }
+            }
+        }
+        let lhs_words = match (self.words(), other.words()) {
+            (Some(a), Some(b)) => Some(bigint_mul(&a, &b)),
+            _ => None,
+        };
+        let value = match (self.value, other.value) {
+            (Some(a), Some(b)) => Some(a * &b),
+            _ => None,
+        };
+
+        Self::reduce(
+            cs.ns(|| "reduce"),
+            &lhs,
+            lhs_words,
+            value,
+            num_limbs * bits_per_limb,
+            2 * bits_per_limb + log2_ceil(num_limbs + 1),
+        )
+    }
+
+    #[inline]
+    fn add_constant<CS: ConstraintSystem<BaseField>>(
+        &self,
+        mut cs: CS,
+        other: &TargetField,
+    ) -> Result<Self, SynthesisError> {
+        let other = Self::alloc_constant(cs.ns(|| "constant"), other)?;
+        self.add(cs.ns(|| "add"), &other)
+    }
+
+    #[inline]
+    fn mul_by_constant<CS: ConstraintSystem<BaseField>>(
+        &self,
+        mut cs: CS,
+        other: &TargetField,
+    ) -> Result<Self, SynthesisError> {
+        let other = Self::alloc_constant(cs.ns(|| "constant"), other)?;
+        self.mul(cs.ns(|| "mul"), &other)
+    }
+
+    #[inline]
+    fn frobenius_map<CS: ConstraintSystem<BaseField>>(
+        &self,
+        _: CS,
+        _power: usize,
+    ) -> Result<Self, SynthesisError> {
+        Ok(self.clone())
+    }
+
+    fn cost_of_mul() -> usize {
+        let (bits_per_limb, num_limbs) = Self::limb_parameters();
+        num_limbs * num_limbs
+            + Self::cost_of_reduce(
+                num_limbs * bits_per_limb,
+                2 * bits_per_limb + log2_ceil(num_limbs + 1),
+            )
+    }
+
+    fn cost_of_inv() -> usize {
+        Self::cost_of_alloc() + Self::cost_of_mul_equals()
+    }
+}
+
+impl<TargetField: PrimeField, BaseField: PrimeField> PartialEq
+    for NonNativeFieldGadget<TargetField, BaseField>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.value.is_some() && other.value.is_some() && self.value == other.value
+    }
+}
+
+impl<TargetField: PrimeField, BaseField: PrimeField> Eq
+    for NonNativeFieldGadget<TargetField, BaseField>
+{
+}
+
+impl<TargetField: PrimeField, BaseField: PrimeField> EqGadget<BaseField>
+    for NonNativeFieldGadget<TargetField, BaseField>
+{
+}
+
+impl<TargetField: PrimeField, BaseField: PrimeField> ConditionalEqGadget<BaseField>
+    for NonNativeFieldGadget<TargetField, BaseField>
+{
+    #[inline]
+    fn conditional_enforce_equal<CS: ConstraintSystem<BaseField>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError> {
+        for (i, (a, b)) in self.limbs.iter().zip(&other.limbs).enumerate() {
+            a.conditional_enforce_equal(cs.ns(|| format!("limb {}", i)), b, condition)?;
+        }
+        Ok(())
+    }
+
+    fn cost() -> usize {
+        Self::limb_parameters().1
+    }
+}
+
+impl<TargetField: PrimeField, BaseField: PrimeField> NEqGadget<BaseField>
+    for NonNativeFieldGadget<TargetField, BaseField>
+{
+    #[inline]
+    fn enforce_not_equal<CS: ConstraintSystem<BaseField>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+    ) -> Result<(), SynthesisError> {
+        let a_minus_b = self.sub(cs.ns(|| "A - B"), other)?;
+        let _ = a_minus_b.inverse(cs.ns(|| "Enforce inverse exists"))?;
+        Ok(())
+    }
+
+    fn cost() -> usize {
+        Self::cost_of_linear() + Self::cost_of_inv()
+    }
+}
+
+impl<TargetField: PrimeField, BaseField: PrimeField> ToBitsGadget<BaseField>
+    for NonNativeFieldGadget<TargetField, BaseField>
+{
+    /// Outputs the unique bit-wise decomposition of `self` in *big-endian*
+    /// form.
+    fn to_bits<CS: ConstraintSystem<BaseField>>(
+        &self,
+        cs: CS,
+    ) -> Result<Vec<Boolean>, SynthesisError> {
+        let mut bits = self.limb_bits(cs)?;
+        // The limbs are canonical, so the bits beyond the modulus are zero.
+        bits.truncate(TargetField::Params::MODULUS_BITS as usize);
+        bits.reverse();
+        Ok(bits)
+    }
+
+    fn to_non_unique_bits<CS: ConstraintSystem<BaseField>>(
+        &self,
+        cs: CS,
+    ) -> Result<Vec<Boolean>, SynthesisError> {
+        self.to_bits(cs)
+    }
+}
+
+impl<TargetField: PrimeField, BaseField: PrimeField> ToBytesGadget<BaseField>
+    for NonNativeFieldGadget<TargetField, BaseField>
+{
+    /// Outputs the unique byte decomposition of `self` in *little-endian*
+    /// form, padded to the length of the native `ToBytes` output.
+    fn to_bytes<CS: ConstraintSystem<BaseField>>(
+        &self,
+        cs: CS,
+    ) -> Result<Vec<UInt8>, SynthesisError> {
+        let mut bits = self.limb_bits(cs)?;
+        bits.truncate(TargetField::Params::MODULUS_BITS as usize);
+        bits.resize(
+            <TargetField::BigInt as BigInteger>::NUM_LIMBS * 64,
+            Boolean::constant(false),
+        );
+        Ok(bits.chunks(8).map(UInt8::from_bits_le).collect())
+    }
+
+    fn to_non_unique_bytes<CS: ConstraintSystem<BaseField>>(
+        &self,
+        cs: CS,
+    ) -> Result<Vec<UInt8>, SynthesisError> {
+        self.to_bytes(cs)
+    }
+}
+
+impl<TargetField: PrimeField, BaseField: PrimeField> CondSelectGadget<BaseField>
+    for NonNativeFieldGadget<TargetField, BaseField>
+{
+    #[inline]
+    fn conditionally_select<CS: ConstraintSystem<BaseField>>(
+        mut cs: CS,
+        cond: &Boolean,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let limbs = true_value
+            .limbs
+            .iter()
+            .zip(&false_value.limbs)
+            .enumerate()
+            .map(|(i, (t, f))| {
+                FpGadget::conditionally_select(cs.ns(|| format!("limb {}", i)), cond, t, f)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let value = select_value(cond.get_value(), true_value.value, false_value.value);
+        Ok(Self { limbs, value })
+    }
+
+    fn cost() -> usize {
+        Self::limb_parameters().1 * <FpGadget<BaseField> as CondSelectGadget<BaseField>>::cost()
+    }
+}
+
+/// Uses two bits to perform a lookup into a table
+/// `b` is little-endian: `b[0]` is LSB.
+impl<TargetField: PrimeField, BaseField: PrimeField> TwoBitLookupGadget<BaseField>
+    for NonNativeFieldGadget<TargetField, BaseField>
+{
+    type TableConstant = TargetField;
+
+    fn two_bit_lookup<CS: ConstraintSystem<BaseField>>(
+        mut cs: CS,
+        b: &[Boolean],
+        c: &[Self::TableConstant],
+    ) -> Result<Self, SynthesisError> {
+        debug_assert!(b.len() == 2);
+        debug_assert!(c.len() == 4);
+
+        // Every limb of the result is the corresponding limb of one of the
+        // (canonical) constants, so the result is canonical as well.
+        let c_limbs = c
+            .iter()
+            .map(Self::get_limbs_representations)
+            .collect::<Vec<_>>();
+        let limbs = (0..c_limbs[0].len())
+            .map(|i| {
+                let table = c_limbs.iter().map(|l| l[i]).collect::<Vec<_>>();
+                FpGadget::two_bit_lookup(cs.ns(|| format!("limb {}", i)), b, &table)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let value = match (b[0].get_value(), b[1].get_value()) {
+            (Some(b0), Some(b1)) => Some(c[(b0 as usize) + 2 * (b1 as usize)]),
+            _ => None,
+        };
+        Ok(Self { limbs, value })
+    }
+
+    fn cost() -> usize {
+        Self::limb_parameters().1 * <FpGadget<BaseField> as TwoBitLookupGadget<BaseField>>::cost()
+    }
+}
+
+impl<TargetField: PrimeField, BaseField: PrimeField> ThreeBitCondNegLookupGadget<BaseField>
+    for NonNativeFieldGadget<TargetField, BaseField>
+{
+    type TableConstant = TargetField;
+
+    fn three_bit_cond_neg_lookup<CS: ConstraintSystem<BaseField>>(
+        mut cs: CS,
+        b: &[Boolean],
+        _b0b1: &Boolean,
+        c: &[Self::TableConstant],
+    ) -> Result<Self, SynthesisError> {
+        debug_assert!(b.len() == 3);
+        debug_assert!(c.len() == 4);
+
+        let y = Self::two_bit_lookup(cs.ns(|| "lookup"), &b[..2], c)?;
+        let neg_y = y.negate(cs.ns(|| "negate"))?;
+        Self::conditionally_select(cs.ns(|| "conditional negation"), &b[2], &neg_y, &y)
+    }
+
+    fn cost() -> usize {
+        <Self as TwoBitLookupGadget<BaseField>>::cost()
+            + Self::cost_of_linear()
+            + <Self as CondSelectGadget<BaseField>>::cost()
+    }
+}
+
+impl<TargetField: PrimeField, BaseField: PrimeField> AllocGadget<TargetField, BaseField>
+    for NonNativeFieldGadget<TargetField, BaseField>
+{
+    #[inline]
+    fn alloc_constant<T, CS: ConstraintSystem<BaseField>>(
+        mut cs: CS,
+        t: T,
+    ) -> Result<Self, SynthesisError>
+    where
+        T: Borrow<TargetField>,
+    {
+        let value = *t.borrow();
+        let limbs = Self::get_limbs_representations(&value)
+            .into_iter()
+            .enumerate()
+            .map(|(i, limb)| FpGadget::alloc_constant(cs.ns(|| format!("limb {}", i)), limb))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            limbs,
+            value: Some(value),
+        })
+    }
+
+    #[inline]
+    fn alloc<FN, T, CS: ConstraintSystem<BaseField>>(
+        cs: CS,
+        value_gen: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<TargetField>,
+    {
+        let value = value_gen().map(|v| *v.borrow()).ok();
+        Self::alloc_canonical(cs, value)
+    }
+
+    /// Allocates each limb as a separate public input; see
+    /// [`NonNativeFieldGadget::get_limbs_representations`].
+    #[inline]
+    fn alloc_input<FN, T, CS: ConstraintSystem<BaseField>>(
+        mut cs: CS,
+        value_gen: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<TargetField>,
+    {
+        let (_, num_limbs) = Self::limb_parameters();
+        let value = value_gen().map(|v| *v.borrow()).ok();
+        let limb_values = value.as_ref().map(Self::get_limbs_representations);
+        let limbs = (0..num_limbs)
+            .map(|i| {
+                FpGadget::alloc_input(cs.ns(|| format!("limb {}", i)), || {
+                    limb_values.as_ref().map(|l| l[i]).get()
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let result = Self { limbs, value };
+        result.limb_bits(cs.ns(|| "range check"))?;
+        result.enforce_in_field(cs.ns(|| "in field"))?;
+        Ok(result)
+    }
+}
+
+/// Enforces that `lhs` and `rhs`, read as polynomials in `2^bits_per_limb`,
+/// evaluate to the same integer. `bound_bits` must bound the absolute value
+/// of the difference of any two coefficients, and be small enough for the
+/// constraints below not to wrap around.
+fn enforce_equal_integers<F: PrimeField, CS: ConstraintSystem<F>>(
+    mut cs: CS,
+    lhs: &[FpGadget<F>],
+    rhs: &[FpGadget<F>],
+    bits_per_limb: usize,
+    bound_bits: usize,
+) -> Result<(), SynthesisError> {
+    let num_carry_bits = bound_bits - bits_per_limb + 2;
+    let shift = power_of_two::<F>(bits_per_limb);
+    let shift_inv = shift.inverse().unwrap();
+    // Carries may be negative, so they are range-checked after adding an
+    // offset.
+    let offset = power_of_two::<F>(num_carry_bits - 1);
+
+    let len = max(lhs.len(), rhs.len());
+    let mut carry = FpGadget::zero(&mut cs)?;
+    for k in 0..len {
+        let mut diff = carry;
+        if let Some(l) = lhs.get(k) {
+            diff = diff.add(&mut cs, l)?;
+        }
+        if let Some(r) = rhs.get(k) {
+            diff = diff.sub(&mut cs, r)?;
+        }
+
+        if k + 1 == len {
+            diff.enforce_equal_constant(cs.ns(|| format!("coefficient {} is zero", k)), F::zero())?;
+            break;
+        }
+
+        let shifted_carry = diff.value.map(|d| d * &shift_inv + &offset);
+        let carry_bits = alloc_bits(
+            cs.ns(|| format!("carry {} bits", k)),
+            shifted_carry.map(|c| c.into_repr().as_ref().to_vec()),
+            num_carry_bits,
+        )?;
+        carry = FpGadget::from_bits_le(cs.ns(|| format!("pack carry {}", k)), &carry_bits)?
+            .sub_constant(&mut cs, &offset)?;
+        let shifted = carry.mul_by_constant(&mut cs, &shift)?;
+        diff.sub(&mut cs, &shifted)?
+            .enforce_equal_constant(cs.ns(|| format!("carry {}", k)), F::zero())?;
+    }
+    Ok(())
+}
+
+/// Returns the number of constraints used by [`enforce_equal_integers`] for
+/// polynomials with `len` coefficients.
+fn cost_of_equal_integers(len: usize, bits_per_limb: usize, bound_bits: usize) -> usize {
+    (len - 1) * (bound_bits - bits_per_limb + 2) + len
+}
+
+/// Allocates the `num_bits` least significant bits of `words`, in
+/// little-endian order.
+fn alloc_bits<F: PrimeField, CS: ConstraintSystem<F>>(
+    mut cs: CS,
+    words: Option<Vec<u64>>,
+    num_bits: usize,
+) -> Result<Vec<Boolean>, SynthesisError> {
+    (0..num_bits)
+        .map(|i| {
+            Boolean::alloc(cs.ns(|| format!("bit {}", i)), || {
+                words.as_ref().map(|w| get_bit(w, i)).get()
+            })
+        })
+        .collect()
+}
+
+fn get_bit(words: &[u64], i: usize) -> bool {
+    words
+        .get(i / 64)
+        .map_or(false, |w| (w >> (i % 64)) & 1 == 1)
+}
+
+fn log2_ceil(x: usize) -> usize {
+    let mut bits = 0;
+    while (1 << bits) < x {
+        bits += 1;
+    }
+    bits
+}
+
+fn power_of_two<F: PrimeField>(exp: usize) -> F {
+    let mut result = F::one();
+    for _ in 0..exp {
+        result.double_in_place();
+    }
+    result
+}
+
+// Witness generation needs a little arithmetic on unbounded integers, given as
+// little-endian `u64` words.
+
+fn bigint_add(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut result = Vec::with_capacity(max(a.len(), b.len()) + 1);
+    let mut carry = 0u128;
+    for i in 0..max(a.len(), b.len()) {
+        let sum = *a.get(i).unwrap_or(&0) as u128 + *b.get(i).unwrap_or(&0) as u128 + carry;
+        result.push(sum as u64);
+        carry = sum >> 64;
+    }
+    result.push(carry as u64);
+    result
+}
+
+/// Computes `a - b`, assuming that `a >= b`.
+fn bigint_sub(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = false;
+    for (i, a) in a.iter().enumerate() {
+        let (diff, borrow_1) = a.overflowing_sub(*b.get(i).unwrap_or(&0));
+        let (diff, borrow_2) = diff.overflowing_sub(borrow as u64);
+        result.push(diff);
+        borrow = borrow_1 || borrow_2;
+    }
+    debug_assert!(!borrow);
+    result
+}
+
+fn bigint_mul(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut result = vec![0u64; a.len() + b.len()];
+    for (i, a) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, b) in b.iter().enumerate() {
+            let t = result[i + j] as u128 + (*a as u128) * (*b as u128) + carry;
+            result[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        result[i + b.len()] = carry as u64;
+    }
+    result
+}
+
+fn bigint_geq(a: &[u64], b: &[u64]) -> bool {
+    for i in (0..max(a.len(), b.len())).rev() {
+        let (a, b) = (*a.get(i).unwrap_or(&0), *b.get(i).unwrap_or(&0));
+        if a != b {
+            return a > b;
+        }
+    }
+    true
+}
+
+/// Computes `floor(n / d)` by schoolbook long division.
+fn bigint_div(n: &[u64], d: &[u64]) -> Vec<u64> {
+    let mut quotient = vec![0u64; n.len()];
+    let mut remainder = vec![0u64; d.len() + 1];
+    for i in (0..n.len() * 64).rev() {
+        let mut carry = get_bit(n, i) as u64;
+        for word in remainder.iter_mut() {
+            let next_carry = *word >> 63;
+            *word = (*word << 1) | carry;
+            carry = next_carry;
+        }
+        if bigint_geq(&remainder, d) {
+            remainder = bigint_sub(&remainder, d);
+            quotient[i / 64] |= 1 << (i % 64);
+        }
+    }
+    quotient
+}
+
+#[cfg(test)]
+mod test {
+    use super::NonNativeFieldGadget;
+    use crate::{
+        prelude::*, test_constraint_counter::constraint_delta,
+        test_constraint_system::TestConstraintSystem, Vec,
+    };
+    use algebra::{
+        bls12_381::{Fq, Fr},
+        bytes::ToBytes,
+        BitIterator, PrimeField, UniformRand,
+    };
+    use r1cs_core::ConstraintSystem;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn nonnative_field_test() {
+        crate::fields::tests::field_test::<_, Fr, NonNativeFieldGadget<Fq, Fr>>();
+    }
+
+    fn random_arithmetic<TargetField: PrimeField, BaseField: PrimeField>(num_iterations: usize) {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<BaseField>::new();
+
+        let mut acc_native = TargetField::rand(&mut rng);
+        let mut acc =
+            NonNativeFieldGadget::<TargetField, BaseField>::alloc(cs.ns(|| "acc"), || {
+                Ok(acc_native)
+            })
+            .unwrap();
+        for i in 0..num_iterations {
+            let mut cs = cs.ns(|| format!("iteration {}", i));
+            let a_native = TargetField::rand(&mut rng);
+            let b_native = TargetField::rand(&mut rng);
+            let a = NonNativeFieldGadget::alloc(cs.ns(|| "a"), || Ok(a_native)).unwrap();
+            let b = NonNativeFieldGadget::alloc(cs.ns(|| "b"), || Ok(b_native)).unwrap();
+
+            acc = acc.mul(cs.ns(|| "acc * a"), &a).unwrap();
+            acc_native *= &a_native;
+            assert_eq!(acc.get_value().unwrap(), acc_native);
+
+            acc = acc.add(cs.ns(|| "acc + b"), &b).unwrap();
+            acc_native += &b_native;
+            assert_eq!(acc.get_value().unwrap(), acc_native);
+
+            let diff = a.sub(cs.ns(|| "a - b"), &b).unwrap();
+            assert_eq!(diff.get_value().unwrap(), a_native - &b_native);
+            let neg = acc.negate(cs.ns(|| "-acc")).unwrap();
+            assert_eq!(neg.get_value().unwrap(), -acc_native);
+        }
+
+        let expected =
+            NonNativeFieldGadget::alloc(cs.ns(|| "expected"), || Ok(acc_native)).unwrap();
+        acc.enforce_equal(cs.ns(|| "acc == expected"), &expected)
+            .unwrap();
+
+        if !cs.is_satisfied() {
+            println!("{:?}", cs.which_is_unsatisfied().unwrap());
+        }
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn nonnative_larger_target_arithmetic_test() {
+        random_arithmetic::<Fq, Fr>(100);
+    }
+
+    #[test]
+    fn nonnative_smaller_target_arithmetic_test() {
+        random_arithmetic::<Fr, Fq>(100);
+    }
+
+    #[test]
+    fn nonnative_mul_cost_test() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let a = NonNativeFieldGadget::<Fq, Fr>::alloc(cs.ns(|| "a"), || Ok(Fq::rand(&mut rng)))
+            .unwrap();
+        let b = NonNativeFieldGadget::<Fq, Fr>::alloc(cs.ns(|| "b"), || Ok(Fq::rand(&mut rng)))
+            .unwrap();
+        let (_, num_constraints) = constraint_delta(&mut cs, |cs| a.mul(cs.ns(|| "a * b"), &b));
+        assert_eq!(
+            num_constraints,
+            <NonNativeFieldGadget<Fq, Fr> as FieldGadget<Fq, Fr>>::cost_of_mul()
+        );
+        let (_, num_constraints) = constraint_delta(&mut cs, |cs| a.inverse(cs.ns(|| "a^-1")));
+        assert_eq!(
+            num_constraints,
+            <NonNativeFieldGadget<Fq, Fr> as FieldGadget<Fq, Fr>>::cost_of_inv()
+        );
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn nonnative_alloc_input_and_bits_test() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let native = Fq::rand(&mut rng);
+        let a = NonNativeFieldGadget::<Fq, Fr>::alloc_input(cs.ns(|| "a"), || Ok(native)).unwrap();
+        let limbs = NonNativeFieldGadget::<Fq, Fr>::get_limbs_representations(&native);
+        assert_eq!(a.limbs.len(), limbs.len());
+        for (limb, expected) in a.limbs.iter().zip(&limbs) {
+            assert_eq!(limb.get_value().unwrap(), *expected);
+        }
+
+        let bits = a.to_bits(cs.ns(|| "to bits")).unwrap();
+        let repr = native.into_repr();
+        let expected_bits = BitIterator::new(repr)
+            .skip(repr.as_ref().len() * 64 - Fq::size_in_bits())
+            .collect::<Vec<_>>();
+        assert_eq!(Boolean::get_values(&bits).unwrap(), expected_bits);
+
+        let bytes = a.to_bytes(cs.ns(|| "to bytes")).unwrap();
+        assert_eq!(
+            UInt8::get_values(&bytes).unwrap(),
+            to_bytes![native].unwrap()
+        );
+        assert!(cs.is_satisfied());
+    }
+}
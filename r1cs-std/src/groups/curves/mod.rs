@@ -1,2 +1,56 @@
+use crate::{fields::fp::FpGadget, prelude::*, Vec};
+use algebra::{FpParameters, PrimeField};
+use r1cs_core::{ConstraintSystem, SynthesisError};
+
 pub mod short_weierstrass;
 pub mod twisted_edwards;
+
+/// Returns the bit length of a compressed point encoding over `F` with
+/// `num_flags` flag bits, which must fit in the unused top bits of the last
+/// byte; otherwise `SynthesisError::InvalidArgument` is returned.
+fn compressed_num_bits<F: PrimeField>(num_flags: usize) -> Result<usize, SynthesisError> {
+    let modulus_bits = F::Params::MODULUS_BITS as usize;
+    let num_bits = (modulus_bits + 7) / 8 * 8;
+    if num_bits - modulus_bits < num_flags {
+        return Err(SynthesisError::InvalidArgument);
+    }
+    Ok(num_bits)
+}
+
+/// Encodes `x` as in `algebra`'s `CanonicalSerialize` for prime fields, as
+/// used by the point serializations: the little-endian bytes of `x`, with
+/// `flags` stored in the top bits of the last byte, starting from the most
+/// significant one.
+pub(crate) fn compressed_bytes<F: PrimeField, CS: ConstraintSystem<F>>(
+    cs: CS,
+    x: &FpGadget<F>,
+    flags: &[Boolean],
+) -> Result<Vec<UInt8>, SynthesisError> {
+    let num_bits = compressed_num_bits::<F>(flags.len())?;
+    let mut bits = x.to_bits(cs)?;
+    bits.reverse();
+    bits.resize(num_bits - flags.len(), Boolean::constant(false));
+    bits.extend(flags.iter().rev().cloned());
+    Ok(bits.chunks(8).map(UInt8::from_bits_le).collect())
+}
+
+/// Inverse of `compressed_bytes`: enforces that the encoded x-coordinate is
+/// canonical and returns it together with the `num_flags` flags. Bytes of the
+/// wrong length are rejected with `SynthesisError::InvalidArgument`.
+pub(crate) fn x_and_flags_from_compressed_bytes<F: PrimeField, CS: ConstraintSystem<F>>(
+    cs: CS,
+    bytes: &[UInt8],
+    num_flags: usize,
+) -> Result<(FpGadget<F>, Vec<Boolean>), SynthesisError> {
+    let num_bits = compressed_num_bits::<F>(num_flags)?;
+    let bits = bytes
+        .iter()
+        .flat_map(UInt8::into_bits_le)
+        .collect::<Vec<_>>();
+    if bits.len() != num_bits {
+        return Err(SynthesisError::InvalidArgument);
+    }
+    let (x_bits, flags) = bits.split_at(num_bits - num_flags);
+    let x = FpGadget::from_bits_le(cs, x_bits)?;
+    Ok((x, flags.iter().rev().cloned().collect()))
+}
@@ -1,7 +1,7 @@
 use algebra::{
     curves::bls12::{Bls12Parameters, G1Prepared, G2Prepared, TwistType},
     fields::Field,
-    BitIterator, One, ProjectiveCurve,
+    BigInteger, BitIterator, One, PrimeField, ProjectiveCurve,
 };
 use r1cs_core::{ConstraintSystem, SynthesisError};
 
@@ -102,7 +102,9 @@ type LCoeff<P> = (Fp2G<P>, Fp2G<P>);
 #[derive(Derivative)]
 #[derivative(
     Clone(bound = "Fp2Gadget<P::Fp2Params, P::Fp>: Clone"),
-    Debug(bound = "Fp2Gadget<P::Fp2Params, P::Fp>: Debug")
+    Debug(bound = "Fp2Gadget<P::Fp2Params, P::Fp>: Debug"),
+    PartialEq(bound = "Fp2Gadget<P::Fp2Params, P::Fp>: PartialEq"),
+    Eq(bound = "Fp2Gadget<P::Fp2Params, P::Fp>: Eq")
 )]
 pub struct G2PreparedGadget<P: Bls12Parameters> {
     pub ell_coeffs: Vec<LCoeff<P>>,
@@ -185,7 +187,93 @@ impl<P: Bls12Parameters> ToBytesGadget<P::Fp> for G2PreparedGadget<P> {
     }
 }
 
+impl<P: Bls12Parameters> ConditionalEqGadget<P::Fp> for G2PreparedGadget<P> {
+    #[inline]
+    fn conditional_enforce_equal<CS: ConstraintSystem<P::Fp>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError> {
+        for (i, (a, b)) in self.ell_coeffs.iter().zip(&other.ell_coeffs).enumerate() {
+            let mut cs = cs.ns(|| format!("Iteration {}", i));
+            a.0.conditional_enforce_equal(cs.ns(|| "c0"), &b.0, condition)?;
+            a.1.conditional_enforce_equal(cs.ns(|| "c1"), &b.1, condition)?;
+        }
+        Ok(())
+    }
+
+    fn cost() -> usize {
+        Self::num_coeffs() * 2 * <Fp2G<P> as ConditionalEqGadget<P::Fp>>::cost()
+    }
+}
+
+impl<P: Bls12Parameters> EqGadget<P::Fp> for G2PreparedGadget<P> {}
+
 impl<P: Bls12Parameters> G2PreparedGadget<P> {
+    /// The length of the `ToBytes` encoding of a non-zero `G2Prepared<P>`.
+    pub fn num_bytes() -> usize {
+        // Each coefficient consists of three `Fp2` elements, and the encoding
+        // ends with the `infinity` flag.
+        Self::num_coeffs() * 3 * Self::fp2_num_bytes() + 1
+    }
+
+    /// The number of line coefficients: one per doubling step, and one per
+    /// addition step of the Miller loop.
+    fn num_coeffs() -> usize {
+        BitIterator::new(P::X)
+            .skip(1)
+            .map(|bit| if bit { 2 } else { 1 })
+            .sum::<usize>()
+    }
+
+    /// Parses the `ToBytes` encoding of a non-zero `G2Prepared<P>`, dividing
+    /// each line coefficient by its `z`-coordinate in-circuit, as
+    /// `alloc_constant` does natively. Nothing checks that the coefficients
+    /// belong to an actual point. Bytes of the wrong length are rejected with
+    /// `SynthesisError::InvalidArgument`.
+    pub fn from_bytes<CS: ConstraintSystem<P::Fp>>(
+        mut cs: CS,
+        bytes: &[UInt8],
+    ) -> Result<Self, SynthesisError> {
+        if bytes.len() != Self::num_bytes() {
+            return Err(SynthesisError::InvalidArgument);
+        }
+        let (coeff_bytes, infinity) = bytes.split_at(bytes.len() - 1);
+        infinity[0].enforce_equal(cs.ns(|| "not infinity"), &UInt8::constant(0))?;
+
+        let fp2_num_bytes = Self::fp2_num_bytes();
+        let mut ell_coeffs = Vec::new();
+        for (i, coeff) in coeff_bytes.chunks(3 * fp2_num_bytes).enumerate() {
+            let mut cs = cs.ns(|| format!("Iteration {}", i));
+            let (x, rest) = coeff.split_at(fp2_num_bytes);
+            let (y, z) = rest.split_at(fp2_num_bytes);
+            let x = Self::fp2_from_bytes(cs.ns(|| "x"), x)?;
+            let y = Self::fp2_from_bytes(cs.ns(|| "y"), y)?;
+            let z_inverse = Self::fp2_from_bytes(cs.ns(|| "z"), z)?.inverse(cs.ns(|| "z^-1"))?;
+
+            let x_normalized = x.mul(cs.ns(|| "normalize x"), &z_inverse)?;
+            let y_normalized = y.mul(cs.ns(|| "normalize y"), &z_inverse)?;
+            ell_coeffs.push((x_normalized, y_normalized));
+        }
+
+        Ok(Self { ell_coeffs })
+    }
+
+    fn fp2_num_bytes() -> usize {
+        2 * <P::Fp as PrimeField>::BigInt::NUM_LIMBS * 8
+    }
+
+    fn fp2_from_bytes<CS: ConstraintSystem<P::Fp>>(
+        mut cs: CS,
+        bytes: &[UInt8],
+    ) -> Result<Fp2G<P>, SynthesisError> {
+        let (c0, c1) = bytes.split_at(bytes.len() / 2);
+        let c0 = FpGadget::from_bytes_le(cs.ns(|| "c0"), c0)?;
+        let c1 = FpGadget::from_bytes_le(cs.ns(|| "c1"), c1)?;
+        Ok(Fp2G::<P>::new(c0, c1))
+    }
+
     pub fn from_affine<CS: ConstraintSystem<P::Fp>>(
         mut cs: CS,
         q: &G2Gadget<P>,
@@ -3,7 +3,7 @@ use algebra::{
         g2::{AteAdditionCoefficients, AteDoubleCoefficients},
         G1Prepared, G2Prepared, MNT4Parameters,
     },
-    Field,
+    BitIterator, Field,
 };
 use r1cs_core::{ConstraintSystem, SynthesisError};
 
@@ -159,7 +159,12 @@ impl<P: MNT4Parameters> ToBytesGadget<P::Fp> for G1PreparedGadget<P> {
 
 type Fp2G<P> = Fp2Gadget<<P as MNT4Parameters>::Fp2Params, <P as MNT4Parameters>::Fp>;
 #[derive(Derivative)]
-#[derivative(Clone(bound = "P: MNT4Parameters"), Debug(bound = "P: MNT4Parameters"))]
+#[derivative(
+    Clone(bound = "P: MNT4Parameters"),
+    Debug(bound = "P: MNT4Parameters"),
+    PartialEq(bound = "P: MNT4Parameters"),
+    Eq(bound = "P: MNT4Parameters")
+)]
 pub struct G2PreparedGadget<P: MNT4Parameters> {
     pub x: Fp2Gadget<P::Fp2Params, P::Fp>,
     pub y: Fp2Gadget<P::Fp2Params, P::Fp>,
@@ -292,6 +297,60 @@ impl<P: MNT4Parameters> ToBytesGadget<P::Fp> for G2PreparedGadget<P> {
     }
 }
 
+impl<P: MNT4Parameters> ConditionalEqGadget<P::Fp> for G2PreparedGadget<P> {
+    #[inline]
+    fn conditional_enforce_equal<CS: ConstraintSystem<P::Fp>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError> {
+        self.x
+            .conditional_enforce_equal(cs.ns(|| "x"), &other.x, condition)?;
+        self.y
+            .conditional_enforce_equal(cs.ns(|| "y"), &other.y, condition)?;
+        self.x_over_twist.conditional_enforce_equal(
+            cs.ns(|| "x_over_twist"),
+            &other.x_over_twist,
+            condition,
+        )?;
+        self.y_over_twist.conditional_enforce_equal(
+            cs.ns(|| "y_over_twist"),
+            &other.y_over_twist,
+            condition,
+        )?;
+        self.double_coefficients.conditional_enforce_equal(
+            cs.ns(|| "double_coefficients"),
+            &other.double_coefficients,
+            condition,
+        )?;
+        self.addition_coefficients.conditional_enforce_equal(
+            cs.ns(|| "addition_coefficients"),
+            &other.addition_coefficients,
+            condition,
+        )?;
+        Ok(())
+    }
+
+    fn cost() -> usize {
+        // One doubling step per bit of the loop count after the leading one,
+        // and one addition step per set bit, plus one more if the loop count
+        // is negative.
+        let bits = BitIterator::new(P::ATE_LOOP_COUNT)
+            .skip_while(|b| !b)
+            .skip(1)
+            .collect::<Vec<_>>();
+        let num_double = bits.len();
+        let num_addition = bits.iter().filter(|b| **b).count() + P::ATE_IS_LOOP_COUNT_NEG as usize;
+        4 * <Fp2G<P> as ConditionalEqGadget<P::Fp>>::cost()
+            + num_double * <AteDoubleCoefficientsGadget<P> as ConditionalEqGadget<P::Fp>>::cost()
+            + num_addition
+                * <AteAdditionCoefficientsGadget<P> as ConditionalEqGadget<P::Fp>>::cost()
+    }
+}
+
+impl<P: MNT4Parameters> EqGadget<P::Fp> for G2PreparedGadget<P> {}
+
 impl<P: MNT4Parameters> G2PreparedGadget<P> {
     pub fn get_value(&self) -> Option<G2Prepared<P>> {
         match (
@@ -412,7 +471,12 @@ impl<P: MNT4Parameters> G2PreparedGadget<P> {
 }
 
 #[derive(Derivative)]
-#[derivative(Clone(bound = "P: MNT4Parameters"), Debug(bound = "P: MNT4Parameters"))]
+#[derivative(
+    Clone(bound = "P: MNT4Parameters"),
+    Debug(bound = "P: MNT4Parameters"),
+    PartialEq(bound = "P: MNT4Parameters"),
+    Eq(bound = "P: MNT4Parameters")
+)]
 pub struct AteDoubleCoefficientsGadget<P: MNT4Parameters> {
     pub c_h: Fp2Gadget<P::Fp2Params, P::Fp>,
     pub c_4c: Fp2Gadget<P::Fp2Params, P::Fp>,
@@ -511,6 +575,32 @@ impl<P: MNT4Parameters> ToBytesGadget<P::Fp> for AteDoubleCoefficientsGadget<P>
     }
 }
 
+impl<P: MNT4Parameters> ConditionalEqGadget<P::Fp> for AteDoubleCoefficientsGadget<P> {
+    #[inline]
+    fn conditional_enforce_equal<CS: ConstraintSystem<P::Fp>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError> {
+        self.c_h
+            .conditional_enforce_equal(cs.ns(|| "c_h"), &other.c_h, condition)?;
+        self.c_4c
+            .conditional_enforce_equal(cs.ns(|| "c_4c"), &other.c_4c, condition)?;
+        self.c_j
+            .conditional_enforce_equal(cs.ns(|| "c_j"), &other.c_j, condition)?;
+        self.c_l
+            .conditional_enforce_equal(cs.ns(|| "c_l"), &other.c_l, condition)?;
+        Ok(())
+    }
+
+    fn cost() -> usize {
+        4 * <Fp2G<P> as ConditionalEqGadget<P::Fp>>::cost()
+    }
+}
+
+impl<P: MNT4Parameters> EqGadget<P::Fp> for AteDoubleCoefficientsGadget<P> {}
+
 impl<P: MNT4Parameters> AteDoubleCoefficientsGadget<P> {
     pub fn get_value(&self) -> Option<AteDoubleCoefficients<P>> {
         match (
@@ -531,7 +621,12 @@ impl<P: MNT4Parameters> AteDoubleCoefficientsGadget<P> {
 }
 
 #[derive(Derivative)]
-#[derivative(Clone(bound = "P: MNT4Parameters"), Debug(bound = "P: MNT4Parameters"))]
+#[derivative(
+    Clone(bound = "P: MNT4Parameters"),
+    Debug(bound = "P: MNT4Parameters"),
+    PartialEq(bound = "P: MNT4Parameters"),
+    Eq(bound = "P: MNT4Parameters")
+)]
 pub struct AteAdditionCoefficientsGadget<P: MNT4Parameters> {
     pub c_l1: Fp2Gadget<P::Fp2Params, P::Fp>,
     pub c_rz: Fp2Gadget<P::Fp2Params, P::Fp>,
@@ -608,6 +703,28 @@ impl<P: MNT4Parameters> ToBytesGadget<P::Fp> for AteAdditionCoefficientsGadget<P
     }
 }
 
+impl<P: MNT4Parameters> ConditionalEqGadget<P::Fp> for AteAdditionCoefficientsGadget<P> {
+    #[inline]
+    fn conditional_enforce_equal<CS: ConstraintSystem<P::Fp>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError> {
+        self.c_l1
+            .conditional_enforce_equal(cs.ns(|| "c_l1"), &other.c_l1, condition)?;
+        self.c_rz
+            .conditional_enforce_equal(cs.ns(|| "c_rz"), &other.c_rz, condition)?;
+        Ok(())
+    }
+
+    fn cost() -> usize {
+        2 * <Fp2G<P> as ConditionalEqGadget<P::Fp>>::cost()
+    }
+}
+
+impl<P: MNT4Parameters> EqGadget<P::Fp> for AteAdditionCoefficientsGadget<P> {}
+
 impl<P: MNT4Parameters> AteAdditionCoefficientsGadget<P> {
     pub fn get_value(&self) -> Option<AteAdditionCoefficients<P>> {
         match (self.c_l1.get_value(), self.c_rz.get_value()) {
@@ -3,7 +3,7 @@ use algebra::{
         g2::{AteAdditionCoefficients, AteDoubleCoefficients},
         G1Prepared, G2Prepared, MNT6Parameters,
     },
-    Field,
+    BitIterator, Field,
 };
 use core::borrow::Borrow;
 use r1cs_core::{ConstraintSystem, SynthesisError};
@@ -163,7 +163,12 @@ impl<P: MNT6Parameters> ToBytesGadget<P::Fp> for G1PreparedGadget<P> {
 
 type Fp3G<P> = Fp3Gadget<<P as MNT6Parameters>::Fp3Params, <P as MNT6Parameters>::Fp>;
 #[derive(Derivative)]
-#[derivative(Clone(bound = "P: MNT6Parameters"), Debug(bound = "P: MNT6Parameters"))]
+#[derivative(
+    Clone(bound = "P: MNT6Parameters"),
+    Debug(bound = "P: MNT6Parameters"),
+    PartialEq(bound = "P: MNT6Parameters"),
+    Eq(bound = "P: MNT6Parameters")
+)]
 pub struct G2PreparedGadget<P: MNT6Parameters> {
     pub x: Fp3Gadget<P::Fp3Params, P::Fp>,
     pub y: Fp3Gadget<P::Fp3Params, P::Fp>,
@@ -302,6 +307,60 @@ impl<P: MNT6Parameters> ToBytesGadget<P::Fp> for G2PreparedGadget<P> {
     }
 }
 
+impl<P: MNT6Parameters> ConditionalEqGadget<P::Fp> for G2PreparedGadget<P> {
+    #[inline]
+    fn conditional_enforce_equal<CS: ConstraintSystem<P::Fp>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError> {
+        self.x
+            .conditional_enforce_equal(cs.ns(|| "x"), &other.x, condition)?;
+        self.y
+            .conditional_enforce_equal(cs.ns(|| "y"), &other.y, condition)?;
+        self.x_over_twist.conditional_enforce_equal(
+            cs.ns(|| "x_over_twist"),
+            &other.x_over_twist,
+            condition,
+        )?;
+        self.y_over_twist.conditional_enforce_equal(
+            cs.ns(|| "y_over_twist"),
+            &other.y_over_twist,
+            condition,
+        )?;
+        self.double_coefficients.conditional_enforce_equal(
+            cs.ns(|| "double_coefficients"),
+            &other.double_coefficients,
+            condition,
+        )?;
+        self.addition_coefficients.conditional_enforce_equal(
+            cs.ns(|| "addition_coefficients"),
+            &other.addition_coefficients,
+            condition,
+        )?;
+        Ok(())
+    }
+
+    fn cost() -> usize {
+        // One doubling step per bit of the loop count after the leading one,
+        // and one addition step per set bit, plus one more if the loop count
+        // is negative.
+        let bits = BitIterator::new(P::ATE_LOOP_COUNT)
+            .skip_while(|b| !b)
+            .skip(1)
+            .collect::<Vec<_>>();
+        let num_double = bits.len();
+        let num_addition = bits.iter().filter(|b| **b).count() + P::ATE_IS_LOOP_COUNT_NEG as usize;
+        4 * <Fp3G<P> as ConditionalEqGadget<P::Fp>>::cost()
+            + num_double * <AteDoubleCoefficientsGadget<P> as ConditionalEqGadget<P::Fp>>::cost()
+            + num_addition
+                * <AteAdditionCoefficientsGadget<P> as ConditionalEqGadget<P::Fp>>::cost()
+    }
+}
+
+impl<P: MNT6Parameters> EqGadget<P::Fp> for G2PreparedGadget<P> {}
+
 impl<P: MNT6Parameters> G2PreparedGadget<P> {
     pub fn get_value(&self) -> Option<G2Prepared<P>> {
         match (
@@ -422,7 +481,12 @@ impl<P: MNT6Parameters> G2PreparedGadget<P> {
 }
 
 #[derive(Derivative)]
-#[derivative(Clone(bound = "P: MNT6Parameters"), Debug(bound = "P: MNT6Parameters"))]
+#[derivative(
+    Clone(bound = "P: MNT6Parameters"),
+    Debug(bound = "P: MNT6Parameters"),
+    PartialEq(bound = "P: MNT6Parameters"),
+    Eq(bound = "P: MNT6Parameters")
+)]
 pub struct AteDoubleCoefficientsGadget<P: MNT6Parameters> {
     pub c_h: Fp3Gadget<P::Fp3Params, P::Fp>,
     pub c_4c: Fp3Gadget<P::Fp3Params, P::Fp>,
@@ -520,6 +584,32 @@ impl<P: MNT6Parameters> ToBytesGadget<P::Fp> for AteDoubleCoefficientsGadget<P>
     }
 }
 
+impl<P: MNT6Parameters> ConditionalEqGadget<P::Fp> for AteDoubleCoefficientsGadget<P> {
+    #[inline]
+    fn conditional_enforce_equal<CS: ConstraintSystem<P::Fp>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError> {
+        self.c_h
+            .conditional_enforce_equal(cs.ns(|| "c_h"), &other.c_h, condition)?;
+        self.c_4c
+            .conditional_enforce_equal(cs.ns(|| "c_4c"), &other.c_4c, condition)?;
+        self.c_j
+            .conditional_enforce_equal(cs.ns(|| "c_j"), &other.c_j, condition)?;
+        self.c_l
+            .conditional_enforce_equal(cs.ns(|| "c_l"), &other.c_l, condition)?;
+        Ok(())
+    }
+
+    fn cost() -> usize {
+        4 * <Fp3G<P> as ConditionalEqGadget<P::Fp>>::cost()
+    }
+}
+
+impl<P: MNT6Parameters> EqGadget<P::Fp> for AteDoubleCoefficientsGadget<P> {}
+
 impl<P: MNT6Parameters> AteDoubleCoefficientsGadget<P> {
     pub fn get_value(&self) -> Option<AteDoubleCoefficients<P>> {
         match (
@@ -540,7 +630,12 @@ impl<P: MNT6Parameters> AteDoubleCoefficientsGadget<P> {
 }
 
 #[derive(Derivative)]
-#[derivative(Clone(bound = "P: MNT6Parameters"), Debug(bound = "P: MNT6Parameters"))]
+#[derivative(
+    Clone(bound = "P: MNT6Parameters"),
+    Debug(bound = "P: MNT6Parameters"),
+    PartialEq(bound = "P: MNT6Parameters"),
+    Eq(bound = "P: MNT6Parameters")
+)]
 pub struct AteAdditionCoefficientsGadget<P: MNT6Parameters> {
     pub c_l1: Fp3Gadget<P::Fp3Params, P::Fp>,
     pub c_rz: Fp3Gadget<P::Fp3Params, P::Fp>,
@@ -613,6 +708,28 @@ impl<P: MNT6Parameters> ToBytesGadget<P::Fp> for AteAdditionCoefficientsGadget<P
     }
 }
 
+impl<P: MNT6Parameters> ConditionalEqGadget<P::Fp> for AteAdditionCoefficientsGadget<P> {
+    #[inline]
+    fn conditional_enforce_equal<CS: ConstraintSystem<P::Fp>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError> {
+        self.c_l1
+            .conditional_enforce_equal(cs.ns(|| "c_l1"), &other.c_l1, condition)?;
+        self.c_rz
+            .conditional_enforce_equal(cs.ns(|| "c_rz"), &other.c_rz, condition)?;
+        Ok(())
+    }
+
+    fn cost() -> usize {
+        2 * <Fp3G<P> as ConditionalEqGadget<P::Fp>>::cost()
+    }
+}
+
+impl<P: MNT6Parameters> EqGadget<P::Fp> for AteAdditionCoefficientsGadget<P> {}
+
 impl<P: MNT6Parameters> AteAdditionCoefficientsGadget<P> {
     pub fn get_value(&self) -> Option<AteAdditionCoefficients<P>> {
         match (self.c_l1.get_value(), self.c_rz.get_value()) {
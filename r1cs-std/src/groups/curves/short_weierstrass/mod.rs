@@ -1,12 +1,13 @@
 use algebra::{
     curves::{
         short_weierstrass_jacobian::{GroupAffine as SWAffine, GroupProjective as SWProjective},
-        SWModelParameters,
+        GLVParameters, SWModelParameters,
     },
-    AffineCurve, BitIterator, Field, One, PrimeField, ProjectiveCurve, Zero,
+    biginteger::BigInteger,
+    AffineCurve, BitIterator, Field, One, PrimeField, ProjectiveCurve, SquareRootField, Zero,
 };
 use core::{borrow::Borrow, marker::PhantomData, ops::Neg};
-use r1cs_core::{ConstraintSystem, SynthesisError};
+use r1cs_core::{ConstraintSystem, LinearCombination, SynthesisError};
 
 use crate::{prelude::*, Assignment, Vec};
 
@@ -69,6 +70,22 @@ impl<P: SWModelParameters, ConstraintF: Field, F: FieldGadget<P::BaseField, Cons
         }
     }
 
+    /// Outputs the affine coordinates `(x, y)` of `self` and whether it is
+    /// the identity. The coordinates of the identity are normalized to
+    /// `(0, 1)`, as for the corresponding affine point, so that equal points
+    /// always have equal coordinates; use these rather than `self.x` and
+    /// `self.y` when hashing a point.
+    pub fn to_affine_coords<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<(F, F, Boolean), SynthesisError> {
+        let zero = F::zero(cs.ns(|| "zero"))?;
+        let one = F::one(cs.ns(|| "one"))?;
+        let x = F::conditionally_select(cs.ns(|| "x or zero"), &self.infinity, &zero, &self.x)?;
+        let y = F::conditionally_select(cs.ns(|| "y or one"), &self.infinity, &one, &self.y)?;
+        Ok((x, y, self.infinity))
+    }
+
     pub fn alloc_without_check<FN, CS: ConstraintSystem<ConstraintF>>(
         mut cs: CS,
         value_gen: F,
@@ -96,6 +113,452 @@ impl<P: SWModelParameters, ConstraintF: Field, F: FieldGadget<P::BaseField, Cons
     }
 }
 
+impl<P, ConstraintF> AffineGadget<P, ConstraintF, FpGadget<ConstraintF>>
+where
+    P: SWModelParameters<BaseField = ConstraintF>,
+    ConstraintF: PrimeField + SquareRootField,
+{
+    /// Decompresses a point from its x-coordinate and the parity of its
+    /// y-coordinate, enforcing `y^2 = x^3 + a * x + b` and that the parity of
+    /// `y` matches `y_is_odd`. If `x` is not the x-coordinate of a point, the
+    /// constraint system is unsatisfiable.
+    pub fn from_x_and_sign<CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        x: FpGadget<ConstraintF>,
+        y_is_odd: &Boolean,
+    ) -> Result<Self, SynthesisError> {
+        Self::from_x_and_y_bit(cs, x, y_is_odd, false)
+    }
+
+    /// Outputs the compressed encoding of `self`, matching the
+    /// `CanonicalSerialize` output of the corresponding affine point: the
+    /// little-endian bytes of `x`, with the top bit of the last byte set iff
+    /// `y > -y` and the next bit set iff `self` is the identity.
+    pub fn to_compressed_bytes<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Vec<UInt8>, SynthesisError> {
+        let zero = FpGadget::zero(cs.ns(|| "zero"))?;
+        let x = FpGadget::conditionally_select(
+            cs.ns(|| "x or zero"),
+            &self.infinity,
+            &zero,
+            &self.x,
+        )?;
+        // y > -y iff 2 * y overflows the modulus, i.e. iff 2 * y is odd.
+        let y_is_positive = self
+            .y
+            .double(cs.ns(|| "2 * y"))?
+            .is_odd(cs.ns(|| "y is positive"))?;
+        let y_is_positive = Boolean::and(
+            cs.ns(|| "not infinity and y is positive"),
+            &self.infinity.not(),
+            &y_is_positive,
+        )?;
+        super::compressed_bytes(cs.ns(|| "to bytes"), &x, &[y_is_positive, self.infinity])
+    }
+
+    /// Outputs the uncompressed encoding of `self`, matching the
+    /// `CanonicalSerialize::serialize_uncompressed` output of the
+    /// corresponding affine point: the little-endian bytes of `x` and then of
+    /// `y`, with the second most significant bit of the last byte set iff
+    /// `self` is the identity, which is encoded as `(0, 1)`.
+    ///
+    /// Unlike `to_bytes`, which mirrors `algebra`'s `ToBytes` and pads each
+    /// coordinate to whole limbs, this uses only as many bytes per coordinate
+    /// as the modulus needs.
+    pub fn to_uncompressed_bytes<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Vec<UInt8>, SynthesisError> {
+        let (x, y, infinity) = self.to_affine_coords(cs.ns(|| "affine coords"))?;
+        let mut bytes = super::compressed_bytes(cs.ns(|| "x to bytes"), &x, &[])?;
+        bytes.extend(super::compressed_bytes(
+            cs.ns(|| "y to bytes"),
+            &y,
+            &[Boolean::constant(false), infinity],
+        )?);
+        Ok(bytes)
+    }
+
+    /// Decompresses the output of `to_compressed_bytes`. Unlike `algebra`'s
+    /// deserialization, this does not check that the point lies in the
+    /// prime-order subgroup. Bytes of the wrong length are rejected with
+    /// `SynthesisError::InvalidArgument`.
+    pub fn from_compressed_bytes<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        bytes: &[UInt8],
+    ) -> Result<Self, SynthesisError> {
+        let (x, flags) =
+            super::x_and_flags_from_compressed_bytes(cs.ns(|| "from bytes"), bytes, 2)?;
+        let (y_is_positive, infinity) = (flags[0], flags[1]);
+        let zero = FpGadget::zero(cs.ns(|| "zero"))?;
+        x.conditional_enforce_equal(cs.ns(|| "x is zero at infinity"), &zero, &infinity)?;
+
+        // The identity has no y-coordinate, so decompress the generator in
+        // its place to keep the system satisfiable.
+        let generator_x =
+            FpGadget::alloc_constant(cs.ns(|| "generator x"), P::AFFINE_GENERATOR_COEFFS.0)?;
+        let x = FpGadget::conditionally_select(
+            cs.ns(|| "x or generator x"),
+            &infinity,
+            &generator_x,
+            &x,
+        )?;
+        let point = Self::from_x_and_y_bit(cs.ns(|| "decompress"), x, &y_is_positive, true)?;
+        let zero = Self::zero(cs.ns(|| "identity"))?;
+        Self::conditionally_select(cs.ns(|| "select identity"), &infinity, &zero, &point)
+    }
+
+    /// Shared by `from_x_and_sign` and `from_compressed_bytes`: `y_bit` is the
+    /// parity of `y`, or, if `double_y` is set, of `2 * y`.
+    fn from_x_and_y_bit<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        x: FpGadget<ConstraintF>,
+        y_bit: &Boolean,
+        double_y: bool,
+    ) -> Result<Self, SynthesisError> {
+        let y = FpGadget::alloc(cs.ns(|| "y"), || {
+            let x = x.get_value().get()?;
+            let y2 = P::add_b(&(x.square() * &x + &P::mul_by_a(&x)));
+            // If there is no square root, any y leaves the system unsatisfied.
+            let y = y2.sqrt().unwrap_or_else(ConstraintF::zero);
+            let bit_value = if double_y { y.double() } else { y };
+            if bit_value.into_repr().is_odd() == y_bit.get_value().get()? {
+                Ok(y)
+            } else {
+                Ok(-y)
+            }
+        })?;
+
+        let x3 = x
+            .square(cs.ns(|| "x^2"))?
+            .mul(cs.ns(|| "x^3"), &x)?;
+        let rhs = x
+            .mul_by_constant(cs.ns(|| "a * x"), &P::COEFF_A)?
+            .add(cs.ns(|| "x^3 + a * x"), &x3)?
+            .add_constant(cs.ns(|| "x^3 + a * x + b"), &P::COEFF_B)?;
+        y.mul_equals(cs.ns(|| "check y^2"), &y, &rhs)?;
+        let bit = if double_y {
+            y.double(cs.ns(|| "2 * y"))?
+                .is_odd(cs.ns(|| "2 * y is odd"))?
+        } else {
+            y.is_odd(cs.ns(|| "y is odd"))?
+        };
+        bit.enforce_equal(cs.ns(|| "check y bit"), y_bit)?;
+
+        Ok(Self::new(x, y, Boolean::Constant(false)))
+    }
+}
+
+impl<P, ConstraintF, F> AffineGadget<P, ConstraintF, F>
+where
+    P: SWModelParameters,
+    ConstraintF: PrimeField,
+    F: FieldGadget<P::BaseField, ConstraintF>,
+{
+    /// Complete addition: unlike `add`, either operand may be the neutral
+    /// element, and the operands may be equal or each other's negation.
+    ///
+    /// The slope is computed from the chord or, when the x-coordinates agree,
+    /// the tangent, and the result is then selected among the computed sum,
+    /// the neutral element and the operands themselves. Points with `y = 0`
+    /// (of order two) are not supported; they do not lie in the prime-order
+    /// subgroup.
+    pub fn add_unified<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let x2_minus_x1 = other.x.sub(cs.ns(|| "x2 - x1"), &self.x)?;
+        let y2_minus_y1 = other.y.sub(cs.ns(|| "y2 - y1"), &self.y)?;
+        let x_equal = crate::fields::is_zero(cs.ns(|| "x1 == x2"), &x2_minus_x1)?;
+        let y_equal = crate::fields::is_zero(cs.ns(|| "y1 == y2"), &y2_minus_y1)?;
+        // If the x-coordinates agree but the points differ, they are each
+        // other's negation.
+        let sum_is_zero = Boolean::and(cs.ns(|| "sum is zero"), &x_equal, &y_equal.not())?;
+        let either_is_zero = Boolean::or(
+            cs.ns(|| "either is zero"),
+            &self.infinity,
+            &other.infinity,
+        )?;
+
+        // Tangent slope: (3 * x1^2 + a) / (2 * y1).
+        let three = P::BaseField::one().double() + &P::BaseField::one();
+        let tangent_n = self
+            .x
+            .square(cs.ns(|| "x1^2"))?
+            .mul_by_constant(cs.ns(|| "3 * x1^2"), &three)?
+            .add_constant(cs.ns(|| "3 * x1^2 + a"), &P::COEFF_A)?;
+        let tangent_d = self.y.double(cs.ns(|| "2 * y1"))?;
+        let lambda_n = F::conditionally_select(
+            cs.ns(|| "select numerator"),
+            &x_equal,
+            &tangent_n,
+            &y2_minus_y1,
+        )?;
+        let lambda_d = F::conditionally_select(
+            cs.ns(|| "select denominator"),
+            &x_equal,
+            &tangent_d,
+            &x2_minus_x1,
+        )?;
+        // In the cases where the computed sum is discarded, the denominator
+        // may be zero, so replace it by one to keep the system satisfiable.
+        let is_discarded = Boolean::or(
+            cs.ns(|| "sum is discarded"),
+            &either_is_zero,
+            &sum_is_zero,
+        )?;
+        let one = F::one(cs.ns(|| "one"))?;
+        let lambda_d = F::conditionally_select(
+            cs.ns(|| "safe denominator"),
+            &is_discarded,
+            &one,
+            &lambda_d,
+        )?;
+        let lambda = F::alloc(cs.ns(|| "lambda"), || {
+            let d = lambda_d.get_value().get()?.inverse().get()?;
+            Ok(lambda_n.get_value().get()? * &d)
+        })?;
+        lambda.mul_equals(cs.ns(|| "check lambda"), &lambda_d, &lambda_n)?;
+
+        let x_3 = lambda
+            .square(cs.ns(|| "lambda^2"))?
+            .sub(cs.ns(|| "lambda^2 - x1"), &self.x)?
+            .sub(cs.ns(|| "lambda^2 - x1 - x2"), &other.x)?;
+        let y_3 = self
+            .x
+            .sub(cs.ns(|| "x1 - x3"), &x_3)?
+            .mul(cs.ns(|| "times lambda"), &lambda)?
+            .sub(cs.ns(|| "minus y1"), &self.y)?;
+        let sum = Self::new(x_3, y_3, Boolean::Constant(false));
+
+        let zero = Self::zero(cs.ns(|| "zero"))?;
+        let result = Self::conditionally_select(
+            cs.ns(|| "select zero"),
+            &sum_is_zero,
+            &zero,
+            &sum,
+        )?;
+        let result = Self::conditionally_select(
+            cs.ns(|| "select self"),
+            &other.infinity,
+            self,
+            &result,
+        )?;
+        Self::conditionally_select(cs.ns(|| "select other"), &self.infinity, other, &result)
+    }
+
+    /// A fixed point of the prime-order subgroup from which sums of variable
+    /// points start, since `add` would treat the identity as an ordinary
+    /// point. It is the cofactor multiple of the first point with
+    /// `x = 1, 2, ...`, so its discrete logarithm is unknown and honest
+    /// inputs only hit the exceptional cases of `add` with negligible
+    /// probability.
+    fn offset() -> SWProjective<P> {
+        let mut x = P::BaseField::one();
+        loop {
+            if let Some(point) = SWAffine::<P>::get_point_from_x(x, false) {
+                let point = point.scale_by_cofactor();
+                if !point.is_zero() {
+                    return point;
+                }
+            }
+            x += &P::BaseField::one();
+        }
+    }
+
+    /// Replaces `self` by the generator if it is the identity, and clears
+    /// `bits` in that case, so that the identity never reaches `add` while
+    /// `bits * self` is unchanged.
+    fn non_zero_base<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        bits: &[Boolean],
+    ) -> Result<(Self, Vec<Boolean>), SynthesisError> {
+        let generator = Self::alloc_constant(
+            cs.ns(|| "generator"),
+            SWProjective::<P>::prime_subgroup_generator(),
+        )?;
+        let base = Self::conditionally_select(
+            cs.ns(|| "select base"),
+            &self.infinity,
+            &generator,
+            self,
+        )?;
+        let is_non_zero = self.infinity.not();
+        let bits = bits
+            .iter()
+            .enumerate()
+            .map(|(i, bit)| {
+                Boolean::and(cs.ns(|| format!("clear bit {}", i)), bit, &is_non_zero)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((base, bits))
+    }
+
+    /// Enforces that the coordinates of `self` satisfy `y^2 = x^3 + a * x + b`.
+    /// `infinity` is ignored, so the coordinates `(0, 1)` used for the
+    /// identity only pass if `b = 1`.
+    pub fn enforce_on_curve<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<(), SynthesisError> {
+        // We do this by checking that y^2 - b = x * (x^2 + a)
+        let x2 = self.x.square(&mut cs.ns(|| "x^2"))?;
+        let y2 = self.y.square(&mut cs.ns(|| "y^2"))?;
+
+        let x2_plus_a = x2.add_constant(cs.ns(|| "x^2 + a"), &P::COEFF_A)?;
+        let y2_minus_b = y2.add_constant(cs.ns(|| "y^2 - b"), &P::COEFF_B.neg())?;
+
+        x2_plus_a.mul_equals(cs.ns(|| "on curve check"), &self.x, &y2_minus_b)
+    }
+
+    /// Outputs whether the coordinates of `self` satisfy the curve equation;
+    /// see `enforce_on_curve`.
+    pub fn is_on_curve<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Boolean, SynthesisError> {
+        let x2 = self.x.square(&mut cs.ns(|| "x^2"))?;
+        let y2 = self.y.square(&mut cs.ns(|| "y^2"))?;
+
+        let x3_plus_ax = x2
+            .add_constant(cs.ns(|| "x^2 + a"), &P::COEFF_A)?
+            .mul(cs.ns(|| "x^3 + a * x"), &self.x)?;
+        let difference = y2
+            .add_constant(cs.ns(|| "y^2 - b"), &P::COEFF_B.neg())?
+            .sub(cs.ns(|| "y^2 - x^3 - a * x - b"), &x3_plus_ax)?;
+        crate::fields::is_zero(cs.ns(|| "is on curve"), &difference)
+    }
+}
+
+impl<P, ConstraintF, F> AffineGadget<P, ConstraintF, F>
+where
+    P: GLVParameters,
+    ConstraintF: PrimeField,
+    F: FieldGadget<P::BaseField, ConstraintF>,
+{
+    /// Applies the GLV endomorphism `(x, y) -> (ENDO_COEFF * x, y)`, which
+    /// multiplies points of the prime-order subgroup by `P::LAMBDA`.
+    pub fn endomorphism<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Self, SynthesisError> {
+        let x = self.x.mul_by_constant(cs.ns(|| "x * endo"), &P::ENDO_COEFF)?;
+        Ok(Self::new(x, self.y.clone(), self.infinity))
+    }
+
+    /// Computes `result + k * self`, where `k` is given as bits in
+    /// *little-endian* form, by splitting it into two halves
+    /// `k = k1 + LAMBDA * k2` and running a simultaneous double-and-add over
+    /// `self` and its endomorphism image, which needs half as many doublings
+    /// as `mul_bits`.
+    ///
+    /// The halves are allocated as bits, and a single linear constraint
+    /// checks `k = k1 + LAMBDA * k2`. Both sides are integers far below the
+    /// modulus of `ConstraintF`, so the check holds over the integers and
+    /// hence modulo the scalar field order. The circuit depends only on the
+    /// number of bits of `k`, which must be at most
+    /// `P::ScalarField::size_in_bits()` and less than
+    /// `ConstraintF::size_in_bits() - 2`; longer scalars are rejected with
+    /// `SynthesisError::InvalidArgument`.
+    ///
+    /// Like `mul_bits`, this uses incomplete addition, so neither `self` nor
+    /// `result` may be the identity element, and `self` must lie in the
+    /// prime-order subgroup.
+    pub fn glv_mul<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        result: &Self,
+        scalar_bits: &[Boolean],
+    ) -> Result<Self, SynthesisError> {
+        let num_bits = scalar_bits.len();
+        if num_bits > P::ScalarField::size_in_bits()
+            || num_bits + 2 >= ConstraintF::size_in_bits()
+        {
+            return Err(SynthesisError::InvalidArgument);
+        }
+        // k1 < LAMBDA and k2 <= k / 2^(k1_len - 1).
+        let lambda = P::LAMBDA.into_repr();
+        let k1_len = lambda.num_bits() as usize;
+        let k2_len = (num_bits + 1).saturating_sub(k1_len);
+
+        let halves = scalar_bits
+            .iter()
+            .rev()
+            .map(|bit| bit.get_value())
+            .collect::<Option<Vec<_>>>()
+            .map(|bits| P::glv_decompose_repr(BigInteger::from_bits(&bits)));
+        let k1_bits = (0..k1_len)
+            .map(|i| {
+                Boolean::alloc(cs.ns(|| format!("k1 bit {}", i)), || {
+                    halves.map(|(k1, _)| k1.get_bit(i)).get()
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let k2_bits = (0..k2_len)
+            .map(|i| {
+                Boolean::alloc(cs.ns(|| format!("k2 bit {}", i)), || {
+                    halves.map(|(_, k2)| k2.get_bit(i)).get()
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut lambda_coeff = ConstraintF::zero();
+        for bit in BitIterator::new(lambda) {
+            lambda_coeff.double_in_place();
+            if bit {
+                lambda_coeff += &ConstraintF::one();
+            }
+        }
+        let one = CS::one();
+        let mut decomposition = LinearCombination::zero();
+        let mut coeff = ConstraintF::one();
+        for bit in scalar_bits {
+            decomposition = decomposition + bit.lc(one, coeff);
+            coeff.double_in_place();
+        }
+        let mut coeff = ConstraintF::one();
+        for bit in &k1_bits {
+            decomposition = decomposition - bit.lc(one, coeff);
+            coeff.double_in_place();
+        }
+        let mut coeff = lambda_coeff;
+        for bit in &k2_bits {
+            decomposition = decomposition - bit.lc(one, coeff);
+            coeff.double_in_place();
+        }
+        cs.enforce(
+            || "k = k1 + lambda * k2",
+            |_| decomposition,
+            |lc| lc + one,
+            |lc| lc,
+        );
+
+        let mut power = self.clone();
+        let mut result = result.clone();
+        for i in 0..core::cmp::max(k1_len, k2_len) {
+            let mut cs = cs.ns(|| format!("Iteration {}", i));
+            if i > 0 {
+                power.double_in_place(cs.ns(|| "Double"))?;
+            }
+            let endo_power = power.endomorphism(cs.ns(|| "Endomorphism"))?;
+            if let Some(bit) = k1_bits.get(i) {
+                let sum = result.add(cs.ns(|| "Add power"), &power)?;
+                result = Self::conditionally_select(cs.ns(|| "Select power"), bit, &sum, &result)?;
+            }
+            if let Some(bit) = k2_bits.get(i) {
+                let sum = result.add(cs.ns(|| "Add endomorphism"), &endo_power)?;
+                result =
+                    Self::conditionally_select(cs.ns(|| "Select endomorphism"), bit, &sum, &result)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
 impl<P, ConstraintF, F> PartialEq for AffineGadget<P, ConstraintF, F>
 where
     P: SWModelParameters,
@@ -349,6 +812,218 @@ where
         ))
     }
 
+    fn conditional_negate<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        cond: &Boolean,
+    ) -> Result<Self, SynthesisError> {
+        let neg_y = self.y.negate(cs.ns(|| "negate y"))?;
+        let y = F::conditionally_select(cs.ns(|| "select y"), cond, &neg_y, &self.y)?;
+        Ok(Self::new(self.x.clone(), y, self.infinity))
+    }
+
+    fn add_unified<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        cs: CS,
+        other: &Self,
+    ) -> Result<Self, SynthesisError> {
+        AffineGadget::add_unified(self, cs, other)
+    }
+
+    /// Window `k` of all but the last window selects
+    /// `(j + 2) * 2^(window * k) * base`, so these tables exclude the
+    /// identity and each partial sum stays below the next selected multiple,
+    /// which keeps the incomplete `add` away from its exceptional cases. The
+    /// last window takes the offsets away again and is added with
+    /// `add_unified`, so that a zero scalar yields the identity.
+    fn fixed_base_scalar_mul<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        base: &SWProjective<P>,
+        bits: &[Boolean],
+        window: usize,
+    ) -> Result<Self, SynthesisError> {
+        if window == 0 {
+            return Err(SynthesisError::InvalidArgument);
+        }
+        let num_windows = (bits.len() + window - 1) / window;
+        let mut result: Option<Self> = None;
+        let mut base_power = *base;
+        let mut offset = SWProjective::<P>::zero();
+        for (i, chunk) in bits.chunks(window).enumerate() {
+            let is_last = i + 1 == num_windows;
+            let mut multiple = if is_last {
+                -offset
+            } else {
+                base_power + &base_power
+            };
+            let mut table = Vec::with_capacity(1 << chunk.len());
+            for j in 0..(1 << chunk.len()) {
+                table.push(Self::alloc_constant(
+                    cs.ns(|| format!("Window {} multiple {}", i, j)),
+                    multiple,
+                )?);
+                multiple += &base_power;
+            }
+            offset += &(base_power + &base_power);
+            // The lookup expects its index in big-endian form.
+            let index_bits = chunk.iter().rev().cloned().collect::<Vec<_>>();
+            let selected = conditionally_select_power_of_two_vector(
+                cs.ns(|| format!("Select window {}", i)),
+                &index_bits,
+                &table,
+            )?;
+            result = Some(match result {
+                None => selected,
+                Some(sum) if is_last => {
+                    sum.add_unified(cs.ns(|| format!("Add window {}", i)), &selected)?
+                },
+                Some(sum) => sum.add(cs.ns(|| format!("Add window {}", i)), &selected)?,
+            });
+            for _ in 0..chunk.len() {
+                base_power.double_in_place();
+            }
+        }
+        match result {
+            Some(result) => Ok(result),
+            None => Self::zero(cs.ns(|| "Declare Result")),
+        }
+    }
+
+    /// Runs `mul_bits` from a fixed offset point instead of the identity, and
+    /// takes the offset away again with `add_unified`, so that a zero product
+    /// yields the identity. An identity `self` is replaced by the generator
+    /// and its bits are cleared.
+    fn scalar_mul_le<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        scalar: &[Boolean],
+    ) -> Result<Self, SynthesisError> {
+        let (base, bits) = self.non_zero_base(cs.ns(|| "base"), scalar)?;
+        let offset = Self::offset();
+        let offset_gadget = Self::alloc_constant(cs.ns(|| "offset"), offset)?;
+        let result = base.mul_bits(cs.ns(|| "mul_bits"), &offset_gadget, bits.iter())?;
+        let neg_offset = Self::alloc_constant(cs.ns(|| "-offset"), -offset)?;
+        result.add_unified(cs.ns(|| "Remove offset"), &neg_offset)
+    }
+
+    /// Shares the doublings between both scalars like the default, but adds
+    /// `p` and `q` one after the other instead of selecting among
+    /// `0, p, q, p + q`: both the identity and `p + q`, which is the identity
+    /// for `q = -p`, would hit the exceptional cases of `add`. The
+    /// accumulator starts from a fixed offset point that is taken away again
+    /// with `add_unified`, and identity inputs are replaced by the generator
+    /// with their bits cleared.
+    fn mul_bits_2<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        p: &Self,
+        p_bits: &[Boolean],
+        q: &Self,
+        q_bits: &[Boolean],
+    ) -> Result<Self, SynthesisError> {
+        let (p, p_bits) = p.non_zero_base(cs.ns(|| "p"), p_bits)?;
+        let (q, q_bits) = q.non_zero_base(cs.ns(|| "q"), q_bits)?;
+        let mut offset = Self::offset();
+        let mut result = Self::alloc_constant(cs.ns(|| "offset"), offset)?;
+
+        let num_bits = core::cmp::max(p_bits.len(), q_bits.len());
+        for i in (0..num_bits).rev() {
+            result.double_in_place(cs.ns(|| format!("{}-th Doubling", i)))?;
+            offset.double_in_place();
+            for (name, point, bits) in &[("p", &p, &p_bits), ("q", &q, &q_bits)] {
+                if let Some(bit) = bits.get(i) {
+                    let sum = result.add(cs.ns(|| format!("Add {}-th {}", i, name)), point)?;
+                    result = Self::conditionally_select(
+                        cs.ns(|| format!("Select {}-th {}", i, name)),
+                        bit,
+                        &sum,
+                        &result,
+                    )?;
+                }
+            }
+        }
+        let neg_offset = Self::alloc_constant(cs.ns(|| "-offset"), -offset)?;
+        result.add_unified(cs.ns(|| "Remove offset"), &neg_offset)
+    }
+
+    /// Like the default, but the entry for a zero window is a dummy and a
+    /// window only adds its selection if one of its bits is set, so that the
+    /// identity never reaches `add`. The accumulator starts from a fixed
+    /// offset point that is taken away again with `add_unified`, and identity
+    /// bases are replaced by the generator with their bits cleared.
+    fn multi_scalar_mul<CS, S>(
+        mut cs: CS,
+        bases: &[Self],
+        scalars: &[S],
+        window: usize,
+    ) -> Result<Self, SynthesisError>
+    where
+        CS: ConstraintSystem<ConstraintF>,
+        S: Borrow<[Boolean]>,
+    {
+        if window == 0 || bases.len() != scalars.len() {
+            return Err(SynthesisError::InvalidArgument);
+        }
+        let mut tables = Vec::with_capacity(bases.len());
+        let mut scalar_bits = Vec::with_capacity(bases.len());
+        for (i, (base, scalar)) in bases.iter().zip(scalars).enumerate() {
+            let (base, bits) =
+                base.non_zero_base(cs.ns(|| format!("Base {}", i)), scalar.borrow())?;
+            // The entry for index zero is never added.
+            let mut table = Vec::with_capacity(1 << window);
+            table.push(base.clone());
+            table.push(base.clone());
+            for j in 2..(1 << window) {
+                let multiple = if j % 2 == 0 {
+                    let mut multiple = table[j / 2].clone();
+                    multiple.double_in_place(cs.ns(|| format!("Base {} multiple {}", i, j)))?;
+                    multiple
+                } else {
+                    table[j - 1].add(cs.ns(|| format!("Base {} multiple {}", i, j)), &base)?
+                };
+                table.push(multiple);
+            }
+            tables.push(table);
+            scalar_bits.push(bits);
+        }
+
+        let num_bits = scalar_bits.iter().map(Vec::len).max().unwrap_or(0);
+        let num_windows = (num_bits + window - 1) / window;
+        let mut offset = Self::offset();
+        let mut result = Self::alloc_constant(cs.ns(|| "offset"), offset)?;
+        for w in (0..num_windows).rev() {
+            let mut cs = cs.ns(|| format!("Window {}", w));
+            if w + 1 != num_windows {
+                for j in 0..window {
+                    result.double_in_place(cs.ns(|| format!("Doubling {}", j)))?;
+                    offset.double_in_place();
+                }
+            }
+            for (i, (bits, table)) in scalar_bits.iter().zip(&tables).enumerate() {
+                // The lookup expects its index in big-endian form.
+                let index_bits = (w * window..(w + 1) * window)
+                    .rev()
+                    .map(|k| bits.get(k).cloned().unwrap_or(Boolean::constant(false)))
+                    .collect::<Vec<_>>();
+                let selected = conditionally_select_power_of_two_vector(
+                    cs.ns(|| format!("Select base {}", i)),
+                    &index_bits,
+                    table,
+                )?;
+                let sum = result.add(cs.ns(|| format!("Add base {}", i)), &selected)?;
+                let is_non_zero =
+                    Boolean::kary_or(cs.ns(|| format!("Base {} window is set", i)), &index_bits)?;
+                result = Self::conditionally_select(
+                    cs.ns(|| format!("Keep base {}", i)),
+                    &is_non_zero,
+                    &sum,
+                    &result,
+                )?;
+            }
+        }
+        let neg_offset = Self::alloc_constant(cs.ns(|| "-offset"), -offset)?;
+        result.add_unified(cs.ns(|| "Remove offset"), &neg_offset)
+    }
+
     fn cost_of_add() -> usize {
         3 * F::cost_of_mul_equals() + F::cost_of_inv()
     }
@@ -503,25 +1178,15 @@ where
             ),
         };
 
-        // Perform on-curve check.
-        let b = P::COEFF_B;
-        let a = P::COEFF_A;
-
         let x = F::alloc(&mut cs.ns(|| "x"), || x)?;
         let y = F::alloc(&mut cs.ns(|| "y"), || y)?;
         let infinity = Boolean::alloc(&mut cs.ns(|| "infinity"), || infinity)?;
 
-        // Check that y^2 = x^3 + ax +b
-        // We do this by checking that y^2 - b = x * (x^2 +a)
-        let x2 = x.square(&mut cs.ns(|| "x^2"))?;
-        let y2 = y.square(&mut cs.ns(|| "y^2"))?;
-
-        let x2_plus_a = x2.add_constant(cs.ns(|| "x^2 + a"), &a)?;
-        let y2_minus_b = y2.add_constant(cs.ns(|| "y^2 - b"), &b.neg())?;
-
-        x2_plus_a.mul_equals(cs.ns(|| "on curve check"), &x, &y2_minus_b)?;
+        // Perform on-curve check.
+        let point = Self::new(x, y, infinity);
+        point.enforce_on_curve(&mut cs)?;
 
-        Ok(Self::new(x, y, infinity))
+        Ok(point)
     }
 
     #[inline]
@@ -577,29 +1242,7 @@ where
             Ok(result)
         } else {
             let ge = Self::alloc(cs.ns(|| "Alloc checked"), value_gen)?;
-            let mut seen_one = false;
-            let mut result = Self::zero(cs.ns(|| "result"))?;
-            // Returns bits in big-endian order
-            for (i, b) in BitIterator::new(r_minus_1).enumerate() {
-                let mut cs = cs.ns(|| format!("Iteration {}", i));
-
-                let old_seen_one = seen_one;
-                if seen_one {
-                    result.double_in_place(cs.ns(|| "Double"))?;
-                } else {
-                    seen_one = b;
-                }
-
-                if b {
-                    result = if old_seen_one {
-                        result.add(cs.ns(|| "Add"), &ge)?
-                    } else {
-                        ge.clone()
-                    };
-                }
-            }
-            let neg_ge = ge.negate(cs.ns(|| "Negate ge"))?;
-            neg_ge.enforce_equal(cs.ns(|| "Check equals"), &result)?;
+            ge.enforce_prime_order(cs.ns(|| "Prime order check"))?;
             Ok(ge)
         }
     }
@@ -783,6 +1426,45 @@ where
         "gadget & native values are diff. after scalar mul"
     );
 
+    // Check mul_bits_naf against mul_bits with the same constant scalar.
+    let scalar = P::ScalarField::rand(&mut rng);
+    let naf = scalar.into_repr().find_wnaf();
+    let mut bits = BitIterator::new(scalar.into_repr())
+        .map(Boolean::constant)
+        .collect::<Vec<_>>();
+    bits.reverse();
+    let num_constraints = cs.num_constraints();
+    let expected = gadget_a
+        .mul_bits(cs.ns(|| "constant mul_bits"), &gadget_b, bits.iter())
+        .unwrap();
+    let constant_mul_bits_cost = cs.num_constraints() - num_constraints;
+    let num_constraints = cs.num_constraints();
+    let result = gadget_a
+        .mul_bits_naf(cs.ns(|| "mul_bits_naf"), &gadget_b, &naf)
+        .unwrap();
+    let naf_cost = cs.num_constraints() - num_constraints;
+    assert_eq!(result.get_value(), expected.get_value());
+    assert_eq!(
+        result.get_value().unwrap().into_affine(),
+        (aa.into_affine().mul(scalar) + &b).into_affine()
+    );
+    assert!(naf_cost < constant_mul_bits_cost);
+
+    // Check conditional_negate against the select-based form.
+    let neg_b = gadget_b.negate(cs.ns(|| "-b")).unwrap();
+    for (i, value) in [true, false].iter().enumerate() {
+        let cond = Boolean::alloc(cs.ns(|| format!("cond {}", i)), || Ok(*value)).unwrap();
+        let expected =
+            GG::conditionally_select(cs.ns(|| format!("select {}", i)), &cond, &neg_b, &gadget_b)
+                .unwrap();
+        let result = gadget_b
+            .conditional_negate(cs.ns(|| format!("conditional_negate {}", i)), &cond)
+            .unwrap();
+        assert_eq!(result.get_value(), expected.get_value());
+        let native = if *value { -b } else { b };
+        assert_eq!(result.get_value().unwrap().into_affine(), native.into_affine());
+    }
+
     if !cs.is_satisfied() {
         println!("{:?}", cs.which_is_unsatisfied().unwrap());
     }
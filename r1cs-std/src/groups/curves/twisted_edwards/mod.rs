@@ -3,16 +3,30 @@ use algebra::{
         twisted_edwards_extended::GroupAffine as TEAffine, MontgomeryModelParameters,
         TEModelParameters,
     },
-    BitIterator, Field, One, PrimeField, Zero,
+    biginteger::BigInteger,
+    BitIterator, Field, One, PrimeField, SquareRootField, Zero,
 };
 
 use r1cs_core::{ConstraintSystem, SynthesisError};
 
-use crate::{prelude::*, Vec};
+use crate::{prelude::*, Assignment, Vec};
 
 use crate::fields::fp::FpGadget;
 use core::{borrow::Borrow, marker::PhantomData};
 
+/// A point on the Montgomery curve `B * v^2 = u^3 + A * u^2 + u` that is
+/// birationally equivalent to the twisted Edwards curve of `P`, with `A` and
+/// `B` given by `P::MontgomeryModelParameters`. The maps are
+///
+/// * `(x, y) -> (u, v) = ((1 + y) / (1 - y), (1 + y) / ((1 - y) * x))`, and
+/// * `(u, v) -> (x, y) = (u / v, (u - 1) / (u + 1))`.
+///
+/// They are undefined at the points with `x = 0` or `v = 0`, and at
+/// `u = -1`; in particular, the Edwards identity has no affine Montgomery
+/// counterpart. Addition in this form is cheaper but incomplete, so it
+/// suits long chains of additions of points known to avoid these cases,
+/// such as the windows of a fixed-base scalar multiplication; convert with
+/// [`AffineGadget::into_montgomery`] and [`Self::into_edwards`].
 #[derive(Derivative)]
 #[derivative(Debug, Clone)]
 #[derivative(Debug(bound = "P: TEModelParameters, ConstraintF: Field"))]
@@ -234,6 +248,212 @@ impl<P: TEModelParameters, ConstraintF: Field, F: FieldGadget<P::BaseField, Cons
 
         Ok(Self::new(x, y))
     }
+
+    /// Enforces that the coordinates of `self` satisfy
+    /// `a * x^2 + y^2 = 1 + d * x^2 * y^2`.
+    pub fn enforce_on_curve<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<(), SynthesisError> {
+        let (d_x2_minus_one, y2, a_x2_minus_one) = self.curve_equation_terms(&mut cs)?;
+        d_x2_minus_one.mul_equals(cs.ns(|| "on curve check"), &y2, &a_x2_minus_one)
+    }
+
+    /// Outputs whether the coordinates of `self` satisfy the curve equation;
+    /// see `enforce_on_curve`.
+    pub fn is_on_curve<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Boolean, SynthesisError> {
+        let (d_x2_minus_one, y2, a_x2_minus_one) = self.curve_equation_terms(&mut cs)?;
+        let difference = d_x2_minus_one
+            .mul(cs.ns(|| "y^2 * (d * x^2 - 1)"), &y2)?
+            .sub(cs.ns(|| "difference"), &a_x2_minus_one)?;
+        crate::fields::is_zero(cs.ns(|| "is on curve"), &difference)
+    }
+
+    /// Converts `self` to the birationally equivalent Montgomery form; see
+    /// [`MontgomeryAffineGadget`]. The caller must ensure that `x != 0`, that
+    /// is, `self` is neither the identity `(0, 1)`, for which the constraints
+    /// are unsatisfiable, nor the point of order two `(0, -1)`, for which `v`
+    /// is left unconstrained.
+    pub fn into_montgomery<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<MontgomeryAffineGadget<P, ConstraintF, F>, SynthesisError> {
+        let one = P::BaseField::one();
+
+        // Compute u = (1 + y) / (1 - y)
+        let u = F::alloc(cs.ns(|| "u"), || {
+            let y = self.y.get_value().get()?;
+            match (one - &y).inverse() {
+                Some(inv) => Ok((one + &y) * &inv),
+                None => Err(SynthesisError::DivisionByZero),
+            }
+        })?;
+        let one_plus_y = self.y.add_constant(cs.ns(|| "1 + y"), &one)?;
+        let one_minus_y = self
+            .y
+            .negate(cs.ns(|| "-y"))?
+            .add_constant(cs.ns(|| "1 - y"), &one)?;
+        u.mul_equals(cs.ns(|| "u equals"), &one_minus_y, &one_plus_y)?;
+
+        // Compute v = u / x
+        let v = F::alloc(cs.ns(|| "v"), || {
+            match self.x.get_value().get()?.inverse() {
+                Some(inv) => Ok(u.get_value().get()? * &inv),
+                None => Err(SynthesisError::DivisionByZero),
+            }
+        })?;
+        v.mul_equals(cs.ns(|| "v equals"), &self.x, &u)?;
+
+        Ok(MontgomeryAffineGadget::new(u, v))
+    }
+
+    /// Converts `p` back from Montgomery form; see
+    /// [`MontgomeryAffineGadget::into_edwards`].
+    pub fn from_montgomery<CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        p: &MontgomeryAffineGadget<P, ConstraintF, F>,
+    ) -> Result<Self, SynthesisError> {
+        p.into_edwards(cs)
+    }
+
+    /// Returns `(d * x^2 - 1, y^2, a * x^2 - 1)`: `self` is on the curve iff
+    /// `a * x^2 - 1 = y^2 * (d * x^2 - 1)`.
+    fn curve_equation_terms<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<(F, F, F), SynthesisError> {
+        let x2 = self.x.square(&mut cs.ns(|| "x^2"))?;
+        let y2 = self.y.square(&mut cs.ns(|| "y^2"))?;
+
+        let one = P::BaseField::one();
+        let d_x2_minus_one = x2
+            .mul_by_constant(cs.ns(|| "d * x^2"), &P::COEFF_D)?
+            .sub_constant(cs.ns(|| "d * x^2 - 1"), &one)?;
+
+        let a_x2_minus_one = x2
+            .mul_by_constant(cs.ns(|| "a * x^2"), &P::COEFF_A)?
+            .sub_constant(cs.ns(|| "a * x^2 - 1"), &one)?;
+
+        Ok((d_x2_minus_one, y2, a_x2_minus_one))
+    }
+}
+
+impl<P, ConstraintF> AffineGadget<P, ConstraintF, FpGadget<ConstraintF>>
+where
+    P: TEModelParameters<BaseField = ConstraintF>,
+    ConstraintF: PrimeField + SquareRootField,
+{
+    /// Decompresses a point from its x-coordinate and the parity of its
+    /// y-coordinate, enforcing `y^2 * (1 - d * x^2) = 1 - a * x^2` and that
+    /// the parity of `y` matches `y_is_odd`. If `x` is not the x-coordinate
+    /// of a point, the constraint system is unsatisfiable.
+    pub fn from_x_and_sign<CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        x: FpGadget<ConstraintF>,
+        y_is_odd: &Boolean,
+    ) -> Result<Self, SynthesisError> {
+        Self::from_x_and_y_bit(cs, x, y_is_odd, false)
+    }
+
+    /// Outputs the compressed encoding of `self`, matching the
+    /// `CanonicalSerialize` output of the corresponding affine point: the
+    /// little-endian bytes of `x`, with the top bit of the last byte set iff
+    /// `y > -y`.
+    pub fn to_compressed_bytes<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Vec<UInt8>, SynthesisError> {
+        // y > -y iff 2 * y overflows the modulus, i.e. iff 2 * y is odd.
+        let y_is_positive = self
+            .y
+            .double(cs.ns(|| "2 * y"))?
+            .is_odd(cs.ns(|| "y is positive"))?;
+        super::compressed_bytes(cs.ns(|| "to bytes"), &self.x, &[y_is_positive])
+    }
+
+    /// Outputs the uncompressed encoding of `self`, matching the
+    /// `CanonicalSerialize::serialize_uncompressed` output of the
+    /// corresponding affine point: the little-endian bytes of `x` and then of
+    /// `y`. Unlike `to_bytes`, which mirrors `algebra`'s `ToBytes` and pads
+    /// each coordinate to whole limbs, this uses only as many bytes per
+    /// coordinate as the modulus needs.
+    pub fn to_uncompressed_bytes<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Vec<UInt8>, SynthesisError> {
+        let mut bytes = super::compressed_bytes(cs.ns(|| "x to bytes"), &self.x, &[])?;
+        let y_bytes = super::compressed_bytes(cs.ns(|| "y to bytes"), &self.y, &[])?;
+        bytes.extend(y_bytes);
+        Ok(bytes)
+    }
+
+    /// Decompresses the output of `to_compressed_bytes`. Unlike `algebra`'s
+    /// deserialization, this does not check that the point lies in the
+    /// prime-order subgroup. Bytes of the wrong length are rejected with
+    /// `SynthesisError::InvalidArgument`.
+    pub fn from_compressed_bytes<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        bytes: &[UInt8],
+    ) -> Result<Self, SynthesisError> {
+        let (x, flags) =
+            super::x_and_flags_from_compressed_bytes(cs.ns(|| "from bytes"), bytes, 1)?;
+        // Like `algebra`, decode `x = 0` as the identity whatever the flag.
+        let x_is_zero = x.is_zero(cs.ns(|| "x is zero"))?;
+        let y_is_positive = Boolean::and(
+            cs.ns(|| "x is nonzero and y is positive"),
+            &x_is_zero.not(),
+            &flags[0],
+        )?;
+        Self::from_x_and_y_bit(cs.ns(|| "decompress"), x, &y_is_positive, true)
+    }
+
+    /// Shared by `from_x_and_sign` and `from_compressed_bytes`: `y_bit` is the
+    /// parity of `y`, or, if `double_y` is set, of `2 * y`.
+    fn from_x_and_y_bit<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        x: FpGadget<ConstraintF>,
+        y_bit: &Boolean,
+        double_y: bool,
+    ) -> Result<Self, SynthesisError> {
+        let one = ConstraintF::one();
+        let y = FpGadget::alloc(cs.ns(|| "y"), || {
+            let x = x.get_value().get()?;
+            let x2 = x.square();
+            let y2 = (one - &(P::COEFF_A * &x2)) * &(one - &(P::COEFF_D * &x2)).inverse().get()?;
+            // If there is no square root, any y leaves the system unsatisfied.
+            let y = y2.sqrt().unwrap_or_else(ConstraintF::zero);
+            let bit_value = if double_y { y.double() } else { y };
+            if bit_value.into_repr().is_odd() == y_bit.get_value().get()? {
+                Ok(y)
+            } else {
+                Ok(-y)
+            }
+        })?;
+
+        let x2 = x.square(cs.ns(|| "x^2"))?;
+        let numerator = x2
+            .mul_by_constant(cs.ns(|| "a * x^2"), &P::COEFF_A)?
+            .negate(cs.ns(|| "-a * x^2"))?
+            .add_constant(cs.ns(|| "1 - a * x^2"), &one)?;
+        let denominator = x2
+            .mul_by_constant(cs.ns(|| "d * x^2"), &P::COEFF_D)?
+            .negate(cs.ns(|| "-d * x^2"))?
+            .add_constant(cs.ns(|| "1 - d * x^2"), &one)?;
+        let y2 = y.square(cs.ns(|| "y^2"))?;
+        y2.mul_equals(cs.ns(|| "check y^2"), &denominator, &numerator)?;
+        let bit = if double_y {
+            y.double(cs.ns(|| "2 * y"))?
+                .is_odd(cs.ns(|| "2 * y is odd"))?
+        } else {
+            y.is_odd(cs.ns(|| "y is odd"))?
+        };
+        bit.enforce_equal(cs.ns(|| "check y bit"), y_bit)?;
+
+        Ok(Self::new(x, y))
+    }
 }
 
 impl<P, ConstraintF, F> ToConstraintFieldGadget<ConstraintF> for AffineGadget<P, ConstraintF, F>
@@ -499,6 +719,16 @@ mod affine_impl {
             ))
         }
 
+        fn conditional_negate<CS: ConstraintSystem<ConstraintF>>(
+            &self,
+            mut cs: CS,
+            cond: &Boolean,
+        ) -> Result<Self, SynthesisError> {
+            let neg_x = self.x.negate(cs.ns(|| "negate x"))?;
+            let x = F::conditionally_select(cs.ns(|| "select x"), cond, &neg_x, &self.x)?;
+            Ok(Self::new(x, self.y.clone()))
+        }
+
         fn cost_of_add() -> usize {
             4 + 2 * F::cost_of_mul()
         }
@@ -551,28 +781,12 @@ mod affine_impl {
                 ),
             };
 
-            let d = P::COEFF_D;
-            let a = P::COEFF_A;
-
             let x = F::alloc(&mut cs.ns(|| "x"), || x)?;
             let y = F::alloc(&mut cs.ns(|| "y"), || y)?;
 
-            // Check that ax^2 + y^2 = 1 + dx^2y^2
-            // We do this by checking that ax^2 - 1 = y^2 * (dx^2 - 1)
-            let x2 = x.square(&mut cs.ns(|| "x^2"))?;
-            let y2 = y.square(&mut cs.ns(|| "y^2"))?;
-
-            let one = P::BaseField::one();
-            let d_x2_minus_one = x2
-                .mul_by_constant(cs.ns(|| "d * x^2"), &d)?
-                .add_constant(cs.ns(|| "d * x^2 - 1"), &one.neg())?;
-
-            let a_x2_minus_one = x2
-                .mul_by_constant(cs.ns(|| "a * x^2"), &a)?
-                .add_constant(cs.ns(|| "a * x^2 - 1"), &one.neg())?;
-
-            d_x2_minus_one.mul_equals(cs.ns(|| "on curve check"), &y2, &a_x2_minus_one)?;
-            Ok(Self::new(x, y))
+            let point = Self::new(x, y);
+            point.enforce_on_curve(&mut cs)?;
+            Ok(point)
         }
 
         fn alloc_checked<FN, T, CS: ConstraintSystem<ConstraintF>>(
@@ -622,29 +836,7 @@ mod affine_impl {
                 Ok(result)
             } else {
                 let ge = Self::alloc(cs.ns(|| "Alloc checked"), value_gen)?;
-                let mut seen_one = false;
-                let mut result = Self::zero(cs.ns(|| "result"))?;
-                // Returns bits in big-endian order
-                for (i, b) in BitIterator::new(r_minus_1).enumerate() {
-                    let mut cs = cs.ns(|| format!("Iteration {}", i));
-
-                    let old_seen_one = seen_one;
-                    if seen_one {
-                        result.double_in_place(cs.ns(|| "Double"))?;
-                    } else {
-                        seen_one = b;
-                    }
-
-                    if b {
-                        result = if old_seen_one {
-                            result.add(cs.ns(|| "Add"), &ge)?
-                        } else {
-                            ge.clone()
-                        };
-                    }
-                }
-                let neg_ge = ge.negate(cs.ns(|| "Negate ge"))?;
-                neg_ge.enforce_equal(cs.ns(|| "Check equals"), &result)?;
+                ge.enforce_prime_order(cs.ns(|| "Prime order check"))?;
                 Ok(ge)
             }
         }
@@ -668,28 +860,12 @@ mod affine_impl {
                 ),
             };
 
-            let d = P::COEFF_D;
-            let a = P::COEFF_A;
-
             let x = F::alloc_input(&mut cs.ns(|| "x"), || x)?;
             let y = F::alloc_input(&mut cs.ns(|| "y"), || y)?;
 
-            // Check that ax^2 + y^2 = 1 + dx^2y^2
-            // We do this by checking that ax^2 - 1 = y^2 * (dx^2 - 1)
-            let x2 = x.square(&mut cs.ns(|| "x^2"))?;
-            let y2 = y.square(&mut cs.ns(|| "y^2"))?;
-
-            let one = P::BaseField::one();
-            let d_x2_minus_one = x2
-                .mul_by_constant(cs.ns(|| "d * x^2"), &d)?
-                .add_constant(cs.ns(|| "d * x^2 - 1"), &one.neg())?;
-
-            let a_x2_minus_one = x2
-                .mul_by_constant(cs.ns(|| "a * x^2"), &a)?
-                .add_constant(cs.ns(|| "a * x^2 - 1"), &one.neg())?;
-
-            d_x2_minus_one.mul_equals(cs.ns(|| "on curve check"), &y2, &a_x2_minus_one)?;
-            Ok(Self::new(x, y))
+            let point = Self::new(x, y);
+            point.enforce_on_curve(&mut cs)?;
+            Ok(point)
         }
     }
 }
@@ -921,6 +1097,16 @@ mod projective_impl {
             ))
         }
 
+        fn conditional_negate<CS: ConstraintSystem<ConstraintF>>(
+            &self,
+            mut cs: CS,
+            cond: &Boolean,
+        ) -> Result<Self, SynthesisError> {
+            let neg_x = self.x.negate(cs.ns(|| "negate x"))?;
+            let x = F::conditionally_select(cs.ns(|| "select x"), cond, &neg_x, &self.x)?;
+            Ok(Self::new(x, self.y.clone()))
+        }
+
         fn precomputed_base_scalar_mul<'a, CS, I, B>(
             &mut self,
             mut cs: CS,
@@ -1165,28 +1351,12 @@ mod projective_impl {
                 ),
             };
 
-            let d = P::COEFF_D;
-            let a = P::COEFF_A;
-
             let x = F::alloc(&mut cs.ns(|| "x"), || x)?;
             let y = F::alloc(&mut cs.ns(|| "y"), || y)?;
 
-            // Check that ax^2 + y^2 = 1 + dx^2y^2
-            // We do this by checking that ax^2 - 1 = y^2 * (dx^2 - 1)
-            let x2 = x.square(&mut cs.ns(|| "x^2"))?;
-            let y2 = y.square(&mut cs.ns(|| "y^2"))?;
-
-            let one = P::BaseField::one();
-            let d_x2_minus_one = x2
-                .mul_by_constant(cs.ns(|| "d * x^2"), &d)?
-                .add_constant(cs.ns(|| "d * x^2 - 1"), &one.neg())?;
-
-            let a_x2_minus_one = x2
-                .mul_by_constant(cs.ns(|| "a * x^2"), &a)?
-                .add_constant(cs.ns(|| "a * x^2 - 1"), &one.neg())?;
-
-            d_x2_minus_one.mul_equals(cs.ns(|| "on curve check"), &y2, &a_x2_minus_one)?;
-            Ok(Self::new(x, y))
+            let point = Self::new(x, y);
+            point.enforce_on_curve(&mut cs)?;
+            Ok(point)
         }
 
         fn alloc_checked<FN, T, CS: ConstraintSystem<ConstraintF>>(
@@ -1241,29 +1411,7 @@ mod projective_impl {
                 Ok(result)
             } else {
                 let ge = Self::alloc(cs.ns(|| "Alloc checked"), value_gen)?;
-                let mut seen_one = false;
-                let mut result = Self::zero(cs.ns(|| "result"))?;
-                // Returns bits in big-endian order
-                for (i, b) in BitIterator::new(r_minus_1).enumerate() {
-                    let mut cs = cs.ns(|| format!("Iteration {}", i));
-
-                    let old_seen_one = seen_one;
-                    if seen_one {
-                        result.double_in_place(cs.ns(|| "Double"))?;
-                    } else {
-                        seen_one = b;
-                    }
-
-                    if b {
-                        result = if old_seen_one {
-                            result.add(cs.ns(|| "Add"), &ge)?
-                        } else {
-                            ge.clone()
-                        };
-                    }
-                }
-                let neg_ge = ge.negate(cs.ns(|| "Negate ge"))?;
-                neg_ge.enforce_equal(cs.ns(|| "Check equals"), &result)?;
+                ge.enforce_prime_order(cs.ns(|| "Prime order check"))?;
                 Ok(ge)
             }
         }
@@ -1287,28 +1435,12 @@ mod projective_impl {
                 ),
             };
 
-            let d = P::COEFF_D;
-            let a = P::COEFF_A;
-
             let x = F::alloc_input(&mut cs.ns(|| "x"), || x)?;
             let y = F::alloc_input(&mut cs.ns(|| "y"), || y)?;
 
-            // Check that ax^2 + y^2 = 1 + dx^2y^2
-            // We do this by checking that ax^2 - 1 = y^2 * (dx^2 - 1)
-            let x2 = x.square(&mut cs.ns(|| "x^2"))?;
-            let y2 = y.square(&mut cs.ns(|| "y^2"))?;
-
-            let one = P::BaseField::one();
-            let d_x2_minus_one = x2
-                .mul_by_constant(cs.ns(|| "d * x^2"), &d)?
-                .add_constant(cs.ns(|| "d * x^2 - 1"), &one.neg())?;
-
-            let a_x2_minus_one = x2
-                .mul_by_constant(cs.ns(|| "a * x^2"), &a)?
-                .add_constant(cs.ns(|| "a * x^2 - 1"), &one.neg())?;
-
-            d_x2_minus_one.mul_equals(cs.ns(|| "on curve check"), &y2, &a_x2_minus_one)?;
-            Ok(Self::new(x, y))
+            let point = Self::new(x, y);
+            point.enforce_on_curve(&mut cs)?;
+            Ok(point)
         }
     }
 }
@@ -1490,14 +1622,249 @@ where
     scalar.reverse();
     let input = Vec::<Boolean>::alloc(cs.ns(|| "Input"), || Ok(scalar)).unwrap();
     let zero = GG::zero(cs.ns(|| "zero")).unwrap();
+    let num_constraints = cs.num_constraints();
     let result = gadget_a
         .mul_bits(cs.ns(|| "mul_bits"), &zero, input.iter())
         .unwrap();
+    let mul_bits_cost = cs.num_constraints() - num_constraints;
     let gadget_value = result.get_value().expect("Gadget_result failed");
     assert_eq!(native_result, gadget_value);
 
     assert!(cs.is_satisfied());
 
+    // Check scalar_mul_le, including the scalars zero and one.
+    let result = gadget_a
+        .scalar_mul_le(cs.ns(|| "scalar_mul_le"), &input)
+        .unwrap();
+    assert_eq!(result.get_value().unwrap(), native_result);
+    let zero_scalar = vec![Boolean::constant(false); input.len()];
+    let result = gadget_a
+        .scalar_mul_le(cs.ns(|| "scalar_mul_le zero"), &zero_scalar)
+        .unwrap();
+    assert_eq!(result.get_value(), zero.get_value());
+    let mut one_scalar = zero_scalar.clone();
+    one_scalar[0] = Boolean::constant(true);
+    let result = gadget_a
+        .scalar_mul_le(cs.ns(|| "scalar_mul_le one"), &one_scalar)
+        .unwrap();
+    assert_eq!(result.get_value(), gadget_a.get_value());
+    assert!(cs.is_satisfied());
+
+    // Check mul_bits_naf against mul_bits with the same constant scalars.
+    for i in 0..3 {
+        let scalar: <TEAffine<P> as Group>::ScalarField = UniformRand::rand(&mut test_rng());
+        let naf = scalar.into_repr().find_wnaf();
+        let mut bits = BitIterator::new(scalar.into_repr())
+            .map(Boolean::constant)
+            .collect::<Vec<_>>();
+        bits.reverse();
+
+        let num_constraints = cs.num_constraints();
+        let expected = gadget_a
+            .mul_bits(cs.ns(|| format!("constant mul_bits {}", i)), &zero, bits.iter())
+            .unwrap();
+        let constant_mul_bits_cost = cs.num_constraints() - num_constraints;
+
+        let num_constraints = cs.num_constraints();
+        let result = gadget_a
+            .mul_bits_naf(cs.ns(|| format!("mul_bits_naf {}", i)), &zero, &naf)
+            .unwrap();
+        let naf_cost = cs.num_constraints() - num_constraints;
+
+        assert_eq!(result.get_value(), expected.get_value());
+        assert_eq!(result.get_value().unwrap(), a.mul(&scalar));
+        assert!(naf_cost < constant_mul_bits_cost);
+    }
+    match gadget_a.mul_bits_naf(cs.ns(|| "invalid digit"), &zero, &[2]) {
+        Err(SynthesisError::InvalidArgument) => {},
+        _ => panic!("expected InvalidArgument for an invalid digit"),
+    }
+    // No doubling follows the last digit.
+    let num_constraints = cs.num_constraints();
+    let result = gadget_a
+        .mul_bits_naf(cs.ns(|| "zero digit"), &zero, &[0])
+        .unwrap();
+    assert_eq!(cs.num_constraints(), num_constraints);
+    assert_eq!(result.get_value(), zero.get_value());
+    assert!(cs.is_satisfied());
+
+    // Check fixed_base_scalar_mul against mul_bits for several window sizes.
+    for window in 1..=4 {
+        let num_constraints = cs.num_constraints();
+        let result = GG::fixed_base_scalar_mul(
+            cs.ns(|| format!("fixed_base_scalar_mul {}", window)),
+            &a,
+            &input,
+            window,
+        )
+        .unwrap();
+        let cost = cs.num_constraints() - num_constraints;
+        assert_eq!(result.get_value().unwrap(), gadget_value);
+        assert!(cost < mul_bits_cost);
+    }
+    match GG::fixed_base_scalar_mul(cs.ns(|| "empty window"), &a, &input, 0) {
+        Err(SynthesisError::InvalidArgument) => {},
+        _ => panic!("expected InvalidArgument for an empty window"),
+    }
+    assert!(cs.is_satisfied());
+
+    // Check mul_bits_2 against two calls to mul_bits.
+    let mut cs = TestConstraintSystem::new();
+
+    let mut rng = test_rng();
+    let p: TEAffine<P> = rng.gen();
+    let q: TEAffine<P> = rng.gen();
+    let p_scalar: <TEAffine<P> as Group>::ScalarField = UniformRand::rand(&mut rng);
+    // A shorter second scalar checks that the bits are padded with zeros.
+    let q_scalar: u64 = rng.gen();
+    let native_result =
+        p.mul(&p_scalar) + &q.mul(&<TEAffine<P> as Group>::ScalarField::from(q_scalar));
+
+    let mut p_bits: Vec<bool> = BitIterator::new(p_scalar.into_repr()).collect();
+    p_bits.reverse();
+    let q_bits: Vec<bool> = (0..64).map(|i| (q_scalar >> i) & 1 == 1).collect();
+
+    let gadget_p = GG::alloc(&mut cs.ns(|| "p"), || Ok(p)).unwrap();
+    let gadget_q = GG::alloc(&mut cs.ns(|| "q"), || Ok(q)).unwrap();
+    let p_input = Vec::<Boolean>::alloc(cs.ns(|| "p bits"), || Ok(p_bits)).unwrap();
+    let q_input = Vec::<Boolean>::alloc(cs.ns(|| "q bits"), || Ok(q_bits)).unwrap();
+    let zero = GG::zero(cs.ns(|| "zero")).unwrap();
+
+    let num_constraints = cs.num_constraints();
+    let p_result = gadget_p
+        .mul_bits(cs.ns(|| "p mul_bits"), &zero, p_input.iter())
+        .unwrap();
+    let baseline = gadget_q
+        .mul_bits(cs.ns(|| "q mul_bits"), &p_result, q_input.iter())
+        .unwrap();
+    let baseline_cost = cs.num_constraints() - num_constraints;
+
+    let num_constraints = cs.num_constraints();
+    let result = GG::mul_bits_2(
+        cs.ns(|| "mul_bits_2"),
+        &gadget_p,
+        &p_input,
+        &gadget_q,
+        &q_input,
+    )
+    .unwrap();
+    let cost = cs.num_constraints() - num_constraints;
+
+    assert_eq!(result.get_value().unwrap(), native_result);
+    assert_eq!(result.get_value(), baseline.get_value());
+    assert!(cost < baseline_cost);
+    assert!(cs.is_satisfied());
+
+    // Check multi_scalar_mul against chained calls to mul_bits.
+    let mut cs = TestConstraintSystem::new();
+    let bases: Vec<TEAffine<P>> = (0..8).map(|_| rng.gen()).collect();
+    let scalars: Vec<<TEAffine<P> as Group>::ScalarField> = (0..8).map(|_| rng.gen()).collect();
+    let mut native_result = TEAffine::<P>::zero();
+    for (base, scalar) in bases.iter().zip(&scalars) {
+        native_result += &base.mul(scalar);
+    }
+
+    let gadget_bases = Vec::<GG>::alloc(cs.ns(|| "bases"), || Ok(&bases[..])).unwrap();
+    let gadget_scalars = scalars
+        .iter()
+        .enumerate()
+        .map(|(i, scalar)| {
+            let mut bits: Vec<bool> = BitIterator::new(scalar.into_repr()).collect();
+            bits.reverse();
+            Vec::<Boolean>::alloc(cs.ns(|| format!("scalar {}", i)), || Ok(bits)).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let num_constraints = cs.num_constraints();
+    let mut baseline = GG::zero(cs.ns(|| "zero")).unwrap();
+    for (i, (base, scalar)) in gadget_bases.iter().zip(&gadget_scalars).enumerate() {
+        baseline = base
+            .mul_bits(
+                cs.ns(|| format!("mul_bits {}", i)),
+                &baseline,
+                scalar.iter(),
+            )
+            .unwrap();
+    }
+    let baseline_cost = cs.num_constraints() - num_constraints;
+    assert_eq!(baseline.get_value().unwrap(), native_result);
+
+    for window in 1..=3 {
+        let num_constraints = cs.num_constraints();
+        let result = GG::multi_scalar_mul(
+            cs.ns(|| format!("multi_scalar_mul {}", window)),
+            &gadget_bases,
+            &gadget_scalars,
+            window,
+        )
+        .unwrap();
+        let cost = cs.num_constraints() - num_constraints;
+        assert_eq!(result.get_value().unwrap(), native_result);
+        assert!(cost < baseline_cost);
+    }
+    match GG::multi_scalar_mul(
+        cs.ns(|| "mismatched lengths"),
+        &gadget_bases,
+        &gadget_scalars[1..],
+        2,
+    ) {
+        Err(SynthesisError::InvalidArgument) => {},
+        _ => panic!("expected InvalidArgument for mismatched lengths"),
+    }
+    assert!(cs.is_satisfied());
+
+    // Allocating a vector of checked points costs one check per point.
+    let mut cs = TestConstraintSystem::new();
+    let points: Vec<TEAffine<P>> = (0..16).map(|_| rng.gen()).collect();
+    let _ = GG::alloc_checked(cs.ns(|| "single"), || Ok(points[0])).unwrap();
+    let single_cost = cs.num_constraints();
+    let gadgets = Vec::<GG>::alloc_checked(cs.ns(|| "batch"), || Ok(&points[..])).unwrap();
+    assert_eq!(cs.num_constraints() - single_cost, 16 * single_cost);
+    for (gadget, point) in gadgets.iter().zip(&points) {
+        assert_eq!(gadget.get_value().unwrap(), *point);
+    }
+    assert!(cs.is_satisfied());
+
+    // enforce_prime_order accepts points of the subgroup and rejects the
+    // point of order two, which `alloc` does not check for.
+    let mut cs = TestConstraintSystem::new();
+    let gadget = GG::alloc(cs.ns(|| "prime order point"), || Ok(points[0])).unwrap();
+    gadget
+        .enforce_prime_order(cs.ns(|| "check prime order point"))
+        .unwrap();
+    assert!(cs.is_satisfied());
+    let small_order = TEAffine::<P>::new(P::BaseField::zero(), -P::BaseField::one());
+    assert!(!small_order.is_in_correct_subgroup_assuming_on_curve());
+    let gadget = GG::alloc(cs.ns(|| "small order point"), || Ok(small_order)).unwrap();
+    assert!(cs.is_satisfied());
+    gadget
+        .enforce_prime_order(cs.ns(|| "check small order point"))
+        .unwrap();
+    assert!(!cs.is_satisfied());
+
+    // It also rejects a subgroup point plus the point of order two, which a
+    // check that the cofactor multiple is non-zero would accept.
+    let mut cs = TestConstraintSystem::new();
+    let mixed = points[0] + &small_order;
+    assert!(!mixed.is_in_correct_subgroup_assuming_on_curve());
+    assert!(!algebra::AffineCurve::mul_by_cofactor(&mixed).is_zero());
+    let gadget = GG::alloc(cs.ns(|| "mixed order point"), || Ok(mixed)).unwrap();
+    gadget
+        .enforce_prime_order(cs.ns(|| "check mixed order point"))
+        .unwrap();
+    assert!(!cs.is_satisfied());
+
+    // Equality of constant points is decided without constraints.
+    let mut cs = TestConstraintSystem::new();
+    let p0 = GG::alloc_constant(cs.ns(|| "constant p0"), points[0]).unwrap();
+    let p1 = GG::alloc_constant(cs.ns(|| "constant p1"), points[1]).unwrap();
+    p0.enforce_equal(cs.ns(|| "p0 == p0"), &p0).unwrap();
+    p0.conditional_enforce_equal(cs.ns(|| "skipped"), &p1, &Boolean::constant(false))
+        .unwrap();
+    assert_eq!(cs.num_constraints(), 0);
+    assert!(p0.enforce_equal(cs.ns(|| "p0 == p1"), &p1).is_err());
+    assert_eq!(cs.num_constraints(), 0);
+
     // Test the cost of allocation, conditional selection, and point addition.
     let mut cs = TestConstraintSystem::new();
 
@@ -1,5 +1,5 @@
-use crate::prelude::*;
-use algebra::{Field, Group};
+use crate::{prelude::*, Vec};
+use algebra::{BitIterator, Field, Group, One, PrimeField};
 use r1cs_core::{ConstraintSystem, SynthesisError};
 
 use core::{borrow::Borrow, fmt::Debug};
@@ -43,6 +43,17 @@ pub trait GroupGadget<G: Group, ConstraintF: Field>:
         self.add(cs.ns(|| "Self - other"), &neg_other)
     }
 
+    /// Complete addition: either operand may be the identity, and they may
+    /// be equal or each other's negation. Defaults to `add`, so gadgets whose
+    /// `add` is incomplete must override it.
+    fn add_unified<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        cs: CS,
+        other: &Self,
+    ) -> Result<Self, SynthesisError> {
+        self.add(cs, other)
+    }
+
     fn add_constant<CS: ConstraintSystem<ConstraintF>>(
         &self,
         cs: CS,
@@ -65,6 +76,60 @@ pub trait GroupGadget<G: Group, ConstraintF: Field>:
 
     fn negate<CS: ConstraintSystem<ConstraintF>>(&self, cs: CS) -> Result<Self, SynthesisError>;
 
+    /// Outputs `-self` if `cond` is set, and `self` otherwise.
+    fn conditional_negate<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        cond: &Boolean,
+    ) -> Result<Self, SynthesisError> {
+        let neg_self = self.negate(cs.ns(|| "negate"))?;
+        Self::conditionally_select(cs.ns(|| "select"), cond, &neg_self, self)
+    }
+
+    /// Enforces that `self` lies in the prime-order subgroup by checking that
+    /// `(r - 1) * self = -self`, where `r` is the order of the subgroup.
+    ///
+    /// Multiplying by `r - 1` rather than `r` keeps the identity out of the
+    /// intermediate sums for points of the subgroup, so this also works with
+    /// incomplete addition. Points outside the subgroup, such as those of
+    /// small order, make the constraints unsatisfiable; contrast this with
+    /// the cofactor clearing that `alloc_checked` may perform instead, which
+    /// maps any point into the subgroup.
+    ///
+    /// This default needs only the subgroup order, so curve gadgets need not
+    /// override it. It deliberately does not check that `h * self` is
+    /// non-zero for the cofactor `h`: that only excludes points whose order
+    /// divides `h`, and accepts a subgroup point plus a torsion point.
+    fn enforce_prime_order<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<(), SynthesisError> {
+        let r_minus_1 = (-G::ScalarField::one()).into_repr();
+        let mut seen_one = false;
+        let mut result = Self::zero(cs.ns(|| "result"))?;
+        // Returns bits in big-endian order
+        for (i, b) in BitIterator::new(r_minus_1).enumerate() {
+            let mut cs = cs.ns(|| format!("Iteration {}", i));
+
+            let old_seen_one = seen_one;
+            if seen_one {
+                result.double_in_place(cs.ns(|| "Double"))?;
+            } else {
+                seen_one = b;
+            }
+
+            if b {
+                result = if old_seen_one {
+                    result.add(cs.ns(|| "Add"), self)?
+                } else {
+                    self.clone()
+                };
+            }
+        }
+        let neg_self = self.negate(cs.ns(|| "Negate self"))?;
+        neg_self.enforce_equal(cs.ns(|| "Check equals"), &result)
+    }
+
     /// Inputs must be specified in *little-endian* form.
     /// If the addition law is incomplete for the identity element,
     /// `result` must not be the identity element.
@@ -89,6 +154,179 @@ pub trait GroupGadget<G: Group, ConstraintF: Field>:
         Ok(result)
     }
 
+    /// Computes `result + scalar * self`, where `scalar` is given by its
+    /// signed digits in *little-endian* form, each of which must be `-1`, `0`
+    /// or `1`, such as the non-adjacent form output by
+    /// `BigInteger::find_wnaf`; other digits are rejected with
+    /// `SynthesisError::InvalidArgument`.
+    ///
+    /// The digits are constants, so zero digits cost no addition: a
+    /// non-adjacent form has about a third of its digits nonzero, whereas
+    /// `mul_bits` adds for every bit. For a scalar that is a witness, use
+    /// `mul_bits` instead. Like `mul_bits`, if the addition law is incomplete
+    /// for the identity element, `result` must not be the identity element.
+    fn mul_bits_naf<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        result: &Self,
+        naf: &[i64],
+    ) -> Result<Self, SynthesisError> {
+        let mut power = self.clone();
+        let mut result = result.clone();
+        for (i, &digit) in naf.iter().enumerate() {
+            match digit {
+                0 => {},
+                -1 | 1 => {
+                    let summand = power.conditional_negate(
+                        cs.ns(|| format!("Negate {}-th power", i)),
+                        &Boolean::constant(digit < 0),
+                    )?;
+                    result = result.add(cs.ns(|| format!("Add {}-th power", i)), &summand)?;
+                },
+                _ => return Err(SynthesisError::InvalidArgument),
+            }
+            if i + 1 != naf.len() {
+                power.double_in_place(cs.ns(|| format!("{}-th Doubling", i)))?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Computes `scalar * self`, where `scalar` is given as bits in
+    /// *little-endian* form, i.e. `scalar[0]` is the least significant bit.
+    ///
+    /// The scalar field generally differs from `ConstraintF`, so scalars are
+    /// passed as bits; a native scalar can be converted with `BitIterator`,
+    /// reversing its big-endian output.
+    ///
+    /// The computation starts from the identity, so this default relies on
+    /// `add` being complete; gadgets with incomplete addition override it.
+    fn scalar_mul_le<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        scalar: &[Boolean],
+    ) -> Result<Self, SynthesisError> {
+        let zero = Self::zero(cs.ns(|| "zero"))?;
+        self.mul_bits(cs.ns(|| "mul_bits"), &zero, scalar.iter())
+    }
+
+    /// Computes `p_bits * p + q_bits * q` with Shamir's trick: both scalars
+    /// share a single doubling loop, and each step adds one of
+    /// `0, p, q, p + q`.
+    ///
+    /// Inputs must be specified in *little-endian* form. The accumulator
+    /// starts at, and may add, the identity, so this default relies on `add`
+    /// being complete; gadgets with incomplete addition override it.
+    fn mul_bits_2<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        p: &Self,
+        p_bits: &[Boolean],
+        q: &Self,
+        q_bits: &[Boolean],
+    ) -> Result<Self, SynthesisError> {
+        let zero = Self::zero(cs.ns(|| "zero"))?;
+        let p_plus_q = p.add(cs.ns(|| "p + q"), q)?;
+        let table = [zero.clone(), p.clone(), q.clone(), p_plus_q];
+
+        let mut result = zero;
+        let num_bits = core::cmp::max(p_bits.len(), q_bits.len());
+        for i in (0..num_bits).rev() {
+            result.double_in_place(cs.ns(|| format!("{}-th Doubling", i)))?;
+            let p_bit = p_bits.get(i).cloned().unwrap_or(Boolean::Constant(false));
+            let q_bit = q_bits.get(i).cloned().unwrap_or(Boolean::Constant(false));
+            let summand = conditionally_select_power_of_two_vector(
+                cs.ns(|| format!("Select {}", i)),
+                &[q_bit, p_bit],
+                &table,
+            )?;
+            result = result.add(cs.ns(|| format!("Add {}-th summand", i)), &summand)?;
+        }
+        Ok(result)
+    }
+
+    /// Computes `sum_i scalars[i] * bases[i]` for variable `bases`, with
+    /// Straus' interleaved window method: the multiples
+    /// `0, b, ..., (2^window - 1) * b` of each base are computed once, and
+    /// every window of `window` bits then costs one table lookup and one
+    /// addition per base, with the doublings shared by all bases. This is
+    /// cheaper than summing independent `mul_bits`, which doubles every base
+    /// separately and adds for every bit.
+    ///
+    /// Pippenger's buckets do not help here: the scalars are witnesses, so
+    /// placing a point in a bucket would take a conditional addition to
+    /// every bucket, which costs more than a lookup.
+    ///
+    /// Inputs must be specified in *little-endian* form, and scalars may
+    /// have different lengths. The tables contain the identity, so this
+    /// default relies on `add` being complete; gadgets with incomplete
+    /// addition override it. A `window` of zero, or a number of scalars
+    /// different from the number of bases, is rejected with
+    /// `SynthesisError::InvalidArgument`.
+    fn multi_scalar_mul<CS, S>(
+        mut cs: CS,
+        bases: &[Self],
+        scalars: &[S],
+        window: usize,
+    ) -> Result<Self, SynthesisError>
+    where
+        CS: ConstraintSystem<ConstraintF>,
+        S: Borrow<[Boolean]>,
+    {
+        if window == 0 || bases.len() != scalars.len() {
+            return Err(SynthesisError::InvalidArgument);
+        }
+        let zero = Self::zero(cs.ns(|| "zero"))?;
+        let mut tables = Vec::with_capacity(bases.len());
+        for (i, base) in bases.iter().enumerate() {
+            let mut table = Vec::with_capacity(1 << window);
+            table.push(zero.clone());
+            table.push(base.clone());
+            for j in 2..(1 << window) {
+                let multiple = if j % 2 == 0 {
+                    let mut multiple = table[j / 2].clone();
+                    multiple.double_in_place(cs.ns(|| format!("Base {} multiple {}", i, j)))?;
+                    multiple
+                } else {
+                    table[j - 1].add(cs.ns(|| format!("Base {} multiple {}", i, j)), base)?
+                };
+                table.push(multiple);
+            }
+            tables.push(table);
+        }
+
+        let num_bits = scalars.iter().map(|s| s.borrow().len()).max().unwrap_or(0);
+        let num_windows = (num_bits + window - 1) / window;
+        let mut result = zero;
+        for w in (0..num_windows).rev() {
+            let mut cs = cs.ns(|| format!("Window {}", w));
+            if w + 1 != num_windows {
+                for j in 0..window {
+                    result.double_in_place(cs.ns(|| format!("Doubling {}", j)))?;
+                }
+            }
+            for (i, (scalar, table)) in scalars.iter().zip(&tables).enumerate() {
+                // The lookup expects its index in big-endian form.
+                let index_bits = (w * window..(w + 1) * window)
+                    .rev()
+                    .map(|k| {
+                        scalar
+                            .borrow()
+                            .get(k)
+                            .cloned()
+                            .unwrap_or(Boolean::constant(false))
+                    })
+                    .collect::<Vec<_>>();
+                let selected = conditionally_select_power_of_two_vector(
+                    cs.ns(|| format!("Select base {}", i)),
+                    &index_bits,
+                    table,
+                )?;
+                result = result.add(cs.ns(|| format!("Add base {}", i)), &selected)?;
+            }
+        }
+        Ok(result)
+    }
+
     fn precomputed_base_scalar_mul<'a, CS, I, B>(
         &mut self,
         mut cs: CS,
@@ -115,6 +353,96 @@ pub trait GroupGadget<G: Group, ConstraintF: Field>:
         Ok(())
     }
 
+    /// Computes `bits * base` for a fixed `base` by processing `window` bits
+    /// at a time: the multiples `0, base_power, ..., (2^window - 1) * base_power`
+    /// of each window are computed natively and one of them is selected, so
+    /// only one addition is needed per window.
+    ///
+    /// Each window costs `2^window - 1` conditional selections plus one
+    /// addition, so small windows (2 or 3 bits) give the fewest constraints.
+    ///
+    /// Inputs must be specified in *little-endian* form. The precomputed
+    /// tables contain the identity, so this default relies on `add` being
+    /// complete; gadgets with incomplete addition override it. A `window` of
+    /// zero is rejected with `SynthesisError::InvalidArgument`.
+    fn fixed_base_scalar_mul<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        base: &G,
+        bits: &[Boolean],
+        window: usize,
+    ) -> Result<Self, SynthesisError> {
+        if window == 0 {
+            return Err(SynthesisError::InvalidArgument);
+        }
+        let mut result = Self::zero(cs.ns(|| "Declare Result"))?;
+        let mut base_power = *base;
+        for (i, chunk) in bits.chunks(window).enumerate() {
+            let mut table = Vec::with_capacity(1 << chunk.len());
+            let mut multiple = G::zero();
+            for j in 0..(1 << chunk.len()) {
+                table.push(Self::alloc_constant(
+                    cs.ns(|| format!("Window {} multiple {}", i, j)),
+                    multiple,
+                )?);
+                multiple += &base_power;
+            }
+            // The lookup expects its index in big-endian form.
+            let index_bits = chunk.iter().rev().cloned().collect::<Vec<_>>();
+            let selected = conditionally_select_power_of_two_vector(
+                cs.ns(|| format!("Select window {}", i)),
+                &index_bits,
+                &table,
+            )?;
+            result = result.add(cs.ns(|| format!("Add window {}", i)), &selected)?;
+            for _ in 0..chunk.len() {
+                base_power.double_in_place();
+            }
+        }
+        Ok(result)
+    }
+
+    /// Computes the sum of `points` by adding them pairwise in a balanced
+    /// tree, so the addition depth is logarithmic in `points.len()`. The
+    /// sum of an empty slice is the identity.
+    ///
+    /// If the addition law is incomplete, no intermediate sum may hit one of
+    /// its exceptional cases.
+    fn sum<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        points: &[Self],
+    ) -> Result<Self, SynthesisError> {
+        if points.is_empty() {
+            return Self::zero(cs.ns(|| "Zero"));
+        }
+        let mut layer = points.to_vec();
+        let mut depth = 0;
+        while layer.len() > 1 {
+            let mut next = Vec::with_capacity((layer.len() + 1) / 2);
+            for (i, pair) in layer.chunks(2).enumerate() {
+                match pair {
+                    [a, b] => {
+                        let ns = cs.ns(|| format!("Add pair {} at depth {}", i, depth));
+                        next.push(a.add(ns, b)?)
+                    },
+                    _ => next.push(pair[0].clone()),
+                }
+            }
+            layer = next;
+            depth += 1;
+        }
+        Ok(layer.swap_remove(0))
+    }
+
+    /// Like `sum`, but takes the points from an iterator.
+    fn sum_iter<CS, I>(cs: CS, points: I) -> Result<Self, SynthesisError>
+    where
+        CS: ConstraintSystem<ConstraintF>,
+        I: IntoIterator<Item = Self>,
+    {
+        let points = points.into_iter().collect::<Vec<_>>();
+        Self::sum(cs, &points)
+    }
+
     fn precomputed_base_3_bit_signed_digit_scalar_mul<'a, CS, I, J, B>(
         _: CS,
         _: &[B],
@@ -205,6 +533,43 @@ mod test {
         let b_b = b.add(cs.ns(|| "b + b"), &b).unwrap();
         assert_eq!(b2, b_b);
 
+        // conditional_negate matches the select-based form.
+        let neg_a = a.negate(cs.ns(|| "-a")).unwrap();
+        for (i, value) in [true, false].iter().enumerate() {
+            let cond = Boolean::alloc(cs.ns(|| format!("cond {}", i)), || Ok(*value)).unwrap();
+            let expected = GG::conditionally_select(
+                cs.ns(|| format!("select {}", i)),
+                &cond,
+                &neg_a,
+                &a,
+            )
+            .unwrap();
+            let result = a
+                .conditional_negate(cs.ns(|| format!("conditional_negate {}", i)), &cond)
+                .unwrap();
+            assert_eq!(result, expected);
+            assert_eq!(result, if *value { neg_a.clone() } else { a.clone() });
+        }
+
+        // A balanced-tree sum matches the left fold.
+        let points = (0..8)
+            .map(|i| GG::alloc(cs.ns(|| format!("point {}", i)), || Ok(G::rand(&mut rng))).unwrap())
+            .collect::<Vec<_>>();
+        let mut fold = points[0].clone();
+        for (i, point) in points.iter().enumerate().skip(1) {
+            fold = fold.add(cs.ns(|| format!("fold {}", i)), point).unwrap();
+        }
+        assert_eq!(GG::sum(cs.ns(|| "sum"), &points).unwrap(), fold);
+        assert_eq!(
+            GG::sum_iter(cs.ns(|| "sum_iter"), points.iter().cloned()).unwrap(),
+            fold
+        );
+        assert_eq!(
+            GG::sum(cs.ns(|| "sum of one"), &points[..1]).unwrap(),
+            points[0]
+        );
+        assert_eq!(GG::sum(cs.ns(|| "empty sum"), &[]).unwrap(), zero);
+
         let _ = a.to_bytes(&mut cs.ns(|| "ToBytes")).unwrap();
         let _ = a
             .to_non_unique_bytes(&mut cs.ns(|| "ToBytes Strict"))
@@ -21,3 +21,552 @@ fn test() {
         G2Gadget,
     >();
 }
+
+#[test]
+fn test_glv_mul() {
+    use crate::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{
+        bls12_377::{Fq, Fr, G1Projective},
+        test_rng, BitIterator, One, PrimeField, ProjectiveCurve, UniformRand, Zero,
+    };
+    use r1cs_core::{ConstraintSystem, SynthesisError};
+
+    let mut rng = test_rng();
+    let mut num_constraints_per_scalar = None;
+    for _ in 0..3 {
+        let mut cs = TestConstraintSystem::new();
+
+        let a = G1Projective::rand(&mut rng);
+        let b = G1Projective::rand(&mut rng);
+        let scalar = Fr::rand(&mut rng);
+        let gadget_a = G1Gadget::alloc(&mut cs.ns(|| "a"), || Ok(a)).unwrap();
+        let gadget_b = G1Gadget::alloc(&mut cs.ns(|| "b"), || Ok(b)).unwrap();
+
+        let mut bits: Vec<bool> = BitIterator::new(scalar.into_repr()).collect();
+        bits.reverse();
+        bits.truncate(Fr::size_in_bits());
+        let input = Vec::<Boolean>::alloc(cs.ns(|| "Input"), || Ok(bits)).unwrap();
+
+        let num_constraints = cs.num_constraints();
+        let result = gadget_a
+            .glv_mul(cs.ns(|| "glv_mul"), &gadget_b, &input)
+            .unwrap();
+        let glv_cost = cs.num_constraints() - num_constraints;
+
+        let num_constraints = cs.num_constraints();
+        let expected = gadget_a
+            .mul_bits(cs.ns(|| "mul_bits"), &gadget_b, input.iter())
+            .unwrap();
+        let mul_bits_cost = cs.num_constraints() - num_constraints;
+
+        assert_eq!(result.get_value().unwrap(), a.mul(scalar) + &b);
+        assert_eq!(result.get_value(), expected.get_value());
+        assert!(cs.is_satisfied());
+        assert!(glv_cost < mul_bits_cost);
+        // The circuit does not depend on the value of the scalar.
+        assert_eq!(*num_constraints_per_scalar.get_or_insert(glv_cost), glv_cost);
+
+        // A decomposition that does not add up to the scalar is rejected.
+        let k1_bit = "glv_mul/k1 bit 0/boolean";
+        let flipped = if cs.get(k1_bit) == Fq::zero() {
+            Fq::one()
+        } else {
+            Fq::zero()
+        };
+        cs.set(k1_bit, flipped);
+        assert!(!cs.is_satisfied());
+    }
+
+    // Scalars longer than the scalar field are rejected.
+    let mut cs = TestConstraintSystem::new();
+    let a = G1Projective::rand(&mut rng);
+    let gadget_a = G1Gadget::alloc(&mut cs.ns(|| "a"), || Ok(a)).unwrap();
+    let input = vec![Boolean::constant(true); Fr::size_in_bits() + 1];
+    match gadget_a.glv_mul(cs.ns(|| "glv_mul"), &gadget_a, &input) {
+        Err(SynthesisError::InvalidArgument) => {},
+        _ => panic!("expected InvalidArgument for an overlong scalar"),
+    }
+}
+
+#[test]
+fn test_add_unified() {
+    use super::Fq2Gadget;
+    use crate::{
+        fields::fp::FpGadget, groups::curves::short_weierstrass::AffineGadget, prelude::*,
+        test_constraint_system::TestConstraintSystem,
+    };
+    use algebra::{
+        bls12_377::Fq,
+        curves::{
+            models::bls12::Bls12Parameters, short_weierstrass_jacobian::GroupProjective,
+            SWModelParameters,
+        },
+        test_rng, Group, UniformRand, Zero,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    fn add_unified_test<P, F>()
+    where
+        P: SWModelParameters,
+        F: FieldGadget<P::BaseField, Fq>,
+    {
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let mut rng = test_rng();
+
+        let a = GroupProjective::<P>::rand(&mut rng);
+        let b = GroupProjective::<P>::rand(&mut rng);
+        let gadget_a = AffineGadget::<P, Fq, F>::alloc(cs.ns(|| "a"), || Ok(a)).unwrap();
+        let gadget_b = AffineGadget::<P, Fq, F>::alloc(cs.ns(|| "b"), || Ok(b)).unwrap();
+        let neg_a = gadget_a.negate(cs.ns(|| "-a")).unwrap();
+        let zero = AffineGadget::<P, Fq, F>::zero(cs.ns(|| "zero")).unwrap();
+
+        let cases = [
+            ("a + b", &gadget_a, &gadget_b, a + &b),
+            ("a + a", &gadget_a, &gadget_a, Group::double(&a)),
+            ("a + (-a)", &gadget_a, &neg_a, GroupProjective::<P>::zero()),
+            ("a + 0", &gadget_a, &zero, a),
+            ("0 + a", &zero, &gadget_a, a),
+            ("0 + 0", &zero, &zero, GroupProjective::<P>::zero()),
+        ];
+        for (name, x, y, expected) in cases.iter() {
+            let result = x.add_unified(cs.ns(|| *name), y).unwrap();
+            assert_eq!(result.get_value().unwrap(), *expected, "{}", name);
+        }
+        assert!(cs.is_satisfied());
+    }
+
+    add_unified_test::<<Parameters as Bls12Parameters>::G1Parameters, FpGadget<Fq>>();
+    add_unified_test::<<Parameters as Bls12Parameters>::G2Parameters, Fq2Gadget>();
+}
+
+#[test]
+fn test_fixed_base_scalar_mul() {
+    use crate::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{
+        bls12_377::{Fq, Fr, G1Projective},
+        test_rng, BitIterator, One, PrimeField, ProjectiveCurve, UniformRand, Zero,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    let mut rng = test_rng();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let base = G1Projective::rand(&mut rng);
+    // Zero and `r - 1` reach the identity and the largest multiple.
+    let scalars = [Fr::rand(&mut rng), Fr::zero(), Fr::one(), -Fr::one()];
+    for (i, scalar) in scalars.iter().enumerate() {
+        let mut bits: Vec<bool> = BitIterator::new(scalar.into_repr()).collect();
+        bits.reverse();
+        bits.truncate(Fr::size_in_bits());
+        let input = Vec::<Boolean>::alloc(cs.ns(|| format!("Input {}", i)), || Ok(bits)).unwrap();
+        for window in 1..=4 {
+            let result = G1Gadget::fixed_base_scalar_mul(
+                cs.ns(|| format!("Scalar {} window {}", i, window)),
+                &base,
+                &input,
+                window,
+            )
+            .unwrap();
+            assert_eq!(result.get_value().unwrap(), base.mul(*scalar));
+        }
+    }
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_scalar_mul_le() {
+    use crate::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{
+        bls12_377::{Fq, Fr, G1Projective},
+        test_rng, BitIterator, One, PrimeField, ProjectiveCurve, UniformRand, Zero,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    let mut rng = test_rng();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let bases = [G1Projective::rand(&mut rng), G1Projective::zero()];
+    let scalars = [
+        Fr::rand(&mut rng),
+        Fr::rand(&mut rng),
+        Fr::zero(),
+        Fr::one(),
+        -Fr::one(),
+    ];
+    for (i, base) in bases.iter().enumerate() {
+        let base_gadget = G1Gadget::alloc(cs.ns(|| format!("Base {}", i)), || Ok(base)).unwrap();
+        for (j, scalar) in scalars.iter().enumerate() {
+            let mut bits: Vec<bool> = BitIterator::new(scalar.into_repr()).collect();
+            bits.reverse();
+            bits.truncate(Fr::size_in_bits());
+            let input =
+                Vec::<Boolean>::alloc(cs.ns(|| format!("Input {} {}", i, j)), || Ok(bits)).unwrap();
+            let result = base_gadget
+                .scalar_mul_le(cs.ns(|| format!("Base {} scalar {}", i, j)), &input)
+                .unwrap();
+            assert_eq!(result.get_value().unwrap(), base.mul(*scalar));
+        }
+    }
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_mul_bits_2() {
+    use crate::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{
+        bls12_377::{Fq, Fr, G1Projective},
+        test_rng, BitIterator, One, PrimeField, ProjectiveCurve, UniformRand, Zero,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    let mut rng = test_rng();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let p = G1Projective::rand(&mut rng);
+    let q = G1Projective::rand(&mut rng);
+    let (a, b) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+    // The table of the default would hold `p + q`, which is the identity for
+    // `q = -p`.
+    let cases = [
+        (p, a, q, b),
+        (p, Fr::zero(), q, b),
+        (p, a, q, Fr::zero()),
+        (p, Fr::zero(), q, Fr::zero()),
+        (p, Fr::one(), q, Fr::one()),
+        (p, a, -p, b),
+        (p, Fr::one(), -p, Fr::one()),
+        (p, a, p, b),
+        (G1Projective::zero(), a, q, b),
+        (p, a, G1Projective::zero(), b),
+    ];
+    let to_bits = |scalar: &Fr| {
+        let mut bits: Vec<bool> = BitIterator::new(scalar.into_repr()).collect();
+        bits.reverse();
+        bits.truncate(Fr::size_in_bits());
+        bits
+    };
+    for (i, (p, p_scalar, q, q_scalar)) in cases.iter().enumerate() {
+        let mut cs = cs.ns(|| format!("Case {}", i));
+        let p_gadget = G1Gadget::alloc(cs.ns(|| "p"), || Ok(p)).unwrap();
+        let q_gadget = G1Gadget::alloc(cs.ns(|| "q"), || Ok(q)).unwrap();
+        let p_bits = Vec::<Boolean>::alloc(cs.ns(|| "p bits"), || Ok(to_bits(p_scalar))).unwrap();
+        let q_bits = Vec::<Boolean>::alloc(cs.ns(|| "q bits"), || Ok(to_bits(q_scalar))).unwrap();
+        let result =
+            G1Gadget::mul_bits_2(cs.ns(|| "mul_bits_2"), &p_gadget, &p_bits, &q_gadget, &q_bits)
+                .unwrap();
+        assert_eq!(
+            result.get_value().unwrap(),
+            p.mul(*p_scalar) + &q.mul(*q_scalar),
+            "case {}",
+            i
+        );
+    }
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_multi_scalar_mul() {
+    use crate::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{
+        bls12_377::{Fq, Fr, G1Projective},
+        test_rng, BitIterator, PrimeField, ProjectiveCurve, UniformRand, Zero,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    let mut rng = test_rng();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let bases = [
+        G1Projective::rand(&mut rng),
+        G1Projective::rand(&mut rng),
+        G1Projective::rand(&mut rng),
+        G1Projective::zero(),
+    ];
+    // The low windows of `2^40` are all zero, as are all windows of zero.
+    let scalars = [
+        Fr::rand(&mut rng),
+        Fr::from(1u64 << 40),
+        Fr::zero(),
+        Fr::rand(&mut rng),
+    ];
+    let base_gadgets = bases
+        .iter()
+        .enumerate()
+        .map(|(i, base)| G1Gadget::alloc(cs.ns(|| format!("Base {}", i)), || Ok(base)).unwrap())
+        .collect::<Vec<_>>();
+    let scalar_gadgets = scalars
+        .iter()
+        .enumerate()
+        .map(|(i, scalar)| {
+            let mut bits: Vec<bool> = BitIterator::new(scalar.into_repr()).collect();
+            bits.reverse();
+            bits.truncate(Fr::size_in_bits());
+            Vec::<Boolean>::alloc(cs.ns(|| format!("Scalar {}", i)), || Ok(bits)).unwrap()
+        })
+        .collect::<Vec<_>>();
+    let expected = bases
+        .iter()
+        .zip(&scalars)
+        .fold(G1Projective::zero(), |sum, (base, scalar)| sum + &base.mul(*scalar));
+    for window in 1..=3 {
+        let result = G1Gadget::multi_scalar_mul(
+            cs.ns(|| format!("Window {}", window)),
+            &base_gadgets,
+            &scalar_gadgets,
+            window,
+        )
+        .unwrap();
+        assert_eq!(result.get_value().unwrap(), expected);
+
+        // A sum of zero windows only is the identity.
+        let result = G1Gadget::multi_scalar_mul(
+            cs.ns(|| format!("Zero scalars window {}", window)),
+            &base_gadgets[..3],
+            &vec![scalar_gadgets[2].clone(); 3],
+            window,
+        )
+        .unwrap();
+        assert!(result.get_value().unwrap().is_zero());
+    }
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_from_x_and_sign() {
+    use crate::{fields::fp::FpGadget, prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{
+        biginteger::BigInteger,
+        bls12_377::{Fq, G1Projective},
+        test_rng, Field, PrimeField, ProjectiveCurve, SquareRootField, UniformRand,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    let mut rng = test_rng();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    for i in 0..10 {
+        let point = G1Projective::rand(&mut rng);
+        let affine = point.into_affine();
+        let x = FpGadget::alloc(cs.ns(|| format!("x {}", i)), || Ok(affine.x)).unwrap();
+        let y_is_odd = Boolean::alloc(cs.ns(|| format!("sign {}", i)), || {
+            Ok(affine.y.into_repr().is_odd())
+        })
+        .unwrap();
+        let result = G1Gadget::from_x_and_sign(cs.ns(|| format!("decompress {}", i)), x, &y_is_odd)
+            .unwrap();
+        assert_eq!(result.get_value().unwrap(), point);
+    }
+    assert!(cs.is_satisfied());
+
+    // An x-coordinate without a matching y leaves the system unsatisfied.
+    let invalid_x = loop {
+        let x = Fq::rand(&mut rng);
+        if (x.square() * &x + &Fq::from(1u64)).sqrt().is_none() {
+            break x;
+        }
+    };
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let x = FpGadget::alloc(cs.ns(|| "x"), || Ok(invalid_x)).unwrap();
+    let _ = G1Gadget::from_x_and_sign(cs.ns(|| "decompress"), x, &Boolean::constant(false))
+        .unwrap();
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_compressed_bytes() {
+    use crate::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{
+        bls12_377::{Fq, G1Projective},
+        test_rng, CanonicalSerialize, ProjectiveCurve, UniformRand, Zero,
+    };
+    use r1cs_core::{ConstraintSystem, SynthesisError};
+
+    let mut rng = test_rng();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let mut points = (0..10)
+        .map(|_| G1Projective::rand(&mut rng))
+        .collect::<Vec<_>>();
+    points.push(G1Projective::zero());
+    for (i, point) in points.into_iter().enumerate() {
+        let mut native_bytes = vec![];
+        point.into_affine().serialize(&mut native_bytes).unwrap();
+
+        let gadget = G1Gadget::alloc(cs.ns(|| format!("point {}", i)), || Ok(point)).unwrap();
+        let bytes = gadget
+            .to_compressed_bytes(cs.ns(|| format!("to bytes {}", i)))
+            .unwrap();
+        let bytes_value = bytes
+            .iter()
+            .map(|b| b.get_value().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(bytes_value, native_bytes);
+
+        let result = G1Gadget::from_compressed_bytes(cs.ns(|| format!("from bytes {}", i)), &bytes)
+            .unwrap();
+        assert_eq!(result.get_value().unwrap(), point);
+
+        let truncated = &bytes[..bytes.len() - 1];
+        match G1Gadget::from_compressed_bytes(cs.ns(|| format!("truncated {}", i)), truncated) {
+            Err(SynthesisError::InvalidArgument) => {},
+            _ => panic!("expected InvalidArgument for truncated bytes"),
+        }
+    }
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_uncompressed_bytes() {
+    use crate::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{
+        bls12_377::{Fq, G1Projective},
+        test_rng, CanonicalSerialize, ProjectiveCurve, UniformRand, Zero,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    let mut rng = test_rng();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let mut points = (0..10)
+        .map(|_| G1Projective::rand(&mut rng))
+        .collect::<Vec<_>>();
+    points.push(G1Projective::zero());
+    for (i, point) in points.into_iter().enumerate() {
+        let mut native_bytes = vec![];
+        point
+            .into_affine()
+            .serialize_uncompressed(&mut native_bytes)
+            .unwrap();
+
+        let gadget = G1Gadget::alloc(cs.ns(|| format!("point {}", i)), || Ok(point)).unwrap();
+        let bytes = gadget
+            .to_uncompressed_bytes(cs.ns(|| format!("to bytes {}", i)))
+            .unwrap();
+        assert_eq!(UInt8::get_values(&bytes), Some(native_bytes));
+    }
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_affine_coords() {
+    use crate::{fields::fp::FpGadget, prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{
+        bls12_377::{Fq, G1Affine, G1Projective},
+        test_rng, ProjectiveCurve, UniformRand, Zero,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    let mut rng = test_rng();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let mut points = (0..10)
+        .map(|_| G1Projective::rand(&mut rng))
+        .collect::<Vec<_>>();
+    points.push(G1Projective::zero());
+    let mut gadgets = points
+        .iter()
+        .enumerate()
+        .map(|(i, point)| G1Gadget::alloc(cs.ns(|| format!("point {}", i)), || Ok(point)).unwrap())
+        .collect::<Vec<_>>();
+
+    // The identity with arbitrary coordinates is normalized as well.
+    points.push(G1Projective::zero());
+    let x = FpGadget::alloc(cs.ns(|| "x"), || Ok(Fq::rand(&mut rng))).unwrap();
+    let y = FpGadget::alloc(cs.ns(|| "y"), || Ok(Fq::rand(&mut rng))).unwrap();
+    let infinity = Boolean::alloc(cs.ns(|| "infinity"), || Ok(true)).unwrap();
+    gadgets.push(G1Gadget::new(x, y, infinity));
+
+    for (i, (point, gadget)) in points.iter().zip(&gadgets).enumerate() {
+        let native: G1Affine = point.into_affine();
+        let (x, y, infinity) = gadget
+            .to_affine_coords(cs.ns(|| format!("affine coords {}", i)))
+            .unwrap();
+        assert_eq!(x.get_value(), Some(native.x));
+        assert_eq!(y.get_value(), Some(native.y));
+        assert_eq!(infinity.get_value(), Some(native.is_zero()));
+    }
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_on_curve() {
+    use crate::{fields::fp::FpGadget, prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{
+        bls12_377::{Fq, G1Projective},
+        test_rng, One, ProjectiveCurve, UniformRand,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    let mut rng = test_rng();
+    let point = G1Projective::rand(&mut rng);
+
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let gadget = G1Gadget::alloc(cs.ns(|| "point"), || Ok(point)).unwrap();
+    let is_on_curve = gadget.is_on_curve(cs.ns(|| "is on curve")).unwrap();
+    gadget.enforce_on_curve(cs.ns(|| "enforce")).unwrap();
+    assert_eq!(is_on_curve.get_value(), Some(true));
+    assert!(cs.is_satisfied());
+
+    // Tamper with the y-coordinate.
+    let point = point.into_affine();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let x = FpGadget::alloc(cs.ns(|| "x"), || Ok(point.x)).unwrap();
+    let y = FpGadget::alloc(cs.ns(|| "y"), || Ok(point.y + &Fq::one())).unwrap();
+    let gadget = G1Gadget::new(x, y, Boolean::constant(false));
+    let is_on_curve = gadget.is_on_curve(cs.ns(|| "is on curve")).unwrap();
+    assert_eq!(is_on_curve.get_value(), Some(false));
+    assert!(cs.is_satisfied());
+    gadget.enforce_on_curve(cs.ns(|| "enforce")).unwrap();
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_enforce_prime_order() {
+    use super::Fq2Gadget;
+    use crate::{
+        fields::fp::FpGadget, groups::curves::short_weierstrass::AffineGadget, prelude::*,
+        test_constraint_system::TestConstraintSystem,
+    };
+    use algebra::{
+        bls12_377::{Fq, Fq2},
+        curves::{
+            models::bls12::Bls12Parameters,
+            short_weierstrass_jacobian::{GroupAffine, GroupProjective},
+            SWModelParameters,
+        },
+        test_rng, AffineCurve, UniformRand, Zero,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    fn enforce_prime_order_test<P, F>(x_from_u64: fn(u64) -> P::BaseField)
+    where
+        P: SWModelParameters,
+        F: FieldGadget<P::BaseField, Fq>,
+    {
+        let mut cs = TestConstraintSystem::<Fq>::new();
+        let point = GroupProjective::<P>::rand(&mut test_rng());
+        let gadget = AffineGadget::<P, Fq, F>::alloc(cs.ns(|| "point"), || Ok(point)).unwrap();
+        gadget.enforce_prime_order(cs.ns(|| "check point")).unwrap();
+        assert!(cs.is_satisfied());
+
+        // Unless it has small order, a point outside the subgroup has order
+        // `m * r` with `m > 1`. The additions computing `(r - 1) * P` combine
+        // multiples `a * P` and `b * P` with `0 < a, b < r`, which are equal,
+        // opposite or zero only if `m * r` divides `a - b`, `a + b` or `a`, so
+        // for `a != b` they never hit an exceptional case of the incomplete
+        // addition and only the final comparison with `-P` fails. Points of
+        // small order would instead fail witness generation.
+        let outside = (1u64..)
+            .filter_map(|i| GroupAffine::<P>::get_point_from_x(x_from_u64(i), false))
+            .find(|p| !p.is_in_correct_subgroup_assuming_on_curve())
+            .unwrap();
+        let gadget = AffineGadget::<P, Fq, F>::alloc(cs.ns(|| "outside subgroup"), || {
+            Ok(outside.into_projective())
+        })
+        .unwrap();
+        assert!(cs.is_satisfied());
+        gadget
+            .enforce_prime_order(cs.ns(|| "check outside subgroup"))
+            .unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    enforce_prime_order_test::<<Parameters as Bls12Parameters>::G1Parameters, FpGadget<Fq>>(
+        Fq::from,
+    );
+    enforce_prime_order_test::<<Parameters as Bls12Parameters>::G2Parameters, Fq2Gadget>(|x| {
+        Fq2::new(Fq::from(x), Fq::zero())
+    });
+}
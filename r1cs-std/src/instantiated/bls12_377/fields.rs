@@ -15,13 +15,283 @@ fn bls12_377_field_gadgets_test() {
 
     field_test::<_, Fq, FqGadget>();
     frobenius_tests::<Fq, Fq, FqGadget>(13);
+    frobenius_pow_tests::<Fq, Fq, FqGadget>(13);
 
     field_test::<_, Fq, Fq2Gadget>();
     frobenius_tests::<Fq2, Fq, Fq2Gadget>(13);
+    frobenius_pow_tests::<Fq2, Fq, Fq2Gadget>(13);
+    lookup_tests::<Fq2, Fq, Fq2Gadget>();
 
     field_test::<_, Fq, Fq6Gadget>();
     frobenius_tests::<Fq6, Fq, Fq6Gadget>(13);
+    frobenius_pow_tests::<Fq6, Fq, Fq6Gadget>(13);
 
     field_test::<_, Fq, Fq12Gadget>();
     frobenius_tests::<Fq12, Fq, Fq12Gadget>(13);
+    frobenius_pow_tests::<Fq12, Fq, Fq12Gadget>(13);
+}
+
+#[test]
+fn bls12_377_cyclotomic_test() {
+    use crate::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{
+        bls12_377::{Fq12, Fr, Parameters},
+        curves::models::bls12::Bls12Parameters,
+        test_rng, BitIterator, Field, PrimeField, UniformRand,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    let mut rng = test_rng();
+
+    // Apply the easy part of the final exponentiation,
+    // f^((p^6 - 1)(p^2 + 1)), to land in the cyclotomic subgroup.
+    let f = Fq12::rand(&mut rng);
+    let mut r = f;
+    r.conjugate();
+    r *= &f.inverse().unwrap();
+    let mut cyclotomic = r;
+    cyclotomic.frobenius_map(2);
+    cyclotomic *= &r;
+
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let a = Fq12Gadget::alloc(cs.ns(|| "a"), || Ok(cyclotomic)).unwrap();
+
+    let num_constraints = cs.num_constraints();
+    let square = a.square(cs.ns(|| "square")).unwrap();
+    let square_cost = cs.num_constraints() - num_constraints;
+
+    let num_constraints = cs.num_constraints();
+    let cyclotomic_square = a.cyclotomic_square(cs.ns(|| "cyclotomic square")).unwrap();
+    let cyclotomic_square_cost = cs.num_constraints() - num_constraints;
+
+    assert_eq!(square.get_value(), cyclotomic_square.get_value());
+    assert_eq!(cyclotomic_square.get_value(), Some(cyclotomic.square()));
+    assert!(cyclotomic_square_cost < square_cost);
+
+    let num_constraints = cs.num_constraints();
+    let pow = a.pow_by_constant(cs.ns(|| "pow"), Parameters::X).unwrap();
+    let pow_cost = cs.num_constraints() - num_constraints;
+
+    let num_constraints = cs.num_constraints();
+    let cyclotomic_pow = a.cyclotomic_exp(cs.ns(|| "cyclotomic exp"), Parameters::X).unwrap();
+    let cyclotomic_pow_cost = cs.num_constraints() - num_constraints;
+
+    assert_eq!(pow.get_value(), cyclotomic_pow.get_value());
+    assert_eq!(cyclotomic_pow.get_value(), Some(cyclotomic.pow(Parameters::X)));
+    assert!(cyclotomic_pow_cost < pow_cost);
+
+    // Exponentiation by a scalar allocated as a witness.
+    let scalar = Fr::rand(&mut rng);
+    let scalar_bits = BitIterator::new(scalar.into_repr())
+        .enumerate()
+        .map(|(i, bit)| Boolean::alloc(cs.ns(|| format!("scalar bit {}", i)), || Ok(bit)))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    let num_constraints = cs.num_constraints();
+    let pow = a.pow(cs.ns(|| "pow by scalar"), &scalar_bits).unwrap();
+    let pow_cost = cs.num_constraints() - num_constraints;
+
+    let num_constraints = cs.num_constraints();
+    let cyclotomic_pow = a
+        .cyclotomic_pow(cs.ns(|| "cyclotomic pow by scalar"), &scalar_bits)
+        .unwrap();
+    let cyclotomic_pow_cost = cs.num_constraints() - num_constraints;
+
+    assert_eq!(pow.get_value(), cyclotomic_pow.get_value());
+    assert_eq!(
+        cyclotomic_pow.get_value(),
+        Some(cyclotomic.pow(scalar.into_repr()))
+    );
+    assert!(cyclotomic_pow_cost < pow_cost);
+    println!(
+        "pow by scalar: {} constraints, cyclotomic: {} constraints",
+        pow_cost, cyclotomic_pow_cost
+    );
+
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn bls12_377_inverse_cost_test() {
+    use crate::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{
+        bls12_377::{Fq12, Fq2, Fq6},
+        test_rng, Field, UniformRand,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    fn check_inverse<FE: Field, F: FieldGadget<FE, Fq>>(
+        cs: &mut TestConstraintSystem<Fq>,
+        name: &str,
+        x: FE,
+    ) -> usize {
+        let x_gadget = F::alloc(cs.ns(|| format!("{} x", name)), || Ok(x)).unwrap();
+        let num_constraints = cs.num_constraints();
+        let x_inv = x_gadget
+            .inverse(cs.ns(|| format!("{} inverse", name)))
+            .unwrap();
+        let cost = cs.num_constraints() - num_constraints;
+
+        let one = F::one(cs.ns(|| format!("{} one", name))).unwrap();
+        x_inv
+            .mul_equals(cs.ns(|| format!("{} x^-1 * x = 1", name)), &x_gadget, &one)
+            .unwrap();
+        assert_eq!(x_inv.get_value(), x.inverse());
+        assert_eq!(cost, F::cost_of_inv());
+        cost
+    }
+
+    let mut rng = test_rng();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let fq2_cost = check_inverse::<_, Fq2Gadget>(&mut cs, "fq2", Fq2::rand(&mut rng));
+    let fq6_cost = check_inverse::<_, Fq6Gadget>(&mut cs, "fq6", Fq6::rand(&mut rng));
+    let fq12_cost = check_inverse::<_, Fq12Gadget>(&mut cs, "fq12", Fq12::rand(&mut rng));
+    assert_eq!(fq2_cost, 3);
+    assert_eq!(fq6_cost, 18);
+    // One Fp6 product and two Fp6 product checks.
+    assert_eq!(fq12_cost, 51);
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn bls12_377_sparse_mul_test() {
+    use crate::{
+        prelude::*, test_constraint_counter::constraint_delta,
+        test_constraint_system::TestConstraintSystem, Vec,
+    };
+    use algebra::{
+        bls12_377::{Fq12, Fq12Parameters, Fq2, Fq6},
+        fields::Fp12Parameters,
+        test_rng, UniformRand, Zero,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    let mut rng = test_rng();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+
+    let fq = Fq::rand(&mut rng);
+    let fq2 = (0..3).map(|_| Fq2::rand(&mut rng)).collect::<Vec<_>>();
+    let fq6 = Fq6::rand(&mut rng);
+    let fq12 = Fq12::rand(&mut rng);
+    let fq_gadget = FqGadget::alloc(cs.ns(|| "fq"), || Ok(fq)).unwrap();
+    let fq2_gadget = Vec::<Fq2Gadget>::alloc(cs.ns(|| "fq2"), || Ok(&fq2[..])).unwrap();
+    let fq6_gadget = Fq6Gadget::alloc(cs.ns(|| "fq6"), || Ok(fq6)).unwrap();
+    let fq12_gadget = Fq12Gadget::alloc(cs.ns(|| "fq12"), || Ok(fq12)).unwrap();
+
+    // Fq2 by Fq.
+    let (result, cost) = constraint_delta(&mut cs, |cs| {
+        fq2_gadget[0]
+            .mul_by_base_field(cs.ns(|| "fq2 by fq"), &fq_gadget)
+            .unwrap()
+    });
+    let mut expected = fq2[0];
+    expected.mul_assign_by_basefield(&fq);
+    assert_eq!(result.get_value(), Some(expected));
+    assert_eq!(cost, 2);
+
+    // Fq6 by Fq2.
+    let (result, cost) = constraint_delta(&mut cs, |cs| {
+        fq6_gadget
+            .mul_by_base_field(cs.ns(|| "fq6 by fq2"), &fq2_gadget[0])
+            .unwrap()
+    });
+    let mut expected = fq6;
+    expected.mul_by_fp2(&fq2[0]);
+    assert_eq!(result.get_value(), Some(expected));
+    assert_eq!(cost, 9);
+
+    let (result, cost) = constraint_delta(&mut cs, |cs| {
+        fq6_gadget
+            .mul_by_0_c1_0(cs.ns(|| "fq6 mul_by_1"), &fq2_gadget[1])
+            .unwrap()
+    });
+    let mut expected = fq6;
+    expected.mul_by_1(&fq2[1]);
+    assert_eq!(result.get_value(), Some(expected));
+    assert_eq!(cost, 9);
+
+    let (result, cost) = constraint_delta(&mut cs, |cs| {
+        fq6_gadget
+            .mul_by_c0_c1_0(cs.ns(|| "fq6 mul_by_01"), &fq2_gadget[0], &fq2_gadget[1])
+            .unwrap()
+    });
+    let mut expected = fq6;
+    expected.mul_by_01(&fq2[0], &fq2[1]);
+    assert_eq!(result.get_value(), Some(expected));
+    assert_eq!(cost, 12);
+
+    // Fq12 by Fq6, by the nonresidue, and by sparse elements.
+    let (result, cost) = constraint_delta(&mut cs, |cs| {
+        fq12_gadget
+            .mul_by_base_field(cs.ns(|| "fq12 by fq6"), &fq6_gadget)
+            .unwrap()
+    });
+    let fq6_in_fq12 = Fq12::new(fq6, Fq6::zero());
+    assert_eq!(result.get_value(), Some(fq12 * &fq6_in_fq12));
+    assert_eq!(cost, 30);
+
+    let (result, cost) = constraint_delta(&mut cs, |cs| {
+        Fq12Gadget::mul_fp6_by_nonresidue(cs.ns(|| "fq6 by nonresidue"), &fq6_gadget).unwrap()
+    });
+    assert_eq!(
+        result.get_value(),
+        Some(Fq12Parameters::mul_fp6_by_nonresidue(&fq6))
+    );
+    assert_eq!(cost, 0);
+
+    let (result, cost) = constraint_delta(&mut cs, |cs| {
+        fq12_gadget
+            .mul_by_014(
+                cs.ns(|| "fq12 mul_by_014"),
+                &fq2_gadget[0],
+                &fq2_gadget[1],
+                &fq2_gadget[2],
+            )
+            .unwrap()
+    });
+    let mut expected = fq12;
+    expected.mul_by_014(&fq2[0], &fq2[1], &fq2[2]);
+    assert_eq!(result.get_value(), Some(expected));
+    assert_eq!(cost, 33);
+
+    let (result, cost) = constraint_delta(&mut cs, |cs| {
+        fq12_gadget
+            .mul_by_034(
+                cs.ns(|| "fq12 mul_by_034"),
+                &fq2_gadget[0],
+                &fq2_gadget[1],
+                &fq2_gadget[2],
+            )
+            .unwrap()
+    });
+    let mut expected = fq12;
+    expected.mul_by_034(&fq2[0], &fq2[1], &fq2[2]);
+    assert_eq!(result.get_value(), Some(expected));
+    assert_eq!(cost, 33);
+
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn bls12_377_to_bytes_matches_serialization() {
+    use super::*;
+    use crate::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{bls12_377::Fq2, test_rng, CanonicalSerialize, UniformRand};
+    use r1cs_core::ConstraintSystem;
+
+    let mut rng = test_rng();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    for i in 0..10 {
+        let x = Fq2::rand(&mut rng);
+        let mut native_bytes = vec![];
+        x.serialize_uncompressed(&mut native_bytes).unwrap();
+
+        let x_gadget = Fq2Gadget::alloc(cs.ns(|| format!("x {}", i)), || Ok(x)).unwrap();
+        let bytes = x_gadget
+            .to_bytes(cs.ns(|| format!("to bytes {}", i)))
+            .unwrap();
+        assert_eq!(UInt8::get_values(&bytes), Some(native_bytes));
+    }
+    assert!(cs.is_satisfied());
 }
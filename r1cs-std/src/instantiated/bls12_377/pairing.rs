@@ -6,3 +6,23 @@ pub type PairingGadget = crate::pairing::bls12::PairingGadget<Parameters>;
 fn test() {
     crate::pairing::tests::bilinearity_test::<algebra::Bls12_377, _, PairingGadget>()
 }
+
+#[test]
+fn test_product_of_pairings() {
+    crate::pairing::tests::product_of_pairings_test::<algebra::Bls12_377, _, PairingGadget>()
+}
+
+#[test]
+fn test_product_of_pairings_is_one() {
+    crate::pairing::tests::product_of_pairings_is_one_test::<algebra::Bls12_377, _, PairingGadget>()
+}
+
+#[test]
+fn test_prepared_g2_eq() {
+    crate::pairing::tests::prepared_g2_eq_test::<algebra::Bls12_377, _, PairingGadget>()
+}
+
+#[test]
+fn test_prepare_g2_neg() {
+    crate::pairing::tests::prepare_g2_neg_test::<algebra::Bls12_377, _, PairingGadget>()
+}
@@ -9,3 +9,195 @@ pub type EdwardsGadget = AffineGadget<EdwardsParameters, Fq, FqGadget>;
 fn test() {
     crate::groups::curves::twisted_edwards::test::<Fq, _, EdwardsGadget>();
 }
+
+#[test]
+fn test_from_x_and_sign() {
+    use crate::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{
+        biginteger::BigInteger, curves::TEModelParameters, test_rng, Field, One, PrimeField,
+        SquareRootField, UniformRand,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    let mut rng = test_rng();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    for i in 0..10 {
+        let point = EdwardsAffine::rand(&mut rng);
+        let x = FqGadget::alloc(cs.ns(|| format!("x {}", i)), || Ok(point.x)).unwrap();
+        let y_is_odd = Boolean::alloc(cs.ns(|| format!("sign {}", i)), || {
+            Ok(point.y.into_repr().is_odd())
+        })
+        .unwrap();
+        let result =
+            EdwardsGadget::from_x_and_sign(cs.ns(|| format!("decompress {}", i)), x, &y_is_odd)
+                .unwrap();
+        let value = GroupGadget::<EdwardsAffine, Fq>::get_value(&result).unwrap();
+        assert_eq!(value, point);
+    }
+    assert!(cs.is_satisfied());
+
+    // An x-coordinate without a matching y leaves the system unsatisfied.
+    let invalid_x = loop {
+        let x = Fq::rand(&mut rng);
+        let x2 = x.square();
+        let y2 = (Fq::one() - &(EdwardsParameters::COEFF_A * &x2))
+            * &(Fq::one() - &(EdwardsParameters::COEFF_D * &x2))
+                .inverse()
+                .unwrap();
+        if y2.sqrt().is_none() {
+            break x;
+        }
+    };
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let x = FqGadget::alloc(cs.ns(|| "x"), || Ok(invalid_x)).unwrap();
+    let _ = EdwardsGadget::from_x_and_sign(cs.ns(|| "decompress"), x, &Boolean::constant(false))
+        .unwrap();
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_compressed_bytes() {
+    use crate::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{test_rng, CanonicalSerialize, UniformRand, Zero};
+    use r1cs_core::ConstraintSystem;
+
+    let mut rng = test_rng();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let mut points = (0..10)
+        .map(|_| EdwardsAffine::rand(&mut rng))
+        .collect::<Vec<_>>();
+    points.push(EdwardsAffine::zero());
+    for (i, point) in points.into_iter().enumerate() {
+        let mut native_bytes = vec![];
+        point.serialize(&mut native_bytes).unwrap();
+
+        let gadget = EdwardsGadget::alloc(cs.ns(|| format!("point {}", i)), || Ok(point)).unwrap();
+        let bytes = gadget
+            .to_compressed_bytes(cs.ns(|| format!("to bytes {}", i)))
+            .unwrap();
+        let bytes_value = bytes
+            .iter()
+            .map(|b| b.get_value().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(bytes_value, native_bytes);
+
+        let result =
+            EdwardsGadget::from_compressed_bytes(cs.ns(|| format!("from bytes {}", i)), &bytes)
+                .unwrap();
+        let value = GroupGadget::<EdwardsAffine, Fq>::get_value(&result).unwrap();
+        assert_eq!(value, point);
+    }
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_uncompressed_bytes() {
+    use crate::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{test_rng, CanonicalSerialize, UniformRand, Zero};
+    use r1cs_core::ConstraintSystem;
+
+    let mut rng = test_rng();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let mut points = (0..10)
+        .map(|_| EdwardsAffine::rand(&mut rng))
+        .collect::<Vec<_>>();
+    points.push(EdwardsAffine::zero());
+    for (i, point) in points.into_iter().enumerate() {
+        let mut native_bytes = vec![];
+        point.serialize_uncompressed(&mut native_bytes).unwrap();
+
+        let gadget = EdwardsGadget::alloc(cs.ns(|| format!("point {}", i)), || Ok(point)).unwrap();
+        let bytes = gadget
+            .to_uncompressed_bytes(cs.ns(|| format!("to bytes {}", i)))
+            .unwrap();
+        assert_eq!(UInt8::get_values(&bytes), Some(native_bytes));
+    }
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_on_curve() {
+    use crate::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use algebra::{test_rng, One, UniformRand};
+    use r1cs_core::ConstraintSystem;
+
+    let mut rng = test_rng();
+    let point = EdwardsAffine::rand(&mut rng);
+
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let gadget = EdwardsGadget::alloc(cs.ns(|| "point"), || Ok(point)).unwrap();
+    let is_on_curve = gadget.is_on_curve(cs.ns(|| "is on curve")).unwrap();
+    gadget.enforce_on_curve(cs.ns(|| "enforce")).unwrap();
+    assert_eq!(is_on_curve.get_value(), Some(true));
+    assert!(cs.is_satisfied());
+
+    // Tamper with the y-coordinate.
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    let x = FqGadget::alloc(cs.ns(|| "x"), || Ok(point.x)).unwrap();
+    let y = FqGadget::alloc(cs.ns(|| "y"), || Ok(point.y + &Fq::one())).unwrap();
+    let gadget = EdwardsGadget::new(x, y);
+    let is_on_curve = gadget.is_on_curve(cs.ns(|| "is on curve")).unwrap();
+    assert_eq!(is_on_curve.get_value(), Some(false));
+    assert!(cs.is_satisfied());
+    gadget.enforce_on_curve(cs.ns(|| "enforce")).unwrap();
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_montgomery_round_trip() {
+    use crate::{
+        groups::curves::twisted_edwards::MontgomeryAffineGadget, prelude::*,
+        test_constraint_system::TestConstraintSystem,
+    };
+    use algebra::{
+        curves::{MontgomeryModelParameters, TEModelParameters},
+        test_rng, Field, UniformRand, Zero,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    type MontParameters = <EdwardsParameters as TEModelParameters>::MontgomeryModelParameters;
+
+    let mut rng = test_rng();
+    let mut cs = TestConstraintSystem::<Fq>::new();
+    for i in 0..10 {
+        let point = EdwardsAffine::rand(&mut rng);
+        let gadget = EdwardsGadget::alloc(cs.ns(|| format!("point {}", i)), || Ok(point)).unwrap();
+
+        let montgomery = gadget
+            .into_montgomery(cs.ns(|| format!("into montgomery {}", i)))
+            .unwrap();
+        let (u, v) = (
+            montgomery.x.get_value().unwrap(),
+            montgomery.y.get_value().unwrap(),
+        );
+        assert_eq!(
+            (u, v),
+            MontgomeryAffineGadget::<EdwardsParameters, Fq, FqGadget>::from_edwards_to_coords(
+                &point
+            )
+            .unwrap()
+        );
+        let coeff_a = <MontParameters as MontgomeryModelParameters>::COEFF_A;
+        assert_eq!(
+            MontParameters::COEFF_B * &v.square(),
+            u.square() * &u + &(coeff_a * &u.square()) + &u
+        );
+
+        let result =
+            EdwardsGadget::from_montgomery(cs.ns(|| format!("from montgomery {}", i)), &montgomery)
+                .unwrap();
+        let value = GroupGadget::<EdwardsAffine, Fq>::get_value(&result).unwrap();
+        assert_eq!(value, point);
+        result
+            .enforce_equal(cs.ns(|| format!("round trip {}", i)), &gadget)
+            .unwrap();
+    }
+    assert!(cs.is_satisfied());
+
+    // The identity has no affine Montgomery counterpart.
+    let identity =
+        EdwardsGadget::alloc(cs.ns(|| "identity"), || Ok(EdwardsAffine::zero())).unwrap();
+    assert!(identity
+        .into_montgomery(cs.ns(|| "identity into montgomery"))
+        .is_err());
+}
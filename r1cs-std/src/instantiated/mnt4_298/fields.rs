@@ -14,10 +14,13 @@ fn mnt4_298_field_gadgets_test() {
 
     field_test::<_, Fq, FqGadget>();
     frobenius_tests::<Fq, Fq, FqGadget>(13);
+    frobenius_pow_tests::<Fq, Fq, FqGadget>(13);
 
     field_test::<_, Fq, Fq2Gadget>();
     frobenius_tests::<Fq2, Fq, Fq2Gadget>(13);
+    frobenius_pow_tests::<Fq2, Fq, Fq2Gadget>(13);
 
     field_test::<_, Fq, Fq4Gadget>();
     frobenius_tests::<Fq4, Fq, Fq4Gadget>(13);
+    frobenius_pow_tests::<Fq4, Fq, Fq4Gadget>(13);
 }
@@ -14,10 +14,14 @@ fn mnt6_298_field_gadgets_test() {
 
     field_test::<_, Fq, FqGadget>();
     frobenius_tests::<Fq, Fq, FqGadget>(13);
+    frobenius_pow_tests::<Fq, Fq, FqGadget>(13);
 
     field_test::<_, Fq, Fq3Gadget>();
     frobenius_tests::<Fq3, Fq, Fq3Gadget>(13);
+    frobenius_pow_tests::<Fq3, Fq, Fq3Gadget>(13);
+    lookup_tests::<Fq3, Fq, Fq3Gadget>();
 
     field_test::<_, Fq, Fq6Gadget>();
     frobenius_tests::<Fq6, Fq, Fq6Gadget>(13);
+    frobenius_pow_tests::<Fq6, Fq, Fq6Gadget>(13);
 }
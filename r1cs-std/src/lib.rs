@@ -83,11 +83,15 @@ pub mod pairing;
 pub mod alloc;
 pub mod eq;
 pub mod select;
+pub mod var;
 
 pub mod prelude {
     pub use crate::{
         alloc::*,
-        bits::{boolean::Boolean, uint32::UInt32, uint8::UInt8, ToBitsGadget, ToBytesGadget},
+        bits::{
+            boolean::Boolean, uint128::UInt128, uint16::UInt16, uint32::UInt32, uint64::UInt64,
+            uint8::UInt8, ToBitsGadget, ToBytesGadget,
+        },
         eq::*,
         fields::{fp::FpGadget, FieldGadget, ToConstraintFieldGadget},
         groups::GroupGadget,
@@ -95,6 +99,9 @@ pub mod prelude {
         pairing::PairingGadget,
         select::*,
     };
+
+    #[cfg(feature = "derive")]
+    pub use r1cs_derive::*;
 }
 
 pub trait Assignment<T> {
@@ -3,6 +3,7 @@ use r1cs_core::{ConstraintSystem, SynthesisError};
 use super::PairingGadget as PG;
 
 use crate::{
+    bits::boolean::Boolean,
     fields::{fp::FpGadget, fp12::Fp12Gadget, fp2::Fp2Gadget, FieldGadget},
     groups::bls12::{G1Gadget, G1PreparedGadget, G2Gadget, G2PreparedGadget},
 };
@@ -104,7 +105,7 @@ impl<P: Bls12Parameters> PG<Bls12<P>, P::Fp> for PairingGadget<P> {
         Ok(f)
     }
 
-    fn final_exponentiation<CS: ConstraintSystem<P::Fp>>(
+    fn final_exponentiation_first_chunk<CS: ConstraintSystem<P::Fp>>(
         mut cs: CS,
         f: &Self::GTGadget,
     ) -> Result<Self::GTGadget, SynthesisError> {
@@ -130,39 +131,53 @@ impl<P: Bls12Parameters> PG<Bls12<P>, P::Fp> for PairingGadget<P> {
             // r = f^((p^6 - 1)(p^2) + (p^6 - 1))
             // r = f^((p^6 - 1)(p^2 + 1))
             r.mul_in_place(cs.ns(|| "mul 0"), &f2)?;
-
-            // Hard part of the final exponentation is below:
-            // From https://eprint.iacr.org/2016/130.pdf, Table 1
-            let mut y0 = r.cyclotomic_square(cs.ns(|| "cyclotomic_sq 1"))?;
-            y0.conjugate_in_place(&mut cs.ns(|| "conjugate 2"))?;
-
-            let mut y5 = Self::exp_by_x(&mut cs.ns(|| "exp_by_x 1"), &r)?;
-
-            let mut y1 = y5.cyclotomic_square(&mut cs.ns(|| "square 1"))?;
-            let mut y3 = y0.mul(&mut cs.ns(|| "mul 1"), &y5)?;
-            y0 = Self::exp_by_x(cs.ns(|| "exp_by_x 2"), &y3)?;
-            let y2 = Self::exp_by_x(cs.ns(|| "exp_by_x 3"), &y0)?;
-            let mut y4 = Self::exp_by_x(cs.ns(|| "exp_by_x 4"), &y2)?;
-            y4.mul_in_place(cs.ns(|| "mul 2"), &y1)?;
-            y1 = Self::exp_by_x(cs.ns(|| "exp_by_x 5"), &y4)?;
-            y3.conjugate_in_place(cs.ns(|| "conjugate 3"))?;
-            y1.mul_in_place(cs.ns(|| "mul 3"), &y3)?;
-            y1.mul_in_place(cs.ns(|| "mul 4"), &r)?;
-            y3 = r.clone();
-            y3.conjugate_in_place(cs.ns(|| "conjugate 4"))?;
-            y0.mul_in_place(cs.ns(|| "mul 5"), &r)?;
-            y0.frobenius_map_in_place(cs.ns(|| "frobmap 3"), 3)?;
-            y4.mul_in_place(cs.ns(|| "mul 6"), &y3)?;
-            y4.frobenius_map_in_place(cs.ns(|| "frobmap 4"), 1)?;
-            y5.mul_in_place(cs.ns(|| "mul 7"), &y2)?;
-            y5.frobenius_map_in_place(cs.ns(|| "frobmap 5"), 2)?;
-            y5.mul_in_place(cs.ns(|| "mul 8"), &y0)?;
-            y5.mul_in_place(cs.ns(|| "mul 9"), &y4)?;
-            y5.mul_in_place(cs.ns(|| "mul 10"), &y1)?;
-            Ok(y5)
+            Ok(r)
         })
     }
 
+    fn final_exponentiation_last_chunk<CS: ConstraintSystem<P::Fp>>(
+        mut cs: CS,
+        r: &Self::GTGadget,
+    ) -> Result<Self::GTGadget, SynthesisError> {
+        // Hard part of the final exponentation is below:
+        // From https://eprint.iacr.org/2016/130.pdf, Table 1
+        let mut y0 = r.cyclotomic_square(cs.ns(|| "cyclotomic_sq 1"))?;
+        y0.conjugate_in_place(&mut cs.ns(|| "conjugate 2"))?;
+
+        let mut y5 = Self::exp_by_x(&mut cs.ns(|| "exp_by_x 1"), r)?;
+
+        let mut y1 = y5.cyclotomic_square(&mut cs.ns(|| "square 1"))?;
+        let mut y3 = y0.mul(&mut cs.ns(|| "mul 1"), &y5)?;
+        y0 = Self::exp_by_x(cs.ns(|| "exp_by_x 2"), &y3)?;
+        let y2 = Self::exp_by_x(cs.ns(|| "exp_by_x 3"), &y0)?;
+        let mut y4 = Self::exp_by_x(cs.ns(|| "exp_by_x 4"), &y2)?;
+        y4.mul_in_place(cs.ns(|| "mul 2"), &y1)?;
+        y1 = Self::exp_by_x(cs.ns(|| "exp_by_x 5"), &y4)?;
+        y3.conjugate_in_place(cs.ns(|| "conjugate 3"))?;
+        y1.mul_in_place(cs.ns(|| "mul 3"), &y3)?;
+        y1.mul_in_place(cs.ns(|| "mul 4"), r)?;
+        y3 = r.clone();
+        y3.conjugate_in_place(cs.ns(|| "conjugate 4"))?;
+        y0.mul_in_place(cs.ns(|| "mul 5"), r)?;
+        y0.frobenius_map_in_place(cs.ns(|| "frobmap 3"), 3)?;
+        y4.mul_in_place(cs.ns(|| "mul 6"), &y3)?;
+        y4.frobenius_map_in_place(cs.ns(|| "frobmap 4"), 1)?;
+        y5.mul_in_place(cs.ns(|| "mul 7"), &y2)?;
+        y5.frobenius_map_in_place(cs.ns(|| "frobmap 5"), 2)?;
+        y5.mul_in_place(cs.ns(|| "mul 8"), &y0)?;
+        y5.mul_in_place(cs.ns(|| "mul 9"), &y4)?;
+        y5.mul_in_place(cs.ns(|| "mul 10"), &y1)?;
+        Ok(y5)
+    }
+
+    fn gt_pow_by_scalar<CS: ConstraintSystem<P::Fp>>(
+        cs: CS,
+        p: &Self::GTGadget,
+        scalar: &[Boolean],
+    ) -> Result<Self::GTGadget, SynthesisError> {
+        p.cyclotomic_pow(cs, scalar)
+    }
+
     fn prepare_g1<CS: ConstraintSystem<P::Fp>>(
         cs: CS,
         p: &Self::G1Gadget,
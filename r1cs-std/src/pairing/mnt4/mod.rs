@@ -8,6 +8,7 @@ use crate::{
         AteAdditionCoefficientsGadget, AteDoubleCoefficientsGadget, G1Gadget, G1PreparedGadget,
         G2Gadget, G2PreparedGadget, G2ProjectiveExtendedGadget,
     },
+    Vec,
 };
 use algebra::{
     curves::mnt4::{MNT4Parameters, MNT4},
@@ -146,13 +147,34 @@ impl<P: MNT4Parameters> PairingGadget<P> {
         Ok((r2, coeff))
     }
 
+    /// Computes the Miller loop of the ate pairing for a single pair of
+    /// prepared points.
     pub fn ate_miller_loop<CS: ConstraintSystem<P::Fp>>(
-        mut cs: CS,
+        cs: CS,
         p: &G1PreparedGadget<P>,
         q: &G2PreparedGadget<P>,
     ) -> Result<Fp4G<P>, SynthesisError> {
-        let mut l1_coeff = Fp2G::<P>::new(p.x.clone(), FpGadget::<P::Fp>::zero(cs.ns(|| "zero"))?);
-        l1_coeff.sub_in_place(cs.ns(|| "l1_coeff"), &q.x_over_twist)?;
+        Self::multi_ate_miller_loop(cs, &[(p, q)])
+    }
+
+    /// Computes the product of the ate Miller loops of all `pairs`.
+    ///
+    /// All pairs share a single accumulator, so the squarings in each loop
+    /// iteration (and the final inversion if the loop count is negative) are
+    /// performed once regardless of the number of pairs; each additional pair
+    /// only adds its line evaluations.
+    fn multi_ate_miller_loop<CS: ConstraintSystem<P::Fp>>(
+        mut cs: CS,
+        pairs: &[(&G1PreparedGadget<P>, &G2PreparedGadget<P>)],
+    ) -> Result<Fp4G<P>, SynthesisError> {
+        let mut l1_coeffs = Vec::with_capacity(pairs.len());
+        for (k, (p, q)) in pairs.iter().enumerate() {
+            let mut cs = cs.ns(|| format!("input {}", k));
+            let zero = FpGadget::<P::Fp>::zero(cs.ns(|| "zero"))?;
+            let mut l1_coeff = Fp2G::<P>::new(p.x.clone(), zero);
+            l1_coeff.sub_in_place(cs.ns(|| "l1_coeff"), &q.x_over_twist)?;
+            l1_coeffs.push(l1_coeff);
+        }
 
         let mut f = Fp4G::<P>::one(cs.ns(|| "one"))?;
 
@@ -163,7 +185,7 @@ impl<P: MNT4Parameters> PairingGadget<P> {
 
         for (j, bit) in BitIterator::new(P::ATE_LOOP_COUNT).enumerate() {
             // code below gets executed for all bits (EXCEPT the MSB itself) of
-            // mnt6_param_p (skipping leading zeros) in MSB to LSB order
+            // mnt4_param_p (skipping leading zeros) in MSB to LSB order
             if !found_one && bit {
                 found_one = true;
                 continue;
@@ -173,62 +195,76 @@ impl<P: MNT4Parameters> PairingGadget<P> {
 
             let mut cs = cs.ns(|| format!("bit {}", j));
 
-            let dc = &q.double_coefficients[dbl_idx];
-            dbl_idx += 1;
-
-            let c_j_x_twist = dc.c_j.mul(cs.ns(|| "dc.c_j * p.x_twist"), &p.x_twist)?;
-            let c0 = dc.c_l.sub(cs.ns(|| "-dc.c_4c + dc.c_l"), &dc.c_4c)?.sub(
-                cs.ns(|| "-dc.c_4c - (dc.c_j * p.x_twist) + dc.c_l"),
-                &c_j_x_twist,
-            )?;
-            let c1 = dc.c_h.mul(cs.ns(|| "dc.c_h * p.y_twist"), &p.y_twist)?;
-            let g_rr_at_p = Fp4G::<P>::new(c0, c1);
+            f.square_in_place(cs.ns(|| "f^2"))?;
 
-            f = f
-                .square(cs.ns(|| "f^2"))?
-                .mul(cs.ns(|| "f^2 * g_rr_at_p"), &g_rr_at_p)?;
+            for (k, (p, q)) in pairs.iter().enumerate() {
+                let mut cs = cs.ns(|| format!("Double input {}", k));
+                let dc = &q.double_coefficients[dbl_idx];
+                let g_rr_at_p = Self::doubling_line_at_p(cs.ns(|| "g_rr_at_p"), p, dc)?;
+                f.mul_in_place(cs.ns(|| "f *= g_rr_at_p"), &g_rr_at_p)?;
+            }
+            dbl_idx += 1;
 
             if bit {
-                let ac = &q.addition_coefficients[add_idx];
+                for (k, ((p, q), l1_coeff)) in pairs.iter().zip(&l1_coeffs).enumerate() {
+                    let mut cs = cs.ns(|| format!("Addition input {}", k));
+                    let ac = &q.addition_coefficients[add_idx];
+                    let g_rq_at_p =
+                        Self::addition_line_at_p(cs.ns(|| "g_rq_at_p"), p, q, l1_coeff, ac)?;
+                    f.mul_in_place(cs.ns(|| "f *= g_rq_at_p"), &g_rq_at_p)?;
+                }
                 add_idx += 1;
-
-                let l1_coeff_c_l1 = l1_coeff.mul(cs.ns(|| "l1_coeff * ac.c_l1"), &ac.c_l1)?;
-                let g_rq_at_p = Fp4G::<P>::new(
-                    ac.c_rz.mul(cs.ns(|| "ac.c_rz * p.y_twist"), &p.y_twist)?,
-                    q.y_over_twist
-                        .mul(cs.ns(|| "q.y_over_twist * ac.c_rz"), &ac.c_rz)?
-                        .add(
-                            cs.ns(|| "q.y_over_twist * ac.c_rz + (l1_coeff * ac.c_l1)"),
-                            &l1_coeff_c_l1,
-                        )?
-                        .negate(cs.ns(|| "-(q.y_over_twist * ac.c_rz + (l1_coeff * ac.c_l1))"))?,
-                );
-                f.mul_in_place(cs.ns(|| "f *= g_rq_at_p"), &g_rq_at_p)?;
             }
         }
 
         if P::ATE_IS_LOOP_COUNT_NEG {
-            let ac = &q.addition_coefficients[add_idx];
-
-            let l1_coeff_c_l1 = l1_coeff.mul(cs.ns(|| "l1_coeff * ac.c_l1"), &ac.c_l1)?;
-            let g_rnegr_at_p = Fp4G::<P>::new(
-                ac.c_rz.mul(cs.ns(|| "ac.c_rz * p.y_twist"), &p.y_twist)?,
-                q.y_over_twist
-                    .mul(cs.ns(|| "q.y_over_twist * ac.c_rz"), &ac.c_rz)?
-                    .add(
-                        cs.ns(|| "q.y_over_twist * ac.c_rz + (l1_coeff * ac.c_l1)"),
-                        &l1_coeff_c_l1,
-                    )?
-                    .negate(cs.ns(|| "-(q.y_over_twist * ac.c_rz + (l1_coeff * ac.c_l1))"))?,
-            );
-            f = f
-                .mul(cs.ns(|| "f * g_rnegr_at_p"), &g_rnegr_at_p)?
-                .inverse(cs.ns(|| "inverse f"))?;
+            for (k, ((p, q), l1_coeff)) in pairs.iter().zip(&l1_coeffs).enumerate() {
+                let mut cs = cs.ns(|| format!("Negative loop count input {}", k));
+                let ac = &q.addition_coefficients[add_idx];
+                let g_rnegr_at_p =
+                    Self::addition_line_at_p(cs.ns(|| "g_rnegr_at_p"), p, q, l1_coeff, ac)?;
+                f.mul_in_place(cs.ns(|| "f *= g_rnegr_at_p"), &g_rnegr_at_p)?;
+            }
+            f = f.inverse(cs.ns(|| "inverse f"))?;
         }
 
         Ok(f)
     }
 
+    fn doubling_line_at_p<CS: ConstraintSystem<P::Fp>>(
+        mut cs: CS,
+        p: &G1PreparedGadget<P>,
+        dc: &AteDoubleCoefficientsGadget<P>,
+    ) -> Result<Fp4G<P>, SynthesisError> {
+        let c_j_x_twist = dc.c_j.mul(cs.ns(|| "dc.c_j * p.x_twist"), &p.x_twist)?;
+        let c0 = dc.c_l.sub(cs.ns(|| "-dc.c_4c + dc.c_l"), &dc.c_4c)?.sub(
+            cs.ns(|| "-dc.c_4c - (dc.c_j * p.x_twist) + dc.c_l"),
+            &c_j_x_twist,
+        )?;
+        let c1 = dc.c_h.mul(cs.ns(|| "dc.c_h * p.y_twist"), &p.y_twist)?;
+        Ok(Fp4G::<P>::new(c0, c1))
+    }
+
+    fn addition_line_at_p<CS: ConstraintSystem<P::Fp>>(
+        mut cs: CS,
+        p: &G1PreparedGadget<P>,
+        q: &G2PreparedGadget<P>,
+        l1_coeff: &Fp2G<P>,
+        ac: &AteAdditionCoefficientsGadget<P>,
+    ) -> Result<Fp4G<P>, SynthesisError> {
+        let l1_coeff_c_l1 = l1_coeff.mul(cs.ns(|| "l1_coeff * ac.c_l1"), &ac.c_l1)?;
+        Ok(Fp4G::<P>::new(
+            ac.c_rz.mul(cs.ns(|| "ac.c_rz * p.y_twist"), &p.y_twist)?,
+            q.y_over_twist
+                .mul(cs.ns(|| "q.y_over_twist * ac.c_rz"), &ac.c_rz)?
+                .add(
+                    cs.ns(|| "q.y_over_twist * ac.c_rz + (l1_coeff * ac.c_l1)"),
+                    &l1_coeff_c_l1,
+                )?
+                .negate(cs.ns(|| "-(q.y_over_twist * ac.c_rz + (l1_coeff * ac.c_l1))"))?,
+        ))
+    }
+
     pub fn final_exponentiation<CS: ConstraintSystem<P::Fp>>(
         mut cs: CS,
         value: &Fp4G<P>,
@@ -298,21 +334,12 @@ impl<P: MNT4Parameters> PG<MNT4<P>, P::Fp> for PairingGadget<P> {
     type GTGadget = GTGadget<P>;
 
     fn miller_loop<CS: ConstraintSystem<P::Fp>>(
-        mut cs: CS,
+        cs: CS,
         ps: &[Self::G1PreparedGadget],
         qs: &[Self::G2PreparedGadget],
     ) -> Result<Self::GTGadget, SynthesisError> {
-        let mut result = Fp4G::<P>::one(cs.ns(|| "one"))?;
-        for (i, (p, q)) in ps.iter().zip(qs.iter()).enumerate() {
-            let miller =
-                Self::ate_miller_loop(cs.ns(|| format!("ate miller loop iteration {}", i)), p, q)?;
-            result.mul_in_place(
-                cs.ns(|| format!("mul ate miller loop iteration {}", i)),
-                &miller,
-            )?;
-        }
-
-        Ok(result)
+        let pairs = ps.iter().zip(qs.iter()).collect::<Vec<_>>();
+        Self::multi_ate_miller_loop(cs, &pairs)
     }
 
     fn final_exponentiation<CS: ConstraintSystem<P::Fp>>(
@@ -322,6 +349,23 @@ impl<P: MNT4Parameters> PG<MNT4<P>, P::Fp> for PairingGadget<P> {
         Self::final_exponentiation(cs, r)
     }
 
+    fn final_exponentiation_first_chunk<CS: ConstraintSystem<P::Fp>>(
+        mut cs: CS,
+        r: &Self::GTGadget,
+    ) -> Result<Self::GTGadget, SynthesisError> {
+        let r_inv = r.inverse(cs.ns(|| "r inverse"))?;
+        Self::final_exponentiation_first_chunk(cs.ns(|| "r_to_first_chunk"), r, &r_inv)
+    }
+
+    fn final_exponentiation_last_chunk<CS: ConstraintSystem<P::Fp>>(
+        mut cs: CS,
+        r: &Self::GTGadget,
+    ) -> Result<Self::GTGadget, SynthesisError> {
+        // `r` is in the cyclotomic subgroup, where inverting is conjugating.
+        let r_inv = r.unitary_inverse(cs.ns(|| "r inverse"))?;
+        Self::final_exponentiation_last_chunk(cs.ns(|| "final_exp_last_chunk"), r, &r_inv)
+    }
+
     fn prepare_g1<CS: ConstraintSystem<P::Fp>>(
         cs: CS,
         p: &Self::G1Gadget,
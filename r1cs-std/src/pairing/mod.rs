@@ -1,5 +1,5 @@
 use crate::prelude::*;
-use algebra::{Field, PairingEngine};
+use algebra::{Field, One, PairingEngine};
 use core::fmt::Debug;
 use r1cs_core::{ConstraintSystem, SynthesisError};
 
@@ -16,10 +16,16 @@ pub trait PairingGadget<PairingE: PairingEngine, ConstraintF: Field> {
         + Debug;
     type G2PreparedGadget: AllocGadget<PairingE::G2Prepared, ConstraintF>
         + ToBytesGadget<ConstraintF>
+        + EqGadget<ConstraintF>
         + Clone
         + Debug;
     type GTGadget: FieldGadget<PairingE::Fqk, ConstraintF> + Clone;
 
+    /// Computes the product of the Miller loops of the pairs `(p[i], q[i])`.
+    ///
+    /// Implementations run a single loop over the bits of the loop count for
+    /// all pairs, so the accumulator is squared once per iteration however
+    /// many pairs are given; each pair only adds its own line evaluations.
     fn miller_loop<CS: ConstraintSystem<ConstraintF>>(
         cs: CS,
         p: &[Self::G1PreparedGadget],
@@ -27,6 +33,29 @@ pub trait PairingGadget<PairingE: PairingEngine, ConstraintF: Field> {
     ) -> Result<Self::GTGadget, SynthesisError>;
 
     fn final_exponentiation<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        p: &Self::GTGadget,
+    ) -> Result<Self::GTGadget, SynthesisError> {
+        let first_chunk =
+            Self::final_exponentiation_first_chunk(cs.ns(|| "final exp first chunk"), p)?;
+        Self::final_exponentiation_last_chunk(cs.ns(|| "final exp last chunk"), &first_chunk)
+    }
+
+    /// Raises `p` to the "easy part" of the final exponent, mapping it into
+    /// the cyclotomic subgroup of `GT`.
+    ///
+    /// Outputs of this method may be multiplied together (or raised to
+    /// powers) before a single call to `final_exponentiation_last_chunk`,
+    /// which is how batched verifiers share the expensive part.
+    fn final_exponentiation_first_chunk<CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        p: &Self::GTGadget,
+    ) -> Result<Self::GTGadget, SynthesisError>;
+
+    /// Raises `p`, which must be in the cyclotomic subgroup (e.g. an output of
+    /// `final_exponentiation_first_chunk`), to the "hard part" of the final
+    /// exponent.
+    fn final_exponentiation_last_chunk<CS: ConstraintSystem<ConstraintF>>(
         cs: CS,
         p: &Self::GTGadget,
     ) -> Result<Self::GTGadget, SynthesisError>;
@@ -51,6 +80,32 @@ pub trait PairingGadget<PairingE: PairingEngine, ConstraintF: Field> {
         Self::final_exponentiation(&mut cs.ns(|| "Final Exp"), &miller_result)
     }
 
+    /// Outputs whether the product of the pairings `e(p[i], q[i])` is one,
+    /// without enforcing it.
+    fn product_of_pairings_is_one<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        p: &[Self::G1PreparedGadget],
+        q: &[Self::G2PreparedGadget],
+    ) -> Result<Boolean, SynthesisError> {
+        let product = Self::product_of_pairings(cs.ns(|| "product of pairings"), p, q)?;
+        let product_minus_one =
+            product.sub_constant(cs.ns(|| "product - 1"), &PairingE::Fqk::one())?;
+        crate::fields::is_zero(cs.ns(|| "is one"), &product_minus_one)
+    }
+
+    /// Raises `p`, an element of `GT` such as an output of `pairing`, to the
+    /// scalar whose big-endian bits are `scalar`.
+    ///
+    /// By default this is `FieldGadget::pow`; implementations override it
+    /// when squarings in the cyclotomic subgroup are cheaper.
+    fn gt_pow_by_scalar<CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        p: &Self::GTGadget,
+        scalar: &[Boolean],
+    ) -> Result<Self::GTGadget, SynthesisError> {
+        p.pow(cs, scalar)
+    }
+
     fn prepare_g1<CS: ConstraintSystem<ConstraintF>>(
         cs: CS,
         q: &Self::G1Gadget,
@@ -60,6 +115,19 @@ pub trait PairingGadget<PairingE: PairingEngine, ConstraintF: Field> {
         cs: CS,
         q: &Self::G2Gadget,
     ) -> Result<Self::G2PreparedGadget, SynthesisError>;
+
+    /// Prepares `-q`, as needed by verifiers that check that a product of
+    /// pairings is one.
+    ///
+    /// Negating an affine point only negates its `y`-coordinate, which costs
+    /// no constraints, so by default this negates `q` and prepares the result.
+    fn prepare_g2_neg<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        q: &Self::G2Gadget,
+    ) -> Result<Self::G2PreparedGadget, SynthesisError> {
+        let q_neg = q.negate(cs.ns(|| "negate q"))?;
+        Self::prepare_g2(cs.ns(|| "prepare -q"), &q_neg)
+    }
 }
 
 #[cfg(test)]
@@ -67,7 +135,7 @@ pub(crate) mod tests {
     use crate::{
         bits::boolean::Boolean, prelude::*, test_constraint_system::TestConstraintSystem, Vec,
     };
-    use algebra::{test_rng, BitIterator, Field, PairingEngine, PrimeField, UniformRand};
+    use algebra::{test_rng, BitIterator, Field, One, PairingEngine, PrimeField, UniformRand};
     use r1cs_core::ConstraintSystem;
 
     #[allow(dead_code)]
@@ -119,7 +187,10 @@ pub(crate) mod tests {
             let mut ans_g = P::pairing(cs.ns(|| "pair(a, b)"), a_prep_g, b_prep_g).unwrap();
             let mut ans_n = E::pairing(a, b);
             ans_n = ans_n.pow(s.into_repr());
+            let ans_g_by_scalar =
+                P::gt_pow_by_scalar(cs.ns(|| "pow by scalar"), &ans_g, &s_iter).unwrap();
             ans_g = ans_g.pow(cs.ns(|| "pow"), &s_iter).unwrap();
+            assert_eq!(ans_g.get_value(), ans_g_by_scalar.get_value());
 
             (ans_g, ans_n)
         };
@@ -159,4 +230,194 @@ pub(crate) mod tests {
 
         assert!(cs.is_satisfied(), "cs is not satisfied");
     }
+
+    #[allow(dead_code)]
+    pub(crate) fn product_of_pairings_test<
+        E: PairingEngine,
+        ConstraintF: Field,
+        P: PairingGadget<E, ConstraintF>,
+    >() {
+        const NUM_PAIRS: usize = 5;
+
+        let mut cs = TestConstraintSystem::<ConstraintF>::new();
+        let mut rng = test_rng();
+
+        let mut expected = E::Fqk::one();
+        let mut ps = Vec::new();
+        let mut qs = Vec::new();
+        for i in 0..NUM_PAIRS {
+            let a = E::G1Projective::rand(&mut rng);
+            let b = E::G2Projective::rand(&mut rng);
+            expected *= &E::pairing(a, b);
+
+            let a_g = P::G1Gadget::alloc(&mut cs.ns(|| format!("a_{}", i)), || Ok(a)).unwrap();
+            let b_g = P::G2Gadget::alloc(&mut cs.ns(|| format!("b_{}", i)), || Ok(b)).unwrap();
+            ps.push(P::prepare_g1(&mut cs.ns(|| format!("a_{} prep", i)), &a_g).unwrap());
+            qs.push(P::prepare_g2(&mut cs.ns(|| format!("b_{} prep", i)), &b_g).unwrap());
+        }
+
+        let num_constraints = cs.num_constraints();
+        let product = P::product_of_pairings(cs.ns(|| "product of pairings"), &ps, &qs).unwrap();
+        let product_cost = cs.num_constraints() - num_constraints;
+
+        let num_constraints = cs.num_constraints();
+        let mut naive = P::GTGadget::one(cs.ns(|| "one")).unwrap();
+        for (i, (p, q)) in ps.iter().zip(&qs).enumerate() {
+            let pairing = P::pairing(cs.ns(|| format!("pair {}", i)), p.clone(), q.clone()).unwrap();
+            naive
+                .mul_in_place(cs.ns(|| format!("mul pair {}", i)), &pairing)
+                .unwrap();
+        }
+        let naive_cost = cs.num_constraints() - num_constraints;
+
+        // Compare a shared Miller loop with separate ones, without the final
+        // exponentiations.
+        let num_constraints = cs.num_constraints();
+        let _ = P::miller_loop(cs.ns(|| "shared miller loop"), &ps, &qs).unwrap();
+        let shared_loop_cost = cs.num_constraints() - num_constraints;
+
+        let num_constraints = cs.num_constraints();
+        for (i, (p, q)) in ps.iter().zip(&qs).enumerate() {
+            let _ = P::miller_loop(
+                cs.ns(|| format!("miller loop {}", i)),
+                &[p.clone()],
+                &[q.clone()],
+            )
+            .unwrap();
+        }
+        let separate_loops_cost = cs.num_constraints() - num_constraints;
+
+        assert_eq!(product.get_value().unwrap(), expected);
+        assert_eq!(product.get_value(), naive.get_value());
+        assert!(product_cost < naive_cost);
+        assert!(shared_loop_cost < separate_loops_cost);
+        assert!(cs.is_satisfied(), "cs is not satisfied");
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn product_of_pairings_is_one_test<
+        E: PairingEngine,
+        ConstraintF: Field,
+        P: PairingGadget<E, ConstraintF>,
+    >() {
+        let mut cs = TestConstraintSystem::<ConstraintF>::new();
+        let mut rng = test_rng();
+
+        let a = E::G1Projective::rand(&mut rng);
+        let b = E::G2Projective::rand(&mut rng);
+        let s = E::Fr::rand(&mut rng);
+        let mut sa = a;
+        sa *= s;
+        let mut sb = b;
+        sb *= s;
+
+        let a_g = P::G1Gadget::alloc(&mut cs.ns(|| "a"), || Ok(a)).unwrap();
+        let b_g = P::G2Gadget::alloc(&mut cs.ns(|| "b"), || Ok(b)).unwrap();
+        let sa_g = P::G1Gadget::alloc(&mut cs.ns(|| "sa"), || Ok(sa)).unwrap();
+        let sb_g = P::G2Gadget::alloc(&mut cs.ns(|| "sb"), || Ok(sb)).unwrap();
+        let neg_a_g = a_g.negate(&mut cs.ns(|| "-a")).unwrap();
+
+        let neg_a_prep_g = P::prepare_g1(&mut cs.ns(|| "-a_prep"), &neg_a_g).unwrap();
+        let sa_prep_g = P::prepare_g1(&mut cs.ns(|| "sa_prep"), &sa_g).unwrap();
+        let b_prep_g = P::prepare_g2(&mut cs.ns(|| "b_prep"), &b_g).unwrap();
+        let sb_prep_g = P::prepare_g2(&mut cs.ns(|| "sb_prep"), &sb_g).unwrap();
+
+        // e(sa, b) * e(-a, sb) = 1
+        let is_one = P::product_of_pairings_is_one(
+            cs.ns(|| "e(sa, b) * e(-a, sb)"),
+            &[sa_prep_g.clone(), neg_a_prep_g],
+            &[b_prep_g.clone(), sb_prep_g],
+        )
+        .unwrap();
+        assert_eq!(is_one.get_value(), Some(true));
+
+        // e(sa, b) * e(sa, b) != 1
+        let is_one = P::product_of_pairings_is_one(
+            cs.ns(|| "e(sa, b) * e(sa, b)"),
+            &[sa_prep_g.clone(), sa_prep_g],
+            &[b_prep_g.clone(), b_prep_g],
+        )
+        .unwrap();
+        assert_eq!(is_one.get_value(), Some(false));
+
+        assert!(cs.is_satisfied(), "cs is not satisfied");
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn prepared_g2_eq_test<
+        E: PairingEngine,
+        ConstraintF: Field,
+        P: PairingGadget<E, ConstraintF>,
+    >() {
+        let mut cs = TestConstraintSystem::<ConstraintF>::new();
+        let mut rng = test_rng();
+
+        let a = E::G2Projective::rand(&mut rng);
+        let b = E::G2Projective::rand(&mut rng);
+        let a_g = P::G2Gadget::alloc(&mut cs.ns(|| "a"), || Ok(a)).unwrap();
+        let b_g = P::G2Gadget::alloc(&mut cs.ns(|| "b"), || Ok(b)).unwrap();
+
+        // Preparing the same point twice yields equal line coefficients.
+        let a_prep_g = P::prepare_g2(&mut cs.ns(|| "a_prep"), &a_g).unwrap();
+        let a_prep_g_again = P::prepare_g2(&mut cs.ns(|| "a_prep again"), &a_g).unwrap();
+        a_prep_g
+            .enforce_equal(&mut cs.ns(|| "a_prep == a_prep again"), &a_prep_g_again)
+            .unwrap();
+        assert!(cs.is_satisfied(), "cs is not satisfied");
+
+        let b_prep_g = P::prepare_g2(&mut cs.ns(|| "b_prep"), &b_g).unwrap();
+        a_prep_g
+            .conditional_enforce_equal(
+                &mut cs.ns(|| "a_prep == b_prep if false"),
+                &b_prep_g,
+                &Boolean::constant(false),
+            )
+            .unwrap();
+        assert!(cs.is_satisfied(), "cs is not satisfied");
+
+        a_prep_g
+            .enforce_equal(&mut cs.ns(|| "a_prep == b_prep"), &b_prep_g)
+            .unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn prepare_g2_neg_test<
+        E: PairingEngine,
+        ConstraintF: Field,
+        P: PairingGadget<E, ConstraintF>,
+    >() {
+        let mut cs = TestConstraintSystem::<ConstraintF>::new();
+        let mut rng = test_rng();
+
+        let a = E::G1Projective::rand(&mut rng);
+        let b = E::G2Projective::rand(&mut rng);
+        let a_g = P::G1Gadget::alloc(&mut cs.ns(|| "a"), || Ok(a)).unwrap();
+        let b_g = P::G2Gadget::alloc(&mut cs.ns(|| "b"), || Ok(b)).unwrap();
+        let neg_b_g = b_g.negate(&mut cs.ns(|| "-b")).unwrap();
+
+        let num_constraints = cs.num_constraints();
+        let neg_b_prep_g = P::prepare_g2_neg(&mut cs.ns(|| "prepare_g2_neg(b)"), &b_g).unwrap();
+        let prepare_neg_cost = cs.num_constraints() - num_constraints;
+
+        let num_constraints = cs.num_constraints();
+        let neg_b_prep_g_expected =
+            P::prepare_g2(&mut cs.ns(|| "prepare_g2(-b)"), &neg_b_g).unwrap();
+        let prepare_cost = cs.num_constraints() - num_constraints;
+
+        assert_eq!(prepare_neg_cost, prepare_cost);
+        neg_b_prep_g
+            .enforce_equal(
+                &mut cs.ns(|| "prepared -b equality"),
+                &neg_b_prep_g_expected,
+            )
+            .unwrap();
+
+        // e(a, -b) = e(a, b)^-1
+        let a_prep_g = P::prepare_g1(&mut cs.ns(|| "a_prep"), &a_g).unwrap();
+        let ans_g = P::pairing(cs.ns(|| "pair(a, -b)"), a_prep_g, neg_b_prep_g).unwrap();
+        assert_eq!(ans_g.get_value(), E::pairing(a, b).inverse());
+
+        assert!(cs.is_satisfied(), "cs is not satisfied");
+    }
 }
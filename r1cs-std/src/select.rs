@@ -1,8 +1,17 @@
-use crate::prelude::*;
-use algebra::Field;
+use crate::{prelude::*, Vec};
+use algebra::{Field, PrimeField};
 use r1cs_core::{ConstraintSystem, SynthesisError};
 
 /// If condition is `true`, return `true_value`; else, select `false_value`.
+///
+/// With the `constant_time` feature, implementations compute the witness of
+/// the result from the values of *both* branches, combining them
+/// arithmetically instead of branching on the value of `cond`, so that the
+/// prover's work does not depend on which branch is selected. The witness of
+/// the result is then missing whenever the witness of either branch is.
+/// Branching on the *shape* of the inputs, e.g. on whether `cond` is a
+/// constant, is fine either way, as that is already public in the constraint
+/// system.
 pub trait CondSelectGadget<ConstraintF: Field>
 where
     Self: Sized,
@@ -17,6 +26,180 @@ where
     fn cost() -> usize;
 }
 
+/// The native counterpart of `CondSelectGadget::conditionally_select`. With
+/// the `constant_time` feature, this computes
+/// `false_value + cond * (true_value - false_value)` without branching on
+/// `cond`; otherwise only the selected value is read.
+pub(crate) fn select_value<F: PrimeField>(
+    cond: Option<bool>,
+    true_value: Option<F>,
+    false_value: Option<F>,
+) -> Option<F> {
+    #[cfg(feature = "constant_time")]
+    {
+        let (cond, t, f) = (F::from(cond? as u8), true_value?, false_value?);
+        Some(f + &(cond * &(t - &f)))
+    }
+    #[cfg(not(feature = "constant_time"))]
+    {
+        cond.and_then(|cond| if cond { true_value } else { false_value })
+    }
+}
+
+/// Selects `values[i]`, where `i` is the integer encoded by `index_bits` in
+/// big-endian order, using a tree of `CondSelectGadget`s whose depth is
+/// `index_bits.len()`. Returns `SynthesisError::InvalidArgument` unless
+/// `values.len()` is `2^index_bits.len()`.
+///
+/// Constant index bits cost nothing: they discard the candidates they rule
+/// out before the tree is built, so only the variable bits add layers.
+pub fn conditionally_select_power_of_two_vector<ConstraintF, CS, T>(
+    mut cs: CS,
+    index_bits: &[Boolean],
+    values: &[T],
+) -> Result<T, SynthesisError>
+where
+    ConstraintF: Field,
+    CS: ConstraintSystem<ConstraintF>,
+    T: CondSelectGadget<ConstraintF> + Clone,
+{
+    if !values.len().is_power_of_two() || values.len().trailing_zeros() as usize != index_bits.len()
+    {
+        return Err(SynthesisError::InvalidArgument);
+    }
+
+    // Keeping the candidates that agree with the constant bits, in order,
+    // their positions are the indices encoded by the variable bits alone.
+    let num_bits = index_bits.len();
+    let mut layer = values
+        .iter()
+        .enumerate()
+        .filter(|(j, _)| {
+            index_bits.iter().enumerate().all(|(i, bit)| match bit {
+                Boolean::Constant(b) => ((j >> (num_bits - 1 - i)) & 1 == 1) == *b,
+                _ => true,
+            })
+        })
+        .map(|(_, value)| value.clone())
+        .collect::<Vec<_>>();
+    let variable_bits = index_bits.iter().filter(|bit| match bit {
+        Boolean::Constant(_) => false,
+        _ => true,
+    });
+
+    // Starting from the least significant bit, each layer selects between
+    // neighbouring candidates and so halves their number.
+    for (i, bit) in variable_bits.rev().enumerate() {
+        let mut cs = cs.ns(|| format!("layer {}", i));
+        layer = layer
+            .chunks(2)
+            .enumerate()
+            .map(|(j, pair)| {
+                T::conditionally_select(cs.ns(|| format!("select {}", j)), bit, &pair[1], &pair[0])
+            })
+            .collect::<Result<_, _>>()?;
+    }
+
+    Ok(layer.pop().unwrap())
+}
+
+macro_rules! impl_cond_select_tuple {
+    ($($T:ident $i:tt),+) => {
+        impl<ConstraintF: Field, $($T: CondSelectGadget<ConstraintF>),+> CondSelectGadget<ConstraintF>
+            for ($($T,)+)
+        {
+            fn conditionally_select<CS: ConstraintSystem<ConstraintF>>(
+                mut cs: CS,
+                cond: &Boolean,
+                true_value: &Self,
+                false_value: &Self,
+            ) -> Result<Self, SynthesisError> {
+                Ok(($(
+                    $T::conditionally_select(
+                        cs.ns(|| concat!("select ", stringify!($i))),
+                        cond,
+                        &true_value.$i,
+                        &false_value.$i,
+                    )?,
+                )+))
+            }
+
+            fn cost() -> usize {
+                0 $(+ <$T as CondSelectGadget<ConstraintF>>::cost())+
+            }
+        }
+    };
+}
+
+impl_cond_select_tuple!(A 0);
+impl_cond_select_tuple!(A 0, B 1);
+impl_cond_select_tuple!(A 0, B 1, C 2);
+impl_cond_select_tuple!(A 0, B 1, C 2, D 3);
+
+macro_rules! impl_cond_select_array {
+    ($N:expr) => {
+        impl<ConstraintF: Field, T: CondSelectGadget<ConstraintF> + Clone>
+            CondSelectGadget<ConstraintF> for [T; $N]
+        {
+            fn conditionally_select<CS: ConstraintSystem<ConstraintF>>(
+                mut cs: CS,
+                cond: &Boolean,
+                true_value: &Self,
+                false_value: &Self,
+            ) -> Result<Self, SynthesisError> {
+                let mut result = true_value.clone();
+                for (i, ((r, t), f)) in result
+                    .iter_mut()
+                    .zip(true_value)
+                    .zip(false_value)
+                    .enumerate()
+                {
+                    *r = T::conditionally_select(cs.ns(|| format!("select {}", i)), cond, t, f)?;
+                }
+                Ok(result)
+            }
+
+            fn cost() -> usize {
+                $N * <T as CondSelectGadget<ConstraintF>>::cost()
+            }
+        }
+    };
+}
+
+impl_cond_select_array!(0);
+impl_cond_select_array!(1);
+impl_cond_select_array!(2);
+impl_cond_select_array!(3);
+impl_cond_select_array!(4);
+impl_cond_select_array!(5);
+impl_cond_select_array!(6);
+impl_cond_select_array!(7);
+impl_cond_select_array!(8);
+impl_cond_select_array!(9);
+impl_cond_select_array!(10);
+impl_cond_select_array!(11);
+impl_cond_select_array!(12);
+impl_cond_select_array!(13);
+impl_cond_select_array!(14);
+impl_cond_select_array!(15);
+impl_cond_select_array!(16);
+impl_cond_select_array!(17);
+impl_cond_select_array!(18);
+impl_cond_select_array!(19);
+impl_cond_select_array!(20);
+impl_cond_select_array!(21);
+impl_cond_select_array!(22);
+impl_cond_select_array!(23);
+impl_cond_select_array!(24);
+impl_cond_select_array!(25);
+impl_cond_select_array!(26);
+impl_cond_select_array!(27);
+impl_cond_select_array!(28);
+impl_cond_select_array!(29);
+impl_cond_select_array!(30);
+impl_cond_select_array!(31);
+impl_cond_select_array!(32);
+
 /// Uses two bits to perform a lookup into a table
 pub trait TwoBitLookupGadget<ConstraintF: Field>
 where
@@ -48,3 +231,269 @@ where
 
     fn cost() -> usize;
 }
+
+#[cfg(test)]
+mod test {
+    use super::{conditionally_select_power_of_two_vector, CondSelectGadget};
+    use crate::{
+        alloc::AllocGadget,
+        bits::{boolean::Boolean, uint8::UInt8},
+        fields::{fp::FpGadget, nonnative::NonNativeFieldGadget},
+        test_constraint_system::TestConstraintSystem,
+        Vec,
+    };
+    use algebra::{
+        bls12_381::{Fq, Fr},
+        UniformRand,
+    };
+    use r1cs_core::{ConstraintSystem, SynthesisError};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_select_power_of_two_vector() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+        let values = (0..8).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+
+        for index in 0..8 {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+
+            let value_gadgets = values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| FpGadget::alloc_constant(cs.ns(|| format!("value {}", i)), v))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            // Big-endian bits of the index
+            let index_bits = (0..3)
+                .rev()
+                .map(|i| {
+                    Boolean::alloc(cs.ns(|| format!("index bit {}", i)), || {
+                        Ok((index >> i) & 1 == 1)
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            let selected = conditionally_select_power_of_two_vector(
+                cs.ns(|| "select"),
+                &index_bits,
+                &value_gadgets,
+            )
+            .unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(selected.value, Some(values[index]));
+            // One selection per internal node of the tree
+            assert_eq!(cs.num_constraints(), 3 + 7);
+        }
+    }
+
+    #[test]
+    fn test_select_power_of_two_vector_constant_bits() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+        let values = (0..8).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+
+        for index in 0..8 {
+            // Bit `i` of `constant_mask` makes bit `i` of the index constant.
+            for constant_mask in 0..8u32 {
+                let mut cs = TestConstraintSystem::<Fr>::new();
+
+                let value_gadgets = values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| FpGadget::alloc(cs.ns(|| format!("value {}", i)), || Ok(v)))
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap();
+                let index_bits = (0..3)
+                    .rev()
+                    .map(|i| {
+                        let bit = (index >> i) & 1 == 1;
+                        if (constant_mask >> i) & 1 == 1 {
+                            Ok(Boolean::constant(bit))
+                        } else {
+                            Boolean::alloc(cs.ns(|| format!("index bit {}", i)), || Ok(bit))
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap();
+
+                let num_constraints = cs.num_constraints();
+                let selected = conditionally_select_power_of_two_vector(
+                    cs.ns(|| "select"),
+                    &index_bits,
+                    &value_gadgets,
+                )
+                .unwrap();
+
+                assert!(cs.is_satisfied());
+                assert_eq!(selected.value, Some(values[index]));
+                // Only the variable bits build the tree.
+                let num_variable_bits = 3 - constant_mask.count_ones();
+                assert_eq!(
+                    cs.num_constraints() - num_constraints,
+                    (1 << num_variable_bits) - 1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_select_power_of_two_vector_length_mismatch() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let values = (0..6)
+            .map(|i| {
+                FpGadget::alloc_constant(cs.ns(|| format!("value {}", i)), &Fr::rand(&mut rng))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let index_bits = vec![Boolean::constant(false); 3];
+
+        match conditionally_select_power_of_two_vector(cs.ns(|| "six"), &index_bits, &values) {
+            Err(SynthesisError::InvalidArgument) => {},
+            _ => panic!("expected InvalidArgument for six values"),
+        }
+        match conditionally_select_power_of_two_vector(
+            cs.ns(|| "four"),
+            &index_bits,
+            &values[..4],
+        ) {
+            Err(SynthesisError::InvalidArgument) => {},
+            _ => panic!("expected InvalidArgument for four values"),
+        }
+    }
+
+    #[test]
+    fn test_select_tuple() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let a = (Fr::rand(&mut rng), true, 0x0fu8);
+        let b = (Fr::rand(&mut rng), false, 0xf0u8);
+
+        for &cond in &[true, false] {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let mut alloc = |name: &str, (f, bit, byte): (Fr, bool, u8)| {
+                let mut cs = cs.ns(|| name);
+                (
+                    FpGadget::alloc(cs.ns(|| "field element"), || Ok(f)).unwrap(),
+                    Boolean::alloc(cs.ns(|| "bit"), || Ok(bit)).unwrap(),
+                    UInt8::alloc(cs.ns(|| "byte"), || Ok(byte)).unwrap(),
+                )
+            };
+            let a_gadget = alloc("a", a);
+            let b_gadget = alloc("b", b);
+            let cond_gadget = Boolean::alloc(cs.ns(|| "cond"), || Ok(cond)).unwrap();
+
+            let selected = CondSelectGadget::conditionally_select(
+                cs.ns(|| "select"),
+                &cond_gadget,
+                &a_gadget,
+                &b_gadget,
+            )
+            .unwrap();
+
+            let expected = if cond { a } else { b };
+            assert_eq!(selected.0.value, Some(expected.0));
+            assert_eq!(selected.1.get_value(), Some(expected.1));
+            assert_eq!(selected.2.get_value(), Some(expected.2));
+            assert!(cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn test_select_array() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let a = [Fr::rand(&mut rng), Fr::rand(&mut rng), Fr::rand(&mut rng)];
+        let b = [Fr::rand(&mut rng), Fr::rand(&mut rng), Fr::rand(&mut rng)];
+
+        for &cond in &[true, false] {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let mut alloc = |name: &str, values: &[Fr; 3]| {
+                let mut cs = cs.ns(|| name);
+                [
+                    FpGadget::alloc(cs.ns(|| "0"), || Ok(values[0])).unwrap(),
+                    FpGadget::alloc(cs.ns(|| "1"), || Ok(values[1])).unwrap(),
+                    FpGadget::alloc(cs.ns(|| "2"), || Ok(values[2])).unwrap(),
+                ]
+            };
+            let a_gadget = alloc("a", &a);
+            let b_gadget = alloc("b", &b);
+            let cond_gadget = Boolean::alloc(cs.ns(|| "cond"), || Ok(cond)).unwrap();
+
+            let selected = <[FpGadget<Fr>; 3]>::conditionally_select(
+                cs.ns(|| "select"),
+                &cond_gadget,
+                &a_gadget,
+                &b_gadget,
+            )
+            .unwrap();
+
+            let expected = if cond { a } else { b };
+            for (s, e) in selected.iter().zip(&expected) {
+                assert_eq!(s.value, Some(*e));
+            }
+            assert!(cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn test_select_reads_both_branches() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+        // With `constant_time`, the result is computed from both branches, so
+        // a missing witness in either of them fails the select, whichever one
+        // is selected. Otherwise only the selected branch must be assigned.
+        for &cond in &[true, false] {
+            for &missing_true in &[true, false] {
+                let mut cs = TestConstraintSystem::<Fr>::new();
+                let cond_gadget = Boolean::alloc(cs.ns(|| "cond"), || Ok(cond)).unwrap();
+                let expect_err = cfg!(feature = "constant_time") || cond == missing_true;
+
+                let mut a = FpGadget::alloc(cs.ns(|| "a"), || Ok(Fr::rand(&mut rng))).unwrap();
+                let mut b = FpGadget::alloc(cs.ns(|| "b"), || Ok(Fr::rand(&mut rng))).unwrap();
+                let mut c =
+                    NonNativeFieldGadget::<Fq, Fr>::alloc(cs.ns(|| "c"), || Ok(Fq::rand(&mut rng)))
+                        .unwrap();
+                let mut d =
+                    NonNativeFieldGadget::<Fq, Fr>::alloc(cs.ns(|| "d"), || Ok(Fq::rand(&mut rng)))
+                        .unwrap();
+                // Drop the whole witness of one branch, limbs included.
+                let (fp, nonnative) = if missing_true {
+                    (&mut a, &mut c)
+                } else {
+                    (&mut b, &mut d)
+                };
+                fp.value = None;
+                nonnative.value = None;
+                for limb in &mut nonnative.limbs {
+                    limb.value = None;
+                }
+
+                let result = FpGadget::conditionally_select(
+                    cs.ns(|| "select fp"),
+                    &cond_gadget,
+                    &a,
+                    &b,
+                );
+                assert_eq!(result.is_err(), expect_err);
+                if let Ok(result) = result {
+                    assert_eq!(result.value, if cond { a.value } else { b.value });
+                }
+
+                let result = NonNativeFieldGadget::conditionally_select(
+                    cs.ns(|| "select nonnative"),
+                    &cond_gadget,
+                    &c,
+                    &d,
+                );
+                assert_eq!(result.is_err(), expect_err);
+                if let Ok(result) = result {
+                    assert_eq!(result.value, if cond { c.value } else { d.value });
+                }
+            }
+        }
+    }
+}
@@ -78,6 +78,33 @@ impl<ConstraintF: Field> ConstraintSystem<ConstraintF> for ConstraintCounter {
     }
 }
 
+/// Panics unless `cs` contains exactly `expected` constraints.
+pub fn assert_constraint_count<ConstraintF: Field, CS: ConstraintSystem<ConstraintF>>(
+    cs: &CS,
+    expected: usize,
+) {
+    assert_eq!(
+        cs.num_constraints(),
+        expected,
+        "expected {} constraints, found {}",
+        expected,
+        cs.num_constraints()
+    );
+}
+
+/// Runs `f` on `cs`, and returns its output together with the number of
+/// constraints it added.
+pub fn constraint_delta<ConstraintF, CS, F, T>(cs: &mut CS, f: F) -> (T, usize)
+where
+    ConstraintF: Field,
+    CS: ConstraintSystem<ConstraintF>,
+    F: FnOnce(&mut CS) -> T,
+{
+    let before = cs.num_constraints();
+    let output = f(cs);
+    (output, cs.num_constraints() - before)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
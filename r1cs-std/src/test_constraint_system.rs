@@ -93,6 +93,17 @@ impl<ConstraintF: Field> TestConstraintSystem<ConstraintF> {
         self.constraints.len()
     }
 
+    /// Returns the number of allocated public inputs, including the constant
+    /// `ONE` input.
+    pub fn num_inputs(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Returns the number of allocated private (auxiliary) variables.
+    pub fn num_aux_variables(&self) -> usize {
+        self.aux.len()
+    }
+
     pub fn set(&mut self, path: &str, to: ConstraintF) {
         match self.named_objects.get(path) {
             Some(&NamedObject::Var(ref v)) => match v.get_unchecked() {
@@ -0,0 +1,130 @@
+use crate::{prelude::*, Vec};
+use algebra::{Field, PrimeField};
+use r1cs_core::{ConstraintVar, Index, Variable};
+
+/// Reads the assignment and the underlying variables of a gadget.
+///
+/// Tuples of up to four gadgets implement this too, which lets generic
+/// gadgets read a group of values at once. The trait is not part of the
+/// prelude, since `FieldGadget` has methods of the same names.
+pub trait R1CSVar<ConstraintF: Field> {
+    type Value;
+    type Variable;
+
+    /// Returns `None` if any part of the assignment is missing.
+    fn get_value(&self) -> Option<Self::Value>;
+
+    fn get_variable(&self) -> Self::Variable;
+}
+
+impl<F: PrimeField> R1CSVar<F> for FpGadget<F> {
+    type Value = F;
+    type Variable = ConstraintVar<F>;
+
+    #[inline]
+    fn get_value(&self) -> Option<F> {
+        FieldGadget::get_value(self)
+    }
+
+    #[inline]
+    fn get_variable(&self) -> ConstraintVar<F> {
+        FieldGadget::get_variable(self)
+    }
+}
+
+impl<ConstraintF: Field> R1CSVar<ConstraintF> for Boolean {
+    type Value = bool;
+    type Variable = ConstraintVar<ConstraintF>;
+
+    #[inline]
+    fn get_value(&self) -> Option<bool> {
+        Boolean::get_value(self)
+    }
+
+    #[inline]
+    fn get_variable(&self) -> ConstraintVar<ConstraintF> {
+        // `Index::Input(0)` is the constant `ConstraintSystem::one()`.
+        let one = Variable::new_unchecked(Index::Input(0));
+        self.lc(one, ConstraintF::one()).into()
+    }
+}
+
+impl<ConstraintF: Field> R1CSVar<ConstraintF> for UInt8 {
+    type Value = u8;
+    /// The bits, least significant first.
+    type Variable = Vec<ConstraintVar<ConstraintF>>;
+
+    #[inline]
+    fn get_value(&self) -> Option<u8> {
+        UInt8::get_value(self)
+    }
+
+    fn get_variable(&self) -> Vec<ConstraintVar<ConstraintF>> {
+        self.into_bits_le()
+            .iter()
+            .map(R1CSVar::<ConstraintF>::get_variable)
+            .collect()
+    }
+}
+
+macro_rules! impl_tuple_r1cs_var {
+    ($($ty:ident : $idx:tt),+) => {
+        impl<ConstraintF: Field, $($ty: R1CSVar<ConstraintF>),+> R1CSVar<ConstraintF>
+            for ($($ty,)+)
+        {
+            type Value = ($($ty::Value,)+);
+            type Variable = ($($ty::Variable,)+);
+
+            #[inline]
+            fn get_value(&self) -> Option<Self::Value> {
+                Some(($(self.$idx.get_value()?,)+))
+            }
+
+            #[inline]
+            fn get_variable(&self) -> Self::Variable {
+                ($(self.$idx.get_variable(),)+)
+            }
+        }
+    };
+}
+
+impl_tuple_r1cs_var!(A: 0);
+impl_tuple_r1cs_var!(A: 0, B: 1);
+impl_tuple_r1cs_var!(A: 0, B: 1, C: 2);
+impl_tuple_r1cs_var!(A: 0, B: 1, C: 2, D: 3);
+
+#[cfg(test)]
+mod test {
+    use super::R1CSVar;
+    use crate::{
+        prelude::*, test_constraint_counter::ConstraintCounter,
+        test_constraint_system::TestConstraintSystem,
+    };
+    use algebra::{bls12_381::Fr, One};
+    use r1cs_core::{ConstraintSystem, ConstraintVar, SynthesisError};
+
+    #[test]
+    fn test_tuple_r1cs_var() {
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let value = Fr::one() + &Fr::one();
+        let fp = FpGadget::alloc(cs.ns(|| "fp"), || Ok(value)).unwrap();
+        let byte = UInt8::alloc(cs.ns(|| "byte"), || Ok(0xa5u8)).unwrap();
+        let pair = (fp.clone(), byte);
+
+        assert_eq!(pair.get_value(), Some((value, 0xa5)));
+        let (fp_var, byte_vars) = pair.get_variable();
+        match fp_var {
+            ConstraintVar::Var(_) => {},
+            _ => panic!("expected the allocated variable"),
+        }
+        assert_eq!(byte_vars.len(), 8);
+
+        // One missing component makes the whole value missing.
+        let mut cs = ConstraintCounter::new();
+        let byte = <UInt8 as AllocGadget<u8, Fr>>::alloc(cs.ns(|| "byte"), || {
+            Err::<u8, _>(SynthesisError::AssignmentMissing)
+        })
+        .unwrap();
+        assert_eq!((fp, byte).get_value(), None);
+    }
+}